@@ -1,7 +1,9 @@
 //! Main [`Asset`] type
 
+#[cfg(feature = "serde")]
+use std::borrow::Cow;
 use std::fmt::{Debug, Formatter};
-use std::io::{Read, Seek, SeekFrom, Write};
+use std::io::{Cursor, Read, Seek, SeekFrom, Write};
 use std::mem::size_of;
 
 use byteorder::{ReadBytesExt, WriteBytesExt, BE, LE};
@@ -34,6 +36,7 @@ use crate::UE4_ASSET_MAGIC;
 
 /// Parent Class Info
 #[derive(FNameContainer, Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ParentClassInfo {
     /// Parent classpath
     pub parent_class_path: FName,
@@ -459,6 +462,51 @@ impl<'a, C: Read + Seek> Asset<C> {
         engine_version: EngineVersion,
         mappings: Option<Usmap>,
     ) -> Result<Self, Error> {
+        let mut asset = Self::with_reader(asset_data, bulk_data, engine_version);
+        asset.asset_data.mappings = mappings;
+        asset.parse_data()?;
+        Ok(asset)
+    }
+
+    /// Create an empty asset that is laid out like a freshly cooked package
+    ///
+    /// Nothing is read from `asset_data` or `bulk_data`
+    pub(crate) fn new_empty(
+        asset_data: C,
+        bulk_data: Option<C>,
+        engine_version: EngineVersion,
+        package_path: &str,
+    ) -> Self {
+        let mut asset = Self::with_reader(asset_data, bulk_data, engine_version);
+        let is_ue5 = asset.get_object_version_ue5() > ObjectVersionUE5::UNKNOWN;
+
+        asset.legacy_file_version = match is_ue5 {
+            true => -8,
+            false => -7,
+        };
+        asset.asset_data.summary.package_flags = match is_ue5 {
+            true => EPackageFlags::PKG_COOKED | EPackageFlags::PKG_FILTER_EDITOR_ONLY,
+            false => EPackageFlags::PKG_FILTER_EDITOR_ONLY,
+        };
+        // ue5 stores the package name where ue4 stored the folder name
+        asset.folder_name = match is_ue5 {
+            true => package_path.to_string(),
+            false => String::from("None"),
+        };
+        asset.generations.push(GenerationInfo::new(0, 0));
+        asset.engine_version_recorded = FEngineVersion::from_engine_version(engine_version);
+        asset.engine_version_compatible = asset.engine_version_recorded.clone();
+        asset.depends_map = Some(Vec::new());
+        if is_ue5 {
+            asset.payload_toc_offset = -1;
+            asset.data_resource_offset = -1;
+        }
+
+        asset
+    }
+
+    /// Create an asset backed by `asset_data` and `bulk_data` without parsing them
+    fn with_reader(asset_data: C, bulk_data: Option<C>, engine_version: EngineVersion) -> Self {
         let use_event_driven_loader = bulk_data.is_some();
 
         let chain = Chain::new(asset_data, bulk_data);
@@ -518,9 +566,7 @@ impl<'a, C: Read + Seek> Asset<C> {
             parent_class: None,
         };
         asset.set_engine_version(engine_version);
-        asset.asset_data.mappings = mappings;
-        asset.parse_data()?;
-        Ok(asset)
+        asset
     }
 
     /// Set asset engine version
@@ -1170,6 +1216,22 @@ impl<'a, C: Read + Seek> Asset<C> {
         });
     }
 
+    /// Add the names which only get added to the name map while writing, e.g. property type names
+    ///
+    /// The name map is written before the exports, so it has to be complete before writing
+    pub(crate) fn add_serialized_names(&mut self) -> Result<(), Error> {
+        let mut cursor = Cursor::new(Vec::new());
+        let mut bulk_cursor = self
+            .asset_data
+            .use_event_driven_loader
+            .then(|| Cursor::new(Vec::new()));
+        self.write_data(&mut cursor, bulk_cursor.as_mut())?;
+
+        self.names_referenced_from_export_data_count =
+            self.name_map.get_ref().get_name_map_index_list().len() as i32;
+        Ok(())
+    }
+
     /// Write asset data
     pub fn write_data<W: Read + Seek + Write>(
         &self,
@@ -1371,12 +1433,15 @@ impl<'a, C: Read + Seek> Asset<C> {
         };
 
         let mut bulk_serializer = match self.asset_data.use_event_driven_loader {
-            true => Some(AssetArchiveWriter::new(
-                raw_bulk_serializer.as_mut().unwrap(),
-                &self.asset_data,
-                &self.imports,
-                self.name_map.clone(),
-            )),
+            true => Some(
+                AssetArchiveWriter::new(
+                    raw_bulk_serializer.as_mut().unwrap(),
+                    &self.asset_data,
+                    &self.imports,
+                    self.name_map.clone(),
+                )
+                .with_package_offset(final_cursor_pos),
+            ),
             false => None,
         };
 
@@ -1641,3 +1706,160 @@ impl<C: Read + Seek> Debug for Asset<C> {
             .finish()
     }
 }
+
+/// Asset as it is stored by serde
+///
+/// Offsets which are recomputed when writing the asset are left out,
+/// FNames are stored by value and bound to the name map again when deserializing
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename = "Asset")]
+struct SerdeAsset<'a> {
+    info: Cow<'a, str>,
+    legacy_file_version: i32,
+    package_guid: Guid,
+    package_source: u32,
+    folder_name: Cow<'a, str>,
+    engine_version_recorded: Cow<'a, FEngineVersion>,
+    engine_version_compatible: Cow<'a, FEngineVersion>,
+    generations: Cow<'a, [GenerationInfo]>,
+    chunk_ids: Cow<'a, [i32]>,
+    soft_object_paths_count: i32,
+    soft_object_paths_offset: i32,
+    gatherable_text_data_count: i32,
+    gatherable_text_data_offset: i32,
+    soft_package_reference_count: i32,
+    searchable_names_offset: i32,
+    thumbnail_table_offset: i32,
+    compression_flags: u32,
+    asset_registry_data_offset: i32,
+    names_referenced_from_export_data_count: i32,
+    payload_toc_offset: i64,
+    data_resource_offset: i32,
+    override_name_map_hashes: Cow<'a, IndexedMap<String, u32>>,
+    name_map: Cow<'a, [String]>,
+    imports: Cow<'a, [Import]>,
+    depends_map: Cow<'a, Option<Vec<Vec<i32>>>>,
+    soft_package_reference_list: Cow<'a, Option<Vec<String>>>,
+    parent_class: Cow<'a, Option<ParentClassInfo>>,
+    asset_data: Cow<'a, AssetData<PackageIndex>>,
+}
+
+#[cfg(feature = "serde")]
+impl<C: Read + Seek> serde::Serialize for Asset<C> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let name_map = self.name_map.get_ref();
+        SerdeAsset {
+            info: Cow::Borrowed(&self.info),
+            legacy_file_version: self.legacy_file_version,
+            package_guid: self.package_guid,
+            package_source: self.package_source,
+            folder_name: Cow::Borrowed(&self.folder_name),
+            engine_version_recorded: Cow::Borrowed(&self.engine_version_recorded),
+            engine_version_compatible: Cow::Borrowed(&self.engine_version_compatible),
+            generations: Cow::Borrowed(&self.generations),
+            chunk_ids: Cow::Borrowed(&self.chunk_ids),
+            soft_object_paths_count: self.soft_object_paths_count,
+            soft_object_paths_offset: self.soft_object_paths_offset,
+            gatherable_text_data_count: self.gatherable_text_data_count,
+            gatherable_text_data_offset: self.gatherable_text_data_offset,
+            soft_package_reference_count: self.soft_package_reference_count,
+            searchable_names_offset: self.searchable_names_offset,
+            thumbnail_table_offset: self.thumbnail_table_offset,
+            compression_flags: self.compression_flags,
+            asset_registry_data_offset: self.asset_registry_data_offset,
+            names_referenced_from_export_data_count: self.names_referenced_from_export_data_count,
+            payload_toc_offset: self.payload_toc_offset,
+            data_resource_offset: self.data_resource_offset,
+            override_name_map_hashes: Cow::Borrowed(&self.override_name_map_hashes),
+            name_map: Cow::Borrowed(name_map.get_name_map_index_list()),
+            imports: Cow::Borrowed(&self.imports),
+            depends_map: Cow::Borrowed(&self.depends_map),
+            soft_package_reference_list: Cow::Borrowed(&self.soft_package_reference_list),
+            parent_class: Cow::Borrowed(&self.parent_class),
+            asset_data: Cow::Borrowed(&self.asset_data),
+        }
+        .serialize(serializer)
+    }
+}
+
+/// Deserializing creates an asset which is not backed by any data,
+/// it can be written with [`Asset::write_data`] just like a parsed asset.
+///
+/// Mappings are not stored and have to be set again for unversioned assets.
+#[cfg(feature = "serde")]
+impl<'de, C: Read + Seek + Default> serde::Deserialize<'de> for Asset<C> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let data = FName::deserialize_with_name_map(|| SerdeAsset::deserialize(deserializer))?;
+        let asset_data = data.asset_data.into_owned();
+
+        let mut name_map = NameMap::new();
+        for name in data.name_map.into_owned() {
+            name_map.get_mut().add_name_reference(name, true);
+        }
+
+        let raw_reader = RawReader::new(
+            Chain::new(
+                C::default(),
+                asset_data.use_event_driven_loader.then(C::default),
+            ),
+            asset_data.object_version,
+            asset_data.object_version_ue5,
+            asset_data.use_event_driven_loader,
+            name_map.clone(),
+        );
+
+        let mut asset = Asset {
+            raw_reader,
+            info: data.info.into_owned(),
+            asset_data,
+            legacy_file_version: data.legacy_file_version,
+            generations: data.generations.into_owned(),
+            package_guid: data.package_guid,
+            engine_version_recorded: data.engine_version_recorded.into_owned(),
+            engine_version_compatible: data.engine_version_compatible.into_owned(),
+            chunk_ids: data.chunk_ids.into_owned(),
+            package_source: data.package_source,
+            folder_name: data.folder_name.into_owned(),
+            header_offset: 0,
+            name_count: 0,
+            name_offset: 0,
+            soft_object_paths_count: data.soft_object_paths_count,
+            soft_object_paths_offset: data.soft_object_paths_offset,
+            gatherable_text_data_count: data.gatherable_text_data_count,
+            gatherable_text_data_offset: data.gatherable_text_data_offset,
+            export_offset: 0,
+            import_offset: 0,
+            depends_offset: 0,
+            soft_package_reference_count: data.soft_package_reference_count,
+            soft_package_reference_offset: 0,
+            searchable_names_offset: data.searchable_names_offset,
+            thumbnail_table_offset: data.thumbnail_table_offset,
+            compression_flags: data.compression_flags,
+            asset_registry_data_offset: data.asset_registry_data_offset,
+            bulk_data_start_offset: 0,
+            world_tile_info_offset: 0,
+            preload_dependency_count: 0,
+            preload_dependency_offset: 0,
+            names_referenced_from_export_data_count: data.names_referenced_from_export_data_count,
+            payload_toc_offset: data.payload_toc_offset,
+            data_resource_offset: data.data_resource_offset,
+
+            override_name_map_hashes: data.override_name_map_hashes.into_owned(),
+            name_map,
+            imports: data.imports.into_owned(),
+            depends_map: data.depends_map.into_owned(),
+            soft_package_reference_list: data.soft_package_reference_list.into_owned(),
+            parent_class: data.parent_class.into_owned(),
+        };
+        asset.rebuild_name_map();
+
+        Ok(asset)
+    }
+}
@@ -29,6 +29,8 @@ pub struct AssetArchiveWriter<'parent_writer, 'asset, ParentWriter: ArchiveWrite
     imports: &'asset [Import],
     /// Asset name map
     name_map: SharedResource<NameMap>,
+    /// Offset of the parent writer in the package
+    package_offset: u64,
 }
 
 impl<'parent_writer, 'asset, ParentWriter: ArchiveWriter<PackageIndex>>
@@ -46,9 +48,16 @@ impl<'parent_writer, 'asset, ParentWriter: ArchiveWriter<PackageIndex>>
             asset_data,
             imports,
             name_map,
+            package_offset: 0,
         }
     }
 
+    /// Set the offset of the parent writer in the package, this is the `.uasset` size when writing a `.uexp` file
+    pub fn with_package_offset(mut self, package_offset: u64) -> Self {
+        self.package_offset = package_offset;
+        self
+    }
+
     /// Get an [`Import`] from this `AssetArchiveWriter`
     pub fn get_import(&self, index: PackageIndex) -> Option<Import> {
         if !index.is_import() {
@@ -153,6 +162,10 @@ impl<'parent_writer, 'asset, ParentWriter: ArchiveWriter<PackageIndex>> ArchiveW
     for AssetArchiveWriter<'parent_writer, 'asset, ParentWriter>
 {
     passthrough_archive_writer!(writer);
+
+    fn package_position(&mut self) -> u64 {
+        self.package_offset + self.writer.position()
+    }
 }
 
 impl<'parent_writer, 'asset, ParentWriter: ArchiveWriter<PackageIndex>> Write
@@ -0,0 +1,198 @@
+//! [`AssetBuilder`] for creating assets without a source file
+
+use std::io::{Read, Seek};
+
+use unreal_asset_base::{
+    engine_version::EngineVersion,
+    flags::EObjectFlags,
+    reader::ArchiveTrait,
+    types::{FName, PackageIndex},
+    Error, Import,
+};
+use unreal_asset_exports::{BaseExport, Export, NormalExport};
+use unreal_asset_properties::Property;
+
+use crate::Asset;
+
+/// Builder for creating an [`Asset`] from scratch
+///
+/// The created asset is laid out like a cooked package for the given engine version,
+/// names that are only referenced while writing, like property type names,
+/// are added to the name map when the asset is built.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::Cursor;
+///
+/// use unreal_asset::{
+///     asset_builder::AssetBuilder,
+///     engine_version::EngineVersion,
+///     properties::int_property::IntProperty,
+///     types::PackageIndex,
+///     Asset,
+/// };
+///
+/// let mut builder = AssetBuilder::new(EngineVersion::VER_UE4_23, "/Game/Mods/ListOfMods");
+/// let class = builder.add_class_import("/Script/Engine", "DataAsset");
+/// let count = IntProperty {
+///     name: builder.add_fname("Count"),
+///     value: 2,
+///     ..Default::default()
+/// };
+/// builder.add_normal_export("ListOfMods", class, PackageIndex::new(0), vec![count.into()]);
+///
+/// let asset: Asset<Cursor<Vec<u8>>> = builder.build().unwrap();
+/// let mut cursor = Cursor::new(Vec::new());
+/// asset.write_data(&mut cursor, None).unwrap();
+/// ```
+pub struct AssetBuilder<C: Read + Seek> {
+    /// Asset that is being built
+    asset: Asset<C>,
+    /// Package path
+    package_path: String,
+}
+
+impl<C: Read + Seek + Default> AssetBuilder<C> {
+    /// Create a new `AssetBuilder` for a package path like `/Game/Mods/ListOfMods`
+    pub fn new(engine_version: EngineVersion, package_path: &str) -> Self {
+        AssetBuilder {
+            asset: Asset::new_empty(C::default(), None, engine_version, package_path),
+            package_path: package_path.to_string(),
+        }
+    }
+}
+
+impl<C: Read + Seek> AssetBuilder<C> {
+    /// Set whether exports are written into a separate `.uexp` file
+    pub fn use_event_driven_loader(mut self, use_event_driven_loader: bool) -> Self {
+        self.asset.asset_data.use_event_driven_loader = use_event_driven_loader;
+        self.asset.raw_reader.use_event_driven_loader = use_event_driven_loader;
+        self
+    }
+
+    /// Get the package path
+    pub fn package_path(&self) -> &str {
+        &self.package_path
+    }
+
+    /// Get the asset name, this is the last segment of the package path
+    pub fn asset_name(&self) -> &str {
+        self.package_path
+            .rsplit('/')
+            .next()
+            .unwrap_or(&self.package_path)
+    }
+
+    /// Get the asset that is being built
+    pub fn asset(&self) -> &Asset<C> {
+        &self.asset
+    }
+
+    /// Get a mutable reference to the asset that is being built
+    pub fn asset_mut(&mut self) -> &mut Asset<C> {
+        &mut self.asset
+    }
+
+    /// Add an `FName`
+    pub fn add_fname(&mut self, slice: &str) -> FName {
+        self.asset.add_fname(slice)
+    }
+
+    /// Add an `Import` if an equal one doesn't exist yet
+    pub fn add_import(
+        &mut self,
+        class_package: &str,
+        class_name: &str,
+        outer_index: PackageIndex,
+        object_name: &str,
+    ) -> PackageIndex {
+        let import = Import::new(
+            self.asset.add_fname(class_package),
+            self.asset.add_fname(class_name),
+            outer_index,
+            self.asset.add_fname(object_name),
+            false,
+        );
+
+        match self.asset.find_import(
+            &import.class_package,
+            &import.class_name,
+            outer_index,
+            &import.object_name,
+        ) {
+            Some(index) => PackageIndex::new(index),
+            None => self.asset.add_import(import),
+        }
+    }
+
+    /// Add an import of a package, e.g. `/Script/Engine`
+    pub fn add_package_import(&mut self, package: &str) -> PackageIndex {
+        self.add_import(
+            "/Script/CoreUObject",
+            "Package",
+            PackageIndex::new(0),
+            package,
+        )
+    }
+
+    /// Add an import of a native class and its package
+    pub fn add_class_import(&mut self, class_package: &str, class_name: &str) -> PackageIndex {
+        let package = self.add_package_import(class_package);
+        self.add_import("/Script/CoreUObject", "Class", package, class_name)
+    }
+
+    /// Add an export
+    pub fn add_export(&mut self, export: impl Into<Export<PackageIndex>>) -> PackageIndex {
+        self.asset.asset_data.exports.push(export.into());
+        PackageIndex::new(self.asset.asset_data.exports.len() as i32)
+    }
+
+    /// Add a `NormalExport` with properties
+    ///
+    /// Object flags and preload dependencies are set up like the cooker would,
+    /// objects referenced from the properties should be added to
+    /// `create_before_serialization_dependencies` when using the event driven loader.
+    pub fn add_normal_export(
+        &mut self,
+        object_name: &str,
+        class_index: PackageIndex,
+        outer_index: PackageIndex,
+        properties: Vec<Property>,
+    ) -> PackageIndex {
+        let is_asset = outer_index.index == 0 && object_name == self.asset_name();
+        let object_name = self.asset.add_fname(object_name);
+
+        let mut base_export = BaseExport {
+            class_index,
+            outer_index,
+            object_name,
+            object_flags: EObjectFlags::RF_PUBLIC | EObjectFlags::RF_TRANSACTIONAL,
+            is_asset,
+            ..Default::default()
+        };
+        if is_asset {
+            base_export.object_flags |= EObjectFlags::RF_STANDALONE;
+        }
+
+        if self.asset.use_event_driven_loader() {
+            base_export.serialization_before_create_dependencies = vec![class_index];
+            if outer_index.index != 0 {
+                base_export.create_before_create_dependencies = vec![outer_index];
+            }
+        }
+
+        self.add_export(NormalExport {
+            base_export,
+            // objects serialize whether they have a guid after their properties
+            extras: vec![0u8; 4],
+            properties,
+        })
+    }
+
+    /// Build the asset
+    pub fn build(mut self) -> Result<Asset<C>, Error> {
+        self.asset.add_serialized_names()?;
+        Ok(self.asset)
+    }
+}
@@ -20,7 +20,8 @@ use unreal_asset_exports::{
     base_export::BaseExport, class_export::ClassExport, data_table_export::DataTableExport,
     enum_export::EnumExport, function_export::FunctionExport, level_export::LevelExport,
     normal_export::NormalExport, properties::fproperty::FProperty, property_export::PropertyExport,
-    raw_export::RawExport, string_table_export::StringTableExport,
+    raw_export::RawExport, static_mesh_export::StaticMeshExport,
+    string_table_export::StringTableExport, texture_2d_export::Texture2DExport,
     user_defined_struct_export::UserDefinedStructExport, world_export::WorldExport, Export,
     ExportNormalTrait,
 };
@@ -30,6 +31,7 @@ use crate::package_file_summary::PackageFileSummary;
 
 /// Unreal asset data, this is relevant for all assets
 #[derive(FNameContainer, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AssetData<Index: PackageIndexTrait> {
     /// Does asset use the event driven loader
     pub use_event_driven_loader: bool,
@@ -48,7 +50,10 @@ pub struct AssetData<Index: PackageIndexTrait> {
     pub object_version_ue5: ObjectVersionUE5,
 
     /// .usmap mappings
+    ///
+    /// Not serialized with serde, mappings have to be set again after deserializing
     #[container_ignore]
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub mappings: Option<Usmap>,
 
     /// Object exports
@@ -349,6 +354,24 @@ pub trait ExportReaderTrait<Index: PackageIndexTrait>:
                 "StringTable" => StringTableExport::from_base(&base_export, self)?.into(),
                 "Enum" | "UserDefinedEnum" => EnumExport::from_base(&base_export, self)?.into(),
                 "Function" => FunctionExport::from_base(&base_export, self)?.into(),
+                "Texture2D" => match Texture2DExport::from_base(&base_export, self) {
+                    Ok(e) => e.into(),
+                    // unsupported texture data shouldn't prevent editing the properties
+                    Err(Error::Unimplemented(_)) => {
+                        self.seek(SeekFrom::Start(base_export.serial_offset as u64))?;
+                        NormalExport::from_base(&base_export, self)?.into()
+                    }
+                    Err(e) => return Err(e),
+                },
+                "StaticMesh" => match StaticMeshExport::from_base(&base_export, self) {
+                    Ok(e) => e.into(),
+                    // unsupported render data shouldn't prevent editing the properties
+                    Err(Error::Unimplemented(_)) => {
+                        self.seek(SeekFrom::Start(base_export.serial_offset as u64))?;
+                        NormalExport::from_base(&base_export, self)?.into()
+                    }
+                    Err(e) => return Err(e),
+                },
                 _ => {
                     if export_class_type.ends_with("DataTable") {
                         DataTableExport::from_base(&base_export, self)?.into()
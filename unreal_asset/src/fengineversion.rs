@@ -5,6 +5,7 @@ use std::fmt::Debug;
 use byteorder::{ReadBytesExt, WriteBytesExt, LE};
 
 use unreal_asset_base::{
+    engine_version::EngineVersion,
     reader::{ArchiveReader, ArchiveWriter},
     types::PackageIndexTrait,
     Error,
@@ -12,6 +13,7 @@ use unreal_asset_base::{
 
 /// EngineVersion for an Asset
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FEngineVersion {
     pub(crate) major: u16,
     pub(crate) minor: u16,
@@ -63,4 +65,21 @@ impl FEngineVersion {
     pub(crate) fn unknown() -> Self {
         Self::new(0, 0, 0, 0, None)
     }
+
+    /// Create an `FEngineVersion` for a release build of `engine_version`
+    pub(crate) fn from_engine_version(engine_version: EngineVersion) -> Self {
+        let version = engine_version as i32;
+        let (major, minor) = if (EngineVersion::VER_UE4_0..=EngineVersion::VER_UE4_27)
+            .contains(&engine_version)
+        {
+            (4, version - EngineVersion::VER_UE4_0 as i32)
+        } else if (EngineVersion::VER_UE5_0..=EngineVersion::VER_UE5_3).contains(&engine_version) {
+            (5, version - EngineVersion::VER_UE5_0 as i32)
+        } else {
+            return Self::unknown();
+        };
+
+        let branch = format!("++UE{major}+Release-{major}.{minor}");
+        Self::new(major, minor as u16, 0, 0, Some(branch))
+    }
 }
@@ -85,6 +85,7 @@ pub use unreal_asset_registry as registry;
 // modules
 pub mod ac7;
 pub mod asset;
+pub mod asset_builder;
 pub mod asset_archive_writer;
 pub mod asset_data;
 pub mod fengineversion;
@@ -7,6 +7,7 @@ use unreal_asset_base::{
 
 /// Package file summary
 #[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PackageFileSummary {
     /// Package flags
     pub package_flags: EPackageFlags,
@@ -0,0 +1,129 @@
+use std::io::Cursor;
+
+use unreal_asset::{
+    asset_builder::AssetBuilder,
+    cast,
+    engine_version::EngineVersion,
+    exports::{Export, ExportBaseTrait, NormalExport},
+    flags::EObjectFlags,
+    properties::{
+        int_property::IntProperty, object_property::ObjectProperty, str_property::StrProperty,
+        Property, PropertyDataTrait,
+    },
+    types::PackageIndex,
+    Asset, Error,
+};
+
+mod shared;
+
+fn build_asset(
+    engine_version: EngineVersion,
+    use_event_driven_loader: bool,
+) -> Result<Asset<Cursor<Vec<u8>>>, Error> {
+    let mut builder = AssetBuilder::new(engine_version, "/Game/Mods/ListOfMods")
+        .use_event_driven_loader(use_event_driven_loader);
+    assert_eq!(builder.asset_name(), "ListOfMods");
+
+    let class = builder.add_class_import("/Script/Engine", "DataAsset");
+    let mesh_package = builder.add_package_import("/Game/Meshes/Cube");
+    let mesh = builder.add_import("/Script/Engine", "StaticMesh", mesh_package, "Cube");
+
+    let properties = vec![
+        IntProperty {
+            name: builder.add_fname("ModCount"),
+            value: 2,
+            ..Default::default()
+        }
+        .into(),
+        StrProperty {
+            name: builder.add_fname("ModName"),
+            ancestry: Default::default(),
+            property_guid: None,
+            duplication_index: 0,
+            value: Some(String::from("Example Mod")),
+        }
+        .into(),
+        ObjectProperty {
+            name: builder.add_fname("Mesh"),
+            value: mesh,
+            ..Default::default()
+        }
+        .into(),
+    ];
+    let list = builder.add_normal_export("ListOfMods", class, PackageIndex::new(0), properties);
+    builder.add_normal_export("Entry", class, list, Vec::new());
+
+    builder.build()
+}
+
+#[test]
+fn built_assets_load() -> Result<(), Error> {
+    for (engine_version, use_event_driven_loader) in [
+        (EngineVersion::VER_UE4_18, false),
+        (EngineVersion::VER_UE4_23, true),
+        (EngineVersion::VER_UE5_1, true),
+    ] {
+        let built = build_asset(engine_version, use_event_driven_loader)?;
+        let (data, bulk) = shared::write_asset(&built)?;
+
+        let mut parsed = Asset::new(
+            Cursor::new(data.as_slice()),
+            bulk.as_deref().map(Cursor::new),
+            engine_version,
+            None,
+        )?;
+        assert!(shared::verify_all_exports_parsed(&parsed));
+        shared::verify_binary_equality(&data, bulk.as_deref(), &mut parsed)?;
+
+        assert_eq!(parsed.imports.len(), 4);
+        assert_eq!(parsed.asset_data.exports.len(), 2);
+
+        let list: &NormalExport<_> = cast!(Export, NormalExport, &parsed.asset_data.exports[0])
+            .expect("ListOfMods is not a normal export");
+        assert_eq!(
+            list.base_export.object_name.get_owned_content(),
+            "ListOfMods"
+        );
+        assert!(list.base_export.is_asset);
+        assert!(list
+            .base_export
+            .object_flags
+            .contains(EObjectFlags::RF_PUBLIC | EObjectFlags::RF_STANDALONE));
+
+        let names = list
+            .properties
+            .iter()
+            .map(|e| e.get_name().get_owned_content())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["ModCount", "ModName", "Mesh"]);
+        assert_eq!(
+            cast!(Property, IntProperty, &list.properties[0]).map(|e| e.value),
+            Some(2)
+        );
+        assert_eq!(
+            cast!(Property, StrProperty, &list.properties[1]).and_then(|e| e.value.as_deref()),
+            Some("Example Mod")
+        );
+        assert_eq!(
+            cast!(Property, ObjectProperty, &list.properties[2])
+                .and_then(|e| parsed.get_import(e.value))
+                .map(|e| e.object_name.get_owned_content()),
+            Some(String::from("Cube"))
+        );
+
+        let entry = parsed.asset_data.exports[1].get_base_export();
+        assert_eq!(entry.outer_index, PackageIndex::new(1));
+        assert!(!entry.is_asset);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn empty_asset() -> Result<(), Error> {
+    let mut asset: Asset<Cursor<Vec<u8>>> =
+        AssetBuilder::new(EngineVersion::VER_UE4_25, "/Game/Empty").build()?;
+    assert!(asset.asset_data.exports.is_empty());
+
+    shared::verify_reparse(&mut asset, EngineVersion::VER_UE4_25)
+}
@@ -0,0 +1,138 @@
+#![cfg(feature = "serde")]
+
+use std::io::Cursor;
+
+use unreal_asset::{
+    cast,
+    engine_version::EngineVersion,
+    exports::{Export, ExportBaseTrait},
+    Asset, Error,
+};
+
+mod shared;
+
+macro_rules! assets_folder {
+    () => {
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/assets/general/")
+    };
+}
+
+/// Asset data, bulk data and engine version of a test asset
+type TestAsset = (&'static [u8], Option<&'static [u8]>, EngineVersion);
+
+const TEST_ASSETS: [TestAsset; 3] = [
+    (
+        include_bytes!(concat!(
+            assets_folder!(),
+            "BloodStained/PB_DT_ItemMaster.uasset"
+        )),
+        None,
+        EngineVersion::VER_UE4_18,
+    ),
+    (
+        include_bytes!(concat!(
+            assets_folder!(),
+            "Misc_426/RaceSimDataAsset.uasset"
+        )),
+        Some(include_bytes!(concat!(
+            assets_folder!(),
+            "Misc_426/RaceSimDataAsset.uexp"
+        ))),
+        EngineVersion::VER_UE4_26,
+    ),
+    (
+        include_bytes!(concat!(
+            assets_folder!(),
+            "pseudoregalia/BP_PlayerGoatMain.uasset"
+        )),
+        Some(include_bytes!(concat!(
+            assets_folder!(),
+            "pseudoregalia/BP_PlayerGoatMain.uexp"
+        ))),
+        EngineVersion::VER_UE5_1,
+    ),
+];
+
+fn to_json(asset: &Asset<Cursor<&[u8]>>) -> String {
+    serde_json::to_string(asset).expect("failed to serialize asset")
+}
+
+fn from_json(json: &str) -> Asset<Cursor<Vec<u8>>> {
+    serde_json::from_str(json).expect("failed to deserialize asset")
+}
+
+#[test]
+fn json_roundtrip() -> Result<(), Error> {
+    for (asset_data, bulk_data, engine_version) in TEST_ASSETS {
+        let parsed = Asset::new(
+            Cursor::new(asset_data),
+            bulk_data.map(Cursor::new),
+            engine_version,
+            None,
+        )?;
+
+        let json = to_json(&parsed);
+        let mut deserialized = from_json(&json);
+        shared::verify_binary_equality(asset_data, bulk_data, &mut deserialized)?;
+
+        // serializing again gives the same json
+        let mut cursor = Cursor::new(Vec::new());
+        let mut bulk_cursor = bulk_data.map(|_| Cursor::new(Vec::new()));
+        deserialized.write_data(&mut cursor, bulk_cursor.as_mut())?;
+        let reparsed = Asset::new(
+            Cursor::new(cursor.get_ref().as_slice()),
+            bulk_cursor
+                .as_ref()
+                .map(|bulk| Cursor::new(bulk.get_ref().as_slice())),
+            engine_version,
+            None,
+        )?;
+        assert_eq!(to_json(&reparsed), json);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn edited_json() -> Result<(), Error> {
+    let (asset_data, bulk_data, engine_version) = TEST_ASSETS[1];
+    let parsed = Asset::new(
+        Cursor::new(asset_data),
+        bulk_data.map(Cursor::new),
+        engine_version,
+        None,
+    )?;
+
+    // rename the first export to a name which is not in the name map yet
+    let mut json = serde_json::to_value(&parsed).expect("failed to serialize asset");
+    let export = json["asset_data"]["exports"][0]
+        .as_object_mut()
+        .and_then(|export| export.values_mut().next())
+        .expect("export is not an enum variant");
+    let base_export = match export.get_mut("base_export") {
+        Some(_) => &mut export["base_export"],
+        None => &mut export["normal_export"]["base_export"],
+    };
+    base_export["object_name"]["value"] = "RenamedExport".into();
+
+    let mut deserialized: Asset<Cursor<Vec<u8>>> =
+        serde_json::from_value(json).expect("failed to deserialize asset");
+    assert!(deserialized
+        .search_name_reference("RenamedExport")
+        .is_some());
+
+    let mut cursor = Cursor::new(Vec::new());
+    let mut bulk_cursor = Some(Cursor::new(Vec::new()));
+    deserialized.write_data(&mut cursor, bulk_cursor.as_mut())?;
+    let reparsed = Asset::new(cursor, bulk_cursor, engine_version, None)?;
+
+    let export = &reparsed.asset_data.exports[0];
+    assert_eq!(
+        export.get_base_export().object_name.get_owned_content(),
+        "RenamedExport"
+    );
+    assert!(cast!(Export, RawExport, export).is_none());
+    shared::verify_reparse(&mut deserialized, engine_version)?;
+
+    Ok(())
+}
@@ -1,6 +1,13 @@
 use std::io::{Cursor, Read, Seek};
 
-use unreal_asset::{cast, engine_version::EngineVersion, Asset, Error, Export};
+use unreal_asset::{
+    asset_builder::AssetBuilder,
+    cast,
+    engine_version::EngineVersion,
+    exports::{Export, NormalExport},
+    types::PackageIndex,
+    Asset, Error,
+};
 
 #[allow(dead_code)]
 pub(crate) fn verify_reparse<C: Read + Seek>(
@@ -61,3 +68,36 @@ pub(crate) fn verify_all_exports_parsed<C: Read + Seek>(asset: &Asset<C>) -> boo
 
     true
 }
+
+/// Write an asset, the `.uexp` file is only returned if the asset uses the event driven loader
+#[allow(dead_code)]
+pub(crate) fn write_asset(
+    asset: &Asset<Cursor<Vec<u8>>>,
+) -> Result<(Vec<u8>, Option<Vec<u8>>), Error> {
+    let mut cursor = Cursor::new(Vec::new());
+    let mut bulk_cursor = asset
+        .asset_data
+        .use_event_driven_loader
+        .then(|| Cursor::new(Vec::new()));
+    asset.write_data(&mut cursor, bulk_cursor.as_mut())?;
+
+    Ok((cursor.into_inner(), bulk_cursor.map(Cursor::into_inner)))
+}
+
+/// Build a top level normal export for an export type which serializes the guid flag itself
+#[allow(dead_code)]
+pub(crate) fn build_normal_export(
+    builder: &mut AssetBuilder<Cursor<Vec<u8>>>,
+    object_name: &str,
+    class_index: PackageIndex,
+) -> NormalExport<PackageIndex> {
+    builder.add_normal_export(object_name, class_index, PackageIndex::new(0), Vec::new());
+
+    let Some(Export::NormalExport(mut normal_export)) =
+        builder.asset_mut().asset_data.exports.pop()
+    else {
+        panic!("{object_name} is not a normal export");
+    };
+    normal_export.extras.clear();
+    normal_export
+}
@@ -0,0 +1,369 @@
+use std::io::Cursor;
+
+use ordered_float::OrderedFloat;
+use unreal_asset::{
+    asset_builder::AssetBuilder,
+    cast,
+    engine_version::EngineVersion,
+    exports::{
+        static_mesh_export::{
+            FBoxSphereBounds, FColorVertexBuffer, FMeshUVChannelInfo, FPositionVertexBuffer,
+            FRawStaticIndexBuffer, FStaticMaterial, FStaticMeshBuffers, FStaticMeshLODResources,
+            FStaticMeshRenderData, FStaticMeshSection, FStaticMeshVertexBuffer,
+            FStaticMeshVertexTangent, FWeightedRandomSampler, StaticMeshExport,
+        },
+        Export,
+    },
+    flags::EBulkDataFlags,
+    types::{
+        bulk_data::FByteBulkData,
+        strip_data_flags::FStripDataFlags,
+        vector::{Color, Vector, Vector2, Vector4},
+        PackageIndex,
+    },
+    Asset, Error,
+};
+
+mod shared;
+
+/// Built static mesh asset and its `.ubulk` file
+type StaticMeshAsset = (Asset<Cursor<Vec<u8>>>, Vec<u8>);
+
+fn vector(x: f32, y: f32, z: f32) -> Vector<OrderedFloat<f32>> {
+    Vector::new(OrderedFloat(x), OrderedFloat(y), OrderedFloat(z))
+}
+
+fn section(material_index: i32, first_index: u32) -> FStaticMeshSection {
+    FStaticMeshSection {
+        material_index,
+        first_index,
+        num_triangles: 1,
+        min_vertex_index: 0,
+        max_vertex_index: 3,
+        enable_collision: true,
+        cast_shadow: true,
+        force_opaque: false,
+        visible_in_ray_tracing: true,
+    }
+}
+
+/// 100x100 quad facing up, split into two single triangle sections
+fn quad_buffers() -> FStaticMeshBuffers {
+    let tangent = FStaticMeshVertexTangent {
+        tangent_x: Vector4::new(127, 0, 0, 0),
+        tangent_z: Vector4::new(0, 0, 127, 127),
+    };
+    let tex_coords = [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)]
+        .map(|(u, v)| Vector2::new(OrderedFloat(u), OrderedFloat(v)));
+
+    FStaticMeshBuffers {
+        strip_flags: FStripDataFlags::new(FStripDataFlags::EDITOR, 0),
+        position_vertex_buffer: FPositionVertexBuffer {
+            stride: 12,
+            num_vertices: 4,
+            vertices: vec![
+                vector(0.0, 0.0, 0.0),
+                vector(100.0, 0.0, 0.0),
+                vector(100.0, 100.0, 0.0),
+                vector(0.0, 100.0, 0.0),
+            ],
+        },
+        static_mesh_vertex_buffer: FStaticMeshVertexBuffer {
+            strip_flags: FStripDataFlags::default(),
+            num_tex_coords: 1,
+            num_vertices: 4,
+            use_full_precision_uvs: false,
+            use_high_precision_tangent_basis: false,
+            tangents: vec![tangent; 4],
+            tex_coords: tex_coords.to_vec(),
+        },
+        color_vertex_buffer: FColorVertexBuffer {
+            strip_flags: FStripDataFlags::default(),
+            stride: 4,
+            num_vertices: 4,
+            colors: vec![Color::new(255, 128, 0, 255); 4],
+        },
+        index_buffer: FRawStaticIndexBuffer::from_indices(&[0, 1, 2, 0, 2, 3]),
+        reversed_index_buffer: Some(FRawStaticIndexBuffer::from_indices(&[2, 1, 0, 3, 2, 0])),
+        depth_only_index_buffer: FRawStaticIndexBuffer::from_indices(&[0, 1, 2, 0, 2, 3]),
+        reversed_depth_only_index_buffer: Some(FRawStaticIndexBuffer::default()),
+        wireframe_index_buffer: None,
+        adjacency_index_buffer: Some(FRawStaticIndexBuffer::default()),
+        ray_tracing_geometry: None,
+        area_weighted_section_samplers: vec![FWeightedRandomSampler::default(); 2],
+        area_weighted_sampler: FWeightedRandomSampler {
+            prob: vec![OrderedFloat(1.0); 2],
+            alias: vec![0, 1],
+            total_weight: OrderedFloat(10000.0),
+        },
+    }
+}
+
+/// Build a static mesh with an inlined LOD and a second LOD stored in a `.ubulk` file
+fn build_static_mesh(
+    engine_version: EngineVersion,
+    use_event_driven_loader: bool,
+) -> Result<StaticMeshAsset, Error> {
+    let mut builder = AssetBuilder::new(engine_version, "/Game/Meshes/SM_Test")
+        .use_event_driven_loader(use_event_driven_loader);
+    let class = builder.add_class_import("/Script/Engine", "StaticMesh");
+    let normal_export = shared::build_normal_export(&mut builder, "SM_Test", class);
+
+    let mut buffers = quad_buffers();
+    // ray tracing geometry is only serialized since UE4.25
+    if engine_version >= EngineVersion::VER_UE4_25 {
+        buffers.ray_tracing_geometry = Some(vec![1, 2, 3]);
+    }
+
+    let mut ubulk = Vec::new();
+    buffers.write(&mut ubulk, engine_version)?;
+    let streamed_lod = FByteBulkData {
+        flags: EBulkDataFlags::BULKDATA_PAYLOAD_AT_END_OF_FILE
+            | EBulkDataFlags::BULKDATA_PAYLOAD_IN_SEPERATE_FILE
+            | EBulkDataFlags::BULKDATA_FORCE_NOT_INLINE_PAYLOAD,
+        element_count: ubulk.len() as i64,
+        size_on_disk: ubulk.len() as i64,
+        offset: 0,
+        bad_data_version: 0,
+        duplicate: None,
+        data: None,
+    };
+
+    let sections = vec![section(0, 0), section(1, 3)];
+    let lods = vec![
+        FStaticMeshLODResources {
+            sections: sections.clone(),
+            inlined: true,
+            buffers: Some(buffers),
+            ..Default::default()
+        },
+        FStaticMeshLODResources {
+            sections,
+            max_deviation: OrderedFloat(1.0),
+            streaming_bulk_data: Some(streamed_lod),
+            availability_info: Some(Default::default()),
+            ..Default::default()
+        },
+    ];
+
+    let render_data = FStaticMeshRenderData {
+        lods,
+        num_inlined_lods: 1,
+        strip_flags: FStripDataFlags::default(),
+        distance_field_data: vec![None, None],
+        bounds: FBoxSphereBounds {
+            origin: vector(50.0, 50.0, 0.0),
+            box_extent: vector(50.0, 50.0, 0.0),
+            sphere_radius: OrderedFloat(70.7),
+        },
+        lods_share_static_lighting: false,
+        screen_sizes: [1.0, 0.5, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0].map(OrderedFloat),
+    };
+
+    let static_materials = ["M_Red", "M_Blue"]
+        .map(|name| FStaticMaterial {
+            material_interface: PackageIndex::new(0),
+            material_slot_name: builder.add_fname(name),
+            uv_channel_data: FMeshUVChannelInfo::default(),
+        })
+        .to_vec();
+
+    builder.add_export(StaticMeshExport {
+        normal_export,
+        strip_flags: FStripDataFlags::new(FStripDataFlags::EDITOR, 0),
+        body_setup: PackageIndex::new(0),
+        nav_collision: PackageIndex::new(0),
+        lighting_guid: [7; 16].into(),
+        sockets: Vec::new(),
+        render_data,
+        occluder_data: None,
+        static_materials,
+    });
+
+    Ok((builder.build()?, ubulk))
+}
+
+#[test]
+fn static_mesh() -> Result<(), Error> {
+    for (engine_version, use_event_driven_loader) in [
+        (EngineVersion::VER_UE4_23, false),
+        (EngineVersion::VER_UE4_25, true),
+        (EngineVersion::VER_UE4_27, true),
+    ] {
+        let (built, ubulk) = build_static_mesh(engine_version, use_event_driven_loader)?;
+        let (data, bulk) = shared::write_asset(&built)?;
+
+        let mut parsed = Asset::new(
+            Cursor::new(data.as_slice()),
+            bulk.as_deref().map(Cursor::new),
+            engine_version,
+            None,
+        )?;
+        shared::verify_binary_equality(&data, bulk.as_deref(), &mut parsed)?;
+
+        let static_mesh = cast!(Export, StaticMeshExport, &parsed.asset_data.exports[0])
+            .expect("SM_Test is not a static mesh")
+            .clone();
+        assert!(static_mesh.normal_export.extras.is_empty());
+        assert_eq!(static_mesh.render_data.lods.len(), 2);
+        assert_eq!(static_mesh.static_materials[1].material_slot_name, "M_Blue");
+
+        let lods = &static_mesh.render_data.lods;
+        let buffers = lods[0].buffers.as_ref().expect("LOD 0 is not inlined");
+        assert_eq!(buffers.index_buffer.indices(), [0, 1, 2, 0, 2, 3]);
+        assert!(!buffers.index_buffer.is_32_bit);
+
+        let bulk_data = lods[1]
+            .streaming_bulk_data
+            .as_ref()
+            .expect("LOD 1 is not streamed");
+        assert!(bulk_data.is_in_separate_file());
+        let payload =
+            bulk_data.read_payload(&mut Cursor::new(&ubulk), parsed.bulk_data_start_offset)?;
+        assert_eq!(
+            &lods[1].read_streamed_buffers(&payload, engine_version)?,
+            buffers
+        );
+        assert!(static_mesh.to_mesh(1).is_err());
+
+        let mesh = static_mesh.to_mesh(0)?;
+        assert_eq!(mesh.positions[2], [100.0, 100.0, 0.0]);
+        assert_eq!(mesh.normals[0], [0.0, 0.0, 1.0]);
+        assert_eq!(mesh.tangents[0], [1.0, 0.0, 0.0, 1.0]);
+        assert_eq!(
+            mesh.tex_coords,
+            [vec![[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]]]
+        );
+        assert_eq!(mesh.colors[0], [255, 128, 0, 255]);
+        assert_eq!(mesh.sections.len(), 2);
+        assert_eq!(mesh.sections[1].material_name.as_deref(), Some("M_Blue"));
+        assert_eq!(mesh.sections[1].first_index, 3);
+
+        let obj = mesh.to_obj()?;
+        let lines = obj.lines().collect::<Vec<_>>();
+        assert_eq!(lines[3], "v 0 0 100");
+        assert!(lines.contains(&"vt 1 0"));
+        assert!(lines.contains(&"vn 0 1 0"));
+        assert!(lines.contains(&"usemtl M_Red"));
+        assert_eq!(lines.last(), Some(&"f 1/1/1 3/3/3 4/4/4"));
+
+        let glb = mesh.to_gltf()?;
+        assert_eq!(&glb[..4], b"glTF");
+        assert_eq!(&glb[4..8], 2u32.to_le_bytes());
+        assert_eq!(&glb[8..12], (glb.len() as u32).to_le_bytes());
+        assert_eq!(&glb[16..20], b"JSON");
+        let json_length = u32::from_le_bytes([glb[12], glb[13], glb[14], glb[15]]) as usize;
+        let json = std::str::from_utf8(&glb[20..20 + json_length]).expect("Invalid glTF JSON");
+        assert!(json.contains(r#""materials":[{"name":"M_Red"},{"name":"M_Blue"}]"#));
+        assert!(json.contains(r#""TEXCOORD_0""#));
+        assert_eq!(&glb[24 + json_length..28 + json_length], b"BIN\0");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn unsupported_static_mesh() -> Result<(), Error> {
+    // render data of unsupported engine versions is kept in the extras of a normal export
+    let (built, _) = build_static_mesh(EngineVersion::VER_UE4_22, false)?;
+    let (data, bulk) = shared::write_asset(&built)?;
+
+    let mut parsed = Asset::new(
+        Cursor::new(data.as_slice()),
+        bulk.as_deref().map(Cursor::new),
+        EngineVersion::VER_UE4_22,
+        None,
+    )?;
+    shared::verify_binary_equality(&data, bulk.as_deref(), &mut parsed)?;
+
+    let normal_export = cast!(Export, NormalExport, &parsed.asset_data.exports[0])
+        .expect("SM_Test is not a normal export");
+    assert!(!normal_export.extras.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn corrupted_static_mesh() -> Result<(), Error> {
+    // invalid render data isn't hidden behind a normal export
+    let (built, _) = build_static_mesh(EngineVersion::VER_UE4_25, true)?;
+    let (data, bulk) = shared::write_asset(&built)?;
+    let mut uexp = bulk.unwrap_or_default();
+
+    // element size of the inlined position buffer
+    let position = uexp
+        .windows(8)
+        .rposition(|e| e == [12, 0, 0, 0, 4, 0, 0, 0])
+        .expect("No position buffer");
+    uexp[position] = 13;
+
+    let parsed = Asset::new(
+        Cursor::new(data.as_slice()),
+        Some(Cursor::new(uexp.as_slice())),
+        EngineVersion::VER_UE4_25,
+        None,
+    )?;
+    assert!(cast!(Export, RawExport, &parsed.asset_data.exports[0]).is_some());
+
+    Ok(())
+}
+
+#[test]
+fn invalid_mesh() {
+    let mut mesh = quad_buffers().to_mesh(&[section(0, 0), section(0, 3)], &[]);
+    assert!(mesh.sections[0].material_name.is_none());
+    assert!(mesh.to_obj().is_ok());
+
+    mesh.sections[1].num_triangles = 2;
+    assert!(mesh.to_obj().is_err());
+
+    mesh.sections.clear();
+    mesh.indices[5] = 4;
+    assert!(mesh.to_gltf().is_err());
+}
+
+#[test]
+fn half_precision_uvs() -> Result<(), Error> {
+    let tex_coords = [
+        // subnormal halves
+        (3.0 * 2f32.powi(-24), 2f32.powi(-15)),
+        (0.75 * 2f32.powi(-24), 2f32.powi(-26)),
+        (f32::NAN, -f32::NAN),
+        (f32::INFINITY, f32::NEG_INFINITY),
+        (1e6, 1.0),
+    ]
+    .map(|(u, v)| Vector2::new(OrderedFloat(u), OrderedFloat(v)));
+    let buffer = FStaticMeshVertexBuffer {
+        strip_flags: FStripDataFlags::default(),
+        num_tex_coords: 1,
+        num_vertices: tex_coords.len() as u32,
+        use_full_precision_uvs: false,
+        use_high_precision_tangent_basis: false,
+        tangents: Vec::new(),
+        tex_coords: tex_coords.to_vec(),
+    };
+
+    let mut data = Vec::new();
+    buffer.write(&mut data)?;
+    let halves = data[data.len() - tex_coords.len() * 4..]
+        .chunks_exact(2)
+        .map(|e| u16::from_le_bytes([e[0], e[1]]))
+        .collect::<Vec<_>>();
+    assert_eq!(halves[..4], [0x0003, 0x0200, 0x0001, 0x0000]);
+    // nan stays nan instead of becoming infinity
+    assert_eq!(halves[4] & 0xfc00, 0x7c00);
+    assert_ne!(halves[4] & 0x3ff, 0);
+    assert_eq!(halves[5] & 0xfc00, 0xfc00);
+    assert_ne!(halves[5] & 0x3ff, 0);
+    assert_eq!(halves[6..], [0x7c00, 0xfc00, 0x7c00, 0x3c00]);
+
+    let read = FStaticMeshVertexBuffer::read(&mut Cursor::new(data))?;
+    assert_eq!(read.tex_coords[0], tex_coords[0]);
+    assert_eq!(read.tex_coords[1].x.0, 2f32.powi(-24));
+    assert_eq!(read.tex_coords[1].y.0, 0.0);
+    assert!(read.tex_coords[2].x.is_nan() && read.tex_coords[2].y.is_nan());
+    assert_eq!(read.tex_coords[3], tex_coords[3]);
+    assert!(read.tex_coords[4].x.is_infinite());
+
+    Ok(())
+}
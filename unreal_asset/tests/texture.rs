@@ -0,0 +1,397 @@
+use std::io::Cursor;
+
+use unreal_asset::{
+    asset_builder::AssetBuilder,
+    cast,
+    engine_version::EngineVersion,
+    exports::{
+        texture::{self, bcn, EPixelFormat, RgbaImage},
+        texture_2d_export::{FTexture2DMipMap, FTexturePlatformData, Texture2DExport},
+        Export,
+    },
+    flags::EBulkDataFlags,
+    types::{bulk_data::FByteBulkData, strip_data_flags::FStripDataFlags},
+    Asset, Error,
+};
+
+mod shared;
+
+/// 4x4 BC1 block with every pixel set to the first color
+fn bc1_block(color: u16) -> [u8; 8] {
+    let [low, high] = color.to_le_bytes();
+    [low, high, 0, 0, 0, 0, 0, 0]
+}
+
+fn mip(bulk_data: FByteBulkData, size_x: i32, size_y: i32) -> FTexture2DMipMap {
+    FTexture2DMipMap {
+        cooked: true,
+        bulk_data,
+        size_x,
+        size_y,
+        size_z: 1,
+    }
+}
+
+/// Built texture asset and its `.ubulk` file
+type TextureAsset = (Asset<Cursor<Vec<u8>>>, Vec<u8>);
+
+/// Build a texture with an 8x8 BC1 mip stored in a `.ubulk` file, an inline 4x4 BC1 mip
+/// and a second inline BGRA8 platform data
+fn build_texture(
+    engine_version: EngineVersion,
+    use_event_driven_loader: bool,
+) -> Result<TextureAsset, Error> {
+    let mut builder = AssetBuilder::new(engine_version, "/Game/Textures/T_Test")
+        .use_event_driven_loader(use_event_driven_loader);
+    let class = builder.add_class_import("/Script/Engine", "Texture2D");
+    let normal_export = shared::build_normal_export(&mut builder, "T_Test", class);
+
+    let ubulk = [bc1_block(0x001f); 4].concat();
+    let streamed_mip = FByteBulkData {
+        flags: EBulkDataFlags::BULKDATA_PAYLOAD_AT_END_OF_FILE
+            | EBulkDataFlags::BULKDATA_PAYLOAD_IN_SEPERATE_FILE
+            | EBulkDataFlags::BULKDATA_FORCE_NOT_INLINE_PAYLOAD,
+        element_count: ubulk.len() as i64,
+        size_on_disk: ubulk.len() as i64,
+        offset: 0,
+        bad_data_version: 0,
+        duplicate: None,
+        data: None,
+    };
+
+    let dxt1 = FTexturePlatformData {
+        placeholder_derived_data: Default::default(),
+        size_x: 8,
+        size_y: 8,
+        packed_data: 1,
+        pixel_format: Some(String::from("PF_DXT1")),
+        opt_data: None,
+        first_mip_to_serialize: 0,
+        mips: vec![
+            mip(streamed_mip, 8, 8),
+            mip(FByteBulkData::new_inline(bc1_block(0xf800).to_vec()), 4, 4),
+        ],
+        is_virtual: false,
+    };
+    let bgra8 = FTexturePlatformData {
+        placeholder_derived_data: Default::default(),
+        size_x: 2,
+        size_y: 1,
+        packed_data: 1,
+        pixel_format: Some(String::from("PF_B8G8R8A8")),
+        opt_data: None,
+        first_mip_to_serialize: 0,
+        mips: vec![mip(
+            FByteBulkData::new_inline(vec![10, 20, 30, 40, 50, 60, 70, 80]),
+            2,
+            1,
+        )],
+        is_virtual: false,
+    };
+
+    let cooked_platform_data = vec![
+        (builder.add_fname("PF_DXT1"), dxt1),
+        (builder.add_fname("PF_B8G8R8A8"), bgra8),
+    ];
+    builder.add_export(Texture2DExport {
+        normal_export,
+        texture_strip_flags: FStripDataFlags::new(FStripDataFlags::EDITOR, 0),
+        texture_2d_strip_flags: FStripDataFlags::default(),
+        cooked: true,
+        cooked_platform_data,
+    });
+
+    Ok((builder.build()?, ubulk))
+}
+
+#[test]
+fn texture_2d() -> Result<(), Error> {
+    for (engine_version, use_event_driven_loader) in [
+        (EngineVersion::VER_UE4_18, false),
+        (EngineVersion::VER_UE4_25, true),
+        (EngineVersion::VER_UE5_1, true),
+    ] {
+        let (built, ubulk) = build_texture(engine_version, use_event_driven_loader)?;
+        let (data, bulk) = shared::write_asset(&built)?;
+
+        let mut parsed = Asset::new(
+            Cursor::new(data.as_slice()),
+            bulk.as_deref().map(Cursor::new),
+            engine_version,
+            None,
+        )?;
+        shared::verify_binary_equality(&data, bulk.as_deref(), &mut parsed)?;
+
+        let texture = cast!(Export, Texture2DExport, &parsed.asset_data.exports[0])
+            .expect("T_Test is not a texture")
+            .clone();
+        assert!(texture.normal_export.extras.is_empty());
+        assert_eq!(texture.cooked_platform_data.len(), 2);
+        assert_eq!(texture.cooked_platform_data[0].0, "PF_DXT1");
+
+        let dxt1 = texture.platform_data().expect("No platform data");
+        assert_eq!(dxt1.get_pixel_format()?, EPixelFormat::PF_DXT1);
+        assert_eq!(dxt1.num_slices(), 1);
+        assert!(!dxt1.is_cubemap());
+        assert!(dxt1.mips[0].bulk_data.is_in_separate_file());
+        assert!(dxt1.mips[1].bulk_data.is_inline());
+
+        let streamed = dxt1.mips[0]
+            .bulk_data
+            .read_payload(&mut Cursor::new(&ubulk), parsed.bulk_data_start_offset)?;
+        let image = dxt1.decode_mip(0, &streamed)?;
+        assert_eq!((image.width, image.height), (8, 8));
+        assert!(image.pixels.chunks_exact(4).all(|e| e == [0, 0, 255, 255]));
+
+        let bulk_data_start_offset = parsed.bulk_data_start_offset;
+        let inline = dxt1.mips[1]
+            .bulk_data
+            .read_payload(&mut parsed, bulk_data_start_offset)?;
+        let image = dxt1.decode_mip(1, &inline)?;
+        assert!(image.pixels.chunks_exact(4).all(|e| e == [255, 0, 0, 255]));
+
+        let bgra8 = &texture.cooked_platform_data[1].1;
+        let payload = bgra8.mips[0].bulk_data.data.as_deref().unwrap_or_default();
+        let image = bgra8.decode_mip(0, payload)?;
+        assert_eq!(image.pixels, [30, 20, 10, 40, 70, 60, 50, 80]);
+
+        let png = image.to_png()?;
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(&png[16..24], [0, 0, 0, 2, 0, 0, 0, 1]);
+        assert_eq!(&png[png.len() - 8..png.len() - 4], b"IEND");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn placeholder_derived_data() -> Result<(), Error> {
+    let placeholder = *b"derived data key";
+    for (engine_version, serialized) in [
+        (EngineVersion::VER_UE4_25, false),
+        (EngineVersion::VER_UE5_1, true),
+    ] {
+        let (mut asset, _) = build_texture(engine_version, true)?;
+        let texture = cast!(Export, Texture2DExport, &mut asset.asset_data.exports[0])
+            .expect("T_Test is not a texture");
+        texture
+            .platform_data_mut()
+            .expect("No platform data")
+            .placeholder_derived_data = placeholder;
+
+        let (data, bulk) = shared::write_asset(&asset)?;
+        // the placeholder comes right before SizeX
+        let uexp = bulk.as_deref().unwrap_or_default();
+        let position = uexp.windows(16).position(|e| e == placeholder);
+        assert_eq!(position.is_some(), serialized);
+        if let Some(position) = position {
+            assert_eq!(uexp[position + 16..position + 24], [8, 0, 0, 0, 8, 0, 0, 0]);
+        }
+
+        let mut parsed = Asset::new(
+            Cursor::new(data.as_slice()),
+            bulk.as_deref().map(Cursor::new),
+            engine_version,
+            None,
+        )?;
+        shared::verify_binary_equality(&data, bulk.as_deref(), &mut parsed)?;
+
+        let texture = cast!(Export, Texture2DExport, &parsed.asset_data.exports[0])
+            .expect("T_Test is not a texture");
+        let dxt1 = texture.platform_data().expect("No platform data");
+        assert_eq!(dxt1.placeholder_derived_data == placeholder, serialized);
+        assert_eq!(dxt1.size_x, 8);
+    }
+
+    Ok(())
+}
+
+/// 8x8 image with a red, green, blue and white quadrant
+fn quadrants() -> RgbaImage {
+    let mut pixels = Vec::new();
+    for y in 0..8 {
+        for x in 0..8 {
+            pixels.extend(match (x < 4, y < 4) {
+                (true, true) => [255, 0, 0, 255],
+                (false, true) => [0, 255, 0, 255],
+                (true, false) => [0, 0, 255, 255],
+                (false, false) => [255, 255, 255, 255],
+            });
+        }
+    }
+    RgbaImage::new(8, 8, pixels)
+}
+
+#[test]
+fn replace_image() -> Result<(), Error> {
+    for (engine_version, use_event_driven_loader) in [
+        (EngineVersion::VER_UE4_18, false),
+        (EngineVersion::VER_UE4_25, true),
+        (EngineVersion::VER_UE5_1, true),
+    ] {
+        let (mut asset, _) = build_texture(engine_version, use_event_driven_loader)?;
+        let image = quadrants();
+
+        let texture = cast!(Export, Texture2DExport, &mut asset.asset_data.exports[0])
+            .expect("T_Test is not a texture");
+        let ubulk = texture.set_image(&image)?;
+        // 8x8, 4x4 and 2x2 BC1 mips are streamed, the 1x1 mip stays inline
+        assert_eq!(ubulk.len(), 32 + 8 + 8);
+
+        let (data, bulk) = shared::write_asset(&asset)?;
+        let mut parsed = Asset::new(
+            Cursor::new(data.as_slice()),
+            bulk.as_deref().map(Cursor::new),
+            engine_version,
+            None,
+        )?;
+        shared::verify_binary_equality(&data, bulk.as_deref(), &mut parsed)?;
+
+        let texture = cast!(Export, Texture2DExport, &parsed.asset_data.exports[0])
+            .expect("T_Test is not a texture");
+        let dxt1 = &texture.cooked_platform_data[0].1;
+        let sizes = dxt1
+            .mips
+            .iter()
+            .map(|e| (e.size_x, e.size_y, e.bulk_data.is_in_separate_file()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            sizes,
+            [(8, 8, true), (4, 4, true), (2, 2, true), (1, 1, false)]
+        );
+        assert!(dxt1.mips[3].bulk_data.is_inline());
+
+        let payload = dxt1.mips[0]
+            .bulk_data
+            .read_payload(&mut Cursor::new(&ubulk), parsed.bulk_data_start_offset)?;
+        assert_eq!(dxt1.decode_mip(0, &payload)?, image);
+
+        // textures without mips don't get any
+        let bgra8 = &texture.cooked_platform_data[1].1;
+        assert_eq!((bgra8.size_x, bgra8.size_y), (8, 8));
+        assert_eq!(bgra8.mips.len(), 1);
+        let payload = bgra8.mips[0].bulk_data.data.as_deref().unwrap_or_default();
+        assert_eq!(bgra8.decode_mip(0, payload)?, image);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn encode_decode() -> Result<(), Error> {
+    // colors lie on a line so they can be represented by block endpoints
+    let mut pixels = Vec::new();
+    for y in 0..12u8 {
+        for x in 0..10u8 {
+            let t = x + y;
+            pixels.extend([t * 12, 250 - t * 10, t * 6, 255 - y * 8]);
+        }
+    }
+    let image = RgbaImage::new(10, 12, pixels);
+
+    for (pixel_format, channels, tolerance) in [
+        (EPixelFormat::PF_DXT1, 3, 16),
+        (EPixelFormat::PF_DXT3, 4, 16),
+        (EPixelFormat::PF_DXT5, 4, 16),
+        (EPixelFormat::PF_BC4, 1, 8),
+        (EPixelFormat::PF_BC5, 2, 8),
+        (EPixelFormat::PF_BC7, 4, 16),
+        (EPixelFormat::PF_B8G8R8A8, 4, 0),
+        (EPixelFormat::PF_R8G8B8A8, 4, 0),
+        (EPixelFormat::PF_FloatRGBA, 4, 0),
+    ] {
+        let data = texture::encode(pixel_format, &image)?;
+        assert_eq!(data.len(), pixel_format.mip_size(10, 12));
+
+        let decoded = texture::decode(pixel_format, 10, 12, &data)?;
+        for (expected, actual) in image.pixels.chunks(4).zip(decoded.pixels.chunks(4)) {
+            for channel in 0..channels {
+                let error = expected[channel].abs_diff(actual[channel]);
+                assert!(
+                    error <= tolerance,
+                    "{pixel_format} {expected:?} decoded as {actual:?}"
+                );
+            }
+        }
+    }
+
+    assert!(texture::encode(EPixelFormat::PF_BC6H, &image).is_err());
+    Ok(())
+}
+
+#[test]
+fn downsample() {
+    let mip = quadrants().downsample();
+    assert_eq!((mip.width, mip.height), (4, 4));
+    assert_eq!(&mip.pixels[..4], [255, 0, 0, 255]);
+
+    let mut mip = mip;
+    for _ in 0..3 {
+        mip = mip.downsample();
+    }
+    assert_eq!((mip.width, mip.height), (1, 1));
+    assert_eq!(mip.pixels, [128, 128, 128, 255]);
+}
+
+#[test]
+fn png() -> Result<(), Error> {
+    let image = quadrants();
+    assert_eq!(RgbaImage::from_png(&image.to_png()?)?, image);
+
+    // 3x2 RGB image using the sub and paeth filters
+    let png = [
+        0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a, 0x00, 0x00, 0x00, 0x0d, 0x49, 0x48, 0x44,
+        0x52, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x02, 0x08, 0x02, 0x00, 0x00, 0x00, 0x12,
+        0x16, 0xf1, 0x4d, 0x00, 0x00, 0x00, 0x1b, 0x49, 0x44, 0x41, 0x54, 0x78, 0x9c, 0x63, 0xe4,
+        0x12, 0x91, 0x03, 0x82, 0x05, 0x46, 0x47, 0x58, 0x58, 0x59, 0x59, 0xef, 0x9e, 0x38, 0x3d,
+        0x29, 0xcc, 0x0d, 0x00, 0x2c, 0x00, 0x05, 0xdf, 0x5c, 0x98, 0xd6, 0x6e, 0x00, 0x00, 0x00,
+        0x00, 0x49, 0x45, 0x4e, 0x44, 0xae, 0x42, 0x60, 0x82,
+    ];
+    let image = RgbaImage::from_png(&png)?;
+    assert_eq!((image.width, image.height), (3, 2));
+    assert_eq!(
+        image.pixels,
+        [
+            10, 20, 30, 255, 40, 50, 60, 255, 200, 100, 0, 255, 15, 25, 35, 255, 5, 250, 7, 255,
+            90, 80, 70, 255
+        ]
+    );
+
+    assert!(RgbaImage::from_png(&png[..40]).is_err());
+    Ok(())
+}
+
+#[test]
+fn bc7_mode_6() {
+    // mode 6 with red endpoints of 0, every other endpoint and p-bit set and all indices 0
+    let block = ((((1u128 << 65) - 1) & !((1u128 << 21) - 1)) | 0x40).to_le_bytes();
+    let pixels = bcn::decode_bc7(&block);
+    assert!(pixels.iter().all(|e| *e == [1, 255, 255, 255]));
+}
+
+#[test]
+fn invalid_mip_size() {
+    let data = FTexturePlatformData {
+        pixel_format: Some(String::from("PF_DXT5")),
+        mips: vec![mip(FByteBulkData::new_inline(vec![0; 16]), 8, 8)],
+        ..Default::default()
+    };
+    let payload = data.mips[0].bulk_data.data.clone().unwrap_or_default();
+    assert!(data.decode_mip(0, &payload).is_err());
+    assert!(data.decode_mip(1, &payload).is_err());
+}
+
+#[test]
+fn bulk_data_out_of_range() {
+    let bulk_data = FByteBulkData {
+        flags: EBulkDataFlags::BULKDATA_PAYLOAD_AT_END_OF_FILE,
+        element_count: 16,
+        size_on_disk: 16,
+        offset: i64::MAX - 8,
+        ..Default::default()
+    };
+    let mut file = Cursor::new(vec![0; 64]);
+    assert!(bulk_data.read_payload(&mut file, 0).is_err());
+    assert!(bulk_data.read_payload(&mut file, 16).is_err());
+}
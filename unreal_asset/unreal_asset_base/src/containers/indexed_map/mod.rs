@@ -855,3 +855,34 @@ where
         Self::from_iter(value)
     }
 }
+
+/// Serialized as a sequence of key-value pairs, this keeps the insertion order
+/// and allows keys which are not strings
+#[cfg(feature = "serde")]
+impl<K, V> serde::Serialize for IndexedMap<K, V>
+where
+    K: Eq + Hash + serde::Serialize,
+    V: serde::Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_seq(self.iter().map(|(_, key, value)| (key, value)))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, K, V> serde::Deserialize<'de> for IndexedMap<K, V>
+where
+    K: Eq + Hash + serde::Deserialize<'de>,
+    V: serde::Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let entries = Vec::<(K, V)>::deserialize(deserializer)?;
+        Ok(entries.into_iter().collect())
+    }
+}
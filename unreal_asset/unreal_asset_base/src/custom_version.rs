@@ -31,6 +31,47 @@ pub struct CustomVersion {
     pub version_mappings: &'static [(EngineVersion, i32)],
 }
 
+/// CustomVersion as it is stored by serde, the friendly name and version mappings
+/// are looked up again from the guid when deserializing
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename = "CustomVersion")]
+struct SerdeCustomVersion<'a> {
+    guid: Guid,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    friendly_name: Option<std::borrow::Cow<'a, str>>,
+    version: i32,
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for CustomVersion {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        SerdeCustomVersion {
+            guid: self.guid,
+            friendly_name: self
+                .friendly_name
+                .as_deref()
+                .map(std::borrow::Cow::Borrowed),
+            version: self.version,
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for CustomVersion {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let version = SerdeCustomVersion::deserialize(deserializer)?;
+        Ok(CustomVersion::new(version.guid, version.version))
+    }
+}
+
 type VersionInfo = (String, Option<&'static [(EngineVersion, i32)]>);
 
 #[rustfmt::skip]
@@ -13,6 +13,7 @@ use crate::object_version::{ObjectVersion, ObjectVersionUE5};
 #[derive(
     Debug, Hash, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, IntoPrimitive, TryFromPrimitive,
 )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i32)]
 #[allow(non_camel_case_types)]
 pub enum EngineVersion {
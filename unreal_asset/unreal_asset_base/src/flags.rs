@@ -5,6 +5,7 @@ use bitflags::bitflags;
 bitflags! {
     /// Object instance flags
     #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct EObjectFlags : u32
     {
         /// No flags
@@ -71,6 +72,7 @@ bitflags! {
 
     /// Package flags
     #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct EPackageFlags : u32
     {
         /// No flags
@@ -123,6 +125,7 @@ bitflags! {
 
     /// Property flags
     #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct EPropertyFlags : u64
     {
         /// None
@@ -230,6 +233,7 @@ bitflags! {
 
     /// Class flags
     #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct EClassFlags : u32
     {
         /// No Flags
@@ -302,6 +306,7 @@ bitflags! {
 
     /// Function flags
     #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct EFunctionFlags : u32 {
         /// None
         const FUNC_NONE = 0x00000000;
@@ -371,6 +376,7 @@ bitflags! {
 
     /// Asset registry dependency propety
     #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct EDependencyProperty : u32 {
         /// None
         const NONE = 0;
@@ -399,6 +405,7 @@ bitflags! {
 
     /// User defined struct flags
     #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct EStructFlags: u32{
         /// No flags
         const NO_FLAGS = 0x00000000;
@@ -472,6 +479,58 @@ bitflags! {
         /// Flags that are always computed; never loaded or done with code generation
         const COMPUTED_FLAGS = Self::NET_DELTA_SERIALIZE_NATIVE.bits() | Self::NET_SERIALIZE_NATIVE.bits() | Self::SERIALIZE_NATIVE.bits() | Self::POST_SERIALIZE_NATIVE.bits() | Self::COPY_NATIVE.bits() | Self::IS_PLAIN_OLD_DATA.bits() | Self::NO_DESTRUCTOR.bits() | Self::ZERO_CONSTRUCTOR.bits() | Self::IDENTICAL_NATIVE.bits() | Self::ADD_STRUCT_REFERENCED_OBJECTS.bits() | Self::EXPORT_TEXT_ITEM_NATIVE.bits() | Self::IMPORT_TEXT_ITEM_NATIVE.bits() | Self::SERIALIZE_FROM_MISMATCHED_TAG.bits() | Self::POST_SCRIPT_CONSTRUCT.bits() | Self::NET_SHARED_SERIALIZATION.bits();
     }
+
+    /// Bulk data flags
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct EBulkDataFlags : u32 {
+        /// No flags
+        const BULKDATA_NONE = 0x00000000;
+        /// If set, payload is stored at the end of the file and not inline
+        const BULKDATA_PAYLOAD_AT_END_OF_FILE = 0x00000001;
+        /// If set, payload should be [un]compressed using ZLIB during serialization
+        const BULKDATA_SERIALIZE_COMPRESSED_ZLIB = 0x00000002;
+        /// Force usage of SerializeElement over bulk serialization
+        const BULKDATA_FORCE_SINGLE_ELEMENT_SERIALIZATION = 0x00000004;
+        /// Bulk data is only used once at runtime in the game
+        const BULKDATA_SINGLE_USE = 0x00000008;
+        /// Bulk data won't be used and doesn't need to be loaded
+        const BULKDATA_UNUSED = 0x00000020;
+        /// Forces the payload to be saved inline, regardless of its size
+        const BULKDATA_FORCE_INLINE_PAYLOAD = 0x00000040;
+        /// Forces the payload to be always streamed, regardless of its size
+        const BULKDATA_FORCE_STREAM_PAYLOAD = 0x00000080;
+        /// If set, payload is stored in a separate file such as .ubulk
+        const BULKDATA_PAYLOAD_IN_SEPERATE_FILE = 0x00000100;
+        /// Bulk data was serialized compressed with a bit window
+        const BULKDATA_SERIALIZE_COMPRESSED_BIT_WINDOW = 0x00000200;
+        /// Forces the payload to be saved outside of the export data
+        const BULKDATA_FORCE_NOT_INLINE_PAYLOAD = 0x00000400;
+        /// This payload is optional and may not be on device
+        const BULKDATA_OPTIONAL_PAYLOAD = 0x00000800;
+        /// This payload will be memory mapped
+        const BULKDATA_MEMORY_MAPPED_PAYLOAD = 0x00001000;
+        /// Bulk data size is 64 bits long
+        const BULKDATA_SIZE_64_BIT = 0x00002000;
+        /// Duplicate non-optional payload in optional bulk data
+        const BULKDATA_DUPLICATE_NON_OPTIONAL_PAYLOAD = 0x00004000;
+        /// Indicates that an old ID is present in the data, at some point when the DDCs are flushed we can remove this
+        const BULKDATA_BAD_DATA_VERSION = 0x00008000;
+        /// Bulk data offset is not fixed up by the bulk data start offset of the package
+        const BULKDATA_NO_OFFSET_FIX_UP = 0x00010000;
+        /// Payload is stored in the workspace domain
+        const BULKDATA_WORKSPACE_DOMAIN_PAYLOAD = 0x00020000;
+        /// Payload can be lazy loaded
+        const BULKDATA_LAZY_LOADABLE = 0x00040000;
+        /// Bulk data can always be discarded
+        const BULKDATA_ALWAYS_ALLOW_DISCARD = 0x10000000;
+        /// Asynchronous read is pending
+        const BULKDATA_HAS_ASYNC_READ_PENDING = 0x20000000;
+        /// Data is memory mapped
+        const BULKDATA_DATA_IS_MEMORY_MAPPED = 0x40000000;
+        /// Bulk data is loaded through the IoDispatcher
+        const BULKDATA_USES_IO_DISPATCHER = 0x80000000;
+    }
 }
 
 impl Default for EObjectFlags {
@@ -509,3 +568,9 @@ impl Default for EStructFlags {
         Self::NO_FLAGS
     }
 }
+
+impl Default for EBulkDataFlags {
+    fn default() -> Self {
+        Self::BULKDATA_NONE
+    }
+}
@@ -42,6 +42,14 @@ pub trait ArchiveWriter<Index: PackageIndexTrait>: ArchiveTrait<Index> + Write {
         }
     }
 
+    /// Position of the cursor in the package
+    ///
+    /// This is different from [`ArchiveTrait::position`] when the archive
+    /// only writes a part of the package, like a `.uexp` file
+    fn package_position(&mut self) -> u64 {
+        self.position()
+    }
+
     /// Write an FString
     fn write_fstring(&mut self, value: Option<&str>) -> Result<usize, Error>;
     /// Write a guid.
@@ -0,0 +1,294 @@
+//! Bulk data
+
+use std::io::{Read, Seek, SeekFrom};
+
+use byteorder::{ReadBytesExt, WriteBytesExt, LE};
+
+use crate::{
+    error::Error,
+    flags::EBulkDataFlags,
+    object_version::ObjectVersionUE5,
+    reader::{ArchiveReader, ArchiveWriter},
+};
+
+use super::PackageIndexTrait;
+
+/// Location of a duplicate of a bulk data payload
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FBulkDataDuplicate {
+    /// Duplicate payload flags
+    pub flags: EBulkDataFlags,
+    /// Duplicate payload size on disk
+    pub size_on_disk: i64,
+    /// Duplicate payload offset
+    pub offset: i64,
+}
+
+/// Bulk data of bytes
+///
+/// The payload is only read together with the header when it is stored inline,
+/// other payloads can be read with [`FByteBulkData::read_payload`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FByteBulkData {
+    /// Bulk data flags
+    pub flags: EBulkDataFlags,
+    /// Element count, this is the uncompressed payload size
+    pub element_count: i64,
+    /// Payload size on disk
+    pub size_on_disk: i64,
+    /// Payload offset
+    ///
+    /// For payloads at the end of the package this is relative to the package bulk data start offset,
+    /// unless `BULKDATA_NO_OFFSET_FIX_UP` is set.
+    /// Offsets of inline payloads are updated when writing.
+    pub offset: i64,
+    /// Value stored after the header when `BULKDATA_BAD_DATA_VERSION` is set
+    pub bad_data_version: u16,
+    /// Duplicate of a non-optional payload
+    pub duplicate: Option<FBulkDataDuplicate>,
+    /// Inline payload
+    pub data: Option<Vec<u8>>,
+}
+
+impl FByteBulkData {
+    /// Create a new `FByteBulkData` instance with an inline payload
+    pub fn new_inline(data: Vec<u8>) -> Self {
+        FByteBulkData {
+            flags: EBulkDataFlags::BULKDATA_FORCE_INLINE_PAYLOAD
+                | EBulkDataFlags::BULKDATA_SINGLE_USE,
+            element_count: data.len() as i64,
+            size_on_disk: data.len() as i64,
+            offset: 0,
+            bad_data_version: 0,
+            duplicate: None,
+            data: Some(data),
+        }
+    }
+
+    /// Read an `FByteBulkData` from an asset
+    pub fn new<Reader: ArchiveReader<impl PackageIndexTrait>>(
+        asset: &mut Reader,
+    ) -> Result<Self, Error> {
+        if asset.get_object_version_ue5() >= ObjectVersionUE5::DATA_RESOURCES {
+            return Err(Error::unimplemented(
+                "Bulk data stored in data resources is not supported".to_string(),
+            ));
+        }
+
+        let flags = EBulkDataFlags::from_bits_retain(asset.read_u32::<LE>()?);
+        let element_count = Self::read_size(asset, flags)?;
+        let size_on_disk = Self::read_size(asset, flags)?;
+        let offset = asset.read_i64::<LE>()?;
+
+        let mut bad_data_version = 0;
+        if flags.contains(EBulkDataFlags::BULKDATA_BAD_DATA_VERSION) {
+            bad_data_version = asset.read_u16::<LE>()?;
+        }
+
+        let mut duplicate = None;
+        let mut data = None;
+        if flags.contains(EBulkDataFlags::BULKDATA_FORCE_INLINE_PAYLOAD) {
+            let remaining = asset.data_length()? as i64 - asset.position() as i64;
+            if size_on_disk < 0 || size_on_disk > remaining {
+                return Err(Error::invalid_file(format!(
+                    "Inline bulk data size {size_on_disk} is out of range"
+                )));
+            }
+
+            let mut payload = vec![0u8; size_on_disk as usize];
+            asset.read_exact(&mut payload)?;
+            data = Some(payload);
+        } else if flags.contains(EBulkDataFlags::BULKDATA_DUPLICATE_NON_OPTIONAL_PAYLOAD) {
+            let flags = EBulkDataFlags::from_bits_retain(asset.read_u32::<LE>()?);
+            let size_on_disk = Self::read_size(asset, flags)?;
+            let offset = asset.read_i64::<LE>()?;
+            duplicate = Some(FBulkDataDuplicate {
+                flags,
+                size_on_disk,
+                offset,
+            });
+        }
+
+        Ok(FByteBulkData {
+            flags,
+            element_count,
+            size_on_disk,
+            offset,
+            bad_data_version,
+            duplicate,
+            data,
+        })
+    }
+
+    /// Write an `FByteBulkData` to an asset
+    pub fn write<Writer: ArchiveWriter<impl PackageIndexTrait>>(
+        &self,
+        asset: &mut Writer,
+    ) -> Result<(), Error> {
+        let (element_count, size_on_disk) = match &self.data {
+            Some(data) if self.is_inline() => (
+                match self.is_compressed() {
+                    true => self.element_count,
+                    false => data.len() as i64,
+                },
+                data.len() as i64,
+            ),
+            _ => (self.element_count, self.size_on_disk),
+        };
+
+        asset.write_u32::<LE>(self.flags.bits())?;
+        Self::write_size(asset, self.flags, element_count)?;
+        Self::write_size(asset, self.flags, size_on_disk)?;
+
+        let offset = match self.is_inline() {
+            true => {
+                let mut header_end = asset.package_position() + 8;
+                if self
+                    .flags
+                    .contains(EBulkDataFlags::BULKDATA_BAD_DATA_VERSION)
+                {
+                    header_end += 2;
+                }
+                header_end as i64
+            }
+            false => self.offset,
+        };
+        asset.write_i64::<LE>(offset)?;
+
+        if self
+            .flags
+            .contains(EBulkDataFlags::BULKDATA_BAD_DATA_VERSION)
+        {
+            asset.write_u16::<LE>(self.bad_data_version)?;
+        }
+
+        if self.is_inline() {
+            let data = self
+                .data
+                .as_ref()
+                .ok_or_else(|| Error::no_data("Inline bulk data has no payload".to_string()))?;
+            asset.write_all(data)?;
+        } else if let Some(duplicate) = &self.duplicate {
+            asset.write_u32::<LE>(duplicate.flags.bits())?;
+            Self::write_size(asset, duplicate.flags, duplicate.size_on_disk)?;
+            asset.write_i64::<LE>(duplicate.offset)?;
+        }
+
+        Ok(())
+    }
+
+    /// Check if the payload is stored inline
+    pub fn is_inline(&self) -> bool {
+        self.flags
+            .contains(EBulkDataFlags::BULKDATA_FORCE_INLINE_PAYLOAD)
+    }
+
+    /// Check if the payload is stored in a separate file, like `.ubulk`
+    pub fn is_in_separate_file(&self) -> bool {
+        self.flags
+            .contains(EBulkDataFlags::BULKDATA_PAYLOAD_IN_SEPERATE_FILE)
+    }
+
+    /// Check if the payload is stored in an optional file, like `.uptnl`
+    pub fn is_optional(&self) -> bool {
+        self.flags
+            .contains(EBulkDataFlags::BULKDATA_OPTIONAL_PAYLOAD)
+    }
+
+    /// Check if the payload is compressed
+    pub fn is_compressed(&self) -> bool {
+        self.flags
+            .contains(EBulkDataFlags::BULKDATA_SERIALIZE_COMPRESSED_ZLIB)
+    }
+
+    /// Get the payload offset in the file that contains it
+    ///
+    /// # Arguments
+    ///
+    /// * `bulk_data_start_offset` - bulk data start offset of the package
+    pub fn payload_offset(&self, bulk_data_start_offset: i64) -> Result<i64, Error> {
+        match self.is_in_separate_file()
+            || self
+                .flags
+                .contains(EBulkDataFlags::BULKDATA_NO_OFFSET_FIX_UP)
+        {
+            true => Ok(self.offset),
+            false => self
+                .offset
+                .checked_add(bulk_data_start_offset)
+                .ok_or_else(|| {
+                    Error::invalid_file(format!("Bulk data offset {} is out of range", self.offset))
+                }),
+        }
+    }
+
+    /// Read the payload
+    ///
+    /// Inline payloads are returned as is, other payloads are read from `file`,
+    /// which is either the package (`.uasset` followed by `.uexp`)
+    /// or a separate file like `.ubulk`.
+    ///
+    /// # Arguments
+    ///
+    /// * `file` - file that contains the payload
+    /// * `bulk_data_start_offset` - bulk data start offset of the package
+    pub fn read_payload<R: Read + Seek>(
+        &self,
+        file: &mut R,
+        bulk_data_start_offset: i64,
+    ) -> Result<Vec<u8>, Error> {
+        if self.is_compressed() {
+            return Err(Error::unimplemented(
+                "Compressed bulk data is not supported".to_string(),
+            ));
+        }
+
+        if let Some(data) = &self.data {
+            return Ok(data.clone());
+        }
+
+        let offset = self.payload_offset(bulk_data_start_offset)?;
+        let file_length = file.seek(SeekFrom::End(0))?;
+        let end = offset.checked_add(self.size_on_disk);
+        if offset < 0
+            || self.size_on_disk < 0
+            || !matches!(end, Some(end) if end <= file_length as i64)
+        {
+            return Err(Error::invalid_file(format!(
+                "Bulk data at offset {offset} with size {} is out of range",
+                self.size_on_disk
+            )));
+        }
+
+        file.seek(SeekFrom::Start(offset as u64))?;
+        let mut data = vec![0u8; self.size_on_disk as usize];
+        file.read_exact(&mut data)?;
+        Ok(data)
+    }
+
+    /// Read a bulk data size
+    fn read_size<Reader: ArchiveReader<impl PackageIndexTrait>>(
+        asset: &mut Reader,
+        flags: EBulkDataFlags,
+    ) -> Result<i64, Error> {
+        Ok(match flags.contains(EBulkDataFlags::BULKDATA_SIZE_64_BIT) {
+            true => asset.read_i64::<LE>()?,
+            false => asset.read_u32::<LE>()? as i64,
+        })
+    }
+
+    /// Write a bulk data size
+    fn write_size<Writer: ArchiveWriter<impl PackageIndexTrait>>(
+        asset: &mut Writer,
+        flags: EBulkDataFlags,
+        size: i64,
+    ) -> Result<(), Error> {
+        match flags.contains(EBulkDataFlags::BULKDATA_SIZE_64_BIT) {
+            true => asset.write_i64::<LE>(size)?,
+            false => asset.write_u32::<LE>(size as u32)?,
+        }
+        Ok(())
+    }
+}
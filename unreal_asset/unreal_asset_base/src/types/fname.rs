@@ -231,6 +231,78 @@ impl Default for FName {
     }
 }
 
+#[cfg(feature = "serde")]
+thread_local! {
+    /// Name map which backed FNames are added to when deserializing
+    static DESERIALIZED_NAME_MAP: std::cell::RefCell<SharedResource<NameMap>> =
+        std::cell::RefCell::new(NameMap::new());
+}
+
+#[cfg(feature = "serde")]
+impl FName {
+    /// Run `deserialize` with a new name map which backed `FName`s are added to when deserializing
+    ///
+    /// This keeps the name map from growing across deserialized assets,
+    /// the `FName`s still have to be moved to the name map they are written with,
+    /// e.g. with `Asset::rebuild_name_map`
+    pub fn deserialize_with_name_map<T>(deserialize: impl FnOnce() -> T) -> T {
+        let previous = DESERIALIZED_NAME_MAP.with(|name_map| name_map.replace(NameMap::new()));
+        let result = deserialize();
+        DESERIALIZED_NAME_MAP.with(|name_map| name_map.replace(previous));
+        result
+    }
+}
+
+/// FName as it is stored by serde
+///
+/// Only the value is stored instead of the name map index,
+/// dummy FNames are marked so that they don't get added to a name map when deserializing
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename = "FName")]
+struct SerdeFName<T> {
+    value: T,
+    number: i32,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    dummy: bool,
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for FName {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.get_content(|value| {
+            SerdeFName {
+                value,
+                number: self.get_number(),
+                dummy: matches!(self, FName::Dummy { .. }),
+            }
+            .serialize(serializer)
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for FName {
+    /// Backed FNames are deserialized into a separate name map,
+    /// see [`FName::deserialize_with_name_map`]
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let name = SerdeFName::<String>::deserialize(deserializer)?;
+        if name.dummy {
+            return Ok(FName::new_dummy(name.value, name.number));
+        }
+
+        let mut name_map = DESERIALIZED_NAME_MAP.with(|name_map| name_map.borrow().clone());
+        let index = name_map.get_mut().add_name_reference(name.value, false);
+        Ok(FName::new(index, name.number, name_map))
+    }
+}
+
 impl std::cmp::PartialEq<str> for FName {
     fn eq(&self, other: &str) -> bool {
         self.get_content(|name| name == other)
@@ -1,10 +1,12 @@
 //! Unreal types
 
+pub mod bulk_data;
 pub mod fname;
 use byteorder::{ReadBytesExt, WriteBytesExt};
 pub use fname::FName;
 
 pub mod movie;
+pub mod strip_data_flags;
 pub mod vector;
 
 use std::hash::Hash;
@@ -74,6 +76,8 @@ pub trait PackageIndexTrait: std::fmt::Debug + Copy + Clone + PartialEq + Eq + T
 ///
 /// When PackageIndex is 0 it makes for a non-existent link.
 #[derive(Debug, Hash, Copy, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct PackageIndex {
     /// Index
     pub index: i32,
@@ -134,7 +138,8 @@ impl std::fmt::Display for PackageIndex {
 // }
 
 /// Asset generation info
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GenerationInfo {
     /// Export count
     pub export_count: i32,
@@ -0,0 +1,73 @@
+//! Strip data flags
+
+use byteorder::{ReadBytesExt, WriteBytesExt};
+
+use crate::{
+    error::Error,
+    reader::{ArchiveReader, ArchiveWriter},
+};
+
+use super::PackageIndexTrait;
+
+/// Flags that describe which data was stripped from an object when it was saved
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FStripDataFlags {
+    /// Global strip flags
+    pub global_strip_flags: u8,
+    /// Class specific strip flags
+    pub class_strip_flags: u8,
+}
+
+impl FStripDataFlags {
+    /// Editor data was stripped
+    pub const EDITOR: u8 = 1;
+    /// Data that is not needed on dedicated servers was stripped
+    pub const SERVER: u8 = 2;
+
+    /// Create a new `FStripDataFlags` instance
+    pub fn new(global_strip_flags: u8, class_strip_flags: u8) -> Self {
+        FStripDataFlags {
+            global_strip_flags,
+            class_strip_flags,
+        }
+    }
+
+    /// Read `FStripDataFlags` from an asset
+    pub fn read<Reader: ArchiveReader<impl PackageIndexTrait>>(
+        asset: &mut Reader,
+    ) -> Result<Self, Error> {
+        let global_strip_flags = asset.read_u8()?;
+        let class_strip_flags = asset.read_u8()?;
+
+        Ok(FStripDataFlags {
+            global_strip_flags,
+            class_strip_flags,
+        })
+    }
+
+    /// Write `FStripDataFlags` to an asset
+    pub fn write<Writer: ArchiveWriter<impl PackageIndexTrait>>(
+        &self,
+        asset: &mut Writer,
+    ) -> Result<(), Error> {
+        asset.write_u8(self.global_strip_flags)?;
+        asset.write_u8(self.class_strip_flags)?;
+        Ok(())
+    }
+
+    /// Check if editor data was stripped
+    pub fn is_editor_data_stripped(&self) -> bool {
+        self.global_strip_flags & Self::EDITOR != 0
+    }
+
+    /// Check if data that is not needed on dedicated servers was stripped
+    pub fn is_data_stripped_for_server(&self) -> bool {
+        self.global_strip_flags & Self::SERVER != 0
+    }
+
+    /// Check if class specific data was stripped
+    pub fn is_class_data_stripped(&self, flag: u8) -> bool {
+        self.class_strip_flags & flag != 0
+    }
+}
@@ -16,6 +16,7 @@ use crate::{ExportBaseTrait, ExportNormalTrait, ExportTrait};
 
 /// Export filter flags
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, TryFromPrimitive, IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum EExportFilterFlags {
     /// None
@@ -28,6 +29,7 @@ pub enum EExportFilterFlags {
 
 /// Minimal information about an export
 #[derive(FNameContainer, Debug, Default, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BaseExport<Index: PackageIndexTrait> {
     /// Class index
     #[container_ignore]
@@ -19,6 +19,7 @@ use crate::{ExportBaseTrait, ExportNormalTrait, ExportTrait};
 
 /// Serialized interface reference
 #[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SerializedInterfaceReference {
     /// Class
     pub class: PackageIndex,
@@ -41,6 +42,7 @@ impl SerializedInterfaceReference {
 
 /// Class export
 #[derive(FNameContainer, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ClassExport<Index: PackageIndexTrait> {
     /// Base struct export
     pub struct_export: StructExport<Index>,
@@ -15,11 +15,15 @@ pub mod data_table_export;
 pub mod enum_export;
 pub mod function_export;
 pub mod level_export;
+pub mod mesh;
 pub mod normal_export;
 pub mod property_export;
 pub mod raw_export;
+pub mod static_mesh_export;
 pub mod string_table_export;
 pub mod struct_export;
+pub mod texture;
+pub mod texture_2d_export;
 pub mod user_defined_struct_export;
 pub mod world_export;
 
@@ -27,7 +31,8 @@ pub use self::{
     base_export::BaseExport, class_export::ClassExport, data_table_export::DataTableExport,
     enum_export::EnumExport, function_export::FunctionExport, level_export::LevelExport,
     normal_export::NormalExport, property_export::PropertyExport, raw_export::RawExport,
-    string_table_export::StringTableExport, struct_export::StructExport,
+    static_mesh_export::StaticMeshExport, string_table_export::StringTableExport,
+    struct_export::StructExport, texture_2d_export::Texture2DExport,
     user_defined_struct_export::UserDefinedStructExport, world_export::WorldExport,
 };
 
@@ -87,6 +92,7 @@ pub trait ExportTrait<Index: PackageIndexTrait>: Debug + Clone + PartialEq + Eq
 
 /// Export
 #[derive(FNameContainer, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[container_nobounds]
 pub enum Export<Index: PackageIndexTrait> {
     /// Base export
@@ -115,6 +121,10 @@ pub enum Export<Index: PackageIndexTrait> {
     DataTableExport(DataTableExport<Index>),
     /// World export
     WorldExport(WorldExport<Index>),
+    /// Texture2D export
+    Texture2DExport(Texture2DExport<Index>),
+    /// StaticMesh export
+    StaticMeshExport(StaticMeshExport<Index>),
 }
 
 /// Macro to mimic `enum_dispatch` functionality because we need generics in traits
@@ -189,7 +199,9 @@ manual_dispatch! {
     UserDefinedStructExport,
     FunctionExport,
     DataTableExport,
-    WorldExport
+    WorldExport,
+    Texture2DExport,
+    StaticMeshExport
 }
 
 // todo: impl hash for export
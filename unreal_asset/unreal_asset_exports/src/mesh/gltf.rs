@@ -0,0 +1,295 @@
+//! Minimal binary glTF writer
+
+use std::fmt::Write;
+
+use unreal_asset_base::Error;
+
+use super::{to_right_handed, Mesh};
+
+/// Binary glTF magic
+const GLB_MAGIC: &[u8; 4] = b"glTF";
+/// Binary glTF container version
+const GLB_VERSION: u32 = 2;
+/// JSON chunk type
+const CHUNK_JSON: &[u8; 4] = b"JSON";
+/// Binary chunk type
+const CHUNK_BIN: &[u8; 4] = b"BIN\0";
+
+/// `UNSIGNED_BYTE` component type
+const UNSIGNED_BYTE: u32 = 5121;
+/// `UNSIGNED_INT` component type
+const UNSIGNED_INT: u32 = 5125;
+/// `FLOAT` component type
+const FLOAT: u32 = 5126;
+
+/// Vertex attribute buffer view target
+const ARRAY_BUFFER: u32 = 34962;
+/// Index buffer view target
+const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+
+/// glTF uses meters, unreal uses centimeters
+const CENTIMETERS_TO_METERS: f32 = 0.01;
+
+/// Quote and escape a JSON string
+fn json_string(value: &str) -> String {
+    let mut string = String::with_capacity(value.len() + 2);
+    string.push('"');
+    for character in value.chars() {
+        match character {
+            '"' => string.push_str("\\\""),
+            '\\' => string.push_str("\\\\"),
+            character if (character as u32) < 0x20 => {
+                let _ = write!(string, "\\u{:04x}", character as u32);
+            }
+            character => string.push(character),
+        }
+    }
+    string.push('"');
+    string
+}
+
+/// Format a JSON number, JSON can't represent infinity or NaN
+fn json_number(value: f32) -> String {
+    match value.is_finite() {
+        true => format!("{value:?}"),
+        false => "0".to_string(),
+    }
+}
+
+/// Normalize a direction, zero length directions are replaced with a fallback
+fn normalize(direction: [f32; 3], fallback: [f32; 3]) -> [f32; 3] {
+    let length = direction.iter().map(|e| e * e).sum::<f32>().sqrt();
+    match length > f32::EPSILON && length.is_finite() {
+        true => direction.map(|e| e / length),
+        false => fallback,
+    }
+}
+
+/// Buffer views and accessors of a single buffer
+#[derive(Default)]
+struct BufferBuilder {
+    /// Buffer contents
+    data: Vec<u8>,
+    /// Buffer view JSON objects
+    buffer_views: Vec<String>,
+    /// Accessor JSON objects
+    accessors: Vec<String>,
+}
+
+impl BufferBuilder {
+    /// Add a buffer view, returns its index
+    fn add_buffer_view(&mut self, data: &[u8], target: u32) -> usize {
+        let offset = self.data.len();
+        self.data.extend_from_slice(data);
+        // every accessor needs to be aligned to its component size
+        self.data.resize(self.data.len().next_multiple_of(4), 0);
+
+        self.buffer_views.push(format!(
+            r#"{{"buffer":0,"byteOffset":{offset},"byteLength":{},"target":{target}}}"#,
+            data.len()
+        ));
+        self.buffer_views.len() - 1
+    }
+
+    /// Add an accessor, returns its index
+    fn add_accessor(&mut self, buffer_view: usize, byte_offset: usize, accessor: &str) -> usize {
+        self.accessors.push(format!(
+            r#"{{"bufferView":{buffer_view},"byteOffset":{byte_offset},{accessor}}}"#
+        ));
+        self.accessors.len() - 1
+    }
+
+    /// Add a buffer view of float vectors with an accessor, returns the accessor index
+    fn add_float_attribute<const N: usize>(&mut self, values: &[[f32; N]], ty: &str) -> usize {
+        let data = values
+            .iter()
+            .flatten()
+            .flat_map(|e| e.to_le_bytes())
+            .collect::<Vec<_>>();
+        let buffer_view = self.add_buffer_view(&data, ARRAY_BUFFER);
+        self.add_accessor(
+            buffer_view,
+            0,
+            &format!(
+                r#""componentType":{FLOAT},"count":{},"type":"{ty}""#,
+                values.len()
+            ),
+        )
+    }
+}
+
+/// Encode a mesh as a binary glTF file
+///
+/// The mesh is converted to glTF's right handed Y up coordinates in meters,
+/// every section becomes a primitive that references a material named after its slot
+pub fn encode_glb(mesh: &Mesh) -> Result<Vec<u8>, Error> {
+    mesh.validate()?;
+    if mesh.positions.is_empty() {
+        return Err(Error::no_data("Mesh has no vertices".to_string()));
+    }
+
+    let mut buffer = BufferBuilder::default();
+    let mut attributes = Vec::new();
+
+    let positions = mesh
+        .positions
+        .iter()
+        .map(|e| to_right_handed(*e).map(|e| e * CENTIMETERS_TO_METERS))
+        .collect::<Vec<_>>();
+    let (mut min, mut max) = ([f32::MAX; 3], [f32::MIN; 3]);
+    for position in &positions {
+        for i in 0..3 {
+            min[i] = min[i].min(position[i]);
+            max[i] = max[i].max(position[i]);
+        }
+    }
+    let data = positions
+        .iter()
+        .flatten()
+        .flat_map(|e| e.to_le_bytes())
+        .collect::<Vec<_>>();
+    let buffer_view = buffer.add_buffer_view(&data, ARRAY_BUFFER);
+    let accessor = buffer.add_accessor(
+        buffer_view,
+        0,
+        &format!(
+            r#""componentType":{FLOAT},"count":{},"type":"VEC3","min":[{}],"max":[{}]"#,
+            positions.len(),
+            min.map(json_number).join(","),
+            max.map(json_number).join(",")
+        ),
+    );
+    attributes.push(format!(r#""POSITION":{accessor}"#));
+
+    if !mesh.normals.is_empty() {
+        let normals = mesh
+            .normals
+            .iter()
+            .map(|e| normalize(to_right_handed(*e), [0.0, 1.0, 0.0]))
+            .collect::<Vec<_>>();
+        let accessor = buffer.add_float_attribute(&normals, "VEC3");
+        attributes.push(format!(r#""NORMAL":{accessor}"#));
+    }
+
+    if !mesh.tangents.is_empty() {
+        // mirroring the mesh flips the handedness of the tangent basis
+        let tangents = mesh
+            .tangents
+            .iter()
+            .map(|[x, y, z, w]| {
+                let [x, y, z] = normalize(to_right_handed([*x, *y, *z]), [1.0, 0.0, 0.0]);
+                let sign = match *w < 0.0 {
+                    true => 1.0,
+                    false => -1.0,
+                };
+                [x, y, z, sign]
+            })
+            .collect::<Vec<_>>();
+        let accessor = buffer.add_float_attribute(&tangents, "VEC4");
+        attributes.push(format!(r#""TANGENT":{accessor}"#));
+    }
+
+    let tex_coords = mesh.tex_coords.iter().filter(|e| !e.is_empty());
+    for (channel, tex_coords) in tex_coords.enumerate() {
+        let accessor = buffer.add_float_attribute(tex_coords, "VEC2");
+        attributes.push(format!(r#""TEXCOORD_{channel}":{accessor}"#));
+    }
+
+    if !mesh.colors.is_empty() {
+        let buffer_view = buffer.add_buffer_view(mesh.colors.concat().as_slice(), ARRAY_BUFFER);
+        let accessor = buffer.add_accessor(
+            buffer_view,
+            0,
+            &format!(
+                r#""componentType":{UNSIGNED_BYTE},"normalized":true,"count":{},"type":"VEC4""#,
+                mesh.colors.len()
+            ),
+        );
+        attributes.push(format!(r#""COLOR_0":{accessor}"#));
+    }
+    let attributes = attributes.join(",");
+
+    let data = mesh
+        .indices
+        .iter()
+        .flat_map(|e| e.to_le_bytes())
+        .collect::<Vec<_>>();
+    let index_buffer_view = buffer.add_buffer_view(&data, ELEMENT_ARRAY_BUFFER);
+
+    let mut materials = Vec::new();
+    let mut primitives = Vec::new();
+    for section in mesh.sections() {
+        // accessors can't be empty
+        if section.num_triangles == 0 {
+            continue;
+        }
+
+        let accessor = buffer.add_accessor(
+            index_buffer_view,
+            section.first_index as usize * 4,
+            &format!(
+                r#""componentType":{UNSIGNED_INT},"count":{},"type":"SCALAR""#,
+                section.num_triangles as usize * 3
+            ),
+        );
+
+        let mut primitive = format!(r#"{{"attributes":{{{attributes}}},"indices":{accessor}"#);
+        if let Some(material_name) = section.material_name {
+            let material = match materials.iter().position(|e| *e == material_name) {
+                Some(material) => material,
+                None => {
+                    materials.push(material_name);
+                    materials.len() - 1
+                }
+            };
+            let _ = write!(primitive, r#","material":{material}"#);
+        }
+        primitive.push('}');
+        primitives.push(primitive);
+    }
+    if primitives.is_empty() {
+        return Err(Error::no_data("Mesh has no triangles".to_string()));
+    }
+
+    let materials = materials
+        .iter()
+        .map(|e| format!(r#"{{"name":{}}}"#, json_string(e)))
+        .collect::<Vec<_>>();
+
+    let mut json = format!(
+        concat!(
+            r#"{{"asset":{{"version":"2.0","generator":"unreal_asset"}},"#,
+            r#""scene":0,"scenes":[{{"nodes":[0]}}],"nodes":[{{"mesh":0}}],"#,
+            r#""meshes":[{{"primitives":[{}]}}],"#,
+            r#""buffers":[{{"byteLength":{}}}],"bufferViews":[{}],"accessors":[{}]"#
+        ),
+        primitives.join(","),
+        buffer.data.len(),
+        buffer.buffer_views.join(","),
+        buffer.accessors.join(",")
+    );
+    if !materials.is_empty() {
+        let _ = write!(json, r#","materials":[{}]"#, materials.join(","));
+    }
+    json.push('}');
+
+    // the JSON chunk is padded with spaces, the binary chunk with zeros
+    let mut json = json.into_bytes();
+    json.resize(json.len().next_multiple_of(4), b' ');
+
+    let length = 12 + 8 + json.len() + 8 + buffer.data.len();
+    let mut glb = Vec::with_capacity(length);
+    glb.extend_from_slice(GLB_MAGIC);
+    glb.extend_from_slice(&GLB_VERSION.to_le_bytes());
+    glb.extend_from_slice(&(length as u32).to_le_bytes());
+
+    glb.extend_from_slice(&(json.len() as u32).to_le_bytes());
+    glb.extend_from_slice(CHUNK_JSON);
+    glb.extend_from_slice(&json);
+
+    glb.extend_from_slice(&(buffer.data.len() as u32).to_le_bytes());
+    glb.extend_from_slice(CHUNK_BIN);
+    glb.extend_from_slice(&buffer.data);
+
+    Ok(glb)
+}
@@ -0,0 +1,109 @@
+//! Mesh exporting
+
+use unreal_asset_base::Error;
+
+pub mod gltf;
+pub mod obj;
+
+/// Range of a mesh index buffer that is drawn with a single material
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct MeshSection {
+    /// Material slot name
+    pub material_name: Option<String>,
+    /// First index of the section
+    pub first_index: u32,
+    /// Triangle count
+    pub num_triangles: u32,
+}
+
+/// Triangle mesh in unreal coordinates, which are left handed, Z up and in centimeters
+///
+/// Vertex attributes are either empty or have an entry for every position
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Mesh {
+    /// Vertex positions
+    pub positions: Vec<[f32; 3]>,
+    /// Vertex normals
+    pub normals: Vec<[f32; 3]>,
+    /// Vertex tangents, `w` stores the sign of the bitangent
+    pub tangents: Vec<[f32; 4]>,
+    /// Texture coordinates of each UV channel
+    pub tex_coords: Vec<Vec<[f32; 2]>>,
+    /// Vertex colors in RGBA order
+    pub colors: Vec<[u8; 4]>,
+    /// Triangle indices
+    pub indices: Vec<u32>,
+    /// Sections, if there are none the whole mesh is a single section
+    pub sections: Vec<MeshSection>,
+}
+
+impl Mesh {
+    /// Encode this mesh as a binary glTF file
+    pub fn to_gltf(&self) -> Result<Vec<u8>, Error> {
+        gltf::encode_glb(self)
+    }
+
+    /// Encode this mesh as a Wavefront OBJ file
+    pub fn to_obj(&self) -> Result<String, Error> {
+        obj::encode_obj(self)
+    }
+
+    /// Get the sections of this mesh, covering the whole index buffer if there are no sections
+    fn sections(&self) -> Vec<MeshSection> {
+        match self.sections.is_empty() {
+            true => vec![MeshSection {
+                material_name: None,
+                first_index: 0,
+                num_triangles: (self.indices.len() / 3) as u32,
+            }],
+            false => self.sections.clone(),
+        }
+    }
+
+    /// Check that attributes, indices and sections are in range
+    fn validate(&self) -> Result<(), Error> {
+        let vertex_count = self.positions.len();
+        let attribute_lengths = [
+            ("normals", self.normals.len()),
+            ("tangents", self.tangents.len()),
+            ("colors", self.colors.len()),
+        ]
+        .into_iter()
+        .chain(
+            self.tex_coords
+                .iter()
+                .map(|e| ("texture coordinates", e.len())),
+        );
+        for (attribute, length) in attribute_lengths {
+            if length != 0 && length != vertex_count {
+                return Err(Error::invalid_file(format!(
+                    "Mesh has {vertex_count} vertices but {length} {attribute}"
+                )));
+            }
+        }
+
+        if let Some(index) = self.indices.iter().find(|e| **e as usize >= vertex_count) {
+            return Err(Error::invalid_file(format!(
+                "Mesh index {index} is out of range for {vertex_count} vertices"
+            )));
+        }
+
+        for section in &self.sections {
+            let end = section.first_index as u64 + section.num_triangles as u64 * 3;
+            if end > self.indices.len() as u64 {
+                return Err(Error::invalid_file(format!(
+                    "Mesh section ends at index {end}, but there are only {} indices",
+                    self.indices.len()
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Convert a direction from unreal coordinates to right handed Y up coordinates
+///
+/// This mirrors the mesh, which also turns clockwise front faces into counter-clockwise ones
+fn to_right_handed([x, y, z]: [f32; 3]) -> [f32; 3] {
+    [x, z, y]
+}
@@ -0,0 +1,61 @@
+//! Wavefront OBJ writer
+
+use std::fmt::Write;
+
+use unreal_asset_base::Error;
+
+use super::{to_right_handed, Mesh};
+
+/// Encode a mesh as a Wavefront OBJ file
+///
+/// Positions stay in centimeters, only the first UV channel is exported and vertex colors are dropped
+pub fn encode_obj(mesh: &Mesh) -> Result<String, Error> {
+    mesh.validate()?;
+
+    let mut obj = String::new();
+    let write_error =
+        |_: std::fmt::Error| Error::invalid_file("Failed to write OBJ file".to_string());
+
+    for position in &mesh.positions {
+        let [x, y, z] = to_right_handed(*position);
+        writeln!(obj, "v {x} {y} {z}").map_err(write_error)?;
+    }
+
+    let tex_coords = mesh.tex_coords.first().filter(|e| !e.is_empty());
+    for [u, v] in tex_coords.into_iter().flatten() {
+        // OBJ texture coordinates start at the bottom left
+        writeln!(obj, "vt {u} {}", 1.0 - v).map_err(write_error)?;
+    }
+
+    for normal in &mesh.normals {
+        let [x, y, z] = to_right_handed(*normal);
+        writeln!(obj, "vn {x} {y} {z}").map_err(write_error)?;
+    }
+
+    for (index, section) in mesh.sections().iter().enumerate() {
+        writeln!(obj, "g section_{index}").map_err(write_error)?;
+        if let Some(material_name) = &section.material_name {
+            writeln!(obj, "usemtl {material_name}").map_err(write_error)?;
+        }
+
+        let first_index = section.first_index as usize;
+        let indices = &mesh.indices[first_index..first_index + section.num_triangles as usize * 3];
+        for triangle in indices.chunks_exact(3) {
+            obj.push('f');
+            for index in triangle {
+                // OBJ indices are one based
+                let index = index + 1;
+                match (tex_coords.is_some(), !mesh.normals.is_empty()) {
+                    (true, true) => write!(obj, " {index}/{index}/{index}"),
+                    (true, false) => write!(obj, " {index}/{index}"),
+                    (false, true) => write!(obj, " {index}//{index}"),
+                    (false, false) => write!(obj, " {index}"),
+                }
+                .map_err(write_error)?;
+            }
+            obj.push('\n');
+        }
+    }
+
+    Ok(obj)
+}
@@ -18,6 +18,7 @@ macro_rules! parse_simple_property {
     ($prop_name:ident) => {
         /// $prop_name
         #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
         pub struct $prop_name {
             /// Generic property
             pub generic_property: FGenericProperty,
@@ -56,6 +57,7 @@ macro_rules! parse_simple_property_index {
     ) => {
         /// $prop_name
         #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
         pub struct $prop_name {
             /// Generic property
             pub generic_property: FGenericProperty,
@@ -100,6 +102,7 @@ macro_rules! parse_simple_property_prop {
     ) => {
         /// $prop_name
         #[derive(FNameContainer, Debug, Clone, PartialEq, Eq, Hash)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
         pub struct $prop_name {
             /// Generic property
             pub generic_property: FGenericProperty,
@@ -146,6 +149,7 @@ pub trait FPropertyTrait: Debug + Clone + PartialEq + Eq + Hash {
 /// FProperty
 #[enum_dispatch(FPropertyTrait)]
 #[derive(FNameContainer, Hash, PartialEq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[container_nobounds]
 pub enum FProperty {
     /// Generic FProperty
@@ -266,6 +270,7 @@ impl ToSerializedName for FProperty {
 
 /// Generic FProperty
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FGenericProperty {
     /// Property name
     pub name: FName,
@@ -293,6 +298,7 @@ pub struct FGenericProperty {
 
 /// Enum FProperty
 #[derive(FNameContainer, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FEnumProperty {
     /// Generic property
     pub generic_property: FGenericProperty,
@@ -305,6 +311,7 @@ pub struct FEnumProperty {
 
 /// Boolean FProperty
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FBoolProperty {
     /// Generic property
     pub generic_property: FGenericProperty,
@@ -0,0 +1,1500 @@
+//! StaticMesh export
+
+use std::io::{Cursor, Read, Write};
+
+use byteorder::{ReadBytesExt, WriteBytesExt, LE};
+use ordered_float::OrderedFloat;
+
+use unreal_asset_base::{
+    engine_version::EngineVersion,
+    reader::{ArchiveReader, ArchiveWriter},
+    types::{
+        bulk_data::FByteBulkData,
+        strip_data_flags::FStripDataFlags,
+        vector::{Color, Vector, Vector2, Vector4},
+        FName, PackageIndex, PackageIndexTrait,
+    },
+    Error, FNameContainer, Guid,
+};
+
+use crate::implement_get;
+use crate::mesh::{Mesh, MeshSection};
+use crate::texture::bcn::{f32_to_half, half_to_f32};
+use crate::ExportTrait;
+use crate::{BaseExport, NormalExport};
+
+/// Maximum amount of LODs a static mesh can have
+pub const MAX_STATIC_MESH_LODS: usize = 8;
+
+/// Class strip flag for adjacency index buffers
+const CDSF_ADJACENCY_DATA: u8 = 1;
+/// Class strip flag for reversed index buffers
+const CDSF_REVERSED_INDEX_BUFFER: u8 = 4;
+/// Class strip flag for ray tracing resources
+const CDSF_RAY_TRACING_RESOURCES: u8 = 8;
+
+/// Read an `i32` serialized bool
+fn read_bool<R: Read>(reader: &mut R) -> Result<bool, Error> {
+    Ok(reader.read_i32::<LE>()? != 0)
+}
+
+/// Write an `i32` serialized bool
+fn write_bool<W: Write>(writer: &mut W, value: bool) -> Result<(), Error> {
+    writer.write_i32::<LE>(value as i32)?;
+    Ok(())
+}
+
+/// Read `FStripDataFlags` from a reader that isn't an asset
+fn read_strip_flags<R: Read>(reader: &mut R) -> Result<FStripDataFlags, Error> {
+    let global_strip_flags = reader.read_u8()?;
+    let class_strip_flags = reader.read_u8()?;
+    Ok(FStripDataFlags::new(global_strip_flags, class_strip_flags))
+}
+
+/// Write `FStripDataFlags` to a writer that isn't an asset
+fn write_strip_flags<W: Write>(writer: &mut W, strip_flags: &FStripDataFlags) -> Result<(), Error> {
+    writer.write_u8(strip_flags.global_strip_flags)?;
+    writer.write_u8(strip_flags.class_strip_flags)?;
+    Ok(())
+}
+
+/// Read an `FVector`
+fn read_vector<R: Read>(reader: &mut R) -> Result<Vector<OrderedFloat<f32>>, Error> {
+    Ok(Vector::new(
+        OrderedFloat(reader.read_f32::<LE>()?),
+        OrderedFloat(reader.read_f32::<LE>()?),
+        OrderedFloat(reader.read_f32::<LE>()?),
+    ))
+}
+
+/// Write an `FVector`
+fn write_vector<W: Write>(writer: &mut W, vector: &Vector<OrderedFloat<f32>>) -> Result<(), Error> {
+    writer.write_f32::<LE>(vector.x.0)?;
+    writer.write_f32::<LE>(vector.y.0)?;
+    writer.write_f32::<LE>(vector.z.0)?;
+    Ok(())
+}
+
+/// Read a `TArray`
+fn read_array<R: Read, T>(
+    reader: &mut R,
+    mut read_element: impl FnMut(&mut R) -> Result<T, Error>,
+) -> Result<Vec<T>, Error> {
+    let count = reader.read_i32::<LE>()?;
+    if count < 0 {
+        return Err(Error::invalid_file(format!("Invalid array length {count}")));
+    }
+
+    let mut array = Vec::new();
+    for _ in 0..count {
+        array.push(read_element(reader)?);
+    }
+    Ok(array)
+}
+
+/// Write a `TArray`
+fn write_array<W: Write, T>(
+    writer: &mut W,
+    array: &[T],
+    mut write_element: impl FnMut(&mut W, &T) -> Result<(), Error>,
+) -> Result<(), Error> {
+    writer.write_i32::<LE>(array.len() as i32)?;
+    for element in array {
+        write_element(writer, element)?;
+    }
+    Ok(())
+}
+
+/// Read a bulk serialized array, which is prefixed with the size of its elements
+fn read_bulk_array<R: Read, T>(
+    reader: &mut R,
+    element_size: i32,
+    mut read_element: impl FnMut(&mut R) -> Result<T, Error>,
+) -> Result<Vec<T>, Error> {
+    let serialized_element_size = reader.read_i32::<LE>()?;
+    let count = reader.read_i32::<LE>()?;
+    if count < 0 {
+        return Err(Error::invalid_file(format!(
+            "Invalid bulk array length {count}"
+        )));
+    }
+    if count > 0 && serialized_element_size != element_size {
+        return Err(Error::invalid_file(format!(
+            "Expected bulk array elements of {element_size} bytes, got {serialized_element_size}"
+        )));
+    }
+
+    let mut array = Vec::new();
+    for _ in 0..count {
+        array.push(read_element(reader)?);
+    }
+    Ok(array)
+}
+
+/// Write a bulk serialized array
+fn write_bulk_array<W: Write, T>(
+    writer: &mut W,
+    element_size: i32,
+    array: &[T],
+    mut write_element: impl FnMut(&mut W, &T) -> Result<(), Error>,
+) -> Result<(), Error> {
+    writer.write_i32::<LE>(element_size)?;
+    writer.write_i32::<LE>(array.len() as i32)?;
+    for element in array {
+        write_element(writer, element)?;
+    }
+    Ok(())
+}
+
+/// Bounding box and sphere sharing the same origin
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FBoxSphereBounds {
+    /// Origin
+    pub origin: Vector<OrderedFloat<f32>>,
+    /// Box extent
+    pub box_extent: Vector<OrderedFloat<f32>>,
+    /// Sphere radius
+    pub sphere_radius: OrderedFloat<f32>,
+}
+
+impl FBoxSphereBounds {
+    /// Read `FBoxSphereBounds`
+    pub fn read<R: Read>(reader: &mut R) -> Result<Self, Error> {
+        Ok(FBoxSphereBounds {
+            origin: read_vector(reader)?,
+            box_extent: read_vector(reader)?,
+            sphere_radius: OrderedFloat(reader.read_f32::<LE>()?),
+        })
+    }
+
+    /// Write `FBoxSphereBounds`
+    pub fn write<W: Write>(&self, writer: &mut W) -> Result<(), Error> {
+        write_vector(writer, &self.origin)?;
+        write_vector(writer, &self.box_extent)?;
+        writer.write_f32::<LE>(self.sphere_radius.0)?;
+        Ok(())
+    }
+}
+
+/// Range of a LOD index buffer that is drawn with a single material
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FStaticMeshSection {
+    /// Index into the static materials of the mesh
+    pub material_index: i32,
+    /// First index of the section in the index buffer
+    pub first_index: u32,
+    /// Triangle count
+    pub num_triangles: u32,
+    /// Smallest vertex index used by the section
+    pub min_vertex_index: u32,
+    /// Largest vertex index used by the section
+    pub max_vertex_index: u32,
+    /// Does the section have collision
+    pub enable_collision: bool,
+    /// Does the section cast shadows
+    pub cast_shadow: bool,
+    /// Is the section treated as opaque for ray tracing
+    pub force_opaque: bool,
+    /// Is the section visible in ray tracing, only serialized since UE4.26
+    pub visible_in_ray_tracing: bool,
+}
+
+impl FStaticMeshSection {
+    /// Read an `FStaticMeshSection`
+    pub fn read<R: Read>(reader: &mut R, engine_version: EngineVersion) -> Result<Self, Error> {
+        Ok(FStaticMeshSection {
+            material_index: reader.read_i32::<LE>()?,
+            first_index: reader.read_u32::<LE>()?,
+            num_triangles: reader.read_u32::<LE>()?,
+            min_vertex_index: reader.read_u32::<LE>()?,
+            max_vertex_index: reader.read_u32::<LE>()?,
+            enable_collision: read_bool(reader)?,
+            cast_shadow: read_bool(reader)?,
+            force_opaque: read_bool(reader)?,
+            visible_in_ray_tracing: match engine_version >= EngineVersion::VER_UE4_26 {
+                true => read_bool(reader)?,
+                false => true,
+            },
+        })
+    }
+
+    /// Write an `FStaticMeshSection`
+    pub fn write<W: Write>(
+        &self,
+        writer: &mut W,
+        engine_version: EngineVersion,
+    ) -> Result<(), Error> {
+        writer.write_i32::<LE>(self.material_index)?;
+        writer.write_u32::<LE>(self.first_index)?;
+        writer.write_u32::<LE>(self.num_triangles)?;
+        writer.write_u32::<LE>(self.min_vertex_index)?;
+        writer.write_u32::<LE>(self.max_vertex_index)?;
+        write_bool(writer, self.enable_collision)?;
+        write_bool(writer, self.cast_shadow)?;
+        write_bool(writer, self.force_opaque)?;
+        if engine_version >= EngineVersion::VER_UE4_26 {
+            write_bool(writer, self.visible_in_ray_tracing)?;
+        }
+        Ok(())
+    }
+}
+
+/// Vertex positions
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FPositionVertexBuffer {
+    /// Vertex stride
+    pub stride: u32,
+    /// Vertex count
+    pub num_vertices: u32,
+    /// Vertex positions
+    pub vertices: Vec<Vector<OrderedFloat<f32>>>,
+}
+
+impl FPositionVertexBuffer {
+    /// Read an `FPositionVertexBuffer`
+    pub fn read<R: Read>(reader: &mut R) -> Result<Self, Error> {
+        let stride = reader.read_u32::<LE>()?;
+        let num_vertices = reader.read_u32::<LE>()?;
+        let vertices = read_bulk_array(reader, 12, read_vector)?;
+
+        Ok(FPositionVertexBuffer {
+            stride,
+            num_vertices,
+            vertices,
+        })
+    }
+
+    /// Write an `FPositionVertexBuffer`
+    pub fn write<W: Write>(&self, writer: &mut W) -> Result<(), Error> {
+        writer.write_u32::<LE>(self.stride)?;
+        writer.write_u32::<LE>(self.num_vertices)?;
+        write_bulk_array(writer, 12, &self.vertices, write_vector)
+    }
+}
+
+/// Packed tangent basis of a vertex
+///
+/// Components are normalized to the `i16` range, low precision tangents only use the `i8` range
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FStaticMeshVertexTangent {
+    /// Tangent
+    pub tangent_x: Vector4<i16>,
+    /// Normal, `w` stores the sign of the bitangent
+    pub tangent_z: Vector4<i16>,
+}
+
+/// Vertex tangents and texture coordinates
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FStaticMeshVertexBuffer {
+    /// Strip flags
+    pub strip_flags: FStripDataFlags,
+    /// Texture coordinate count per vertex
+    pub num_tex_coords: u32,
+    /// Vertex count
+    pub num_vertices: u32,
+    /// Are texture coordinates stored as `f32` instead of half floats
+    pub use_full_precision_uvs: bool,
+    /// Are tangents stored as `i16` instead of `i8`
+    pub use_high_precision_tangent_basis: bool,
+    /// Vertex tangents
+    pub tangents: Vec<FStaticMeshVertexTangent>,
+    /// Texture coordinates, all channels of a vertex are stored next to each other
+    pub tex_coords: Vec<Vector2<OrderedFloat<f32>>>,
+}
+
+impl FStaticMeshVertexBuffer {
+    /// Read an `FStaticMeshVertexBuffer`
+    pub fn read<R: Read>(reader: &mut R) -> Result<Self, Error> {
+        let strip_flags = read_strip_flags(reader)?;
+        let num_tex_coords = reader.read_u32::<LE>()?;
+        let num_vertices = reader.read_u32::<LE>()?;
+        let use_full_precision_uvs = read_bool(reader)?;
+        let use_high_precision_tangent_basis = read_bool(reader)?;
+
+        let mut tangents = Vec::new();
+        let mut tex_coords = Vec::new();
+        if !strip_flags.is_data_stripped_for_server() {
+            tangents = match use_high_precision_tangent_basis {
+                true => read_bulk_array(reader, 16, |reader| {
+                    let mut read_vector4 = || -> Result<Vector4<i16>, Error> {
+                        Ok(Vector4::new(
+                            reader.read_i16::<LE>()?,
+                            reader.read_i16::<LE>()?,
+                            reader.read_i16::<LE>()?,
+                            reader.read_i16::<LE>()?,
+                        ))
+                    };
+                    Ok(FStaticMeshVertexTangent {
+                        tangent_x: read_vector4()?,
+                        tangent_z: read_vector4()?,
+                    })
+                })?,
+                false => read_bulk_array(reader, 8, |reader| {
+                    let mut read_vector4 = || -> Result<Vector4<i16>, Error> {
+                        Ok(Vector4::new(
+                            reader.read_i8()? as i16,
+                            reader.read_i8()? as i16,
+                            reader.read_i8()? as i16,
+                            reader.read_i8()? as i16,
+                        ))
+                    };
+                    Ok(FStaticMeshVertexTangent {
+                        tangent_x: read_vector4()?,
+                        tangent_z: read_vector4()?,
+                    })
+                })?,
+            };
+
+            tex_coords = match use_full_precision_uvs {
+                true => read_bulk_array(reader, 8, |reader| {
+                    Ok(Vector2::new(
+                        OrderedFloat(reader.read_f32::<LE>()?),
+                        OrderedFloat(reader.read_f32::<LE>()?),
+                    ))
+                })?,
+                false => read_bulk_array(reader, 4, |reader| {
+                    Ok(Vector2::new(
+                        OrderedFloat(half_to_f32(reader.read_u16::<LE>()?)),
+                        OrderedFloat(half_to_f32(reader.read_u16::<LE>()?)),
+                    ))
+                })?,
+            };
+        }
+
+        Ok(FStaticMeshVertexBuffer {
+            strip_flags,
+            num_tex_coords,
+            num_vertices,
+            use_full_precision_uvs,
+            use_high_precision_tangent_basis,
+            tangents,
+            tex_coords,
+        })
+    }
+
+    /// Write an `FStaticMeshVertexBuffer`
+    pub fn write<W: Write>(&self, writer: &mut W) -> Result<(), Error> {
+        write_strip_flags(writer, &self.strip_flags)?;
+        writer.write_u32::<LE>(self.num_tex_coords)?;
+        writer.write_u32::<LE>(self.num_vertices)?;
+        write_bool(writer, self.use_full_precision_uvs)?;
+        write_bool(writer, self.use_high_precision_tangent_basis)?;
+
+        if !self.strip_flags.is_data_stripped_for_server() {
+            match self.use_high_precision_tangent_basis {
+                true => write_bulk_array(writer, 16, &self.tangents, |writer, tangent| {
+                    for vector in [&tangent.tangent_x, &tangent.tangent_z] {
+                        for value in [vector.x, vector.y, vector.z, vector.w] {
+                            writer.write_i16::<LE>(value)?;
+                        }
+                    }
+                    Ok(())
+                })?,
+                false => write_bulk_array(writer, 8, &self.tangents, |writer, tangent| {
+                    for vector in [&tangent.tangent_x, &tangent.tangent_z] {
+                        for value in [vector.x, vector.y, vector.z, vector.w] {
+                            writer.write_i8(value as i8)?;
+                        }
+                    }
+                    Ok(())
+                })?,
+            };
+
+            match self.use_full_precision_uvs {
+                true => write_bulk_array(writer, 8, &self.tex_coords, |writer, tex_coord| {
+                    writer.write_f32::<LE>(tex_coord.x.0)?;
+                    writer.write_f32::<LE>(tex_coord.y.0)?;
+                    Ok(())
+                })?,
+                false => write_bulk_array(writer, 4, &self.tex_coords, |writer, tex_coord| {
+                    writer.write_u16::<LE>(f32_to_half(tex_coord.x.0))?;
+                    writer.write_u16::<LE>(f32_to_half(tex_coord.y.0))?;
+                    Ok(())
+                })?,
+            };
+        }
+        Ok(())
+    }
+}
+
+/// Vertex colors
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FColorVertexBuffer {
+    /// Strip flags
+    pub strip_flags: FStripDataFlags,
+    /// Vertex stride
+    pub stride: u32,
+    /// Vertex count, zero if the mesh has no vertex colors
+    pub num_vertices: u32,
+    /// Vertex colors
+    pub colors: Vec<Color<u8>>,
+}
+
+impl FColorVertexBuffer {
+    /// Read an `FColorVertexBuffer`
+    pub fn read<R: Read>(reader: &mut R) -> Result<Self, Error> {
+        let strip_flags = read_strip_flags(reader)?;
+        let stride = reader.read_u32::<LE>()?;
+        let num_vertices = reader.read_u32::<LE>()?;
+
+        let mut colors = Vec::new();
+        if !strip_flags.is_data_stripped_for_server() && num_vertices > 0 {
+            // FColor is stored as BGRA
+            colors = read_bulk_array(reader, 4, |reader| {
+                let [b, g, r, a] = reader.read_u32::<LE>()?.to_le_bytes();
+                Ok(Color::new(r, g, b, a))
+            })?;
+        }
+
+        Ok(FColorVertexBuffer {
+            strip_flags,
+            stride,
+            num_vertices,
+            colors,
+        })
+    }
+
+    /// Write an `FColorVertexBuffer`
+    pub fn write<W: Write>(&self, writer: &mut W) -> Result<(), Error> {
+        write_strip_flags(writer, &self.strip_flags)?;
+        writer.write_u32::<LE>(self.stride)?;
+        writer.write_u32::<LE>(self.num_vertices)?;
+
+        if !self.strip_flags.is_data_stripped_for_server() && self.num_vertices > 0 {
+            write_bulk_array(writer, 4, &self.colors, |writer, color| {
+                writer.write_all(&[color.b, color.g, color.r, color.a])?;
+                Ok(())
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// Index buffer
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FRawStaticIndexBuffer {
+    /// Are indices stored as `u32` instead of `u16`
+    pub is_32_bit: bool,
+    /// Raw index data
+    pub index_data: Vec<u8>,
+    /// Should 16 bit indices be expanded when loaded, only serialized since UE4.25
+    pub should_expand_to_32_bit: bool,
+}
+
+impl FRawStaticIndexBuffer {
+    /// Create a new `FRawStaticIndexBuffer` from indices, 32 bit indices are only used if needed
+    pub fn from_indices(indices: &[u32]) -> Self {
+        let is_32_bit = indices.iter().any(|e| *e > u16::MAX as u32);
+        let index_data = match is_32_bit {
+            true => indices.iter().flat_map(|e| e.to_le_bytes()).collect(),
+            false => indices
+                .iter()
+                .flat_map(|e| (*e as u16).to_le_bytes())
+                .collect(),
+        };
+
+        FRawStaticIndexBuffer {
+            is_32_bit,
+            index_data,
+            should_expand_to_32_bit: false,
+        }
+    }
+
+    /// Read an `FRawStaticIndexBuffer`
+    pub fn read<R: Read>(reader: &mut R, engine_version: EngineVersion) -> Result<Self, Error> {
+        let is_32_bit = read_bool(reader)?;
+        let index_data = read_bulk_array(reader, 1, |reader| Ok(reader.read_u8()?))?;
+        let should_expand_to_32_bit = match engine_version >= EngineVersion::VER_UE4_25 {
+            true => read_bool(reader)?,
+            false => false,
+        };
+
+        Ok(FRawStaticIndexBuffer {
+            is_32_bit,
+            index_data,
+            should_expand_to_32_bit,
+        })
+    }
+
+    /// Write an `FRawStaticIndexBuffer`
+    pub fn write<W: Write>(
+        &self,
+        writer: &mut W,
+        engine_version: EngineVersion,
+    ) -> Result<(), Error> {
+        write_bool(writer, self.is_32_bit)?;
+        write_bulk_array(writer, 1, &self.index_data, |writer, value| {
+            writer.write_u8(*value)?;
+            Ok(())
+        })?;
+        if engine_version >= EngineVersion::VER_UE4_25 {
+            write_bool(writer, self.should_expand_to_32_bit)?;
+        }
+        Ok(())
+    }
+
+    /// Get the indices of this buffer
+    pub fn indices(&self) -> Vec<u32> {
+        match self.is_32_bit {
+            true => self
+                .index_data
+                .chunks_exact(4)
+                .map(|e| u32::from_le_bytes([e[0], e[1], e[2], e[3]]))
+                .collect(),
+            false => self
+                .index_data
+                .chunks_exact(2)
+                .map(|e| u16::from_le_bytes([e[0], e[1]]) as u32)
+                .collect(),
+        }
+    }
+}
+
+/// Alias table used to pick random triangles weighted by their area
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FWeightedRandomSampler {
+    /// Probabilities
+    pub prob: Vec<OrderedFloat<f32>>,
+    /// Aliases
+    pub alias: Vec<i32>,
+    /// Sum of all weights
+    pub total_weight: OrderedFloat<f32>,
+}
+
+impl FWeightedRandomSampler {
+    /// Read an `FWeightedRandomSampler`
+    pub fn read<R: Read>(reader: &mut R) -> Result<Self, Error> {
+        Ok(FWeightedRandomSampler {
+            prob: read_array(reader, |reader| Ok(OrderedFloat(reader.read_f32::<LE>()?)))?,
+            alias: read_array(reader, |reader| Ok(reader.read_i32::<LE>()?))?,
+            total_weight: OrderedFloat(reader.read_f32::<LE>()?),
+        })
+    }
+
+    /// Write an `FWeightedRandomSampler`
+    pub fn write<W: Write>(&self, writer: &mut W) -> Result<(), Error> {
+        write_array(writer, &self.prob, |writer, value| {
+            writer.write_f32::<LE>(value.0)?;
+            Ok(())
+        })?;
+        write_array(writer, &self.alias, |writer, value| {
+            writer.write_i32::<LE>(*value)?;
+            Ok(())
+        })?;
+        writer.write_f32::<LE>(self.total_weight.0)?;
+        Ok(())
+    }
+}
+
+/// Vertex and index buffers of a LOD
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FStaticMeshBuffers {
+    /// Strip flags, class flags decide which optional index buffers are serialized
+    pub strip_flags: FStripDataFlags,
+    /// Vertex positions
+    pub position_vertex_buffer: FPositionVertexBuffer,
+    /// Vertex tangents and texture coordinates
+    pub static_mesh_vertex_buffer: FStaticMeshVertexBuffer,
+    /// Vertex colors
+    pub color_vertex_buffer: FColorVertexBuffer,
+    /// Index buffer
+    pub index_buffer: FRawStaticIndexBuffer,
+    /// Reversed index buffer
+    pub reversed_index_buffer: Option<FRawStaticIndexBuffer>,
+    /// Depth only index buffer
+    pub depth_only_index_buffer: FRawStaticIndexBuffer,
+    /// Reversed depth only index buffer
+    pub reversed_depth_only_index_buffer: Option<FRawStaticIndexBuffer>,
+    /// Wireframe index buffer
+    pub wireframe_index_buffer: Option<FRawStaticIndexBuffer>,
+    /// Adjacency index buffer
+    pub adjacency_index_buffer: Option<FRawStaticIndexBuffer>,
+    /// Raw ray tracing geometry, only serialized since UE4.25
+    pub ray_tracing_geometry: Option<Vec<u8>>,
+    /// Area weighted samplers of each section
+    pub area_weighted_section_samplers: Vec<FWeightedRandomSampler>,
+    /// Area weighted sampler of the whole LOD
+    pub area_weighted_sampler: FWeightedRandomSampler,
+}
+
+impl FStaticMeshBuffers {
+    /// Read `FStaticMeshBuffers`
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - reader to read from
+    /// * `engine_version` - engine version of the asset
+    /// * `num_sections` - section count of the LOD
+    pub fn read<R: Read>(
+        reader: &mut R,
+        engine_version: EngineVersion,
+        num_sections: usize,
+    ) -> Result<Self, Error> {
+        let strip_flags = read_strip_flags(reader)?;
+        let position_vertex_buffer = FPositionVertexBuffer::read(reader)?;
+        let static_mesh_vertex_buffer = FStaticMeshVertexBuffer::read(reader)?;
+        let color_vertex_buffer = FColorVertexBuffer::read(reader)?;
+
+        let index_buffer = FRawStaticIndexBuffer::read(reader, engine_version)?;
+        let has_reversed_index_buffers =
+            !strip_flags.is_class_data_stripped(CDSF_REVERSED_INDEX_BUFFER);
+        let reversed_index_buffer = match has_reversed_index_buffers {
+            true => Some(FRawStaticIndexBuffer::read(reader, engine_version)?),
+            false => None,
+        };
+        let depth_only_index_buffer = FRawStaticIndexBuffer::read(reader, engine_version)?;
+        let reversed_depth_only_index_buffer = match has_reversed_index_buffers {
+            true => Some(FRawStaticIndexBuffer::read(reader, engine_version)?),
+            false => None,
+        };
+        let wireframe_index_buffer = match strip_flags.is_editor_data_stripped() {
+            true => None,
+            false => Some(FRawStaticIndexBuffer::read(reader, engine_version)?),
+        };
+        let adjacency_index_buffer = match strip_flags.is_class_data_stripped(CDSF_ADJACENCY_DATA) {
+            true => None,
+            false => Some(FRawStaticIndexBuffer::read(reader, engine_version)?),
+        };
+
+        let ray_tracing_geometry = match engine_version >= EngineVersion::VER_UE4_25
+            && !strip_flags.is_class_data_stripped(CDSF_RAY_TRACING_RESOURCES)
+        {
+            true => Some(read_bulk_array(reader, 1, |reader| Ok(reader.read_u8()?))?),
+            false => None,
+        };
+
+        let mut area_weighted_section_samplers = Vec::new();
+        for _ in 0..num_sections {
+            area_weighted_section_samplers.push(FWeightedRandomSampler::read(reader)?);
+        }
+        let area_weighted_sampler = FWeightedRandomSampler::read(reader)?;
+
+        Ok(FStaticMeshBuffers {
+            strip_flags,
+            position_vertex_buffer,
+            static_mesh_vertex_buffer,
+            color_vertex_buffer,
+            index_buffer,
+            reversed_index_buffer,
+            depth_only_index_buffer,
+            reversed_depth_only_index_buffer,
+            wireframe_index_buffer,
+            adjacency_index_buffer,
+            ray_tracing_geometry,
+            area_weighted_section_samplers,
+            area_weighted_sampler,
+        })
+    }
+
+    /// Write `FStaticMeshBuffers`
+    ///
+    /// Optional buffers are written depending on the strip flags, missing ones are written empty
+    pub fn write<W: Write>(
+        &self,
+        writer: &mut W,
+        engine_version: EngineVersion,
+    ) -> Result<(), Error> {
+        let empty = FRawStaticIndexBuffer::default();
+
+        write_strip_flags(writer, &self.strip_flags)?;
+        self.position_vertex_buffer.write(writer)?;
+        self.static_mesh_vertex_buffer.write(writer)?;
+        self.color_vertex_buffer.write(writer)?;
+
+        self.index_buffer.write(writer, engine_version)?;
+        let has_reversed_index_buffers = !self
+            .strip_flags
+            .is_class_data_stripped(CDSF_REVERSED_INDEX_BUFFER);
+        if has_reversed_index_buffers {
+            self.reversed_index_buffer
+                .as_ref()
+                .unwrap_or(&empty)
+                .write(writer, engine_version)?;
+        }
+        self.depth_only_index_buffer.write(writer, engine_version)?;
+        if has_reversed_index_buffers {
+            self.reversed_depth_only_index_buffer
+                .as_ref()
+                .unwrap_or(&empty)
+                .write(writer, engine_version)?;
+        }
+        if !self.strip_flags.is_editor_data_stripped() {
+            self.wireframe_index_buffer
+                .as_ref()
+                .unwrap_or(&empty)
+                .write(writer, engine_version)?;
+        }
+        if !self.strip_flags.is_class_data_stripped(CDSF_ADJACENCY_DATA) {
+            self.adjacency_index_buffer
+                .as_ref()
+                .unwrap_or(&empty)
+                .write(writer, engine_version)?;
+        }
+
+        if engine_version >= EngineVersion::VER_UE4_25
+            && !self
+                .strip_flags
+                .is_class_data_stripped(CDSF_RAY_TRACING_RESOURCES)
+        {
+            let ray_tracing_geometry = self.ray_tracing_geometry.as_deref().unwrap_or_default();
+            write_bulk_array(writer, 1, ray_tracing_geometry, |writer, value| {
+                writer.write_u8(*value)?;
+                Ok(())
+            })?;
+        }
+
+        for sampler in &self.area_weighted_section_samplers {
+            sampler.write(writer)?;
+        }
+        self.area_weighted_sampler.write(writer)?;
+        Ok(())
+    }
+
+    /// Decode these buffers to a [`Mesh`]
+    ///
+    /// # Arguments
+    ///
+    /// * `sections` - sections of the LOD
+    /// * `static_materials` - materials of the mesh, used to name sections
+    pub fn to_mesh(
+        &self,
+        sections: &[FStaticMeshSection],
+        static_materials: &[FStaticMaterial],
+    ) -> Mesh {
+        let positions = self
+            .position_vertex_buffer
+            .vertices
+            .iter()
+            .map(|e| [e.x.0, e.y.0, e.z.0])
+            .collect();
+
+        let vertex_buffer = &self.static_mesh_vertex_buffer;
+        let scale = match vertex_buffer.use_high_precision_tangent_basis {
+            true => i16::MAX as f32,
+            false => i8::MAX as f32,
+        };
+        let normals = vertex_buffer
+            .tangents
+            .iter()
+            .map(|e| {
+                let normal = &e.tangent_z;
+                [normal.x, normal.y, normal.z].map(|e| (e as f32 / scale).max(-1.0))
+            })
+            .collect();
+        let tangents = vertex_buffer
+            .tangents
+            .iter()
+            .map(|e| {
+                let tangent = &e.tangent_x;
+                let [x, y, z] =
+                    [tangent.x, tangent.y, tangent.z].map(|e| (e as f32 / scale).max(-1.0));
+                let sign = match e.tangent_z.w < 0 {
+                    true => -1.0,
+                    false => 1.0,
+                };
+                [x, y, z, sign]
+            })
+            .collect();
+
+        let num_tex_coords = vertex_buffer.num_tex_coords as usize;
+        let tex_coords = (0..num_tex_coords)
+            .map(|channel| {
+                vertex_buffer
+                    .tex_coords
+                    .iter()
+                    .skip(channel)
+                    .step_by(num_tex_coords)
+                    .map(|e| [e.x.0, e.y.0])
+                    .collect()
+            })
+            .collect();
+
+        let colors = self
+            .color_vertex_buffer
+            .colors
+            .iter()
+            .map(|e| [e.r, e.g, e.b, e.a])
+            .collect();
+
+        let sections = sections
+            .iter()
+            .map(|section| MeshSection {
+                material_name: usize::try_from(section.material_index)
+                    .ok()
+                    .and_then(|e| static_materials.get(e))
+                    .map(|e| e.material_slot_name.get_owned_content()),
+                first_index: section.first_index,
+                num_triangles: section.num_triangles,
+            })
+            .collect();
+
+        Mesh {
+            positions,
+            normals,
+            tangents,
+            tex_coords,
+            colors,
+            indices: self.index_buffer.indices(),
+            sections,
+        }
+    }
+}
+
+/// Metadata of the vertex and index buffers of a streamed LOD
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FStaticMeshLODAvailabilityInfo {
+    /// Triangle count of the depth only index buffer
+    pub depth_only_num_triangles: u32,
+    /// Packed LOD flags
+    pub packed: u32,
+    /// Texture coordinate count per vertex
+    pub num_tex_coords: u32,
+    /// Vertex count of the tangent and texture coordinate buffer
+    pub num_vertices: u32,
+    /// Are texture coordinates stored as `f32` instead of half floats
+    pub use_full_precision_uvs: bool,
+    /// Are tangents stored as `i16` instead of `i8`
+    pub use_high_precision_tangent_basis: bool,
+    /// Position vertex stride
+    pub position_stride: u32,
+    /// Position vertex count
+    pub position_num_vertices: u32,
+    /// Color vertex stride
+    pub color_stride: u32,
+    /// Color vertex count
+    pub color_num_vertices: u32,
+    /// Index count and `is_32_bit` of the index, reversed, depth only, reversed depth only,
+    /// wireframe and adjacency index buffers
+    pub index_buffers: [(u32, bool); 6],
+}
+
+impl FStaticMeshLODAvailabilityInfo {
+    /// Read `FStaticMeshLODAvailabilityInfo`
+    pub fn read<R: Read>(reader: &mut R) -> Result<Self, Error> {
+        let depth_only_num_triangles = reader.read_u32::<LE>()?;
+        let packed = reader.read_u32::<LE>()?;
+        let num_tex_coords = reader.read_u32::<LE>()?;
+        let num_vertices = reader.read_u32::<LE>()?;
+        let use_full_precision_uvs = read_bool(reader)?;
+        let use_high_precision_tangent_basis = read_bool(reader)?;
+        let position_stride = reader.read_u32::<LE>()?;
+        let position_num_vertices = reader.read_u32::<LE>()?;
+        let color_stride = reader.read_u32::<LE>()?;
+        let color_num_vertices = reader.read_u32::<LE>()?;
+
+        let mut index_buffers = [(0, false); 6];
+        for (num_indices, is_32_bit) in &mut index_buffers {
+            *num_indices = reader.read_u32::<LE>()?;
+            *is_32_bit = read_bool(reader)?;
+        }
+
+        Ok(FStaticMeshLODAvailabilityInfo {
+            depth_only_num_triangles,
+            packed,
+            num_tex_coords,
+            num_vertices,
+            use_full_precision_uvs,
+            use_high_precision_tangent_basis,
+            position_stride,
+            position_num_vertices,
+            color_stride,
+            color_num_vertices,
+            index_buffers,
+        })
+    }
+
+    /// Write `FStaticMeshLODAvailabilityInfo`
+    pub fn write<W: Write>(&self, writer: &mut W) -> Result<(), Error> {
+        writer.write_u32::<LE>(self.depth_only_num_triangles)?;
+        writer.write_u32::<LE>(self.packed)?;
+        writer.write_u32::<LE>(self.num_tex_coords)?;
+        writer.write_u32::<LE>(self.num_vertices)?;
+        write_bool(writer, self.use_full_precision_uvs)?;
+        write_bool(writer, self.use_high_precision_tangent_basis)?;
+        writer.write_u32::<LE>(self.position_stride)?;
+        writer.write_u32::<LE>(self.position_num_vertices)?;
+        writer.write_u32::<LE>(self.color_stride)?;
+        writer.write_u32::<LE>(self.color_num_vertices)?;
+
+        for (num_indices, is_32_bit) in &self.index_buffers {
+            writer.write_u32::<LE>(*num_indices)?;
+            write_bool(writer, *is_32_bit)?;
+        }
+        Ok(())
+    }
+}
+
+/// Sizes of the buffers of a LOD
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FStaticMeshBuffersSize {
+    /// Size of the serialized buffers
+    pub serialized_buffers_size: u32,
+    /// Size of the depth only index buffer
+    pub depth_only_ib_size: u32,
+    /// Size of the reversed index buffers
+    pub reversed_ibs_size: u32,
+}
+
+/// Level of detail of a static mesh
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FStaticMeshLODResources {
+    /// Strip flags
+    pub strip_flags: FStripDataFlags,
+    /// Sections
+    pub sections: Vec<FStaticMeshSection>,
+    /// Maximum deviation from the base LOD
+    pub max_deviation: OrderedFloat<f32>,
+    /// Was the LOD removed while cooking
+    pub is_lod_cooked_out: bool,
+    /// Are the buffers stored in the asset instead of being streamed
+    pub inlined: bool,
+    /// Buffers of an inlined LOD
+    pub buffers: Option<FStaticMeshBuffers>,
+    /// Buffers of a streamed LOD, see [`FStaticMeshLODResources::read_streamed_buffers`]
+    pub streaming_bulk_data: Option<FByteBulkData>,
+    /// Buffer metadata of a streamed LOD
+    pub availability_info: Option<FStaticMeshLODAvailabilityInfo>,
+    /// Buffer sizes
+    pub buffers_size: FStaticMeshBuffersSize,
+}
+
+impl FStaticMeshLODResources {
+    /// Read `FStaticMeshLODResources` from an asset
+    pub fn new<Reader: ArchiveReader<impl PackageIndexTrait>>(
+        asset: &mut Reader,
+    ) -> Result<Self, Error> {
+        let engine_version = asset.get_engine_version();
+
+        let strip_flags = FStripDataFlags::read(asset)?;
+        let sections = read_array(asset, |asset| {
+            FStaticMeshSection::read(asset, engine_version)
+        })?;
+        let max_deviation = OrderedFloat(asset.read_f32::<LE>()?);
+        let is_lod_cooked_out = read_bool(asset)?;
+        let inlined = read_bool(asset)?;
+
+        let mut buffers = None;
+        let mut streaming_bulk_data = None;
+        let mut availability_info = None;
+        if !strip_flags.is_data_stripped_for_server() && !is_lod_cooked_out {
+            match inlined {
+                true => {
+                    buffers = Some(FStaticMeshBuffers::read(
+                        asset,
+                        engine_version,
+                        sections.len(),
+                    )?)
+                }
+                false => {
+                    streaming_bulk_data = Some(FByteBulkData::new(asset)?);
+                    availability_info = Some(FStaticMeshLODAvailabilityInfo::read(asset)?);
+                }
+            }
+        }
+
+        let buffers_size = FStaticMeshBuffersSize {
+            serialized_buffers_size: asset.read_u32::<LE>()?,
+            depth_only_ib_size: asset.read_u32::<LE>()?,
+            reversed_ibs_size: asset.read_u32::<LE>()?,
+        };
+
+        Ok(FStaticMeshLODResources {
+            strip_flags,
+            sections,
+            max_deviation,
+            is_lod_cooked_out,
+            inlined,
+            buffers,
+            streaming_bulk_data,
+            availability_info,
+            buffers_size,
+        })
+    }
+
+    /// Write `FStaticMeshLODResources` to an asset
+    pub fn write<Writer: ArchiveWriter<impl PackageIndexTrait>>(
+        &self,
+        asset: &mut Writer,
+    ) -> Result<(), Error> {
+        let engine_version = asset.get_engine_version();
+
+        self.strip_flags.write(asset)?;
+        write_array(asset, &self.sections, |asset, section| {
+            section.write(asset, engine_version)
+        })?;
+        asset.write_f32::<LE>(self.max_deviation.0)?;
+        write_bool(asset, self.is_lod_cooked_out)?;
+        write_bool(asset, self.inlined)?;
+
+        if !self.strip_flags.is_data_stripped_for_server() && !self.is_lod_cooked_out {
+            match self.inlined {
+                true => {
+                    let buffers = self.buffers.as_ref().ok_or_else(|| {
+                        Error::no_data("Inlined static mesh LOD has no buffers".to_string())
+                    })?;
+                    buffers.write(asset, engine_version)?;
+                }
+                false => {
+                    let bulk_data = self.streaming_bulk_data.as_ref().ok_or_else(|| {
+                        Error::no_data("Streamed static mesh LOD has no bulk data".to_string())
+                    })?;
+                    bulk_data.write(asset)?;
+                    self.availability_info
+                        .clone()
+                        .unwrap_or_default()
+                        .write(asset)?;
+                }
+            }
+        }
+
+        asset.write_u32::<LE>(self.buffers_size.serialized_buffers_size)?;
+        asset.write_u32::<LE>(self.buffers_size.depth_only_ib_size)?;
+        asset.write_u32::<LE>(self.buffers_size.reversed_ibs_size)?;
+        Ok(())
+    }
+
+    /// Read the buffers of a streamed LOD from the payload of its bulk data
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - payload of `streaming_bulk_data`, usually stored in the `.ubulk` file
+    /// * `engine_version` - engine version of the asset
+    pub fn read_streamed_buffers(
+        &self,
+        payload: &[u8],
+        engine_version: EngineVersion,
+    ) -> Result<FStaticMeshBuffers, Error> {
+        FStaticMeshBuffers::read(
+            &mut Cursor::new(payload),
+            engine_version,
+            self.sections.len(),
+        )
+    }
+}
+
+/// Signed distance field of a LOD
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FDistanceFieldVolumeData {
+    /// Compressed distance field volume
+    pub compressed_distance_field_volume: Vec<u8>,
+    /// Volume size
+    pub size: Vector<i32>,
+    /// Bounding box minimum
+    pub local_bounding_box_min: Vector<OrderedFloat<f32>>,
+    /// Bounding box maximum
+    pub local_bounding_box_max: Vector<OrderedFloat<f32>>,
+    /// Is the bounding box valid
+    pub local_bounding_box_is_valid: bool,
+    /// Minimum and maximum distance
+    pub distance_min_max: Vector2<OrderedFloat<f32>>,
+    /// Was the mesh closed
+    pub mesh_was_closed: bool,
+    /// Was the distance field built as if the mesh was two sided
+    pub built_as_if_two_sided: bool,
+    /// Was the mesh a plane
+    pub mesh_was_plane: bool,
+}
+
+impl FDistanceFieldVolumeData {
+    /// Read `FDistanceFieldVolumeData`
+    pub fn read<R: Read>(reader: &mut R) -> Result<Self, Error> {
+        Ok(FDistanceFieldVolumeData {
+            compressed_distance_field_volume: read_array(reader, |reader| Ok(reader.read_u8()?))?,
+            size: Vector::new(
+                reader.read_i32::<LE>()?,
+                reader.read_i32::<LE>()?,
+                reader.read_i32::<LE>()?,
+            ),
+            local_bounding_box_min: read_vector(reader)?,
+            local_bounding_box_max: read_vector(reader)?,
+            local_bounding_box_is_valid: reader.read_u8()? != 0,
+            distance_min_max: Vector2::new(
+                OrderedFloat(reader.read_f32::<LE>()?),
+                OrderedFloat(reader.read_f32::<LE>()?),
+            ),
+            mesh_was_closed: read_bool(reader)?,
+            built_as_if_two_sided: read_bool(reader)?,
+            mesh_was_plane: read_bool(reader)?,
+        })
+    }
+
+    /// Write `FDistanceFieldVolumeData`
+    pub fn write<W: Write>(&self, writer: &mut W) -> Result<(), Error> {
+        write_array(
+            writer,
+            &self.compressed_distance_field_volume,
+            |writer, value| {
+                writer.write_u8(*value)?;
+                Ok(())
+            },
+        )?;
+        writer.write_i32::<LE>(self.size.x)?;
+        writer.write_i32::<LE>(self.size.y)?;
+        writer.write_i32::<LE>(self.size.z)?;
+        write_vector(writer, &self.local_bounding_box_min)?;
+        write_vector(writer, &self.local_bounding_box_max)?;
+        writer.write_u8(self.local_bounding_box_is_valid as u8)?;
+        writer.write_f32::<LE>(self.distance_min_max.x.0)?;
+        writer.write_f32::<LE>(self.distance_min_max.y.0)?;
+        write_bool(writer, self.mesh_was_closed)?;
+        write_bool(writer, self.built_as_if_two_sided)?;
+        write_bool(writer, self.mesh_was_plane)?;
+        Ok(())
+    }
+}
+
+/// Cooked render data of a static mesh
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FStaticMeshRenderData {
+    /// LODs
+    pub lods: Vec<FStaticMeshLODResources>,
+    /// Amount of LODs that are not streamed
+    pub num_inlined_lods: u8,
+    /// Distance field strip flags
+    pub strip_flags: FStripDataFlags,
+    /// Distance fields of each LOD, empty if they were stripped
+    pub distance_field_data: Vec<Option<FDistanceFieldVolumeData>>,
+    /// Bounds
+    pub bounds: FBoxSphereBounds,
+    /// Do all LODs share the static lighting of the base LOD
+    pub lods_share_static_lighting: bool,
+    /// Screen size at which each LOD is used
+    pub screen_sizes: [OrderedFloat<f32>; MAX_STATIC_MESH_LODS],
+}
+
+impl FStaticMeshRenderData {
+    /// Read `FStaticMeshRenderData` from an asset
+    pub fn new<Reader: ArchiveReader<impl PackageIndexTrait>>(
+        asset: &mut Reader,
+    ) -> Result<Self, Error> {
+        let lods = read_array(asset, FStaticMeshLODResources::new)?;
+        let num_inlined_lods = asset.read_u8()?;
+
+        let strip_flags = FStripDataFlags::read(asset)?;
+        let mut distance_field_data = Vec::new();
+        if !strip_flags.is_data_stripped_for_server() && !strip_flags.is_class_data_stripped(1) {
+            for _ in 0..lods.len() {
+                distance_field_data.push(match read_bool(asset)? {
+                    true => Some(FDistanceFieldVolumeData::read(asset)?),
+                    false => None,
+                });
+            }
+        }
+
+        let bounds = FBoxSphereBounds::read(asset)?;
+        let lods_share_static_lighting = read_bool(asset)?;
+
+        let mut screen_sizes = [OrderedFloat(0.0); MAX_STATIC_MESH_LODS];
+        for screen_size in &mut screen_sizes {
+            if !read_bool(asset)? {
+                return Err(Error::unimplemented(
+                    "Uncooked per platform screen sizes are not supported".to_string(),
+                ));
+            }
+            *screen_size = OrderedFloat(asset.read_f32::<LE>()?);
+        }
+
+        Ok(FStaticMeshRenderData {
+            lods,
+            num_inlined_lods,
+            strip_flags,
+            distance_field_data,
+            bounds,
+            lods_share_static_lighting,
+            screen_sizes,
+        })
+    }
+
+    /// Write `FStaticMeshRenderData` to an asset
+    pub fn write<Writer: ArchiveWriter<impl PackageIndexTrait>>(
+        &self,
+        asset: &mut Writer,
+    ) -> Result<(), Error> {
+        asset.write_i32::<LE>(self.lods.len() as i32)?;
+        for lod in &self.lods {
+            lod.write(asset)?;
+        }
+        asset.write_u8(self.num_inlined_lods)?;
+
+        self.strip_flags.write(asset)?;
+        if !self.strip_flags.is_data_stripped_for_server()
+            && !self.strip_flags.is_class_data_stripped(1)
+        {
+            for index in 0..self.lods.len() {
+                match self.distance_field_data.get(index).and_then(|e| e.as_ref()) {
+                    Some(distance_field_data) => {
+                        write_bool(asset, true)?;
+                        distance_field_data.write(asset)?;
+                    }
+                    None => write_bool(asset, false)?,
+                }
+            }
+        }
+
+        self.bounds.write(asset)?;
+        write_bool(asset, self.lods_share_static_lighting)?;
+
+        for screen_size in &self.screen_sizes {
+            write_bool(asset, true)?;
+            asset.write_f32::<LE>(screen_size.0)?;
+        }
+        Ok(())
+    }
+}
+
+/// Simplified mesh used for software occlusion culling
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FStaticMeshOccluderData {
+    /// Vertices
+    pub vertices: Vec<Vector<OrderedFloat<f32>>>,
+    /// Indices
+    pub indices: Vec<u16>,
+}
+
+/// Texture streaming information of a UV channel
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FMeshUVChannelInfo {
+    /// Is the info initialized
+    pub initialized: bool,
+    /// Are the densities overridden
+    pub override_densities: bool,
+    /// UV densities of each channel
+    pub local_uv_densities: [OrderedFloat<f32>; 4],
+}
+
+/// Material slot of a static mesh
+#[derive(FNameContainer, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FStaticMaterial {
+    /// Material
+    #[container_ignore]
+    pub material_interface: PackageIndex,
+    /// Slot name
+    pub material_slot_name: FName,
+    /// Texture streaming information
+    #[container_ignore]
+    pub uv_channel_data: FMeshUVChannelInfo,
+}
+
+impl FStaticMaterial {
+    /// Read an `FStaticMaterial` from an asset
+    pub fn new<Reader: ArchiveReader<impl PackageIndexTrait>>(
+        asset: &mut Reader,
+    ) -> Result<Self, Error> {
+        let material_interface = PackageIndex::new(asset.read_i32::<LE>()?);
+        let material_slot_name = asset.read_fname()?;
+
+        let initialized = read_bool(asset)?;
+        let override_densities = read_bool(asset)?;
+        let mut local_uv_densities = [OrderedFloat(0.0); 4];
+        for density in &mut local_uv_densities {
+            *density = OrderedFloat(asset.read_f32::<LE>()?);
+        }
+
+        Ok(FStaticMaterial {
+            material_interface,
+            material_slot_name,
+            uv_channel_data: FMeshUVChannelInfo {
+                initialized,
+                override_densities,
+                local_uv_densities,
+            },
+        })
+    }
+
+    /// Write an `FStaticMaterial` to an asset
+    pub fn write<Writer: ArchiveWriter<impl PackageIndexTrait>>(
+        &self,
+        asset: &mut Writer,
+    ) -> Result<(), Error> {
+        asset.write_i32::<LE>(self.material_interface.index)?;
+        asset.write_fname(&self.material_slot_name)?;
+
+        write_bool(asset, self.uv_channel_data.initialized)?;
+        write_bool(asset, self.uv_channel_data.override_densities)?;
+        for density in &self.uv_channel_data.local_uv_densities {
+            asset.write_f32::<LE>(density.0)?;
+        }
+        Ok(())
+    }
+}
+
+/// StaticMesh export
+///
+/// Only cooked meshes from UE4.23 to UE4.27 are supported,
+/// meshes from other engine versions are read as a [`NormalExport`]
+#[derive(FNameContainer, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StaticMeshExport<Index: PackageIndexTrait> {
+    /// Base normal export
+    pub normal_export: NormalExport<Index>,
+
+    /// Strip flags
+    #[container_ignore]
+    pub strip_flags: FStripDataFlags,
+    /// Body setup
+    #[container_ignore]
+    pub body_setup: PackageIndex,
+    /// Navigation collision
+    #[container_ignore]
+    pub nav_collision: PackageIndex,
+    /// Lighting guid
+    #[container_ignore]
+    pub lighting_guid: Guid,
+    /// Sockets
+    #[container_ignore]
+    pub sockets: Vec<PackageIndex>,
+    /// Render data
+    #[container_ignore]
+    pub render_data: FStaticMeshRenderData,
+    /// Occluder data
+    #[container_ignore]
+    pub occluder_data: Option<FStaticMeshOccluderData>,
+    /// Material slots
+    pub static_materials: Vec<FStaticMaterial>,
+}
+
+implement_get!(StaticMeshExport);
+
+impl<Index: PackageIndexTrait> StaticMeshExport<Index> {
+    /// Read a `StaticMeshExport` from an asset
+    pub fn from_base<Reader: ArchiveReader<Index>>(
+        base: &BaseExport<Index>,
+        asset: &mut Reader,
+    ) -> Result<Self, Error> {
+        let engine_version = asset.get_engine_version();
+        if engine_version < EngineVersion::VER_UE4_23 || engine_version >= EngineVersion::VER_UE5_0
+        {
+            return Err(Error::unimplemented(format!(
+                "Static meshes are not supported for engine version {engine_version:?}"
+            )));
+        }
+
+        let normal_export = NormalExport::from_base(base, asset)?;
+        asset.read_i32::<LE>()?;
+
+        let strip_flags = FStripDataFlags::read(asset)?;
+        if !strip_flags.is_editor_data_stripped() {
+            return Err(Error::unimplemented(
+                "Static mesh source data is not supported".to_string(),
+            ));
+        }
+        if !read_bool(asset)? {
+            return Err(Error::unimplemented(
+                "Uncooked static meshes are not supported".to_string(),
+            ));
+        }
+
+        let body_setup = PackageIndex::new(asset.read_i32::<LE>()?);
+        let nav_collision = PackageIndex::new(asset.read_i32::<LE>()?);
+        let lighting_guid = asset.read_guid()?;
+        let sockets = read_array(asset, |asset| {
+            Ok(PackageIndex::new(asset.read_i32::<LE>()?))
+        })?;
+
+        let render_data = FStaticMeshRenderData::new(asset)?;
+
+        let occluder_data = match read_bool(asset)? {
+            true => Some(FStaticMeshOccluderData {
+                vertices: read_array(asset, read_vector)?,
+                indices: read_array(asset, |asset| Ok(asset.read_u16::<LE>()?))?,
+            }),
+            false => None,
+        };
+
+        if read_bool(asset)? {
+            return Err(Error::unimplemented(
+                "Static meshes with SpeedTree wind are not supported".to_string(),
+            ));
+        }
+
+        let static_materials = read_array(asset, FStaticMaterial::new)?;
+
+        Ok(StaticMeshExport {
+            normal_export,
+            strip_flags,
+            body_setup,
+            nav_collision,
+            lighting_guid,
+            sockets,
+            render_data,
+            occluder_data,
+            static_materials,
+        })
+    }
+
+    /// Decode an inlined LOD to a [`Mesh`]
+    ///
+    /// Streamed LODs need to be read with [`FStaticMeshLODResources::read_streamed_buffers`]
+    /// and decoded with [`FStaticMeshBuffers::to_mesh`]
+    pub fn to_mesh(&self, lod_index: usize) -> Result<Mesh, Error> {
+        let lod = self
+            .render_data
+            .lods
+            .get(lod_index)
+            .ok_or_else(|| Error::no_data(format!("Static mesh has no LOD {lod_index}")))?;
+        let buffers = lod
+            .buffers
+            .as_ref()
+            .ok_or_else(|| Error::no_data(format!("Static mesh LOD {lod_index} is not inlined")))?;
+
+        Ok(buffers.to_mesh(&lod.sections, &self.static_materials))
+    }
+}
+
+impl<Index: PackageIndexTrait> ExportTrait<Index> for StaticMeshExport<Index> {
+    fn write<Writer: ArchiveWriter<Index>>(&self, asset: &mut Writer) -> Result<(), Error> {
+        self.normal_export.write(asset)?;
+        asset.write_i32::<LE>(0)?;
+
+        self.strip_flags.write(asset)?;
+        write_bool(asset, true)?;
+
+        asset.write_i32::<LE>(self.body_setup.index)?;
+        asset.write_i32::<LE>(self.nav_collision.index)?;
+        asset.write_guid(&self.lighting_guid)?;
+        write_array(asset, &self.sockets, |asset, socket| {
+            asset.write_i32::<LE>(socket.index)?;
+            Ok(())
+        })?;
+
+        self.render_data.write(asset)?;
+
+        match &self.occluder_data {
+            Some(occluder_data) => {
+                write_bool(asset, true)?;
+                write_array(asset, &occluder_data.vertices, write_vector)?;
+                write_array(asset, &occluder_data.indices, |asset, index| {
+                    asset.write_u16::<LE>(*index)?;
+                    Ok(())
+                })?;
+            }
+            None => write_bool(asset, false)?,
+        }
+
+        // no SpeedTree wind
+        write_bool(asset, false)?;
+
+        write_array(asset, &self.static_materials, |asset, material| {
+            material.write(asset)
+        })?;
+
+        Ok(())
+    }
+}
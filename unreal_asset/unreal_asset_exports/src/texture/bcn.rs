@@ -0,0 +1,753 @@
+//! Block compression (BC1-BC7) decoding
+//!
+//! Every decoder takes a single compressed block and returns the 4x4 block of RGBA pixels in row-major order
+
+/// Decoded 4x4 block of RGBA pixels
+pub type Block = [[u8; 4]; 16];
+
+/// 2 bit interpolation weights
+const WEIGHTS_2: [u32; 4] = [0, 21, 43, 64];
+/// 3 bit interpolation weights
+const WEIGHTS_3: [u32; 8] = [0, 9, 18, 27, 37, 46, 55, 64];
+/// 4 bit interpolation weights
+pub(super) const WEIGHTS_4: [u32; 16] =
+    [0, 4, 9, 13, 17, 21, 26, 30, 34, 38, 43, 47, 51, 55, 60, 64];
+
+/// Two subset partitions, a set bit means that the pixel belongs to the second subset
+const PARTITIONS_2: [u16; 64] = [
+    0xcccc, 0x8888, 0xeeee, 0xecc8, 0xc880, 0xfeec, 0xfec8, 0xec80, 0xc800, 0xffec, 0xfe80, 0xe800,
+    0xffe8, 0xff00, 0xfff0, 0xf000, 0xf710, 0x008e, 0x7100, 0x08ce, 0x008c, 0x7310, 0x3100, 0x8cce,
+    0x088c, 0x3110, 0x6666, 0x366c, 0x17e8, 0x0ff0, 0x718e, 0x399c, 0xaaaa, 0xf0f0, 0x5a5a, 0x33cc,
+    0x3c3c, 0x55aa, 0x9696, 0xa55a, 0x73ce, 0x13c8, 0x324c, 0x3bdc, 0x6996, 0xc33c, 0x9966, 0x0660,
+    0x0272, 0x04e4, 0x4e40, 0x2720, 0xc936, 0x936c, 0x39c6, 0x639c, 0x9336, 0x9cc6, 0x817e, 0xe718,
+    0xccf0, 0x0fcc, 0x7744, 0xee22,
+];
+
+/// Three subset partitions
+#[rustfmt::skip]
+const PARTITIONS_3: [[u8; 16]; 64] = [
+    [0, 0, 1, 1, 0, 0, 1, 1, 0, 2, 2, 1, 2, 2, 2, 2],
+    [0, 0, 0, 1, 0, 0, 1, 1, 2, 2, 1, 1, 2, 2, 2, 1],
+    [0, 0, 0, 0, 2, 0, 0, 1, 2, 2, 1, 1, 2, 2, 1, 1],
+    [0, 2, 2, 2, 0, 0, 2, 2, 0, 0, 1, 1, 0, 1, 1, 1],
+    [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 2, 2, 1, 1, 2, 2],
+    [0, 0, 1, 1, 0, 0, 1, 1, 0, 0, 2, 2, 0, 0, 2, 2],
+    [0, 0, 2, 2, 0, 0, 2, 2, 1, 1, 1, 1, 1, 1, 1, 1],
+    [0, 0, 1, 1, 0, 0, 1, 1, 2, 2, 1, 1, 2, 2, 1, 1],
+    [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2],
+    [0, 0, 0, 0, 1, 1, 1, 1, 1, 1, 1, 1, 2, 2, 2, 2],
+    [0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 2, 2, 2, 2],
+    [0, 0, 1, 2, 0, 0, 1, 2, 0, 0, 1, 2, 0, 0, 1, 2],
+    [0, 1, 1, 2, 0, 1, 1, 2, 0, 1, 1, 2, 0, 1, 1, 2],
+    [0, 1, 2, 2, 0, 1, 2, 2, 0, 1, 2, 2, 0, 1, 2, 2],
+    [0, 0, 1, 1, 0, 1, 1, 2, 1, 1, 2, 2, 1, 2, 2, 2],
+    [0, 0, 1, 1, 2, 0, 0, 1, 2, 2, 0, 0, 2, 2, 2, 0],
+    [0, 0, 0, 1, 0, 0, 1, 1, 0, 1, 1, 2, 1, 1, 2, 2],
+    [0, 1, 1, 1, 0, 0, 1, 1, 2, 0, 0, 1, 2, 2, 0, 0],
+    [0, 0, 0, 0, 1, 1, 2, 2, 1, 1, 2, 2, 1, 1, 2, 2],
+    [0, 0, 2, 2, 0, 0, 2, 2, 0, 0, 2, 2, 1, 1, 1, 1],
+    [0, 1, 1, 1, 0, 1, 1, 1, 0, 2, 2, 2, 0, 2, 2, 2],
+    [0, 0, 0, 1, 0, 0, 0, 1, 2, 2, 2, 1, 2, 2, 2, 1],
+    [0, 0, 0, 0, 0, 0, 1, 1, 0, 1, 2, 2, 0, 1, 2, 2],
+    [0, 0, 0, 0, 1, 1, 0, 0, 2, 2, 1, 0, 2, 2, 1, 0],
+    [0, 1, 2, 2, 0, 1, 2, 2, 0, 0, 1, 1, 0, 0, 0, 0],
+    [0, 0, 1, 2, 0, 0, 1, 2, 1, 1, 2, 2, 2, 2, 2, 2],
+    [0, 1, 1, 0, 1, 2, 2, 1, 1, 2, 2, 1, 0, 1, 1, 0],
+    [0, 0, 0, 0, 0, 1, 1, 0, 1, 2, 2, 1, 1, 2, 2, 1],
+    [0, 0, 2, 2, 1, 1, 0, 2, 1, 1, 0, 2, 0, 0, 2, 2],
+    [0, 1, 1, 0, 0, 1, 1, 0, 2, 0, 0, 2, 2, 2, 2, 2],
+    [0, 0, 1, 1, 0, 1, 2, 2, 0, 1, 2, 2, 0, 0, 1, 1],
+    [0, 0, 0, 0, 2, 0, 0, 0, 2, 2, 1, 1, 2, 2, 2, 1],
+    [0, 0, 0, 0, 0, 0, 0, 2, 1, 1, 2, 2, 1, 2, 2, 2],
+    [0, 2, 2, 2, 0, 0, 2, 2, 0, 0, 1, 2, 0, 0, 1, 1],
+    [0, 0, 1, 1, 0, 0, 1, 2, 0, 0, 2, 2, 0, 2, 2, 2],
+    [0, 1, 2, 0, 0, 1, 2, 0, 0, 1, 2, 0, 0, 1, 2, 0],
+    [0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 0, 0, 0, 0],
+    [0, 1, 2, 0, 1, 2, 0, 1, 2, 0, 1, 2, 0, 1, 2, 0],
+    [0, 1, 2, 0, 2, 0, 1, 2, 1, 2, 0, 1, 0, 1, 2, 0],
+    [0, 0, 1, 1, 2, 2, 0, 0, 1, 1, 2, 2, 0, 0, 1, 1],
+    [0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 0, 0, 0, 0, 1, 1],
+    [0, 1, 0, 1, 0, 1, 0, 1, 2, 2, 2, 2, 2, 2, 2, 2],
+    [0, 0, 0, 0, 0, 0, 0, 0, 2, 1, 2, 1, 2, 1, 2, 1],
+    [0, 0, 2, 2, 1, 1, 2, 2, 0, 0, 2, 2, 1, 1, 2, 2],
+    [0, 0, 2, 2, 0, 0, 1, 1, 0, 0, 2, 2, 0, 0, 1, 1],
+    [0, 2, 2, 0, 1, 2, 2, 1, 0, 2, 2, 0, 1, 2, 2, 1],
+    [0, 1, 0, 1, 2, 2, 2, 2, 2, 2, 2, 2, 0, 1, 0, 1],
+    [0, 0, 0, 0, 2, 1, 2, 1, 2, 1, 2, 1, 2, 1, 2, 1],
+    [0, 1, 0, 1, 0, 1, 0, 1, 0, 1, 0, 1, 2, 2, 2, 2],
+    [0, 2, 2, 2, 0, 1, 1, 1, 0, 2, 2, 2, 0, 1, 1, 1],
+    [0, 0, 0, 2, 1, 1, 1, 2, 0, 0, 0, 2, 1, 1, 1, 2],
+    [0, 0, 0, 0, 2, 1, 1, 2, 2, 1, 1, 2, 2, 1, 1, 2],
+    [0, 2, 2, 2, 0, 1, 1, 1, 0, 1, 1, 1, 0, 2, 2, 2],
+    [0, 0, 0, 2, 1, 1, 1, 2, 1, 1, 1, 2, 0, 0, 0, 2],
+    [0, 1, 1, 0, 0, 1, 1, 0, 0, 1, 1, 0, 2, 2, 2, 2],
+    [0, 0, 0, 0, 0, 0, 0, 0, 2, 1, 1, 2, 2, 1, 1, 2],
+    [0, 1, 1, 0, 0, 1, 1, 0, 2, 2, 2, 2, 2, 2, 2, 2],
+    [0, 0, 2, 2, 0, 0, 1, 1, 0, 0, 1, 1, 0, 0, 2, 2],
+    [0, 0, 2, 2, 1, 1, 2, 2, 1, 1, 2, 2, 0, 0, 2, 2],
+    [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2, 1, 1, 2],
+    [0, 0, 0, 2, 0, 0, 0, 1, 0, 0, 0, 2, 0, 0, 0, 1],
+    [0, 2, 2, 2, 1, 2, 2, 2, 0, 2, 2, 2, 1, 2, 2, 2],
+    [0, 1, 0, 1, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2],
+    [0, 1, 1, 1, 2, 0, 1, 1, 2, 2, 0, 1, 2, 2, 2, 0],
+];
+
+/// Anchor index of the second subset of two subset partitions
+#[rustfmt::skip]
+const ANCHORS_2: [u8; 64] = [
+    15, 15, 15, 15, 15, 15, 15, 15,
+    15, 15, 15, 15, 15, 15, 15, 15,
+    15, 2, 8, 2, 2, 8, 8, 15,
+    2, 8, 2, 2, 8, 8, 2, 2,
+    15, 15, 6, 8, 2, 8, 15, 15,
+    2, 8, 2, 2, 2, 15, 15, 6,
+    6, 2, 6, 8, 15, 15, 2, 2,
+    15, 15, 15, 15, 15, 2, 2, 15,
+];
+
+/// Anchor index of the second subset of three subset partitions
+#[rustfmt::skip]
+const ANCHORS_3_SECOND: [u8; 64] = [
+    3, 3, 15, 15, 8, 3, 15, 15,
+    8, 8, 6, 6, 6, 5, 3, 3,
+    3, 3, 8, 15, 3, 3, 6, 10,
+    5, 8, 8, 6, 8, 5, 15, 15,
+    8, 15, 3, 5, 6, 10, 8, 15,
+    15, 3, 15, 5, 15, 15, 15, 15,
+    3, 15, 5, 5, 5, 8, 5, 10,
+    5, 10, 8, 13, 15, 12, 3, 3,
+];
+
+/// Anchor index of the third subset of three subset partitions
+#[rustfmt::skip]
+const ANCHORS_3_THIRD: [u8; 64] = [
+    15, 8, 8, 3, 15, 15, 3, 8,
+    15, 15, 15, 15, 15, 15, 15, 8,
+    15, 8, 15, 3, 15, 8, 15, 8,
+    3, 15, 6, 10, 15, 15, 10, 8,
+    15, 3, 15, 10, 10, 8, 9, 10,
+    6, 15, 8, 15, 3, 6, 6, 8,
+    15, 3, 15, 15, 15, 15, 15, 15,
+    15, 15, 15, 15, 3, 15, 15, 8,
+];
+
+/// Reads bits of a 128 bit block starting from the least significant bit
+struct BitReader {
+    /// Block bits
+    bits: u128,
+    /// Current bit position
+    position: u32,
+}
+
+impl BitReader {
+    /// Create a new `BitReader` for a 16 byte block
+    fn new(block: &[u8]) -> Self {
+        let mut bytes = [0u8; 16];
+        bytes.copy_from_slice(&block[..16]);
+        BitReader {
+            bits: u128::from_le_bytes(bytes),
+            position: 0,
+        }
+    }
+
+    /// Read `count` bits
+    fn read(&mut self, count: u32) -> u32 {
+        if count == 0 {
+            return 0;
+        }
+        let value = (self.bits >> self.position) as u32 & (u32::MAX >> (32 - count));
+        self.position += count;
+        value
+    }
+}
+
+/// Interpolate between two endpoints
+pub(super) fn interpolate(e0: u32, e1: u32, weight: u32) -> u32 {
+    ((64 - weight) * e0 + weight * e1 + 32) >> 6
+}
+
+/// Expand a color channel with `bits` precision to 8 bits
+fn expand(value: u32, bits: u32) -> u32 {
+    (value << (8 - bits)) | (value >> (2 * bits - 8))
+}
+
+/// Convert an RGB565 color to 8 bit RGB
+pub(super) fn rgb565(color: u16) -> [u32; 3] {
+    let color = color as u32;
+    [
+        expand(color >> 11, 5),
+        expand((color >> 5) & 0x3f, 6),
+        expand(color & 0x1f, 5),
+    ]
+}
+
+/// Decode the color part of a BC1/BC2/BC3 block
+fn decode_color(block: &[u8], allow_transparent: bool) -> Block {
+    let c0 = u16::from_le_bytes([block[0], block[1]]);
+    let c1 = u16::from_le_bytes([block[2], block[3]]);
+    let indices = u32::from_le_bytes([block[4], block[5], block[6], block[7]]);
+
+    let (e0, e1) = (rgb565(c0), rgb565(c1));
+    let mut palette = [[0u8; 4]; 4];
+    for channel in 0..3 {
+        palette[0][channel] = e0[channel] as u8;
+        palette[1][channel] = e1[channel] as u8;
+        if c0 > c1 || !allow_transparent {
+            palette[2][channel] = ((2 * e0[channel] + e1[channel]) / 3) as u8;
+            palette[3][channel] = ((e0[channel] + 2 * e1[channel]) / 3) as u8;
+        } else {
+            palette[2][channel] = ((e0[channel] + e1[channel]) / 2) as u8;
+        }
+    }
+    palette[0][3] = 255;
+    palette[1][3] = 255;
+    palette[2][3] = 255;
+    palette[3][3] = match c0 > c1 || !allow_transparent {
+        true => 255,
+        false => 0,
+    };
+
+    let mut pixels = [[0u8; 4]; 16];
+    for (i, pixel) in pixels.iter_mut().enumerate() {
+        *pixel = palette[((indices >> (2 * i)) & 3) as usize];
+    }
+    pixels
+}
+
+/// Decode a BC4 style block of 8 bit values
+fn decode_channel(block: &[u8]) -> [u8; 16] {
+    let a0 = block[0] as u32;
+    let a1 = block[1] as u32;
+
+    let mut palette = [0u8; 8];
+    palette[0] = a0 as u8;
+    palette[1] = a1 as u8;
+    if a0 > a1 {
+        for (i, value) in palette.iter_mut().enumerate().skip(2) {
+            *value = (((8 - i as u32) * a0 + (i as u32 - 1) * a1) / 7) as u8;
+        }
+    } else {
+        for (i, value) in palette.iter_mut().enumerate().take(6).skip(2) {
+            *value = (((6 - i as u32) * a0 + (i as u32 - 1) * a1) / 5) as u8;
+        }
+        palette[6] = 0;
+        palette[7] = 255;
+    }
+
+    let mut index_bytes = [0u8; 8];
+    index_bytes[..6].copy_from_slice(&block[2..8]);
+    let indices = u64::from_le_bytes(index_bytes);
+
+    let mut values = [0u8; 16];
+    for (i, value) in values.iter_mut().enumerate() {
+        *value = palette[((indices >> (3 * i)) & 7) as usize];
+    }
+    values
+}
+
+/// Decode a BC1 (DXT1) block
+pub fn decode_bc1(block: &[u8]) -> Block {
+    decode_color(block, true)
+}
+
+/// Decode a BC2 (DXT3) block
+pub fn decode_bc2(block: &[u8]) -> Block {
+    let mut pixels = decode_color(&block[8..], false);
+    let alpha = u64::from_le_bytes(block[..8].try_into().unwrap_or_default());
+    for (i, pixel) in pixels.iter_mut().enumerate() {
+        pixel[3] = ((alpha >> (4 * i)) & 0xf) as u8 * 17;
+    }
+    pixels
+}
+
+/// Decode a BC3 (DXT5) block
+pub fn decode_bc3(block: &[u8]) -> Block {
+    let mut pixels = decode_color(&block[8..], false);
+    let alpha = decode_channel(block);
+    for (pixel, alpha) in pixels.iter_mut().zip(alpha) {
+        pixel[3] = alpha;
+    }
+    pixels
+}
+
+/// Decode a BC4 block, the red channel is replicated to green and blue
+pub fn decode_bc4(block: &[u8]) -> Block {
+    let mut pixels = [[0u8; 4]; 16];
+    for (pixel, red) in pixels.iter_mut().zip(decode_channel(block)) {
+        *pixel = [red, red, red, 255];
+    }
+    pixels
+}
+
+/// Decode a BC5 block, the blue channel is reconstructed as the z component of a normal
+pub fn decode_bc5(block: &[u8]) -> Block {
+    let red = decode_channel(block);
+    let green = decode_channel(&block[8..]);
+
+    let mut pixels = [[0u8; 4]; 16];
+    for (i, pixel) in pixels.iter_mut().enumerate() {
+        let x = red[i] as f32 / 127.5 - 1.0;
+        let y = green[i] as f32 / 127.5 - 1.0;
+        let z = (1.0 - x * x - y * y).max(0.0).sqrt();
+        let blue = ((z + 1.0) * 127.5).round() as u8;
+        *pixel = [red[i], green[i], blue, 255];
+    }
+    pixels
+}
+
+/// BC7 mode description
+struct Bc7Mode {
+    /// Subset count
+    subsets: usize,
+    /// Partition bits
+    partition_bits: u32,
+    /// Rotation bits
+    rotation_bits: u32,
+    /// Index selection bits
+    index_selection_bits: u32,
+    /// Color bits
+    color_bits: u32,
+    /// Alpha bits
+    alpha_bits: u32,
+    /// Each endpoint has a P-bit
+    endpoint_p_bits: bool,
+    /// Each subset has a shared P-bit
+    shared_p_bits: bool,
+    /// Primary index bits
+    index_bits: u32,
+    /// Secondary index bits
+    secondary_index_bits: u32,
+}
+
+/// BC7 modes
+#[rustfmt::skip]
+const BC7_MODES: [Bc7Mode; 8] = [
+    Bc7Mode { subsets: 3, partition_bits: 4, rotation_bits: 0, index_selection_bits: 0, color_bits: 4, alpha_bits: 0, endpoint_p_bits: true, shared_p_bits: false, index_bits: 3, secondary_index_bits: 0 },
+    Bc7Mode { subsets: 2, partition_bits: 6, rotation_bits: 0, index_selection_bits: 0, color_bits: 6, alpha_bits: 0, endpoint_p_bits: false, shared_p_bits: true, index_bits: 3, secondary_index_bits: 0 },
+    Bc7Mode { subsets: 3, partition_bits: 6, rotation_bits: 0, index_selection_bits: 0, color_bits: 5, alpha_bits: 0, endpoint_p_bits: false, shared_p_bits: false, index_bits: 2, secondary_index_bits: 0 },
+    Bc7Mode { subsets: 2, partition_bits: 6, rotation_bits: 0, index_selection_bits: 0, color_bits: 7, alpha_bits: 0, endpoint_p_bits: true, shared_p_bits: false, index_bits: 2, secondary_index_bits: 0 },
+    Bc7Mode { subsets: 1, partition_bits: 0, rotation_bits: 2, index_selection_bits: 1, color_bits: 5, alpha_bits: 6, endpoint_p_bits: false, shared_p_bits: false, index_bits: 2, secondary_index_bits: 3 },
+    Bc7Mode { subsets: 1, partition_bits: 0, rotation_bits: 2, index_selection_bits: 0, color_bits: 7, alpha_bits: 8, endpoint_p_bits: false, shared_p_bits: false, index_bits: 2, secondary_index_bits: 2 },
+    Bc7Mode { subsets: 1, partition_bits: 0, rotation_bits: 0, index_selection_bits: 0, color_bits: 7, alpha_bits: 7, endpoint_p_bits: true, shared_p_bits: false, index_bits: 4, secondary_index_bits: 0 },
+    Bc7Mode { subsets: 2, partition_bits: 6, rotation_bits: 0, index_selection_bits: 0, color_bits: 5, alpha_bits: 5, endpoint_p_bits: true, shared_p_bits: false, index_bits: 2, secondary_index_bits: 0 },
+];
+
+/// Get the interpolation weights for an index bit count
+fn weights(index_bits: u32) -> &'static [u32] {
+    match index_bits {
+        2 => &WEIGHTS_2,
+        3 => &WEIGHTS_3,
+        _ => &WEIGHTS_4,
+    }
+}
+
+/// Get the subset of a pixel
+fn subset(subsets: usize, partition: usize, pixel: usize) -> usize {
+    match subsets {
+        2 => ((PARTITIONS_2[partition] >> pixel) & 1) as usize,
+        3 => PARTITIONS_3[partition][pixel] as usize,
+        _ => 0,
+    }
+}
+
+/// Check if a pixel is the anchor of a subset, anchors are stored with one less index bit
+fn is_anchor(subsets: usize, partition: usize, pixel: usize) -> bool {
+    pixel == 0
+        || match subsets {
+            2 => ANCHORS_2[partition] as usize == pixel,
+            3 => {
+                ANCHORS_3_SECOND[partition] as usize == pixel
+                    || ANCHORS_3_THIRD[partition] as usize == pixel
+            }
+            _ => false,
+        }
+}
+
+/// Decode a BC7 block
+pub fn decode_bc7(block: &[u8]) -> Block {
+    let Some(mode_index) = (0..8).find(|mode| block[0] & (1 << mode) != 0) else {
+        return [[0u8; 4]; 16];
+    };
+    let mode = &BC7_MODES[mode_index];
+
+    let mut bits = BitReader::new(block);
+    bits.read(mode_index as u32 + 1);
+    let partition = bits.read(mode.partition_bits) as usize;
+    let rotation = bits.read(mode.rotation_bits);
+    let index_selection = bits.read(mode.index_selection_bits);
+
+    // endpoints[subset][endpoint][channel]
+    let mut endpoints = [[[0u32; 4]; 2]; 3];
+    for channel in 0..4 {
+        let channel_bits = match channel {
+            3 => mode.alpha_bits,
+            _ => mode.color_bits,
+        };
+        for subset_endpoints in endpoints.iter_mut().take(mode.subsets) {
+            for endpoint in subset_endpoints.iter_mut() {
+                endpoint[channel] = bits.read(channel_bits);
+            }
+        }
+    }
+
+    let mut color_bits = mode.color_bits;
+    let mut alpha_bits = mode.alpha_bits;
+    if mode.endpoint_p_bits || mode.shared_p_bits {
+        for subset_endpoints in endpoints.iter_mut().take(mode.subsets) {
+            let shared = match mode.shared_p_bits {
+                true => bits.read(1),
+                false => 0,
+            };
+            for endpoint in subset_endpoints.iter_mut() {
+                let p_bit = match mode.endpoint_p_bits {
+                    true => bits.read(1),
+                    false => shared,
+                };
+                for channel in endpoint.iter_mut() {
+                    *channel = (*channel << 1) | p_bit;
+                }
+            }
+        }
+        color_bits += 1;
+        if alpha_bits > 0 {
+            alpha_bits += 1;
+        }
+    }
+
+    for subset_endpoints in endpoints.iter_mut().take(mode.subsets) {
+        for endpoint in subset_endpoints.iter_mut() {
+            for channel in endpoint.iter_mut().take(3) {
+                *channel = expand(*channel, color_bits);
+            }
+            endpoint[3] = match alpha_bits {
+                0 => 255,
+                _ => expand(endpoint[3], alpha_bits),
+            };
+        }
+    }
+
+    let mut indices = [0u32; 16];
+    for (pixel, index) in indices.iter_mut().enumerate() {
+        let anchor = is_anchor(mode.subsets, partition, pixel) as u32;
+        *index = bits.read(mode.index_bits - anchor);
+    }
+
+    let mut secondary_indices = [0u32; 16];
+    if mode.secondary_index_bits > 0 {
+        for (pixel, index) in secondary_indices.iter_mut().enumerate() {
+            let anchor = (pixel == 0) as u32;
+            *index = bits.read(mode.secondary_index_bits - anchor);
+        }
+    }
+
+    let mut pixels = [[0u8; 4]; 16];
+    for (pixel_index, pixel) in pixels.iter_mut().enumerate() {
+        let [e0, e1] = endpoints[subset(mode.subsets, partition, pixel_index)];
+
+        let (color_weight, alpha_weight) = match mode.secondary_index_bits {
+            0 => {
+                let weight = weights(mode.index_bits)[indices[pixel_index] as usize];
+                (weight, weight)
+            }
+            _ => {
+                let primary = weights(mode.index_bits)[indices[pixel_index] as usize];
+                let secondary =
+                    weights(mode.secondary_index_bits)[secondary_indices[pixel_index] as usize];
+                match index_selection {
+                    0 => (primary, secondary),
+                    _ => (secondary, primary),
+                }
+            }
+        };
+
+        for channel in 0..3 {
+            pixel[channel] = interpolate(e0[channel], e1[channel], color_weight) as u8;
+        }
+        pixel[3] = interpolate(e0[3], e1[3], alpha_weight) as u8;
+
+        match rotation {
+            1 => pixel.swap(0, 3),
+            2 => pixel.swap(1, 3),
+            3 => pixel.swap(2, 3),
+            _ => {}
+        }
+    }
+    pixels
+}
+
+/// BC6H endpoint fields
+#[derive(Clone, Copy)]
+#[allow(clippy::upper_case_acronyms)]
+enum Field {
+    /// Red endpoint 0 of subset 0
+    RW,
+    /// Red endpoint 1 of subset 0
+    RX,
+    /// Red endpoint 0 of subset 1
+    RY,
+    /// Red endpoint 1 of subset 1
+    RZ,
+    /// Green endpoint 0 of subset 0
+    GW,
+    /// Green endpoint 1 of subset 0
+    GX,
+    /// Green endpoint 0 of subset 1
+    GY,
+    /// Green endpoint 1 of subset 1
+    GZ,
+    /// Blue endpoint 0 of subset 0
+    BW,
+    /// Blue endpoint 1 of subset 0
+    BX,
+    /// Blue endpoint 0 of subset 1
+    BY,
+    /// Blue endpoint 1 of subset 1
+    BZ,
+    /// Partition
+    D,
+}
+
+/// BC6H mode description
+struct Bc6hMode {
+    /// Are the endpoints other than the first one stored as deltas
+    transformed: bool,
+    /// Endpoint bits
+    endpoint_bits: u32,
+    /// Delta bits for red, green and blue
+    delta_bits: [u32; 3],
+    /// Subset count
+    subsets: usize,
+    /// Field layout as (field, first bit, bit count)
+    layout: &'static [(Field, u32, u32)],
+}
+
+use Field::*;
+
+/// BC6H modes, in the order of the mode numbers
+#[rustfmt::skip]
+const BC6H_MODES: [Bc6hMode; 14] = [
+    Bc6hMode { transformed: true, endpoint_bits: 10, delta_bits: [5, 5, 5], subsets: 2, layout: &[
+        (GY, 4, 1), (BY, 4, 1), (BZ, 4, 1), (RW, 0, 10), (GW, 0, 10), (BW, 0, 10), (RX, 0, 5), (GZ, 4, 1),
+        (GY, 0, 4), (GX, 0, 5), (BZ, 0, 1), (GZ, 0, 4), (BX, 0, 5), (BZ, 1, 1), (BY, 0, 4), (RY, 0, 5),
+        (BZ, 2, 1), (RZ, 0, 5), (BZ, 3, 1), (D, 0, 5),
+    ] },
+    Bc6hMode { transformed: true, endpoint_bits: 7, delta_bits: [6, 6, 6], subsets: 2, layout: &[
+        (GY, 5, 1), (GZ, 4, 1), (GZ, 5, 1), (RW, 0, 7), (BZ, 0, 1), (BZ, 1, 1), (BY, 4, 1), (GW, 0, 7),
+        (BY, 5, 1), (BZ, 2, 1), (GY, 4, 1), (BW, 0, 7), (BZ, 3, 1), (BZ, 5, 1), (BZ, 4, 1), (RX, 0, 6),
+        (GY, 0, 4), (GX, 0, 6), (GZ, 0, 4), (BX, 0, 6), (BY, 0, 4), (RY, 0, 6), (RZ, 0, 6), (D, 0, 5),
+    ] },
+    Bc6hMode { transformed: true, endpoint_bits: 11, delta_bits: [5, 4, 4], subsets: 2, layout: &[
+        (RW, 0, 10), (GW, 0, 10), (BW, 0, 10), (RX, 0, 5), (RW, 10, 1), (GY, 0, 4), (GX, 0, 4), (GW, 10, 1),
+        (BZ, 0, 1), (GZ, 0, 4), (BX, 0, 4), (BW, 10, 1), (BZ, 1, 1), (BY, 0, 4), (RY, 0, 5), (BZ, 2, 1),
+        (RZ, 0, 5), (BZ, 3, 1), (D, 0, 5),
+    ] },
+    Bc6hMode { transformed: true, endpoint_bits: 11, delta_bits: [4, 5, 4], subsets: 2, layout: &[
+        (RW, 0, 10), (GW, 0, 10), (BW, 0, 10), (RX, 0, 4), (RW, 10, 1), (GZ, 4, 1), (GY, 0, 4), (GX, 0, 5),
+        (GW, 10, 1), (GZ, 0, 4), (BX, 0, 4), (BW, 10, 1), (BZ, 1, 1), (BY, 0, 4), (RY, 0, 4), (BZ, 0, 1),
+        (BZ, 2, 1), (RZ, 0, 4), (GY, 4, 1), (BZ, 3, 1), (D, 0, 5),
+    ] },
+    Bc6hMode { transformed: true, endpoint_bits: 11, delta_bits: [4, 4, 5], subsets: 2, layout: &[
+        (RW, 0, 10), (GW, 0, 10), (BW, 0, 10), (RX, 0, 4), (RW, 10, 1), (BY, 4, 1), (GY, 0, 4), (GX, 0, 4),
+        (GW, 10, 1), (BZ, 0, 1), (GZ, 0, 4), (BX, 0, 5), (BW, 10, 1), (BY, 0, 4), (RY, 0, 4), (BZ, 1, 1),
+        (BZ, 2, 1), (RZ, 0, 4), (BZ, 4, 1), (BZ, 3, 1), (D, 0, 5),
+    ] },
+    Bc6hMode { transformed: true, endpoint_bits: 9, delta_bits: [5, 5, 5], subsets: 2, layout: &[
+        (RW, 0, 9), (BY, 4, 1), (GW, 0, 9), (GY, 4, 1), (BW, 0, 9), (BZ, 4, 1), (RX, 0, 5), (GZ, 4, 1),
+        (GY, 0, 4), (GX, 0, 5), (BZ, 0, 1), (GZ, 0, 4), (BX, 0, 5), (BZ, 1, 1), (BY, 0, 4), (RY, 0, 5),
+        (BZ, 2, 1), (RZ, 0, 5), (BZ, 3, 1), (D, 0, 5),
+    ] },
+    Bc6hMode { transformed: true, endpoint_bits: 8, delta_bits: [6, 5, 5], subsets: 2, layout: &[
+        (RW, 0, 8), (GZ, 4, 1), (BY, 4, 1), (GW, 0, 8), (BZ, 2, 1), (GY, 4, 1), (BW, 0, 8), (BZ, 3, 1),
+        (BZ, 4, 1), (RX, 0, 6), (GY, 0, 4), (GX, 0, 5), (BZ, 0, 1), (GZ, 0, 4), (BX, 0, 5), (BZ, 1, 1),
+        (BY, 0, 4), (RY, 0, 6), (RZ, 0, 6), (D, 0, 5),
+    ] },
+    Bc6hMode { transformed: true, endpoint_bits: 8, delta_bits: [5, 6, 5], subsets: 2, layout: &[
+        (RW, 0, 8), (BZ, 0, 1), (BY, 4, 1), (GW, 0, 8), (GY, 5, 1), (GY, 4, 1), (BW, 0, 8), (GZ, 5, 1),
+        (BZ, 4, 1), (RX, 0, 5), (GZ, 4, 1), (GY, 0, 4), (GX, 0, 6), (GZ, 0, 4), (BX, 0, 5), (BZ, 1, 1),
+        (BY, 0, 4), (RY, 0, 5), (BZ, 2, 1), (RZ, 0, 5), (BZ, 3, 1), (D, 0, 5),
+    ] },
+    Bc6hMode { transformed: true, endpoint_bits: 8, delta_bits: [5, 5, 6], subsets: 2, layout: &[
+        (RW, 0, 8), (BZ, 1, 1), (BY, 4, 1), (GW, 0, 8), (BY, 5, 1), (GY, 4, 1), (BW, 0, 8), (BZ, 5, 1),
+        (BZ, 4, 1), (RX, 0, 5), (GZ, 4, 1), (GY, 0, 4), (GX, 0, 5), (BZ, 0, 1), (GZ, 0, 4), (BX, 0, 6),
+        (BY, 0, 4), (RY, 0, 5), (BZ, 2, 1), (RZ, 0, 5), (BZ, 3, 1), (D, 0, 5),
+    ] },
+    Bc6hMode { transformed: false, endpoint_bits: 6, delta_bits: [6, 6, 6], subsets: 2, layout: &[
+        (RW, 0, 6), (GZ, 4, 1), (BZ, 0, 1), (BZ, 1, 1), (BY, 4, 1), (GW, 0, 6), (GY, 5, 1), (BY, 5, 1),
+        (BZ, 2, 1), (GY, 4, 1), (BW, 0, 6), (GZ, 5, 1), (BZ, 3, 1), (BZ, 5, 1), (BZ, 4, 1), (RX, 0, 6),
+        (GY, 0, 4), (GX, 0, 6), (GZ, 0, 4), (BX, 0, 6), (BY, 0, 4), (RY, 0, 6), (RZ, 0, 6), (D, 0, 5),
+    ] },
+    Bc6hMode { transformed: false, endpoint_bits: 10, delta_bits: [10, 10, 10], subsets: 1, layout: &[
+        (RW, 0, 10), (GW, 0, 10), (BW, 0, 10), (RX, 0, 10), (GX, 0, 10), (BX, 0, 10),
+    ] },
+    Bc6hMode { transformed: true, endpoint_bits: 11, delta_bits: [9, 9, 9], subsets: 1, layout: &[
+        (RW, 0, 10), (GW, 0, 10), (BW, 0, 10), (RX, 0, 9), (RW, 10, 1), (GX, 0, 9), (GW, 10, 1), (BX, 0, 9),
+        (BW, 10, 1),
+    ] },
+    Bc6hMode { transformed: true, endpoint_bits: 12, delta_bits: [8, 8, 8], subsets: 1, layout: &[
+        (RW, 0, 10), (GW, 0, 10), (BW, 0, 10), (RX, 0, 8), (RW, 11, 1), (RW, 10, 1), (GX, 0, 8), (GW, 11, 1),
+        (GW, 10, 1), (BX, 0, 8), (BW, 11, 1), (BW, 10, 1),
+    ] },
+    Bc6hMode { transformed: true, endpoint_bits: 16, delta_bits: [4, 4, 4], subsets: 1, layout: &[
+        (RW, 0, 10), (GW, 0, 10), (BW, 0, 10), (RX, 0, 4), (RW, 15, 1), (RW, 14, 1), (RW, 13, 1), (RW, 12, 1),
+        (RW, 11, 1), (RW, 10, 1), (GX, 0, 4), (GW, 15, 1), (GW, 14, 1), (GW, 13, 1), (GW, 12, 1), (GW, 11, 1),
+        (GW, 10, 1), (BX, 0, 4), (BW, 15, 1), (BW, 14, 1), (BW, 13, 1), (BW, 12, 1), (BW, 11, 1), (BW, 10, 1),
+    ] },
+];
+
+/// Sign extend a value with `bits` bits
+fn sign_extend(value: u32, bits: u32) -> i32 {
+    let shift = 32 - bits;
+    ((value << shift) as i32) >> shift
+}
+
+/// Unquantize an unsigned BC6H endpoint component
+fn unquantize_bc6h(value: u32, bits: u32) -> u32 {
+    if bits >= 15 || value == 0 {
+        value
+    } else if value == (1 << bits) - 1 {
+        0xffff
+    } else {
+        ((value << 16) + 0x8000) >> bits
+    }
+}
+
+/// Convert a half float to a float
+pub(crate) fn half_to_f32(half: u16) -> f32 {
+    let sign = (half >> 15) as u32;
+    let exponent = ((half >> 10) & 0x1f) as u32;
+    let mantissa = (half & 0x3ff) as u32;
+
+    let bits = match exponent {
+        0 if mantissa == 0 => sign << 31,
+        0 => {
+            // subnormal, normalize it
+            let mut exponent = 127 - 15 + 1;
+            let mut mantissa = mantissa;
+            while mantissa & 0x400 == 0 {
+                mantissa <<= 1;
+                exponent -= 1;
+            }
+            (sign << 31) | (exponent << 23) | ((mantissa & 0x3ff) << 13)
+        }
+        0x1f => (sign << 31) | (0xff << 23) | (mantissa << 13),
+        _ => (sign << 31) | ((exponent + 127 - 15) << 23) | (mantissa << 13),
+    };
+    f32::from_bits(bits)
+}
+
+/// Convert a float to a half float, rounding to the nearest representable value
+pub(crate) fn f32_to_half(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = (bits >> 16) & 0x8000;
+    let float_exponent = (bits >> 23) & 0xff;
+    let mantissa = bits & 0x7fffff;
+
+    if float_exponent == 0xff {
+        // infinity or nan, nan keeps the high bits of its payload and stays nan
+        let payload = mantissa >> 13;
+        return (sign | 0x7c00 | payload | (mantissa != 0 && payload == 0) as u32) as u16;
+    }
+
+    let half = match float_exponent as i32 - 127 + 15 {
+        e if e >= 0x1f => sign | 0x7c00,
+        e if e < -10 => sign,
+        e if e <= 0 => {
+            // subnormal, the implicit bit becomes part of the mantissa
+            let mantissa = mantissa | 0x800000;
+            let shift = (14 - e) as u32;
+            sign | ((mantissa >> shift) + ((mantissa >> (shift - 1)) & 1))
+        }
+        // round to nearest, a mantissa overflow correctly carries into the exponent
+        e => (sign | ((e as u32) << 10) | (mantissa >> 13)) + ((mantissa >> 12) & 1),
+    };
+    half as u16
+}
+
+/// Convert a linear float to an 8 bit channel value, clamping it to the 0..1 range
+pub(crate) fn f32_to_u8(value: f32) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+/// Decode an unsigned BC6H block, HDR values are clamped to the 0..1 range
+pub fn decode_bc6h(block: &[u8]) -> Block {
+    let mut bits = BitReader::new(block);
+    let mut mode_bits = bits.read(2);
+    if mode_bits > 1 {
+        mode_bits |= bits.read(3) << 2;
+    }
+
+    let mode_index = match mode_bits {
+        0 => 0,
+        1 => 1,
+        2 => 2,
+        6 => 3,
+        10 => 4,
+        14 => 5,
+        18 => 6,
+        22 => 7,
+        26 => 8,
+        30 => 9,
+        3 => 10,
+        7 => 11,
+        11 => 12,
+        15 => 13,
+        // reserved modes decode to black
+        _ => return [[0, 0, 0, 255]; 16],
+    };
+    let mode = &BC6H_MODES[mode_index];
+
+    let mut fields = [0u32; 13];
+    for &(field, first_bit, count) in mode.layout {
+        fields[field as usize] |= bits.read(count) << first_bit;
+    }
+    let partition = fields[D as usize] as usize;
+
+    // endpoints[endpoint][channel], endpoints are ordered w, x, y, z
+    let mut endpoints = [[0u32; 3]; 4];
+    for (channel, first_field) in [RW, GW, BW].into_iter().enumerate() {
+        for (endpoint, values) in endpoints.iter_mut().enumerate() {
+            values[channel] = fields[first_field as usize + endpoint];
+        }
+    }
+
+    let endpoint_count = mode.subsets * 2;
+    if mode.transformed {
+        let mask = (1u32 << mode.endpoint_bits) - 1;
+        let base = endpoints[0];
+        for endpoint in endpoints.iter_mut().take(endpoint_count).skip(1) {
+            for (channel, value) in endpoint.iter_mut().enumerate().take(3) {
+                let delta = sign_extend(*value, mode.delta_bits[channel]);
+                *value = (base[channel] as i32 + delta) as u32 & mask;
+            }
+        }
+    }
+
+    for endpoint in endpoints.iter_mut().take(endpoint_count) {
+        for channel in endpoint.iter_mut() {
+            *channel = unquantize_bc6h(*channel, mode.endpoint_bits);
+        }
+    }
+
+    let index_bits = match mode.subsets {
+        1 => 4,
+        _ => 3,
+    };
+    let mut pixels = [[0u8; 4]; 16];
+    for (pixel_index, pixel) in pixels.iter_mut().enumerate() {
+        let anchor = is_anchor(mode.subsets, partition, pixel_index) as u32;
+        let weight = weights(index_bits)[bits.read(index_bits - anchor) as usize];
+        let subset = subset(mode.subsets, partition, pixel_index);
+
+        for channel in 0..3 {
+            let value = interpolate(
+                endpoints[subset * 2][channel],
+                endpoints[subset * 2 + 1][channel],
+                weight,
+            );
+            let half = ((value * 31) >> 6) as u16;
+            pixel[channel] = f32_to_u8(half_to_f32(half));
+        }
+        pixel[3] = 255;
+    }
+    pixels
+}
@@ -0,0 +1,282 @@
+//! Block compression (BC1-BC5, BC7) encoding
+//!
+//! Every encoder takes a 4x4 block of RGBA pixels in row-major order and returns the compressed block,
+//! endpoints are fitted to the range of the block along its principal axis
+
+use super::bcn::{interpolate, rgb565, Block, WEIGHTS_4};
+
+/// Writes bits of a 128 bit block starting from the least significant bit
+struct BitWriter {
+    /// Block bits
+    bits: u128,
+    /// Current bit position
+    position: u32,
+}
+
+impl BitWriter {
+    /// Create a new `BitWriter`
+    fn new() -> Self {
+        BitWriter {
+            bits: 0,
+            position: 0,
+        }
+    }
+
+    /// Write the lowest `count` bits of `value`
+    fn write(&mut self, value: u32, count: u32) {
+        let mask = (1u128 << count) - 1;
+        self.bits |= (value as u128 & mask) << self.position;
+        self.position += count;
+    }
+}
+
+/// Fit two endpoints to the range of points along their principal axis
+fn fit_endpoints<const N: usize>(points: &[[f32; N]]) -> ([f32; N], [f32; N]) {
+    let count = points.len().max(1) as f32;
+    let mut mean = [0f32; N];
+    for point in points {
+        for (mean, value) in mean.iter_mut().zip(point) {
+            *mean += value / count;
+        }
+    }
+
+    let mut covariance = [[0f32; N]; N];
+    let mut axis = [0f32; N];
+    let mut farthest = 0f32;
+    for point in points {
+        let delta: [f32; N] = std::array::from_fn(|i| point[i] - mean[i]);
+        for (row, a) in covariance.iter_mut().zip(delta) {
+            for (value, b) in row.iter_mut().zip(delta) {
+                *value += a * b;
+            }
+        }
+
+        // the farthest point always has a component along the principal axis
+        let distance = delta.iter().map(|e| e * e).sum::<f32>();
+        if distance > farthest {
+            farthest = distance;
+            axis = delta;
+        }
+    }
+    if farthest < f32::EPSILON {
+        return (mean, mean);
+    }
+
+    // power iteration
+    for _ in 0..8 {
+        let next: [f32; N] =
+            std::array::from_fn(|i| covariance[i].iter().zip(axis).map(|(c, a)| c * a).sum());
+        let length = next.iter().map(|e| e * e).sum::<f32>().sqrt();
+        if length < f32::EPSILON {
+            break;
+        }
+        axis = next.map(|e| e / length);
+    }
+
+    let (mut min, mut max) = (f32::MAX, f32::MIN);
+    for point in points {
+        let projection = point
+            .iter()
+            .zip(mean)
+            .zip(axis)
+            .map(|((point, mean), axis)| (point - mean) * axis)
+            .sum::<f32>();
+        min = min.min(projection);
+        max = max.max(projection);
+    }
+
+    (
+        std::array::from_fn(|i| (mean[i] + axis[i] * min).clamp(0.0, 255.0)),
+        std::array::from_fn(|i| (mean[i] + axis[i] * max).clamp(0.0, 255.0)),
+    )
+}
+
+/// Find the index of the palette entry closest to a color
+fn nearest<const N: usize>(palette: &[[u32; N]], color: [u32; N]) -> u32 {
+    let mut best = (0, u32::MAX);
+    for (index, entry) in palette.iter().enumerate() {
+        let error = entry
+            .iter()
+            .zip(color)
+            .map(|(a, b)| a.abs_diff(b).pow(2))
+            .sum::<u32>();
+        if error < best.1 {
+            best = (index as u32, error);
+        }
+    }
+    best.0
+}
+
+/// Quantize an 8 bit RGB color to RGB565
+fn to_rgb565(color: [f32; 3]) -> u16 {
+    let red = (color[0] * 31.0 / 255.0).round() as u16;
+    let green = (color[1] * 63.0 / 255.0).round() as u16;
+    let blue = (color[2] * 31.0 / 255.0).round() as u16;
+    (red << 11) | (green << 5) | blue
+}
+
+/// Encode the color part of a BC1/BC2/BC3 block
+fn encode_color(pixels: &Block, allow_transparent: bool) -> [u8; 8] {
+    let transparent = allow_transparent && pixels.iter().any(|e| e[3] < 128);
+    let points = pixels
+        .iter()
+        .filter(|e| !transparent || e[3] >= 128)
+        .map(|e| [e[0] as f32, e[1] as f32, e[2] as f32])
+        .collect::<Vec<_>>();
+
+    let (start, end) = fit_endpoints(&points);
+    let (mut c0, mut c1) = (to_rgb565(start), to_rgb565(end));
+    // four color mode is selected by c0 > c1, three color mode with transparency by c0 <= c1
+    if (transparent && c0 > c1) || (!transparent && c0 < c1) {
+        std::mem::swap(&mut c0, &mut c1);
+    }
+
+    let (e0, e1) = (rgb565(c0), rgb565(c1));
+    let mut palette = vec![e0, e1];
+    if c0 > c1 || !allow_transparent {
+        palette.push(std::array::from_fn(|i| (2 * e0[i] + e1[i]) / 3));
+        palette.push(std::array::from_fn(|i| (e0[i] + 2 * e1[i]) / 3));
+    } else {
+        palette.push(std::array::from_fn(|i| (e0[i] + e1[i]) / 2));
+    }
+
+    let mut indices = 0u32;
+    for (i, pixel) in pixels.iter().enumerate() {
+        let index = match transparent && pixel[3] < 128 {
+            true => 3,
+            false => nearest(
+                &palette,
+                [pixel[0] as u32, pixel[1] as u32, pixel[2] as u32],
+            ),
+        };
+        indices |= index << (2 * i);
+    }
+
+    let mut block = [0u8; 8];
+    block[..2].copy_from_slice(&c0.to_le_bytes());
+    block[2..4].copy_from_slice(&c1.to_le_bytes());
+    block[4..].copy_from_slice(&indices.to_le_bytes());
+    block
+}
+
+/// Encode a BC4 style block of 8 bit values
+fn encode_channel(values: [u8; 16]) -> [u8; 8] {
+    let max = values.iter().copied().max().unwrap_or_default() as u32;
+    let min = values.iter().copied().min().unwrap_or_default() as u32;
+
+    // eight value mode is selected by a0 > a1, equal endpoints only need the first entry
+    let mut palette = vec![[max], [min]];
+    if max > min {
+        for i in 2..8 {
+            palette.push([((8 - i) * max + (i - 1) * min) / 7]);
+        }
+    } else {
+        palette.truncate(1);
+    }
+
+    let mut indices = 0u64;
+    for (i, value) in values.iter().enumerate() {
+        indices |= (nearest(&palette, [*value as u32]) as u64) << (3 * i);
+    }
+
+    let mut block = [0u8; 8];
+    block[0] = max as u8;
+    block[1] = min as u8;
+    block[2..].copy_from_slice(&indices.to_le_bytes()[..6]);
+    block
+}
+
+/// Get a single channel of a block
+fn channel(pixels: &Block, channel: usize) -> [u8; 16] {
+    pixels.map(|e| e[channel])
+}
+
+/// Encode a BC1 (DXT1) block, pixels with an alpha below 128 become transparent
+pub fn encode_bc1(pixels: &Block) -> [u8; 8] {
+    encode_color(pixels, true)
+}
+
+/// Encode a BC2 (DXT3) block
+pub fn encode_bc2(pixels: &Block) -> [u8; 16] {
+    let mut alpha = 0u64;
+    for (i, pixel) in pixels.iter().enumerate() {
+        alpha |= ((pixel[3] as u64 * 15 + 127) / 255) << (4 * i);
+    }
+
+    let mut block = [0u8; 16];
+    block[..8].copy_from_slice(&alpha.to_le_bytes());
+    block[8..].copy_from_slice(&encode_color(pixels, false));
+    block
+}
+
+/// Encode a BC3 (DXT5) block
+pub fn encode_bc3(pixels: &Block) -> [u8; 16] {
+    let mut block = [0u8; 16];
+    block[..8].copy_from_slice(&encode_channel(channel(pixels, 3)));
+    block[8..].copy_from_slice(&encode_color(pixels, false));
+    block
+}
+
+/// Encode a BC4 block from the red channel
+pub fn encode_bc4(pixels: &Block) -> [u8; 8] {
+    encode_channel(channel(pixels, 0))
+}
+
+/// Encode a BC5 block from the red and green channels
+pub fn encode_bc5(pixels: &Block) -> [u8; 16] {
+    let mut block = [0u8; 16];
+    block[..8].copy_from_slice(&encode_channel(channel(pixels, 0)));
+    block[8..].copy_from_slice(&encode_channel(channel(pixels, 1)));
+    block
+}
+
+/// Quantize an 8 bit RGBA endpoint to 7 bits per channel and a shared p-bit
+fn quantize_bc7(color: [f32; 4]) -> ([u32; 4], u32) {
+    let mut best = ([0; 4], 0, f32::MAX);
+    for p_bit in 0..2 {
+        let quantized = color.map(|e| ((e - p_bit as f32) / 2.0).round().clamp(0.0, 127.0) as u32);
+        let error = quantized
+            .iter()
+            .zip(color)
+            .map(|(quantized, color)| (((quantized << 1) | p_bit) as f32 - color).powi(2))
+            .sum::<f32>();
+        if error < best.2 {
+            best = (quantized, p_bit, error);
+        }
+    }
+    (best.0, best.1)
+}
+
+/// Encode a BC7 block using mode 6
+pub fn encode_bc7(pixels: &Block) -> [u8; 16] {
+    let points = pixels.map(|e| e.map(|e| e as f32));
+    let (start, end) = fit_endpoints(&points);
+    let mut endpoints = [quantize_bc7(start), quantize_bc7(end)];
+
+    let expanded = endpoints.map(|(color, p_bit)| color.map(|e| (e << 1) | p_bit));
+    let palette = WEIGHTS_4.map(|weight| {
+        std::array::from_fn::<u32, 4, _>(|i| interpolate(expanded[0][i], expanded[1][i], weight))
+    });
+    let mut indices = pixels.map(|e| nearest(&palette, e.map(|e| e as u32)));
+
+    // the most significant bit of the first index is implicitly zero
+    if indices[0] >= 8 {
+        endpoints.swap(0, 1);
+        indices = indices.map(|e| 15 - e);
+    }
+
+    let mut bits = BitWriter::new();
+    bits.write(1 << 6, 7);
+    for channel in 0..4 {
+        for (color, _) in &endpoints {
+            bits.write(color[channel], 7);
+        }
+    }
+    for (_, p_bit) in &endpoints {
+        bits.write(*p_bit, 1);
+    }
+    for (i, index) in indices.iter().enumerate() {
+        bits.write(*index, if i == 0 { 3 } else { 4 });
+    }
+    bits.bits.to_le_bytes()
+}
@@ -0,0 +1,245 @@
+//! Texture pixel data decoding and encoding
+
+use unreal_asset_base::Error;
+
+pub mod bcn;
+pub mod bcn_encode;
+pub mod pixel_format;
+pub mod png;
+
+pub use pixel_format::EPixelFormat;
+
+/// Image with 8 bit RGBA pixels
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RgbaImage {
+    /// Width
+    pub width: u32,
+    /// Height
+    pub height: u32,
+    /// Pixels in row-major order
+    pub pixels: Vec<u8>,
+}
+
+impl RgbaImage {
+    /// Create a new `RgbaImage` instance
+    pub fn new(width: u32, height: u32, pixels: Vec<u8>) -> Self {
+        RgbaImage {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// Decode an image from a PNG file
+    pub fn from_png(data: &[u8]) -> Result<Self, Error> {
+        png::decode_rgba(data)
+    }
+
+    /// Encode this image as a PNG file
+    pub fn to_png(&self) -> Result<Vec<u8>, Error> {
+        png::encode_rgba(self.width, self.height, &self.pixels)
+    }
+
+    /// Get the color of a pixel, coordinates outside of the image are clamped to its edges
+    fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let x = x.min(self.width.saturating_sub(1)) as usize;
+        let y = y.min(self.height.saturating_sub(1)) as usize;
+        let offset = (y * self.width as usize + x) * 4;
+        [
+            self.pixels[offset],
+            self.pixels[offset + 1],
+            self.pixels[offset + 2],
+            self.pixels[offset + 3],
+        ]
+    }
+
+    /// Create the next mip of this image by averaging each 2x2 block of pixels
+    pub fn downsample(&self) -> RgbaImage {
+        let width = (self.width / 2).max(1);
+        let height = (self.height / 2).max(1);
+
+        let mut pixels = Vec::with_capacity(width as usize * height as usize * 4);
+        for y in 0..height {
+            for x in 0..width {
+                let mut sum = [0u32; 4];
+                let mut count = 0;
+                for source_y in y * 2..(y * 2 + 2).min(self.height) {
+                    for source_x in x * 2..(x * 2 + 2).min(self.width) {
+                        for (sum, value) in sum.iter_mut().zip(self.pixel(source_x, source_y)) {
+                            *sum += value as u32;
+                        }
+                        count += 1;
+                    }
+                }
+                pixels.extend(sum.map(|e| ((e + count / 2) / count.max(1)) as u8));
+            }
+        }
+
+        RgbaImage::new(width, height, pixels)
+    }
+}
+
+/// Decode pixel data of a single mip to an [`RgbaImage`]
+///
+/// # Arguments
+///
+/// * `pixel_format` - pixel format of the data
+/// * `width` - mip width
+/// * `height` - mip height
+/// * `data` - mip data
+pub fn decode(
+    pixel_format: EPixelFormat,
+    width: u32,
+    height: u32,
+    data: &[u8],
+) -> Result<RgbaImage, Error> {
+    let expected_size = pixel_format.mip_size(width, height);
+    if data.len() < expected_size {
+        return Err(Error::invalid_file(format!(
+            "Expected {expected_size} bytes of {pixel_format} data for a {width}x{height} mip, got {}",
+            data.len()
+        )));
+    }
+
+    let block_decoder: Option<fn(&[u8]) -> bcn::Block> = match pixel_format {
+        EPixelFormat::PF_DXT1 => Some(bcn::decode_bc1),
+        EPixelFormat::PF_DXT3 => Some(bcn::decode_bc2),
+        EPixelFormat::PF_DXT5 => Some(bcn::decode_bc3),
+        EPixelFormat::PF_BC4 => Some(bcn::decode_bc4),
+        EPixelFormat::PF_BC5 => Some(bcn::decode_bc5),
+        EPixelFormat::PF_BC6H => Some(bcn::decode_bc6h),
+        EPixelFormat::PF_BC7 => Some(bcn::decode_bc7),
+        _ => None,
+    };
+
+    let mut pixels = vec![0u8; width as usize * height as usize * 4];
+    if let Some(decode_block) = block_decoder {
+        let blocks_x = width.div_ceil(4).max(1) as usize;
+        let block_bytes = pixel_format.block_bytes();
+        for (block_index, block) in data[..expected_size].chunks_exact(block_bytes).enumerate() {
+            let block_x = (block_index % blocks_x) * 4;
+            let block_y = (block_index / blocks_x) * 4;
+
+            for (pixel_index, pixel) in decode_block(block).iter().enumerate() {
+                let x = block_x + pixel_index % 4;
+                let y = block_y + pixel_index / 4;
+                if x < width as usize && y < height as usize {
+                    let offset = (y * width as usize + x) * 4;
+                    pixels[offset..offset + 4].copy_from_slice(pixel);
+                }
+            }
+        }
+    } else {
+        let block_bytes = pixel_format.block_bytes();
+        for (pixel, source) in pixels
+            .chunks_exact_mut(4)
+            .zip(data.chunks_exact(block_bytes))
+        {
+            match pixel_format {
+                EPixelFormat::PF_B8G8R8A8 => {
+                    pixel.copy_from_slice(&[source[2], source[1], source[0], source[3]])
+                }
+                EPixelFormat::PF_R8G8B8A8 => pixel.copy_from_slice(source),
+                EPixelFormat::PF_G8 => {
+                    pixel.copy_from_slice(&[source[0], source[0], source[0], 255])
+                }
+                EPixelFormat::PF_G16 => {
+                    pixel.copy_from_slice(&[source[1], source[1], source[1], 255])
+                }
+                EPixelFormat::PF_FloatRGBA => {
+                    for (channel, half) in pixel.iter_mut().zip(source.chunks_exact(2)) {
+                        *channel = bcn::f32_to_u8(bcn::half_to_f32(u16::from_le_bytes([
+                            half[0], half[1],
+                        ])));
+                    }
+                }
+                EPixelFormat::PF_A32B32G32R32F => {
+                    for (channel, float) in pixel.iter_mut().zip(source.chunks_exact(4)) {
+                        *channel = bcn::f32_to_u8(f32::from_le_bytes([
+                            float[0], float[1], float[2], float[3],
+                        ]));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    Ok(RgbaImage::new(width, height, pixels))
+}
+
+/// Encode an [`RgbaImage`] to pixel data of a single mip
+///
+/// Block compressed formats are padded by repeating the edge pixels
+///
+/// # Arguments
+///
+/// * `pixel_format` - pixel format to encode to
+/// * `image` - mip image
+pub fn encode(pixel_format: EPixelFormat, image: &RgbaImage) -> Result<Vec<u8>, Error> {
+    let expected_size = image.width as usize * image.height as usize * 4;
+    if image.pixels.len() != expected_size || expected_size == 0 {
+        return Err(Error::invalid_file(format!(
+            "Expected {expected_size} bytes of pixel data for a {}x{} image, got {}",
+            image.width,
+            image.height,
+            image.pixels.len()
+        )));
+    }
+
+    let mut data = Vec::with_capacity(pixel_format.mip_size(image.width, image.height));
+    if pixel_format.is_block_compressed() {
+        for block_y in (0..image.height).step_by(4) {
+            for block_x in (0..image.width).step_by(4) {
+                let mut block: bcn::Block = [[0u8; 4]; 16];
+                for (i, pixel) in block.iter_mut().enumerate() {
+                    *pixel = image.pixel(block_x + i as u32 % 4, block_y + i as u32 / 4);
+                }
+
+                match pixel_format {
+                    EPixelFormat::PF_DXT1 => data.extend(bcn_encode::encode_bc1(&block)),
+                    EPixelFormat::PF_DXT3 => data.extend(bcn_encode::encode_bc2(&block)),
+                    EPixelFormat::PF_DXT5 => data.extend(bcn_encode::encode_bc3(&block)),
+                    EPixelFormat::PF_BC4 => data.extend(bcn_encode::encode_bc4(&block)),
+                    EPixelFormat::PF_BC5 => data.extend(bcn_encode::encode_bc5(&block)),
+                    EPixelFormat::PF_BC7 => data.extend(bcn_encode::encode_bc7(&block)),
+                    _ => {
+                        return Err(Error::unimplemented(format!(
+                            "Encoding {pixel_format} is not supported"
+                        )))
+                    }
+                }
+            }
+        }
+    } else {
+        for pixel in image.pixels.chunks_exact(4) {
+            let [red, green, blue, alpha] = [pixel[0], pixel[1], pixel[2], pixel[3]];
+            let luminance =
+                ((red as u32 * 77 + green as u32 * 150 + blue as u32 * 29 + 128) >> 8) as u8;
+
+            match pixel_format {
+                EPixelFormat::PF_B8G8R8A8 => data.extend([blue, green, red, alpha]),
+                EPixelFormat::PF_R8G8B8A8 => data.extend(pixel),
+                EPixelFormat::PF_G8 => data.push(luminance),
+                EPixelFormat::PF_G16 => data.extend((luminance as u16 * 257).to_le_bytes()),
+                EPixelFormat::PF_FloatRGBA => {
+                    for channel in pixel {
+                        data.extend(bcn::f32_to_half(*channel as f32 / 255.0).to_le_bytes());
+                    }
+                }
+                EPixelFormat::PF_A32B32G32R32F => {
+                    for channel in pixel {
+                        data.extend((*channel as f32 / 255.0).to_le_bytes());
+                    }
+                }
+                _ => {
+                    return Err(Error::unimplemented(format!(
+                        "Encoding {pixel_format} is not supported"
+                    )))
+                }
+            }
+        }
+    }
+
+    Ok(data)
+}
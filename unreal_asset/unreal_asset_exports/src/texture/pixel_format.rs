@@ -0,0 +1,142 @@
+//! Pixel formats
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use unreal_asset_base::Error;
+
+/// Texture pixel format
+///
+/// Only the pixel formats that are commonly used by cooked textures are listed here
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[allow(non_camel_case_types)]
+pub enum EPixelFormat {
+    /// 32 bit float RGBA
+    PF_A32B32G32R32F,
+    /// 8 bit BGRA
+    PF_B8G8R8A8,
+    /// 8 bit grayscale
+    PF_G8,
+    /// 16 bit grayscale
+    PF_G16,
+    /// BC1 compressed RGB with 1 bit alpha
+    PF_DXT1,
+    /// BC2 compressed RGBA with explicit alpha
+    PF_DXT3,
+    /// BC3 compressed RGBA with interpolated alpha
+    PF_DXT5,
+    /// 16 bit float RGBA
+    PF_FloatRGBA,
+    /// BC5 compressed two channel data, usually normal maps
+    PF_BC5,
+    /// 8 bit RGBA
+    PF_R8G8B8A8,
+    /// BC4 compressed single channel data
+    PF_BC4,
+    /// BC6H compressed HDR RGB
+    PF_BC6H,
+    /// BC7 compressed RGBA
+    PF_BC7,
+}
+
+impl EPixelFormat {
+    /// Get the pixel format name as it's serialized in an asset
+    pub fn name(&self) -> &'static str {
+        match self {
+            EPixelFormat::PF_A32B32G32R32F => "PF_A32B32G32R32F",
+            EPixelFormat::PF_B8G8R8A8 => "PF_B8G8R8A8",
+            EPixelFormat::PF_G8 => "PF_G8",
+            EPixelFormat::PF_G16 => "PF_G16",
+            EPixelFormat::PF_DXT1 => "PF_DXT1",
+            EPixelFormat::PF_DXT3 => "PF_DXT3",
+            EPixelFormat::PF_DXT5 => "PF_DXT5",
+            EPixelFormat::PF_FloatRGBA => "PF_FloatRGBA",
+            EPixelFormat::PF_BC5 => "PF_BC5",
+            EPixelFormat::PF_R8G8B8A8 => "PF_R8G8B8A8",
+            EPixelFormat::PF_BC4 => "PF_BC4",
+            EPixelFormat::PF_BC6H => "PF_BC6H",
+            EPixelFormat::PF_BC7 => "PF_BC7",
+        }
+    }
+
+    /// Get the size of a block in pixels, uncompressed formats have 1x1 blocks
+    pub fn block_size(&self) -> u32 {
+        match self.is_block_compressed() {
+            true => 4,
+            false => 1,
+        }
+    }
+
+    /// Get the size of a block in bytes
+    pub fn block_bytes(&self) -> usize {
+        match self {
+            EPixelFormat::PF_A32B32G32R32F => 16,
+            EPixelFormat::PF_B8G8R8A8 | EPixelFormat::PF_R8G8B8A8 => 4,
+            EPixelFormat::PF_G8 => 1,
+            EPixelFormat::PF_G16 => 2,
+            EPixelFormat::PF_FloatRGBA => 8,
+            EPixelFormat::PF_DXT1 | EPixelFormat::PF_BC4 => 8,
+            EPixelFormat::PF_DXT3
+            | EPixelFormat::PF_DXT5
+            | EPixelFormat::PF_BC5
+            | EPixelFormat::PF_BC6H
+            | EPixelFormat::PF_BC7 => 16,
+        }
+    }
+
+    /// Check if this pixel format is block compressed
+    pub fn is_block_compressed(&self) -> bool {
+        matches!(
+            self,
+            EPixelFormat::PF_DXT1
+                | EPixelFormat::PF_DXT3
+                | EPixelFormat::PF_DXT5
+                | EPixelFormat::PF_BC4
+                | EPixelFormat::PF_BC5
+                | EPixelFormat::PF_BC6H
+                | EPixelFormat::PF_BC7
+        )
+    }
+
+    /// Get the size in bytes of a mip with this pixel format
+    pub fn mip_size(&self, width: u32, height: u32) -> usize {
+        let block_size = self.block_size();
+        let blocks_x = width.div_ceil(block_size).max(1) as usize;
+        let blocks_y = height.div_ceil(block_size).max(1) as usize;
+        blocks_x * blocks_y * self.block_bytes()
+    }
+}
+
+impl FromStr for EPixelFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "PF_A32B32G32R32F" => EPixelFormat::PF_A32B32G32R32F,
+            "PF_B8G8R8A8" => EPixelFormat::PF_B8G8R8A8,
+            "PF_G8" => EPixelFormat::PF_G8,
+            "PF_G16" => EPixelFormat::PF_G16,
+            "PF_DXT1" => EPixelFormat::PF_DXT1,
+            "PF_DXT3" => EPixelFormat::PF_DXT3,
+            "PF_DXT5" => EPixelFormat::PF_DXT5,
+            "PF_FloatRGBA" => EPixelFormat::PF_FloatRGBA,
+            "PF_BC5" => EPixelFormat::PF_BC5,
+            "PF_R8G8B8A8" => EPixelFormat::PF_R8G8B8A8,
+            "PF_BC4" => EPixelFormat::PF_BC4,
+            "PF_BC6H" => EPixelFormat::PF_BC6H,
+            "PF_BC7" => EPixelFormat::PF_BC7,
+            _ => {
+                return Err(Error::unimplemented(format!(
+                    "Unsupported pixel format {s}"
+                )))
+            }
+        })
+    }
+}
+
+impl Display for EPixelFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
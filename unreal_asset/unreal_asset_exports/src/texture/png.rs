@@ -0,0 +1,305 @@
+//! Minimal PNG reader and writer
+
+use std::io::{Read, Write};
+
+use byteorder::{WriteBytesExt, BE};
+use flate2::{read::ZlibDecoder, write::ZlibEncoder, Compression, Crc};
+
+use unreal_asset_base::Error;
+
+use super::RgbaImage;
+
+/// PNG file signature
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+
+/// Write a PNG chunk
+fn write_chunk<W: Write>(writer: &mut W, ty: &[u8; 4], data: &[u8]) -> Result<(), Error> {
+    writer.write_u32::<BE>(data.len() as u32)?;
+    writer.write_all(ty)?;
+    writer.write_all(data)?;
+
+    let mut crc = Crc::new();
+    crc.update(ty);
+    crc.update(data);
+    writer.write_u32::<BE>(crc.sum())?;
+    Ok(())
+}
+
+/// Encode 8 bit RGBA pixels as a PNG file
+pub fn encode_rgba(width: u32, height: u32, pixels: &[u8]) -> Result<Vec<u8>, Error> {
+    let row_size = width as usize * 4;
+    if pixels.len() != row_size * height as usize {
+        return Err(Error::invalid_file(format!(
+            "Expected {} bytes of pixel data for a {width}x{height} image, got {}",
+            row_size * height as usize,
+            pixels.len()
+        )));
+    }
+
+    let mut png = Vec::new();
+    png.write_all(&PNG_SIGNATURE)?;
+
+    let mut header = Vec::with_capacity(13);
+    header.write_u32::<BE>(width)?;
+    header.write_u32::<BE>(height)?;
+    // 8 bit depth, RGBA color, deflate compression, adaptive filtering, no interlacing
+    header.write_all(&[8, 6, 0, 0, 0])?;
+    write_chunk(&mut png, b"IHDR", &header)?;
+
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    for row in pixels.chunks_exact(row_size.max(1)).take(height as usize) {
+        // no filter
+        encoder.write_u8(0)?;
+        encoder.write_all(row)?;
+    }
+    write_chunk(&mut png, b"IDAT", &encoder.finish()?)?;
+    write_chunk(&mut png, b"IEND", &[])?;
+
+    Ok(png)
+}
+
+/// PNG image header
+struct Header {
+    /// Width
+    width: u32,
+    /// Height
+    height: u32,
+    /// Bits per sample
+    bit_depth: u8,
+    /// Color type
+    color_type: u8,
+}
+
+impl Header {
+    /// Get the sample count of a pixel
+    fn channels(&self) -> Result<usize, Error> {
+        let channels = match self.color_type {
+            0 | 3 => 1,
+            2 => 3,
+            4 => 2,
+            6 => 4,
+            _ => {
+                return Err(Error::invalid_file(format!(
+                    "Invalid PNG color type {}",
+                    self.color_type
+                )))
+            }
+        };
+
+        let valid_depth = match self.color_type {
+            0 => matches!(self.bit_depth, 1 | 2 | 4 | 8 | 16),
+            3 => matches!(self.bit_depth, 1 | 2 | 4 | 8),
+            _ => matches!(self.bit_depth, 8 | 16),
+        };
+        if !valid_depth {
+            return Err(Error::invalid_file(format!(
+                "Invalid PNG bit depth {} for color type {}",
+                self.bit_depth, self.color_type
+            )));
+        }
+        Ok(channels)
+    }
+}
+
+/// Predict a byte with the paeth filter
+fn paeth(a: u8, b: u8, c: u8) -> u8 {
+    let p = a as i16 + b as i16 - c as i16;
+    let (pa, pb, pc) = (
+        (p - a as i16).abs(),
+        (p - b as i16).abs(),
+        (p - c as i16).abs(),
+    );
+    if pa <= pb && pa <= pc {
+        a
+    } else if pb <= pc {
+        b
+    } else {
+        c
+    }
+}
+
+/// Reverse the filters of decompressed PNG rows
+fn unfilter(
+    data: &[u8],
+    row_size: usize,
+    height: usize,
+    bytes_per_pixel: usize,
+) -> Result<Vec<u8>, Error> {
+    let mut rows = vec![0u8; row_size * height];
+    for (y, filtered) in data.chunks_exact(row_size + 1).take(height).enumerate() {
+        let (previous, current) = rows.split_at_mut(y * row_size);
+        let previous = match y {
+            0 => None,
+            _ => Some(&previous[(y - 1) * row_size..]),
+        };
+        let current = &mut current[..row_size];
+
+        let filter = filtered[0];
+        for (x, value) in filtered[1..].iter().enumerate() {
+            let a = match x >= bytes_per_pixel {
+                true => current[x - bytes_per_pixel],
+                false => 0,
+            };
+            let b = previous.map(|e| e[x]).unwrap_or_default();
+            let c = match x >= bytes_per_pixel {
+                true => previous.map(|e| e[x - bytes_per_pixel]).unwrap_or_default(),
+                false => 0,
+            };
+
+            let prediction = match filter {
+                0 => 0,
+                1 => a,
+                2 => b,
+                3 => ((a as u16 + b as u16) / 2) as u8,
+                4 => paeth(a, b, c),
+                _ => return Err(Error::invalid_file(format!("Invalid PNG filter {filter}"))),
+            };
+            current[x] = value.wrapping_add(prediction);
+        }
+    }
+    Ok(rows)
+}
+
+/// Decode a PNG file to 8 bit RGBA pixels
+///
+/// Interlaced images are not supported, 16 bit samples are truncated to 8 bits
+pub fn decode_rgba(data: &[u8]) -> Result<RgbaImage, Error> {
+    if data.get(..8) != Some(&PNG_SIGNATURE) {
+        return Err(Error::invalid_file("Invalid PNG signature".to_string()));
+    }
+
+    let mut header = None;
+    let mut palette = Vec::new();
+    let mut compressed = Vec::new();
+
+    let mut position = PNG_SIGNATURE.len();
+    loop {
+        let chunk_header = data
+            .get(position..position + 8)
+            .ok_or_else(|| Error::invalid_file("Unexpected end of PNG file".to_string()))?;
+        let length = u32::from_be_bytes([
+            chunk_header[0],
+            chunk_header[1],
+            chunk_header[2],
+            chunk_header[3],
+        ]) as usize;
+        let ty = &chunk_header[4..8];
+
+        let chunk_start = position + 8;
+        let chunk = chunk_start
+            .checked_add(length)
+            .and_then(|end| data.get(chunk_start..end))
+            .ok_or_else(|| Error::invalid_file("Unexpected end of PNG file".to_string()))?;
+        // skip the chunk crc
+        position = chunk_start + length + 4;
+
+        match ty {
+            b"IHDR" => {
+                if chunk.len() < 13 {
+                    return Err(Error::invalid_file("Invalid PNG header".to_string()));
+                }
+                if chunk[12] != 0 {
+                    return Err(Error::unimplemented(
+                        "Interlaced PNG files are not supported".to_string(),
+                    ));
+                }
+                header = Some(Header {
+                    width: u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]),
+                    height: u32::from_be_bytes([chunk[4], chunk[5], chunk[6], chunk[7]]),
+                    bit_depth: chunk[8],
+                    color_type: chunk[9],
+                });
+            }
+            b"PLTE" => {
+                palette = chunk
+                    .chunks_exact(3)
+                    .map(|e| [e[0], e[1], e[2], 255])
+                    .collect();
+            }
+            b"tRNS" => {
+                for (entry, alpha) in palette.iter_mut().zip(chunk) {
+                    entry[3] = *alpha;
+                }
+            }
+            b"IDAT" => compressed.extend_from_slice(chunk),
+            b"IEND" => break,
+            _ => {}
+        }
+    }
+
+    let header = header.ok_or_else(|| Error::invalid_file("PNG has no header".to_string()))?;
+    let channels = header.channels()?;
+    let bit_depth = header.bit_depth as usize;
+    let (width, height) = (header.width as usize, header.height as usize);
+
+    let bits_per_pixel = channels * bit_depth;
+    let row_size = (width * bits_per_pixel).div_ceil(8);
+    let filtered_size = (row_size + 1)
+        .checked_mul(height)
+        .ok_or_else(|| Error::invalid_file("PNG is too large".to_string()))?;
+
+    let mut filtered = Vec::new();
+    ZlibDecoder::new(compressed.as_slice())
+        .take(filtered_size as u64)
+        .read_to_end(&mut filtered)?;
+    if filtered.len() < filtered_size {
+        return Err(Error::invalid_file(format!(
+            "Expected {filtered_size} bytes of PNG data, got {}",
+            filtered.len()
+        )));
+    }
+
+    let rows = unfilter(&filtered, row_size, height, bits_per_pixel.div_ceil(8))?;
+
+    let max_value = (1u32 << bit_depth.min(8)) - 1;
+    let sample = |row: &[u8], index: usize| -> u8 {
+        match bit_depth {
+            16 => row[index * 2],
+            8 => row[index],
+            _ => {
+                let bit = index * bit_depth;
+                ((row[bit / 8] >> (8 - bit_depth - bit % 8)) as u32 & max_value) as u8
+            }
+        }
+    };
+    // scale sub-byte grayscale samples to 8 bits
+    let gray = |value: u8| (value as u32 * 255 / max_value) as u8;
+
+    let mut pixels = Vec::with_capacity(width * height * 4);
+    for row in rows.chunks_exact(row_size.max(1)).take(height) {
+        for x in 0..width {
+            let index = x * channels;
+            let pixel = match header.color_type {
+                0 => {
+                    let value = gray(sample(row, index));
+                    [value, value, value, 255]
+                }
+                2 => [
+                    sample(row, index),
+                    sample(row, index + 1),
+                    sample(row, index + 2),
+                    255,
+                ],
+                3 => {
+                    let entry = sample(row, index);
+                    *palette.get(entry as usize).ok_or_else(|| {
+                        Error::invalid_file(format!("Invalid PNG palette index {entry}"))
+                    })?
+                }
+                4 => {
+                    let value = sample(row, index);
+                    [value, value, value, sample(row, index + 1)]
+                }
+                _ => [
+                    sample(row, index),
+                    sample(row, index + 1),
+                    sample(row, index + 2),
+                    sample(row, index + 3),
+                ],
+            };
+            pixels.extend_from_slice(&pixel);
+        }
+    }
+
+    Ok(RgbaImage::new(header.width, header.height, pixels))
+}
@@ -0,0 +1,512 @@
+//! Texture2D export
+
+use byteorder::{ReadBytesExt, WriteBytesExt, LE};
+
+use unreal_asset_base::{
+    engine_version::EngineVersion,
+    flags::EBulkDataFlags,
+    reader::{ArchiveReader, ArchiveWriter},
+    types::{
+        bulk_data::FByteBulkData, strip_data_flags::FStripDataFlags, FName, PackageIndexTrait,
+    },
+    Error, FNameContainer,
+};
+
+use crate::implement_get;
+use crate::texture::{self, EPixelFormat, RgbaImage};
+use crate::ExportTrait;
+use crate::{BaseExport, NormalExport};
+
+/// Texture mip
+#[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FTexture2DMipMap {
+    /// Is the mip cooked, only serialized before UE5
+    pub cooked: bool,
+    /// Mip data
+    #[container_ignore]
+    pub bulk_data: FByteBulkData,
+    /// Width
+    pub size_x: i32,
+    /// Height
+    pub size_y: i32,
+    /// Depth, only serialized since UE4.20
+    pub size_z: i32,
+}
+
+impl FTexture2DMipMap {
+    /// Read an `FTexture2DMipMap` from an asset
+    pub fn new<Reader: ArchiveReader<impl PackageIndexTrait>>(
+        asset: &mut Reader,
+    ) -> Result<Self, Error> {
+        let cooked = match asset.get_engine_version() < EngineVersion::VER_UE5_0 {
+            true => asset.read_i32::<LE>()? != 0,
+            false => true,
+        };
+        let bulk_data = FByteBulkData::new(asset)?;
+        let size_x = asset.read_i32::<LE>()?;
+        let size_y = asset.read_i32::<LE>()?;
+        let size_z = match asset.get_engine_version() >= EngineVersion::VER_UE4_20 {
+            true => asset.read_i32::<LE>()?,
+            false => 1,
+        };
+
+        Ok(FTexture2DMipMap {
+            cooked,
+            bulk_data,
+            size_x,
+            size_y,
+            size_z,
+        })
+    }
+
+    /// Write an `FTexture2DMipMap` to an asset
+    pub fn write<Writer: ArchiveWriter<impl PackageIndexTrait>>(
+        &self,
+        asset: &mut Writer,
+    ) -> Result<(), Error> {
+        if asset.get_engine_version() < EngineVersion::VER_UE5_0 {
+            asset.write_i32::<LE>(self.cooked as i32)?;
+        }
+        self.bulk_data.write(asset)?;
+        asset.write_i32::<LE>(self.size_x)?;
+        asset.write_i32::<LE>(self.size_y)?;
+        if asset.get_engine_version() >= EngineVersion::VER_UE4_20 {
+            asset.write_i32::<LE>(self.size_z)?;
+        }
+        Ok(())
+    }
+}
+
+/// Optional texture platform data
+#[derive(FNameContainer, Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FOptTexturePlatformData {
+    /// Arbitrary extra data that the runtime may need
+    pub ext_data: u32,
+    /// Number of mips making up the mip tail, which must all reside in the same file
+    pub num_mips_in_tail: u32,
+}
+
+/// Cooked texture data for a pixel format
+#[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FTexturePlatformData {
+    /// Placeholder for derived data which cooked packages don't contain, only serialized since UE5
+    #[container_ignore]
+    pub placeholder_derived_data: [u8; 16],
+    /// Width
+    pub size_x: i32,
+    /// Height
+    pub size_y: i32,
+    /// Slice count and flags, see [`FTexturePlatformData::num_slices`] and [`FTexturePlatformData::is_cubemap`]
+    pub packed_data: u32,
+    /// Pixel format name
+    pub pixel_format: Option<String>,
+    /// Optional data, the packed data flag for it is set when writing
+    pub opt_data: Option<FOptTexturePlatformData>,
+    /// Index of the first mip that was cooked
+    pub first_mip_to_serialize: i32,
+    /// Mips
+    pub mips: Vec<FTexture2DMipMap>,
+    /// Is the texture virtual, only serialized since UE4.23
+    pub is_virtual: bool,
+}
+
+impl FTexturePlatformData {
+    /// Packed data flag for optional data
+    pub const HAS_OPT_DATA: u32 = 1 << 30;
+    /// Packed data flag for cubemaps
+    pub const CUBEMAP: u32 = 1 << 31;
+    /// Packed data flag for a CPU copy of the texture
+    pub const HAS_CPU_COPY: u32 = 1 << 29;
+
+    /// Read an `FTexturePlatformData` from an asset
+    pub fn new<Reader: ArchiveReader<impl PackageIndexTrait>>(
+        asset: &mut Reader,
+    ) -> Result<Self, Error> {
+        let mut placeholder_derived_data = [0u8; 16];
+        if asset.get_engine_version() >= EngineVersion::VER_UE5_0 {
+            asset.read_exact(&mut placeholder_derived_data)?;
+        }
+
+        let size_x = asset.read_i32::<LE>()?;
+        let size_y = asset.read_i32::<LE>()?;
+        let packed_data = asset.read_u32::<LE>()?;
+        let pixel_format = asset.read_fstring()?;
+
+        let opt_data = match packed_data & Self::HAS_OPT_DATA != 0 {
+            true => Some(FOptTexturePlatformData {
+                ext_data: asset.read_u32::<LE>()?,
+                num_mips_in_tail: asset.read_u32::<LE>()?,
+            }),
+            false => None,
+        };
+        if packed_data & Self::HAS_CPU_COPY != 0 {
+            return Err(Error::unimplemented(
+                "Textures with a CPU copy are not supported".to_string(),
+            ));
+        }
+
+        let first_mip_to_serialize = asset.read_i32::<LE>()?;
+
+        let mip_count = asset.read_i32::<LE>()?;
+        if mip_count < 0 {
+            return Err(Error::invalid_file(format!(
+                "Invalid texture mip count {mip_count}"
+            )));
+        }
+        let mut mips = Vec::new();
+        for _ in 0..mip_count {
+            mips.push(FTexture2DMipMap::new(asset)?);
+        }
+
+        let is_virtual = match asset.get_engine_version() >= EngineVersion::VER_UE4_23 {
+            true => asset.read_i32::<LE>()? != 0,
+            false => false,
+        };
+        if is_virtual {
+            return Err(Error::unimplemented(
+                "Virtual textures are not supported".to_string(),
+            ));
+        }
+
+        Ok(FTexturePlatformData {
+            placeholder_derived_data,
+            size_x,
+            size_y,
+            packed_data,
+            pixel_format,
+            opt_data,
+            first_mip_to_serialize,
+            mips,
+            is_virtual,
+        })
+    }
+
+    /// Write an `FTexturePlatformData` to an asset
+    pub fn write<Writer: ArchiveWriter<impl PackageIndexTrait>>(
+        &self,
+        asset: &mut Writer,
+    ) -> Result<(), Error> {
+        let packed_data = match self.opt_data.is_some() {
+            true => self.packed_data | Self::HAS_OPT_DATA,
+            false => self.packed_data & !Self::HAS_OPT_DATA,
+        };
+
+        if asset.get_engine_version() >= EngineVersion::VER_UE5_0 {
+            asset.write_all(&self.placeholder_derived_data)?;
+        }
+        asset.write_i32::<LE>(self.size_x)?;
+        asset.write_i32::<LE>(self.size_y)?;
+        asset.write_u32::<LE>(packed_data)?;
+        asset.write_fstring(self.pixel_format.as_deref())?;
+
+        if let Some(opt_data) = &self.opt_data {
+            asset.write_u32::<LE>(opt_data.ext_data)?;
+            asset.write_u32::<LE>(opt_data.num_mips_in_tail)?;
+        }
+
+        asset.write_i32::<LE>(self.first_mip_to_serialize)?;
+        asset.write_i32::<LE>(self.mips.len() as i32)?;
+        for mip in &self.mips {
+            mip.write(asset)?;
+        }
+
+        if asset.get_engine_version() >= EngineVersion::VER_UE4_23 {
+            asset.write_i32::<LE>(self.is_virtual as i32)?;
+        }
+        Ok(())
+    }
+
+    /// Get the slice count
+    pub fn num_slices(&self) -> u32 {
+        self.packed_data & (Self::HAS_CPU_COPY - 1)
+    }
+
+    /// Check if the texture is a cubemap
+    pub fn is_cubemap(&self) -> bool {
+        self.packed_data & Self::CUBEMAP != 0
+    }
+
+    /// Get the pixel format
+    pub fn get_pixel_format(&self) -> Result<EPixelFormat, Error> {
+        self.pixel_format
+            .as_deref()
+            .ok_or_else(|| Error::no_data("Texture has no pixel format".to_string()))?
+            .parse()
+    }
+
+    /// Decode a mip
+    ///
+    /// # Arguments
+    ///
+    /// * `mip_index` - index of the mip in `mips`
+    /// * `data` - mip payload, see [`FByteBulkData::read_payload`]
+    pub fn decode_mip(&self, mip_index: usize, data: &[u8]) -> Result<RgbaImage, Error> {
+        let mip = self
+            .mips
+            .get(mip_index)
+            .ok_or_else(|| Error::no_data(format!("Texture has no mip {mip_index}")))?;
+
+        let (Ok(width), Ok(height)) = (u32::try_from(mip.size_x), u32::try_from(mip.size_y)) else {
+            return Err(Error::invalid_file(format!(
+                "Invalid mip size {}x{}",
+                mip.size_x, mip.size_y
+            )));
+        };
+
+        texture::decode(self.get_pixel_format()?, width, height, data)
+    }
+
+    /// Replace the pixel data with an image
+    ///
+    /// The image is encoded to the current pixel format and the mip chain is regenerated from it.
+    /// Mips that weren't stored inline before are streamed from a separate file,
+    /// their payloads get appended to `ubulk` which should be saved as the `.ubulk` file of the package.
+    /// All other mips are stored inline.
+    ///
+    /// # Arguments
+    ///
+    /// * `image` - new top mip
+    /// * `ubulk` - `.ubulk` file contents
+    pub fn set_image(&mut self, image: &RgbaImage, ubulk: &mut Vec<u8>) -> Result<(), Error> {
+        let pixel_format = self.get_pixel_format()?;
+        if self.is_cubemap() || self.num_slices() > 1 {
+            return Err(Error::unimplemented(
+                "Replacing the pixel data of textures with multiple slices is not supported"
+                    .to_string(),
+            ));
+        }
+        let (Ok(size_x), Ok(size_y)) = (i32::try_from(image.width), i32::try_from(image.height))
+        else {
+            return Err(Error::invalid_file(format!(
+                "Invalid image size {}x{}",
+                image.width, image.height
+            )));
+        };
+
+        // keep textures without mips that way, otherwise generate a full mip chain
+        let mip_count = match self.mips.len() > 1 {
+            true => 32 - image.width.max(image.height).leading_zeros() as usize,
+            false => 1,
+        };
+        let inline_mips = self.mips.iter().filter(|e| e.bulk_data.is_inline()).count();
+        let streamed_mips = match inline_mips < self.mips.len() {
+            true => mip_count.saturating_sub(inline_mips),
+            false => 0,
+        };
+
+        // flags that describe how the old payloads were stored
+        let stored_flags = EBulkDataFlags::BULKDATA_SERIALIZE_COMPRESSED_ZLIB
+            | EBulkDataFlags::BULKDATA_SERIALIZE_COMPRESSED_BIT_WINDOW
+            | EBulkDataFlags::BULKDATA_OPTIONAL_PAYLOAD
+            | EBulkDataFlags::BULKDATA_DUPLICATE_NON_OPTIONAL_PAYLOAD
+            | EBulkDataFlags::BULKDATA_FORCE_INLINE_PAYLOAD
+            | EBulkDataFlags::BULKDATA_PAYLOAD_AT_END_OF_FILE
+            | EBulkDataFlags::BULKDATA_PAYLOAD_IN_SEPERATE_FILE;
+        let mip_flags = |inline: bool| {
+            let flags = self
+                .mips
+                .iter()
+                .find(|e| e.bulk_data.is_inline() == inline)
+                .map(|e| e.bulk_data.flags & !stored_flags)
+                .unwrap_or(EBulkDataFlags::BULKDATA_SINGLE_USE);
+            match inline {
+                true => flags | EBulkDataFlags::BULKDATA_FORCE_INLINE_PAYLOAD,
+                false => {
+                    flags
+                        | EBulkDataFlags::BULKDATA_PAYLOAD_AT_END_OF_FILE
+                        | EBulkDataFlags::BULKDATA_PAYLOAD_IN_SEPERATE_FILE
+                }
+            }
+        };
+        let (inline_flags, streamed_flags) = (mip_flags(true), mip_flags(false));
+
+        let mut mips = Vec::with_capacity(mip_count);
+        let mut mip_image = image.clone();
+        for mip_index in 0..mip_count {
+            if mip_index > 0 {
+                mip_image = mip_image.downsample();
+            }
+            let data = texture::encode(pixel_format, &mip_image)?;
+
+            let bulk_data = match mip_index < streamed_mips {
+                true => {
+                    let offset = ubulk.len() as i64;
+                    let size = data.len() as i64;
+                    ubulk.extend(data);
+                    FByteBulkData {
+                        flags: streamed_flags,
+                        element_count: size,
+                        size_on_disk: size,
+                        offset,
+                        bad_data_version: 0,
+                        duplicate: None,
+                        data: None,
+                    }
+                }
+                false => FByteBulkData {
+                    flags: inline_flags,
+                    ..FByteBulkData::new_inline(data)
+                },
+            };
+
+            mips.push(FTexture2DMipMap {
+                cooked: true,
+                bulk_data,
+                size_x: mip_image.width as i32,
+                size_y: mip_image.height as i32,
+                size_z: 1,
+            });
+        }
+
+        self.size_x = size_x;
+        self.size_y = size_y;
+        self.first_mip_to_serialize = 0;
+        self.mips = mips;
+        Ok(())
+    }
+}
+
+/// Texture2D export
+#[derive(FNameContainer, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Texture2DExport<Index: PackageIndexTrait> {
+    /// Base normal export
+    pub normal_export: NormalExport<Index>,
+    /// Texture strip flags
+    #[container_ignore]
+    pub texture_strip_flags: FStripDataFlags,
+    /// Texture2D strip flags
+    #[container_ignore]
+    pub texture_2d_strip_flags: FStripDataFlags,
+    /// Is the texture cooked
+    pub cooked: bool,
+    /// Cooked platform data for each pixel format
+    pub cooked_platform_data: Vec<(FName, FTexturePlatformData)>,
+}
+
+implement_get!(Texture2DExport);
+
+impl<Index: PackageIndexTrait> Texture2DExport<Index> {
+    /// Read a `Texture2DExport` from an asset
+    pub fn from_base<Reader: ArchiveReader<Index>>(
+        base: &BaseExport<Index>,
+        asset: &mut Reader,
+    ) -> Result<Self, Error> {
+        let normal_export = NormalExport::from_base(base, asset)?;
+        asset.read_i32::<LE>()?;
+
+        let texture_strip_flags = FStripDataFlags::read(asset)?;
+        if !texture_strip_flags.is_editor_data_stripped() {
+            return Err(Error::unimplemented(
+                "Texture source data is not supported".to_string(),
+            ));
+        }
+        let texture_2d_strip_flags = FStripDataFlags::read(asset)?;
+        let cooked = asset.read_i32::<LE>()? != 0;
+
+        let mut cooked_platform_data = Vec::new();
+        if cooked {
+            let mut pixel_format = asset.read_fname()?;
+            while pixel_format != "None" {
+                let skip_offset_position = asset.position() as i64;
+                let skip_offset = match asset.get_engine_version() {
+                    e if e >= EngineVersion::VER_UE5_0 => {
+                        skip_offset_position + asset.read_i64::<LE>()?
+                    }
+                    e if e >= EngineVersion::VER_UE4_20 => asset.read_i64::<LE>()?,
+                    _ => asset.read_i32::<LE>()? as i64,
+                };
+
+                let platform_data = FTexturePlatformData::new(asset)?;
+                if asset.position() as i64 != skip_offset {
+                    return Err(Error::invalid_file(format!(
+                        "Texture platform data ended at {}, expected {}",
+                        asset.position(),
+                        skip_offset
+                    )));
+                }
+
+                cooked_platform_data.push((pixel_format, platform_data));
+                pixel_format = asset.read_fname()?;
+            }
+        }
+
+        Ok(Texture2DExport {
+            normal_export,
+            texture_strip_flags,
+            texture_2d_strip_flags,
+            cooked,
+            cooked_platform_data,
+        })
+    }
+
+    /// Get the platform data of the first cooked pixel format
+    pub fn platform_data(&self) -> Option<&FTexturePlatformData> {
+        self.cooked_platform_data.first().map(|(_, data)| data)
+    }
+
+    /// Get a mutable reference to the platform data of the first cooked pixel format
+    pub fn platform_data_mut(&mut self) -> Option<&mut FTexturePlatformData> {
+        self.cooked_platform_data.first_mut().map(|(_, data)| data)
+    }
+
+    /// Replace the pixel data of every cooked pixel format with an image
+    ///
+    /// Returns the new `.ubulk` file contents, which are empty if no mips are streamed.
+    /// See [`FTexturePlatformData::set_image`]
+    pub fn set_image(&mut self, image: &RgbaImage) -> Result<Vec<u8>, Error> {
+        let mut ubulk = Vec::new();
+        for (_, platform_data) in &mut self.cooked_platform_data {
+            platform_data.set_image(image, &mut ubulk)?;
+        }
+        Ok(ubulk)
+    }
+}
+
+impl<Index: PackageIndexTrait> ExportTrait<Index> for Texture2DExport<Index> {
+    fn write<Writer: ArchiveWriter<Index>>(&self, asset: &mut Writer) -> Result<(), Error> {
+        self.normal_export.write(asset)?;
+        asset.write_i32::<LE>(0)?;
+
+        self.texture_strip_flags.write(asset)?;
+        self.texture_2d_strip_flags.write(asset)?;
+        asset.write_i32::<LE>(self.cooked as i32)?;
+
+        if self.cooked {
+            let engine_version = asset.get_engine_version();
+            for (pixel_format, platform_data) in &self.cooked_platform_data {
+                asset.write_fname(pixel_format)?;
+
+                let skip_offset_position = asset.position();
+                match engine_version >= EngineVersion::VER_UE4_20 {
+                    true => asset.write_i64::<LE>(0)?,
+                    false => asset.write_i32::<LE>(0)?,
+                };
+
+                platform_data.write(asset)?;
+
+                // UE5 stores the offset relative to the skip offset, older versions store the package offset
+                let end = asset.position();
+                let skip_offset = match engine_version >= EngineVersion::VER_UE5_0 {
+                    true => (end - skip_offset_position) as i64,
+                    false => asset.package_position() as i64,
+                };
+
+                asset.set_position(skip_offset_position)?;
+                match engine_version >= EngineVersion::VER_UE4_20 {
+                    true => asset.write_i64::<LE>(skip_offset)?,
+                    false => asset.write_i32::<LE>(skip_offset as i32)?,
+                };
+                asset.set_position(end)?;
+            }
+
+            let none = asset.get_name_map().get_mut().add_fname("None");
+            asset.write_fname(&none)?;
+        }
+
+        Ok(())
+    }
+}
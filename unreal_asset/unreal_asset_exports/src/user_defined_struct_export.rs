@@ -16,6 +16,7 @@ use crate::{ExportBaseTrait, ExportNormalTrait, ExportTrait};
 
 /// Struct export
 #[derive(FNameContainer, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UserDefinedStructExport<Index: PackageIndexTrait> {
     /// Base struct export
     pub struct_export: StructExport<Index>,
@@ -28,6 +28,7 @@ use unreal_asset_base::{
 
 /// Kismet expression token
 #[derive(Debug, PartialEq, Eq, Hash, Copy, Clone, TryFromPrimitive, IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum EExprToken {
     /// A local variable.
@@ -224,6 +225,7 @@ pub enum EExprToken {
 
 /// Kismet cast token
 #[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CastToken {
     /// Old kismet cast token
     Old(OldCastToken),
@@ -233,6 +235,7 @@ pub enum CastToken {
 
 /// Old kismet cast token
 #[derive(Debug, PartialEq, Eq, Hash, Copy, Clone, TryFromPrimitive, IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum OldCastToken {
     /// Cast object to interface
@@ -247,6 +250,7 @@ pub enum OldCastToken {
 
 /// New kismet cast token
 #[derive(Debug, PartialEq, Eq, Hash, Copy, Clone, TryFromPrimitive, IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum NewCastToken {
     /// Cast object to interface
@@ -293,6 +297,7 @@ pub enum NewCastToken {
 
 /// Kismet instrumentation type
 #[derive(Debug, PartialEq, Eq, Hash, Copy, Clone, TryFromPrimitive, IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum EScriptInstrumentationType {
     /// Class
@@ -333,6 +338,7 @@ pub enum EScriptInstrumentationType {
 
 /// Kismet text literal type
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, TryFromPrimitive, IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum EBlueprintTextLiteralType {
     /// Text is an empty string. The bytecode contains no strings, and you should use FText::GetEmpty() to initialize the FText instance.
@@ -349,6 +355,7 @@ pub enum EBlueprintTextLiteralType {
 
 /// Kismet field path
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FieldPath {
     /// Path
     pub path: Vec<FName>,
@@ -437,6 +444,7 @@ macro_rules! declare_expression {
     ) => {
         #[doc = stringify!($name)]
         #[derive(FNameContainer, Debug, Clone, PartialEq, Eq, Hash)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
         pub struct $name {
             /// Kismet token
             #[container_ignore]
@@ -467,6 +475,7 @@ macro_rules! implement_expression {
         $(
             $(#[$inner $($args)*])*
             #[derive(FNameContainer, Debug, Clone, PartialEq, Eq, Hash)]
+            #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
             pub struct $name {
                 /// Kismet token
                 #[container_ignore]
@@ -557,6 +566,7 @@ macro_rules! implement_value_expression {
 
 /// Kismet script text
 #[derive(FNameContainer, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FScriptText {
     /// Literal type
     #[container_ignore]
@@ -726,6 +736,7 @@ impl FScriptText {
 // todo: replace with an enum with 2 variants
 /// Represents a Kismet bytecode pointer to an FProperty or FField.
 #[derive(FNameContainer, Debug, Default, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct KismetPropertyPointer {
     /// Pointer serialized as PackageIndex. Used in versions older than [`KismetPropertyPointer::XFER_PROP_POINTER_SWITCH_TO_SERIALIZING_AS_FIELD_PATH_VERSION`]
     #[container_ignore]
@@ -808,6 +819,7 @@ impl KismetPropertyPointer {
 
 /// Kismet switch case
 #[derive(FNameContainer, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct KismetSwitchCase {
     /// Case value index
     pub case_index_value_term: KismetExpression,
@@ -863,6 +875,7 @@ pub trait KismetExpressionEnumEqTrait {
     KismetExpressionDataTrait
 )]
 #[derive(FNameContainer, Debug, Clone, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[container_nobounds]
 pub enum KismetExpression {
     /// A local variable.
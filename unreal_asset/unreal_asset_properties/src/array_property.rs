@@ -4,6 +4,7 @@ use crate::property_prelude::*;
 
 /// Array property
 #[derive(FNameContainer, Debug, Default, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ArrayProperty {
     /// Name
     pub name: FName,
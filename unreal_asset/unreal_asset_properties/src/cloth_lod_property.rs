@@ -5,6 +5,7 @@ use crate::vector_property::Vector4Property;
 
 /// Mesh to mesh vertex data
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MeshToMeshVertData {
     /// Position barycentric coords and distance
     pub position_bary_coords_and_dist: Vector4Property,
@@ -102,6 +103,7 @@ impl MeshToMeshVertData {
 
 /// Cloth lod data property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ClothLodDataProperty {
     /// Base struct property
     pub struct_property: StructProperty,
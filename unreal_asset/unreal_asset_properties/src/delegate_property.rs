@@ -4,6 +4,7 @@ use crate::property_prelude::*;
 
 /// Delegate
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Delegate {
     /// Delegate object
     #[container_ignore]
@@ -21,6 +22,7 @@ impl Delegate {
 
 /// Delegate property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DelegateProperty {
     /// Name
     pub name: FName,
@@ -80,6 +82,7 @@ macro_rules! impl_multicast {
     ($property_name:ident) => {
         /// $property_name
         #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
         pub struct $property_name {
             /// Name
             pub name: FName,
@@ -4,6 +4,7 @@ use crate::property_prelude::*;
 
 /// Font character
 #[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FontCharacter {
     /// Start U coordinate
     pub start_u: i32,
@@ -51,6 +52,7 @@ impl FontCharacter {
 
 /// Font character property
 #[derive(FNameContainer, Debug, Hash, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FontCharacterProperty {
     /// Name
     pub name: FName,
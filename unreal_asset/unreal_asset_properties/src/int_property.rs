@@ -33,6 +33,7 @@ macro_rules! impl_int_property {
 
 /// Int8 property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Int8Property {
     /// Name
     pub name: FName,
@@ -49,6 +50,7 @@ impl_property_data_trait!(Int8Property);
 
 /// Byte property value
 #[derive(FNameContainer, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BytePropertyValue {
     /// Byte variant
     Byte(u8),
@@ -64,6 +66,7 @@ impl Default for BytePropertyValue {
 
 /// Byte property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ByteProperty {
     /// Name
     pub name: FName,
@@ -82,6 +85,7 @@ impl_property_data_trait!(ByteProperty);
 
 /// Bool property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BoolProperty {
     /// Name
     pub name: FName,
@@ -98,6 +102,7 @@ impl_property_data_trait!(BoolProperty);
 
 /// Int32 property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct IntProperty {
     /// Name
     pub name: FName,
@@ -114,6 +119,7 @@ impl_property_data_trait!(IntProperty);
 
 /// Int16 property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Int16Property {
     /// Name
     pub name: FName,
@@ -130,6 +136,7 @@ impl_property_data_trait!(Int16Property);
 
 /// Int64 property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Int64Property {
     /// Name
     pub name: FName,
@@ -146,6 +153,7 @@ impl_property_data_trait!(Int64Property);
 
 /// UInt16 property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UInt16Property {
     /// Name
     pub name: FName,
@@ -162,6 +170,7 @@ impl_property_data_trait!(UInt16Property);
 
 /// UInt32 property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UInt32Property {
     /// Name
     pub name: FName,
@@ -178,6 +187,7 @@ impl_property_data_trait!(UInt32Property);
 
 /// UInt64 property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UInt64Property {
     /// Name
     pub name: FName,
@@ -194,6 +204,7 @@ impl_property_data_trait!(UInt64Property);
 
 /// Float property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FloatProperty {
     /// Name
     pub name: FName,
@@ -210,6 +221,7 @@ impl_property_data_trait!(FloatProperty);
 
 /// Double property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DoubleProperty {
     /// Name
     pub name: FName,
@@ -338,6 +338,7 @@ pub trait PropertyTrait: PropertyDataTrait + Debug + Hash + Clone + PartialEq +
 #[allow(clippy::large_enum_variant)]
 #[enum_dispatch(PropertyTrait, PropertyDataTrait)]
 #[derive(FNameContainer, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[container_nobounds]
 pub enum Property {
     /// Bool property
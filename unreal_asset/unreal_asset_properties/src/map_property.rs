@@ -8,6 +8,7 @@ use crate::property_prelude::*;
 
 /// Map property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MapProperty {
     /// Name
     pub name: FName,
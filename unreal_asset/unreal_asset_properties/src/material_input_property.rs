@@ -6,6 +6,7 @@ use crate::vector_property::{Vector2DProperty, VectorProperty};
 
 /// Material expression
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MaterialExpression {
     /// Name
     pub name: FName,
@@ -21,6 +22,7 @@ pub struct MaterialExpression {
 
 /// Color material input property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ColorMaterialInputProperty {
     /// Name
     pub name: FName,
@@ -39,6 +41,7 @@ impl_property_data_trait!(ColorMaterialInputProperty);
 
 /// Scalar material input property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ScalarMaterialInputProperty {
     /// Name
     pub name: FName,
@@ -57,6 +60,7 @@ impl_property_data_trait!(ScalarMaterialInputProperty);
 
 /// Shading model material input property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ShadingModelMaterialInputProperty {
     /// Name
     pub name: FName,
@@ -75,6 +79,7 @@ impl_property_data_trait!(ShadingModelMaterialInputProperty);
 
 /// Vector material input property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VectorMaterialInputProperty {
     /// Name
     pub name: FName,
@@ -93,6 +98,7 @@ impl_property_data_trait!(VectorMaterialInputProperty);
 
 /// Vector2 material input property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Vector2MaterialInputProperty {
     /// Name
     pub name: FName,
@@ -111,6 +117,7 @@ impl_property_data_trait!(Vector2MaterialInputProperty);
 
 /// Expression input property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExpressionInputProperty {
     /// Name
     pub name: FName,
@@ -127,6 +134,7 @@ impl_property_data_trait!(ExpressionInputProperty);
 
 /// Material attributes input property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MaterialAttributesInputProperty {
     /// Name
     pub name: FName,
@@ -4,6 +4,7 @@ use crate::property_prelude::*;
 
 /// Movie scene evaluation template pointer property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MovieSceneEvalTemplatePtrProperty {
     /// Name
     pub name: FName,
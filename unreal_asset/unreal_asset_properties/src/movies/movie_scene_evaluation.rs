@@ -9,6 +9,7 @@ use crate::property_prelude::*;
 
 /// Movie scene evaluation entry
 #[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FEntry {
     /// Start index
     pub start_index: i32,
@@ -48,6 +49,7 @@ impl FEntry {
 
 /// Evaluation tree entry handle
 #[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EvaluationTreeEntryHandle {
     /// Entry index
     pub entry_index: i32,
@@ -75,6 +77,7 @@ impl EvaluationTreeEntryHandle {
 
 /// Movie scene evaluation tree node handle
 #[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MovieSceneEvaluationTreeNodeHandle {
     /// Children handle
     pub children_handle: EvaluationTreeEntryHandle,
@@ -110,6 +113,7 @@ impl MovieSceneEvaluationTreeNodeHandle {
 
 /// Generic evaluation tree entry container
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TEvaluationTreeEntryContainer<T>
 where
     T: Debug + Clone + PartialEq + Eq + Hash,
@@ -174,6 +178,7 @@ where
 
 /// Generic movie scene evaluation tree
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TMovieSceneEvaluationTree<T>
 where
     T: Debug + Clone + PartialEq + Eq + Hash,
@@ -245,6 +250,7 @@ where
 
 /// Movie scene evaluation tree node
 #[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MovieSceneEvaluationTreeNode {
     /// Frame number range
     pub range: FFrameNumberRange,
@@ -290,6 +296,7 @@ impl MovieSceneEvaluationTreeNode {
 
 /// Movie entity and metadata index
 #[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FEntityAndMetaDataIndex {
     /// Entity index
     pub entity_index: i32,
@@ -325,6 +332,7 @@ impl FEntityAndMetaDataIndex {
 
 /// Movie scene evaluation field entity tree
 #[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MovieSceneEvaluationFieldEntityTree {
     /// Serialized data
     pub serialized_data: TMovieSceneEvaluationTree<FEntityAndMetaDataIndex>,
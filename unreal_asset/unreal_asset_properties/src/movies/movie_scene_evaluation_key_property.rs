@@ -7,6 +7,7 @@ use super::movie_scene_track_identifier_property::MovieSceneTrackIdentifier;
 
 /// Movie scene evaluation key
 #[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MovieSceneEvaluationKey {
     /// Movie sequence id
     pub sequence_id: MovieSceneSequenceId,
@@ -47,6 +48,7 @@ impl MovieSceneEvaluationKey {
 
 /// Movie scene evaluation key property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MovieSceneEvaluationKeyProperty {
     /// Name
     pub name: FName,
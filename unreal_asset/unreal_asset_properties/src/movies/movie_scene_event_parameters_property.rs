@@ -4,6 +4,7 @@ use crate::property_prelude::*;
 
 /// Movie scene event parameters
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MovieSceneEventParameters {
     /// Struct type
     pub struct_type: SoftObjectPath,
@@ -44,6 +45,7 @@ impl MovieSceneEventParameters {
 
 /// Movie scene event parameters property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MovieSceneEventParametersProperty {
     /// Name
     pub name: FName,
@@ -9,6 +9,7 @@ use super::movie_scene_float_value_property::MovieSceneFloatValue;
 
 /// Movie scene float channel
 #[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MovieSceneFloatChannel {
     /// Pre infinity extrapolation
     pub pre_infinity_extrap: RichCurveExtrapolation,
@@ -115,6 +116,7 @@ impl MovieSceneFloatChannel {
 
 /// Movie scene float channel property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MovieSceneFloatChannelProperty {
     /// Name
     pub name: FName,
@@ -6,6 +6,7 @@ use crate::property_prelude::*;
 
 /// Movie scene segment identifier
 #[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MovieSceneSegmentIdentifier {
     /// Identifier index
     pub identifier_index: i32,
@@ -33,6 +34,7 @@ impl MovieSceneSegmentIdentifier {
 
 /// Movie scene segment
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MovieSceneSegment {
     /// Name
     pub name: FName,
@@ -125,6 +127,7 @@ impl MovieSceneSegment {
 
 /// Movie scene segment property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MovieSceneSegmentProperty {
     /// Name
     pub name: FName,
@@ -181,6 +184,7 @@ impl PropertyTrait for MovieSceneSegmentProperty {
 
 /// Movie scene segment identifier property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MovieSceneSegmentIdentifierProperty {
     /// Name
     pub name: FName,
@@ -9,6 +9,7 @@ use super::{
 
 /// Movie scene track field data
 #[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MovieSceneTrackFieldData {
     /// Data tree
     pub field: TMovieSceneEvaluationTree<MovieSceneTrackIdentifier>,
@@ -42,6 +43,7 @@ impl MovieSceneTrackFieldData {
 
 /// Movie scene track field data property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MovieSceneTrackFieldDataProperty {
     /// Name
     pub name: FName,
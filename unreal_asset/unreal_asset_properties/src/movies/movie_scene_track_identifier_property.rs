@@ -4,6 +4,7 @@ use crate::property_prelude::*;
 
 /// Movie scene track identifier
 #[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MovieSceneTrackIdentifier {
     /// Identifier value
     pub value: u32,
@@ -31,6 +32,7 @@ impl MovieSceneTrackIdentifier {
 
 /// Movie scene track identifier property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MovieSceneTrackIdentifierProperty {
     /// Name
     pub name: FName,
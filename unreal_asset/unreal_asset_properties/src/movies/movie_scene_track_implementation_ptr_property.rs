@@ -4,6 +4,7 @@ use crate::property_prelude::*;
 
 /// Movie scene track implementation pointer property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MovieSceneTrackImplementationPtrProperty {
     /// Name
     pub name: FName,
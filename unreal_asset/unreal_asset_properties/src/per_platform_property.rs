@@ -4,6 +4,7 @@ use crate::property_prelude::*;
 
 /// Per platform bool property
 #[derive(FNameContainer, Debug, Hash, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PerPlatformBoolProperty {
     /// Name
     pub name: FName,
@@ -20,6 +21,7 @@ impl_property_data_trait!(PerPlatformBoolProperty);
 
 /// Per platform int property
 #[derive(FNameContainer, Debug, Hash, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PerPlatformIntProperty {
     /// Name
     pub name: FName,
@@ -36,6 +38,7 @@ impl_property_data_trait!(PerPlatformIntProperty);
 
 /// Per platform float property
 #[derive(FNameContainer, Debug, Hash, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PerPlatformFloatProperty {
     /// Name
     pub name: FName,
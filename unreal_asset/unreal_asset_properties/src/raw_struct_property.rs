@@ -4,6 +4,7 @@ use crate::property_prelude::*;
 
 /// Raw struct property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RawStructProperty {
     /// Name
     pub name: FName,
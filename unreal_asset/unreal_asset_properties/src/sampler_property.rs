@@ -4,6 +4,7 @@ use crate::property_prelude::*;
 
 /// Weighted random sampler property
 #[derive(FNameContainer, Debug, Hash, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct WeightedRandomSamplerProperty {
     /// Name
     pub name: FName,
@@ -24,6 +25,7 @@ impl_property_data_trait!(WeightedRandomSamplerProperty);
 
 /// Skeletal mesh area weighted triangle sampler
 #[derive(FNameContainer, Debug, Hash, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SkeletalMeshAreaWeightedTriangleSampler {
     /// Name
     pub name: FName,
@@ -44,6 +46,7 @@ impl_property_data_trait!(SkeletalMeshAreaWeightedTriangleSampler);
 
 /// Skeleetal mesh sampling lod built data property
 #[derive(FNameContainer, Debug, Hash, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SkeletalMeshSamplingLODBuiltDataProperty {
     /// Name
     pub name: FName,
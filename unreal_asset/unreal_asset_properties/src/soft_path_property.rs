@@ -4,6 +4,7 @@ use crate::property_prelude::*;
 
 /// Soft path property value
 #[derive(FNameContainer, Debug, Hash, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SoftObjectPathPropertyValue {
     /// asset.get_object_version() < ObjectVersion::VER_UE4_ADDED_SOFT_OBJECT_PATH
     Old(Option<String>),
@@ -48,6 +49,7 @@ impl SoftObjectPathPropertyValue {
 
 /// Soft asset path property
 #[derive(FNameContainer, Debug, Hash, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SoftAssetPathProperty {
     /// Name
     pub name: FName,
@@ -64,6 +66,7 @@ impl_property_data_trait!(SoftAssetPathProperty);
 
 /// Soft object path property
 #[derive(FNameContainer, Debug, Hash, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SoftObjectPathProperty {
     /// Name
     pub name: FName,
@@ -80,6 +83,7 @@ impl_property_data_trait!(SoftObjectPathProperty);
 
 /// Soft class path property
 #[derive(FNameContainer, Debug, Hash, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SoftClassPathProperty {
     /// Name
     pub name: FName,
@@ -96,6 +100,7 @@ impl_property_data_trait!(SoftClassPathProperty);
 
 /// String asset reference property
 #[derive(FNameContainer, Debug, Hash, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct StringAssetReferenceProperty {
     /// Name
     pub name: FName,
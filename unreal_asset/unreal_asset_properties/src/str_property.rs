@@ -15,6 +15,7 @@ use crate::property_prelude::*;
     TryFromPrimitive,
     Hash,
 )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i8)]
 pub enum TextHistoryType {
     /// None
@@ -52,6 +53,7 @@ pub enum TextHistoryType {
 
 /// String property
 #[derive(FNameContainer, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct StrProperty {
     /// Name
     pub name: FName,
@@ -68,6 +70,7 @@ impl_property_data_trait!(StrProperty);
 
 /// Text property
 #[derive(FNameContainer, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TextProperty {
     /// Name
     pub name: FName,
@@ -94,6 +97,7 @@ impl_property_data_trait!(TextProperty);
 
 /// Name property
 #[derive(FNameContainer, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NameProperty {
     /// Name
     pub name: FName,
@@ -6,6 +6,7 @@ use crate::property_prelude::*;
 ///
 /// This gets created when an unknown property was encountered while deserializing
 #[derive(FNameContainer, Debug, Hash, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UnknownProperty {
     /// Name
     pub name: FName,
@@ -8,6 +8,7 @@ use crate::vector_property::{BoxProperty, IntPointProperty};
 //todo: what is this file even doing in properties?
 /// World tile layer
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FWorldTileLayer {
     /// Name
     pub name: Option<String>,
@@ -91,6 +92,7 @@ impl FWorldTileLayer {
 
 /// World tile lod info
 #[derive(FNameContainer, Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FWorldTileLODInfo {
     /// Relative streaming distance
     pub relative_streaming_distance: i32,
@@ -134,6 +136,7 @@ impl FWorldTileLODInfo {
 
 /// World tile ifno
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FWorldTileInfo {
     /// Position
     #[container_ignore]
@@ -54,7 +54,8 @@ impl<R: Read + Seek> UnrealReadExt for R {
         let len = self.read_i32::<LE>()?;
 
         let (len, is_wide) = match len < 0 {
-            true => (-len, true),
+            // i32::MIN can not be negated and is rejected as too long
+            true => (len.saturating_neg(), true),
             false => (len, false),
         };
         read_fstring_len(self, len, is_wide)
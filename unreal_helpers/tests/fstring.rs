@@ -34,6 +34,11 @@ fn test_read_fstring() -> Result<(), FStringError> {
         FStringError::InvalidStringTerminator(25972, 6)
     ));
 
+    // Length which can not be negated
+    let mut cursor = Cursor::new(i32::MIN.to_le_bytes().to_vec());
+    let err = cursor.read_fstring().expect_err("Expected err");
+    assert!(matches!(err, FStringError::InvalidStringSize(_, 4)));
+
     Ok(())
 }
 
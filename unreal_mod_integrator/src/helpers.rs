@@ -22,7 +22,7 @@ pub fn get_asset(
         return Ok(asset);
     }
 
-    if let Some(mod_pak_index) = find_asset(mod_paks, name) {
+    if let Some(mod_pak_index) = find_asset(mod_paks, name)? {
         return read_asset(
             |name| {
                 mod_paks[mod_pak_index].read_entry(name).map_or_else(
@@ -41,7 +41,7 @@ pub fn get_asset(
         );
     }
 
-    let game_pak_index = find_asset(game_paks, name)
+    let game_pak_index = find_asset(game_paks, name)?
         .ok_or_else(|| IntegrationError::asset_not_found(name.clone()))?;
 
     read_asset(
@@ -62,13 +62,16 @@ pub fn get_asset(
     )
 }
 
-pub fn find_asset(paks: &[PakReader<BufReader<File>>], name: &String) -> Option<usize> {
+pub fn find_asset(
+    paks: &[PakReader<BufReader<File>>],
+    name: &String,
+) -> Result<Option<usize>, Error> {
     for (i, pak) in paks.iter().enumerate() {
-        if pak.contains_entry(name) {
-            return Some(i);
+        if pak.contains_entry(name)? {
+            return Ok(Some(i));
         }
     }
-    None
+    Ok(None)
 }
 
 pub fn read_asset<F>(
@@ -413,6 +413,7 @@ pub fn integrate_mods<
 
     for mod_file in mod_files {
         let mut pak = PakReader::new(BufReader::new(mod_file));
+        pak.set_lazy_index(true);
         pak.load_index()?;
 
         let record = pak.read_entry(&String::from("metadata.json"))?;
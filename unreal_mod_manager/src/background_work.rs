@@ -83,6 +83,7 @@ fn download_mod(
     let file = fs::File::open(&file_path)?;
 
     let mut pak = PakReader::new(&file);
+    pak.set_lazy_index(true);
     pak.load_index()?;
 
     let metadata = pak.read_entry(&String::from("metadata.json"))?;
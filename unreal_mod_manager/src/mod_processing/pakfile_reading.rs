@@ -30,6 +30,8 @@ pub(crate) fn read_pak_files(
             let file = fs::File::open(file_path)
                 .map_err(|err| ModLoaderWarning::from(err).with_mod_id(file_name.clone()))?;
             let mut pak = PakReader::new(&file);
+            // only the metadata is needed, don't decode every entry of the pak
+            pak.set_lazy_index(true);
 
             pak.load_index()
                 .map_err(|err| ModLoaderWarning::from(err).with_mod_id(file_name.clone()))?;
@@ -0,0 +1,13 @@
+#![no_main]
+
+use std::io::Cursor;
+
+use libfuzzer_sys::fuzz_target;
+use unreal_pak::{AesKey, PakReader};
+
+fuzz_target!(|data: &[u8]| {
+    let mut reader = PakReader::new(Cursor::new(data));
+    reader.set_encryption_key(AesKey::new([0x42; 32]));
+    reader.set_verify_hashes(true);
+    let _ = reader.load_index();
+});
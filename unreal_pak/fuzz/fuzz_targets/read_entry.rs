@@ -0,0 +1,25 @@
+#![no_main]
+
+use std::io::{Cursor, Read};
+
+use libfuzzer_sys::fuzz_target;
+use unreal_pak::{AesKey, PakReader};
+
+fuzz_target!(|data: &[u8]| {
+    let mut reader = PakReader::new(Cursor::new(data));
+    reader.set_encryption_key(AesKey::new([0x42; 32]));
+    if reader.load_index().is_err() {
+        return;
+    }
+
+    let names = match reader.get_entry_names() {
+        Ok(names) => names.into_iter().cloned().collect::<Vec<_>>(),
+        Err(_) => return,
+    };
+    for name in &names {
+        let _ = reader.read_entry(name);
+        if let Ok(mut entry) = reader.open_entry(name) {
+            let _ = entry.read_to_end(&mut Vec::new());
+        }
+    }
+});
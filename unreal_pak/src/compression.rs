@@ -1,16 +1,185 @@
 //! Compression abstraction
 //! Currently supportted compressions (in addition to no compression):
 //! - Zlib
+//! - Gzip
+//! - Zstd
+//! - LZ4
+//!
+//! Additional compression methods (like Oodle) can be added by implementing [`CompressionCodec`]
+//! and registering the codec with [`register_codec`].
 
-//* Note: when adding more compressions you should only have to update stuff in this file, but in a few places.
+//* Note: when adding more built-in compressions you should only have to update stuff in this file, but in a few places.
 
+use std::fmt;
 use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::sync::{Arc, OnceLock, RwLock};
 
-use flate2::{read::ZlibDecoder, write::ZlibEncoder};
+use flate2::{
+    read::{GzDecoder, ZlibDecoder},
+    write::{GzEncoder, ZlibEncoder},
+};
 
 use crate::error::PakError;
 use crate::pakversion::PakVersion;
 
+/// A compression method which can be used to compress and decompress pak entries.
+///
+/// Implement this trait and call [`register_codec`] to add support for compression methods
+/// which are not built into this crate.
+pub trait CompressionCodec: Send + Sync {
+    /// Name of the compression method as it is stored in the compression method table of the pak
+    /// file, for example `"Oodle"`. Names are compared case-insensitively.
+    fn name(&self) -> &'static str;
+
+    /// Compress a single compression block.
+    fn compress(&self, data: &[u8]) -> io::Result<Vec<u8>>;
+
+    /// Decompress a single compression block and append the result to `buf`.
+    /// `decompressed_size` is the expected size of the decompressed block, it comes from the pak file
+    /// so it should not be trusted for allocating more memory than the decompressed data needs.
+    fn decompress(
+        &self,
+        buf: &mut Vec<u8>,
+        data: &[u8],
+        decompressed_size: usize,
+    ) -> io::Result<()>;
+}
+
+struct ZlibCodec;
+
+impl CompressionCodec for ZlibCodec {
+    fn name(&self) -> &'static str {
+        "Zlib"
+    }
+
+    fn compress(&self, data: &[u8]) -> io::Result<Vec<u8>> {
+        let mut encoder = ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(data)?;
+        encoder.finish()
+    }
+
+    fn decompress(
+        &self,
+        buf: &mut Vec<u8>,
+        data: &[u8],
+        decompressed_size: usize,
+    ) -> io::Result<()> {
+        ZlibDecoder::new(data)
+            .take(decompressed_size as u64)
+            .read_to_end(buf)?;
+        Ok(())
+    }
+}
+
+struct GzipCodec;
+
+impl CompressionCodec for GzipCodec {
+    fn name(&self) -> &'static str {
+        "Gzip"
+    }
+
+    fn compress(&self, data: &[u8]) -> io::Result<Vec<u8>> {
+        let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(data)?;
+        encoder.finish()
+    }
+
+    fn decompress(
+        &self,
+        buf: &mut Vec<u8>,
+        data: &[u8],
+        decompressed_size: usize,
+    ) -> io::Result<()> {
+        GzDecoder::new(data)
+            .take(decompressed_size as u64)
+            .read_to_end(buf)?;
+        Ok(())
+    }
+}
+
+struct ZstdCodec;
+
+impl CompressionCodec for ZstdCodec {
+    fn name(&self) -> &'static str {
+        "Zstd"
+    }
+
+    fn compress(&self, data: &[u8]) -> io::Result<Vec<u8>> {
+        Ok(ruzstd::encoding::compress_to_vec(
+            data,
+            ruzstd::encoding::CompressionLevel::Fastest,
+        ))
+    }
+
+    fn decompress(
+        &self,
+        buf: &mut Vec<u8>,
+        mut data: &[u8],
+        decompressed_size: usize,
+    ) -> io::Result<()> {
+        let decoder = ruzstd::decoding::StreamingDecoder::new(&mut data)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        decoder.take(decompressed_size as u64).read_to_end(buf)?;
+        Ok(())
+    }
+}
+
+struct Lz4Codec;
+
+impl CompressionCodec for Lz4Codec {
+    fn name(&self) -> &'static str {
+        "LZ4"
+    }
+
+    fn compress(&self, data: &[u8]) -> io::Result<Vec<u8>> {
+        Ok(lz4_flex::block::compress(data))
+    }
+
+    fn decompress(
+        &self,
+        buf: &mut Vec<u8>,
+        data: &[u8],
+        decompressed_size: usize,
+    ) -> io::Result<()> {
+        // the output buffer is allocated up front, LZ4 can not expand data by more than 255 times
+        if decompressed_size > data.len().saturating_mul(255) {
+            return Err(io::ErrorKind::InvalidData.into());
+        }
+        let decompressed = lz4_flex::block::decompress(data, decompressed_size)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        buf.extend_from_slice(&decompressed);
+        Ok(())
+    }
+}
+
+fn codecs() -> &'static RwLock<Vec<Arc<dyn CompressionCodec>>> {
+    static CODECS: OnceLock<RwLock<Vec<Arc<dyn CompressionCodec>>>> = OnceLock::new();
+    CODECS.get_or_init(|| {
+        RwLock::new(vec![
+            Arc::new(ZlibCodec),
+            Arc::new(GzipCodec),
+            Arc::new(ZstdCodec),
+            Arc::new(Lz4Codec),
+        ])
+    })
+}
+
+/// Register a compression codec so it can be used for reading and writing pak files.
+/// A codec with the same name as an already registered one replaces it.
+pub fn register_codec<C: CompressionCodec + 'static>(codec: C) {
+    let mut codecs = codecs().write().unwrap_or_else(|err| err.into_inner());
+    codecs.retain(|registered| !registered.name().eq_ignore_ascii_case(codec.name()));
+    codecs.push(Arc::new(codec));
+}
+
+fn find_codec(name: &str) -> Option<Arc<dyn CompressionCodec>> {
+    let codecs = codecs().read().unwrap_or_else(|err| err.into_inner());
+    codecs
+        .iter()
+        .find(|codec| codec.name().eq_ignore_ascii_case(name))
+        .cloned()
+}
+
 /// Enum representing which compression method is being used for an entry
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum Compression {
@@ -29,17 +198,40 @@ impl Compression {
         Self::Known("Zlib")
     }
 
+    /// Create Gzip Compression configuration
+    pub fn gzip() -> Self {
+        Self::Known("Gzip")
+    }
+
+    /// Create Zstd Compression configuration
+    pub fn zstd() -> Self {
+        Self::Known("Zstd")
+    }
+
+    /// Create LZ4 Compression configuration
+    pub fn lz4() -> Self {
+        Self::Known("LZ4")
+    }
+
+    /// Create a Compression configuration from the name of a registered codec.
+    /// Returns `None` if no codec with that name was registered.
+    pub fn from_name(name: &str) -> Option<Self> {
+        find_codec(name).map(|codec| Self::Known(codec.name()))
+    }
+
     pub(crate) fn from_reader<R: Read + Seek>(reader: &mut R) -> io::Result<Self> {
         let mut buf = [0; 0x20];
         reader.read_exact(&mut buf)?;
 
-        Ok(if buf == [0; 0x20] {
-            Self::None
-        } else if buf == pad_zeroes("Zlib".as_bytes()) {
-            Self::zlib()
-        } else {
-            Self::Unknown(buf)
-        })
+        if buf == [0; 0x20] {
+            return Ok(Self::None);
+        }
+
+        let len = buf.iter().position(|byte| *byte == 0).unwrap_or(buf.len());
+        Ok(std::str::from_utf8(&buf[..len])
+            .ok()
+            .and_then(Self::from_name)
+            .unwrap_or(Self::Unknown(buf)))
     }
 
     pub(crate) fn from_u32(
@@ -47,7 +239,7 @@ impl Compression {
         pak_version: PakVersion,
         compression: &CompressionMethods,
     ) -> Self {
-        if pak_version >= PakVersion::FnameBasedCompressionMethod {
+        if pak_version >= PakVersion::FnameBasedCompressionMethodInitial {
             if compression_method_num == 0 {
                 Compression::None
             } else if compression_method_num <= 5 {
@@ -60,6 +252,7 @@ impl Compression {
         } else {
             match compression_method_num {
                 0x01 | 0x10 | 0x20 => Compression::zlib(),
+                0x02 => Compression::gzip(),
                 _ => Compression::None,
             }
         }
@@ -72,7 +265,7 @@ impl Compression {
     ) -> Result<u32, PakError> {
         match self {
             Self::Known(method) => {
-                if pak_version >= PakVersion::FnameBasedCompressionMethod {
+                if pak_version >= PakVersion::FnameBasedCompressionMethodInitial {
                     match compression
                         .0
                         .iter()
@@ -80,17 +273,18 @@ impl Compression {
                         .find(|(_, method)| *method == self)
                     {
                         Some((i, _)) => Ok((i + 1) as u32),
-                        None => Err(PakError::compression_unsupported_unknown()),
+                        None => Err(PakError::compression_unsupported(*self)),
                     }
                 } else {
                     match *method {
                         "Zlib" => Ok(1),
-                        _ => Err(PakError::compression_unsupported_unknown()),
+                        "Gzip" => Ok(2),
+                        _ => Err(PakError::compression_unsupported(*self)),
                     }
                 }
             }
             Self::None => Ok(0),
-            _ => Err(PakError::compression_unsupported_unknown()),
+            _ => Err(PakError::compression_unsupported(*self)),
         }
     }
 
@@ -102,40 +296,49 @@ impl Compression {
         }
     }
 
-    // These are panics becasue they should hard fail during developement.
-
-    pub(crate) fn decompress(&self, buf: &mut Vec<u8>, data: &[u8]) -> io::Result<()> {
+    fn codec(&self) -> Result<Arc<dyn CompressionCodec>, PakError> {
         match self {
-            Self::Known(method) => match *method {
-                "Zlib" => {
-                    let mut decoder = ZlibDecoder::new(data);
-                    decoder.read_to_end(buf)?;
-                    Ok(())
-                }
-                _ => panic!("Found Compression::Known with unknown compression."),
-            },
-            _ => panic!("Attempted to decompress with Compression type that can't decompress."),
+            Self::Known(method) => {
+                find_codec(method).ok_or_else(|| PakError::compression_unsupported(*self))
+            }
+            _ => Err(PakError::compression_unsupported(*self)),
         }
     }
 
-    pub(crate) fn compress(&self, data: &[u8]) -> io::Result<Vec<u8>> {
+    pub(crate) fn decompress(
+        &self,
+        buf: &mut Vec<u8>,
+        data: &[u8],
+        decompressed_size: usize,
+    ) -> Result<(), PakError> {
+        Ok(self.codec()?.decompress(buf, data, decompressed_size)?)
+    }
+
+    pub(crate) fn compress(&self, data: &[u8]) -> Result<Vec<u8>, PakError> {
+        Ok(self.codec()?.compress(data)?)
+    }
+}
+
+impl fmt::Display for Compression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            Self::Known(method) => match *method {
-                "Zlib" => {
-                    let mut encoder = ZlibEncoder::new(Vec::new(), flate2::Compression::default());
-                    encoder.write_all(data)?;
-                    Ok(encoder.finish()?)
-                }
-                _ => panic!("Found Compression::Known with unknown compression."),
-            },
-            _ => panic!("Attempted to compress with Compression type that can't compress."),
+            Self::None => f.write_str("None"),
+            Self::Known(method) => f.write_str(method),
+            Self::Unknown(method) => {
+                let len = method
+                    .iter()
+                    .position(|byte| *byte == 0)
+                    .unwrap_or(method.len());
+                write!(f, "{}", String::from_utf8_lossy(&method[..len]))
+            }
         }
     }
 }
 
 fn pad_zeroes(slice: &[u8]) -> [u8; 0x20] {
     let mut arr = [0; 0x20];
-    arr[..slice.len()].copy_from_slice(slice);
+    let len = slice.len().min(0x20);
+    arr[..len].copy_from_slice(&slice[..len]);
     arr
 }
 
@@ -143,10 +346,48 @@ fn pad_zeroes(slice: &[u8]) -> [u8; 0x20] {
 pub(crate) struct CompressionMethods(pub [Compression; 5]);
 
 impl CompressionMethods {
-    pub fn zlib() -> Self {
-        let mut methods = Self::default();
-        methods.0[0] = Compression::zlib();
-        methods
+    /// Number of compression methods stored in the footer, UE 4.22 only has 4
+    fn len(pak_version: PakVersion) -> usize {
+        match pak_version {
+            PakVersion::FnameBasedCompressionMethodInitial => 4,
+            _ => 5,
+        }
+    }
+
+    /// Add a compression method to the table if it is not in there yet.
+    pub fn add(&mut self, method: Compression, pak_version: PakVersion) -> Result<(), PakError> {
+        if matches!(method, Compression::None) || self.0.contains(&method) {
+            return Ok(());
+        }
+
+        match self.0[..Self::len(pak_version)]
+            .iter_mut()
+            .find(|slot| matches!(slot, Compression::None))
+        {
+            Some(slot) => {
+                *slot = method;
+                Ok(())
+            }
+            None => Err(PakError::configuration_invalid()),
+        }
+    }
+
+    /// Whether entries using the compression method can be written in this pak version,
+    /// and there is space for it in the table
+    pub fn can_store(&self, method: Compression, pak_version: PakVersion) -> bool {
+        match method {
+            Compression::None => true,
+            Compression::Known(name) => {
+                if pak_version >= PakVersion::FnameBasedCompressionMethodInitial {
+                    let mut compression_methods = *self;
+                    compression_methods.add(method, pak_version).is_ok()
+                } else {
+                    pak_version >= PakVersion::CompressionEncryption
+                        && matches!(name, "Zlib" | "Gzip")
+                }
+            }
+            Compression::Unknown(_) => false,
+        }
     }
 
     /// Read compression from provided reader. Position of the reader after return not specified.
@@ -155,7 +396,7 @@ impl CompressionMethods {
         // This is why first the length of the remaining stream is determined and then only
         // the existing bytes read.
         let old_pos = reader.stream_position()?;
-        let remaining_len = reader.seek(SeekFrom::End(0))? - old_pos;
+        let remaining_len = reader.seek(SeekFrom::End(0))?.saturating_sub(old_pos);
         reader.seek(SeekFrom::Start(old_pos))?;
 
         let mut methods = Self::default();
@@ -169,13 +410,12 @@ impl CompressionMethods {
         Ok(methods)
     }
 
-    pub fn as_bytes(&self) -> Vec<u8> {
-        // TODO UE4.22 only add 4
-        let num_entries = 5usize;
+    pub fn as_bytes(&self, pak_version: PakVersion) -> Vec<u8> {
+        let num_entries = Self::len(pak_version);
 
         let mut buf = Vec::with_capacity(num_entries * 0x20);
-        for i in 0..num_entries {
-            buf.extend_from_slice(&self.0[i].as_bytes());
+        for method in &self.0[..num_entries] {
+            buf.extend_from_slice(&method.as_bytes());
         }
 
         buf
@@ -0,0 +1,136 @@
+//! Comparing the contents of two pak files
+
+use std::collections::BTreeSet;
+use std::io::{Read, Seek};
+
+use crate::compression::Compression;
+use crate::error::PakError;
+use crate::header::Header;
+use crate::pakreader::PakReader;
+use crate::pakversion::PakVersion;
+
+/// Differences between two pak files, created with [`PakDiff::compare`].
+///
+/// Differences are stored as `(old, new)` pairs, `None` means the value did not change.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct PakDiff {
+    /// Pak versions of the old and new pak
+    pub pak_version: Option<(PakVersion, PakVersion)>,
+    /// Mount points of the old and new pak
+    pub mount_point: Option<(String, String)>,
+    /// Entries only in the new pak
+    pub added: Vec<String>,
+    /// Entries only in the old pak
+    pub removed: Vec<String>,
+    /// Entries in both paks which differ in content or in how they are stored
+    pub changed: Vec<EntryDiff>,
+}
+
+/// Differences of an entry which exists in both compared paks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntryDiff {
+    /// Name of the entry
+    pub name: String,
+    /// Whether the decompressed data of the entry changed
+    pub content_changed: bool,
+    /// Compression methods of the old and new entry
+    pub compression: Option<(Compression, Compression)>,
+    /// Compressed sizes of the old and new entry
+    pub compressed_size: Option<(u64, u64)>,
+    /// Decompressed sizes of the old and new entry
+    pub decompressed_size: Option<(u64, u64)>,
+    /// Whether the old and new entry are encrypted
+    pub encrypted: Option<(bool, bool)>,
+}
+
+impl PakDiff {
+    /// Compare two paks, both need to have their index loaded.
+    ///
+    /// Entries are compared using the SHA1 hashes stored in the index. When the hashes differ or are not
+    /// available, which is the case for paks using version 10 or higher, the decompressed data is compared.
+    /// Entries are matched by their name relative to the mount point.
+    pub fn compare<A, B>(old: &mut PakReader<A>, new: &mut PakReader<B>) -> Result<Self, PakError>
+    where
+        A: Read + Seek,
+        B: Read + Seek,
+    {
+        let mut diff = PakDiff {
+            pak_version: changed(old.get_pak_version(), new.get_pak_version()),
+            mount_point: changed(old.mount_point.clone(), new.mount_point.clone()),
+            ..Default::default()
+        };
+
+        let old_names = old
+            .get_entry_names()?
+            .into_iter()
+            .cloned()
+            .collect::<BTreeSet<_>>();
+        let new_names = new
+            .get_entry_names()?
+            .into_iter()
+            .cloned()
+            .collect::<BTreeSet<_>>();
+
+        diff.added = new_names.difference(&old_names).cloned().collect();
+        diff.removed = old_names.difference(&new_names).cloned().collect();
+
+        for name in old_names.intersection(&new_names) {
+            let (Some(old_header), Some(new_header)) =
+                (old.get_header(name)?, new.get_header(name)?)
+            else {
+                continue;
+            };
+
+            let mut entry = EntryDiff {
+                name: name.clone(),
+                content_changed: false,
+                compression: changed(old_header.compression_method, new_header.compression_method),
+                compressed_size: changed(old_header.compressed_size, new_header.compressed_size),
+                decompressed_size: changed(
+                    old_header.decompressed_size,
+                    new_header.decompressed_size,
+                ),
+                encrypted: changed(old_header.is_encrypted(), new_header.is_encrypted()),
+            };
+
+            entry.content_changed = if entry.decompressed_size.is_some() {
+                true
+            } else if same_hash(&old_header, &new_header) && entry.compression.is_none() {
+                false
+            } else {
+                old.read_entry(name)? != new.read_entry(name)?
+            };
+
+            if entry.content_changed
+                || entry.compression.is_some()
+                || entry.compressed_size.is_some()
+                || entry.encrypted.is_some()
+            {
+                diff.changed.push(entry);
+            }
+        }
+
+        Ok(diff)
+    }
+
+    /// Whether the paks are the same
+    pub fn is_empty(&self) -> bool {
+        self.pak_version.is_none()
+            && self.mount_point.is_none()
+            && self.added.is_empty()
+            && self.removed.is_empty()
+            && self.changed.is_empty()
+    }
+}
+
+fn changed<T: PartialEq>(old: T, new: T) -> Option<(T, T)> {
+    match old == new {
+        true => None,
+        false => Some((old, new)),
+    }
+}
+
+/// Whether both headers have the same hash, hashes of encoded entries are not stored and are all zeroes
+fn same_hash(old: &Header, new: &Header) -> bool {
+    old.hash != [0u8; 20] && old.hash == new.hash
+}
@@ -0,0 +1,78 @@
+//! AES-256 encryption of pak indexes and entries
+//!
+//! Unreal Engine encrypts pak data using AES-256 in ECB mode without any padding scheme,
+//! which means that all encrypted data is stored aligned to the AES block size.
+
+use std::fmt;
+
+use aes::cipher::{generic_array::GenericArray, BlockDecrypt, BlockEncrypt, KeyInit};
+use aes::Aes256;
+
+use crate::error::PakError;
+
+/// Size of a single AES block, encrypted data is always aligned to this
+pub(crate) const AES_BLOCK_SIZE: u64 = 16;
+
+/// Align a length to the AES block size
+pub(crate) fn align(len: u64) -> u64 {
+    len.saturating_add(AES_BLOCK_SIZE - 1) & !(AES_BLOCK_SIZE - 1)
+}
+
+/// An AES-256 key used to decrypt and encrypt pak files
+#[derive(Clone)]
+pub struct AesKey(Aes256);
+
+impl AesKey {
+    /// Create a key from its raw 32 bytes.
+    pub fn new(key: [u8; 32]) -> Self {
+        Self(Aes256::new(GenericArray::from_slice(&key)))
+    }
+
+    /// Create a key from a hex string like `0x1A2B...`, the `0x` prefix is optional.
+    pub fn from_hex(key: &str) -> Result<Self, PakError> {
+        let key = key.trim();
+        let key = key
+            .strip_prefix("0x")
+            .or_else(|| key.strip_prefix("0X"))
+            .unwrap_or(key);
+
+        if key.len() != 64 || !key.is_ascii() {
+            return Err(PakError::encryption_key_invalid());
+        }
+
+        let mut bytes = [0u8; 32];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&key[i * 2..i * 2 + 2], 16)
+                .map_err(|_| PakError::encryption_key_invalid())?;
+        }
+
+        Ok(Self::new(bytes))
+    }
+
+    /// Decrypt data in place, the length of data must be a multiple of the AES block size.
+    pub(crate) fn decrypt(&self, data: &mut [u8]) {
+        for block in data.chunks_exact_mut(AES_BLOCK_SIZE as usize) {
+            self.0.decrypt_block(GenericArray::from_mut_slice(block));
+        }
+    }
+
+    /// Encrypt data in place, the length of data must be a multiple of the AES block size.
+    pub(crate) fn encrypt(&self, data: &mut [u8]) {
+        for block in data.chunks_exact_mut(AES_BLOCK_SIZE as usize) {
+            self.0.encrypt_block(GenericArray::from_mut_slice(block));
+        }
+    }
+}
+
+impl From<[u8; 32]> for AesKey {
+    fn from(key: [u8; 32]) -> Self {
+        Self::new(key)
+    }
+}
+
+impl fmt::Debug for AesKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // never print the actual key
+        f.write_str("AesKey(..)")
+    }
+}
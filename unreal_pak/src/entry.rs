@@ -1,11 +1,15 @@
-use std::io::{Read, Seek, SeekFrom, Write};
+use std::borrow::Cow;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+
+use sha1::{Digest, Sha1};
 
 use crate::compression::CompressionMethods;
+use crate::encryption::{self, AesKey};
 use crate::error::PakError;
-use crate::hash;
-use crate::header::{Block, Header};
+use crate::header::{Block, Header, FLAG_ENCRYPTED};
 use crate::pakversion::PakVersion;
 use crate::Compression;
+use crate::{hash, read_bytes, MAX_PREALLOCATION};
 
 /// Read a pak entry at the given offset in the reader
 ///
@@ -14,11 +18,18 @@ use crate::Compression;
 /// * `reader` - Anything that implements Read + Seek
 /// * `pak_version` - Version of the pak format used
 /// * `offset` - The offset of the start of the header of the file
+/// * `key` - Key used to decrypt the entry if it is encrypted
+/// * `name` - Name of the entry, used for error reporting
+/// * `verify` - Check the SHA1 hash of the entry
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn read_entry<R>(
     reader: &mut R,
     pak_version: PakVersion,
     compression: &CompressionMethods,
     offset: u64,
+    key: Option<&AesKey>,
+    name: &str,
+    verify: bool,
 ) -> Result<Vec<u8>, PakError>
 where
     R: Read + Seek,
@@ -27,88 +38,378 @@ where
 
     let header = Header::read(reader, pak_version, compression)?;
 
-    match header.compression_method {
+    let key = if header.is_encrypted() {
+        Some(key.ok_or_else(PakError::encryption_key_missing)?)
+    } else {
+        None
+    };
+
+    // tools disagree on whether the hash includes the encryption padding or is of the decompressed data,
+    // so all of them are accepted
+    let mut stored_hasher = Sha1::new();
+    let mut unpadded_hasher = Sha1::new();
+
+    let data = match header.compression_method {
         Compression::None => {
-            let mut data = vec![0u8; header.decompressed_size as usize];
-            reader.read_exact(data.as_mut_slice())?;
-            Ok(data)
+            let mut data = read_data(reader, header.decompressed_size, key)?;
+            if verify {
+                stored_hasher.update(&data);
+            }
+            data.truncate(header.decompressed_size as usize);
+            data
         }
         Compression::Known(_) => {
-            let mut data = Vec::with_capacity(header.decompressed_size as usize);
+            let mut data =
+                Vec::with_capacity(header.decompressed_size.min(MAX_PREALLOCATION) as usize);
 
             let compression_blocks = header
                 .compression_blocks
                 .as_ref()
                 .ok_or_else(PakError::entry_invalid)?;
+            let block_size = match header.compression_block_size {
+                Some(block_size) if block_size > 0 => block_size as u64,
+                _ => header.decompressed_size,
+            };
+
             for block in compression_blocks {
                 // we do not need to seek here because the reader is at the end of the header and compression blocks are continuous
-                let mut compressed_data = vec![0u8; block.size as usize];
-                reader.read_exact(&mut compressed_data)?;
-                header
-                    .compression_method
-                    .decompress(&mut data, compressed_data.as_slice())?;
+                let compressed_data = read_data(reader, block.size, key)?;
+                let compressed_data_unpadded = &compressed_data[..block.size as usize];
+                if verify {
+                    stored_hasher.update(&compressed_data);
+                    unpadded_hasher.update(compressed_data_unpadded);
+                }
+
+                let remaining_size = header.decompressed_size.saturating_sub(data.len() as u64);
+                header.compression_method.decompress(
+                    &mut data,
+                    compressed_data_unpadded,
+                    block_size.min(remaining_size) as usize,
+                )?;
             }
 
+            data
+        }
+        _ => return Err(PakError::compression_unsupported(header.compression_method)),
+    };
+
+    if verify {
+        let stored_hash: [u8; 20] = stored_hasher.finalize().into();
+        let unpadded_hash: [u8; 20] = unpadded_hasher.finalize().into();
+
+        if header.hash != stored_hash && header.hash != unpadded_hash && header.hash != hash(&data)
+        {
+            return Err(PakError::entry_hash_mismatch(name.to_owned()));
+        }
+    }
+
+    Ok(data)
+}
+
+/// Read `size` bytes of entry data, decrypting them if a key is given.
+/// Encrypted data is padded to the AES block size so the returned data might be longer than `size`.
+fn read_data<R: Read>(
+    reader: &mut R,
+    size: u64,
+    key: Option<&AesKey>,
+) -> Result<Vec<u8>, PakError> {
+    match key {
+        Some(key) => {
+            let mut data = read_bytes(reader, encryption::align(size))?;
+            key.decrypt(&mut data);
             Ok(data)
         }
-        _ => Err(PakError::compression_unsupported(header.compression_method)),
+        None => Ok(read_bytes(reader, size)?),
     }
 }
 
-/// Write an entry with Header at the position the write is at
+/// Metadata of a pak entry as stored in the index
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntryInfo {
+    /// Offset of the entry in the pak file, the data is preceded by a copy of the entry header
+    pub offset: u64,
+    /// Size of the data in the pak file, without encryption padding
+    pub compressed_size: u64,
+    /// Size of the data after decompression
+    pub decompressed_size: u64,
+    /// Compression method of the entry
+    pub compression: Compression,
+    /// Decompressed size of each compression block, only stored by version 3 and higher
+    pub compression_block_size: Option<u32>,
+    /// Compressed size of each compression block, empty for uncompressed entries
+    pub block_sizes: Vec<u64>,
+    /// SHA1 hash of the stored data.
+    /// `None` when the index does not contain it, which is the case for most entries since version 10.
+    pub hash: Option<[u8; 20]>,
+    /// Whether the data of the entry is encrypted
+    pub encrypted: bool,
+    /// Timestamp of the entry, only stored by version 1
+    pub timestamp: Option<u64>,
+}
+
+impl EntryInfo {
+    pub(crate) fn new(header: &Header) -> Self {
+        EntryInfo {
+            offset: header.offset,
+            compressed_size: header.compressed_size,
+            decompressed_size: header.decompressed_size,
+            compression: header.compression_method,
+            compression_block_size: header.compression_block_size,
+            block_sizes: header
+                .compression_blocks
+                .iter()
+                .flatten()
+                .map(|block| block.size)
+                .collect(),
+            // encoded entries do not store a hash
+            hash: (header.hash != [0u8; 20]).then_some(header.hash),
+            encrypted: header.is_encrypted(),
+            timestamp: header.timestamp,
+        }
+    }
+
+    /// Number of compression blocks
+    pub fn block_count(&self) -> usize {
+        self.block_sizes.len()
+    }
+}
+
+/// Size of the chunks in which uncompressed entries are read by [`EntryReader`]
+const UNCOMPRESSED_CHUNK_SIZE: u64 = 0x10000;
+
+/// A reader over the data of a single pak entry.
+///
+/// Compression blocks are only read and decompressed when data inside of them is requested,
+/// so seeking to the middle of an entry does not require decompressing everything before it.
+/// Hashes are not verified when reading an entry this way.
+pub struct EntryReader<'a, R>
+where
+    R: Read + Seek,
+{
+    reader: &'a mut R,
+    header: Header,
+    key: Option<&'a AesKey>,
+    /// start of the entry data, only used for uncompressed entries
+    data_offset: u64,
+    /// decompressed size of each block, the last block might be smaller
+    block_size: u64,
+    /// position in the decompressed data
+    position: u64,
+    /// index and decompressed data of the currently loaded block
+    block: Option<(usize, Vec<u8>)>,
+}
+
+impl<'a, R> EntryReader<'a, R>
+where
+    R: Read + Seek,
+{
+    /// Create a reader for the entry described by `header`, which has to come from the index.
+    pub(crate) fn new(
+        reader: &'a mut R,
+        pak_version: PakVersion,
+        header: Header,
+        key: Option<&'a AesKey>,
+    ) -> Result<Self, PakError> {
+        let key = if header.is_encrypted() {
+            Some(key.ok_or_else(PakError::encryption_key_missing)?)
+        } else {
+            None
+        };
+
+        let block_size = match header.compression_method {
+            Compression::None => UNCOMPRESSED_CHUNK_SIZE,
+            Compression::Known(_) => match header.compression_block_size {
+                Some(block_size) if block_size > 0 => block_size as u64,
+                _ => header.decompressed_size.max(1),
+            },
+            _ => return Err(PakError::compression_unsupported(header.compression_method)),
+        };
+
+        Ok(EntryReader {
+            reader,
+            key,
+            data_offset: header
+                .offset
+                .saturating_add(Header::calculate_header_len(pak_version, None)),
+            block_size,
+            position: 0,
+            block: None,
+            header,
+        })
+    }
+
+    /// Decompressed size of the entry
+    pub fn len(&self) -> u64 {
+        self.header.decompressed_size
+    }
+
+    /// Whether the entry is empty
+    pub fn is_empty(&self) -> bool {
+        self.header.decompressed_size == 0
+    }
+
+    /// Read and decompress the block with the given index
+    fn load_block(&mut self, index: usize) -> Result<Vec<u8>, PakError> {
+        let block_start = index as u64 * self.block_size;
+        let size = self
+            .block_size
+            .min(self.header.decompressed_size - block_start);
+
+        match self.header.compression_method {
+            Compression::None => {
+                // uncompressed chunks are aligned to the AES block size, so they can be decrypted on their own
+                self.reader.seek(SeekFrom::Start(
+                    self.data_offset.saturating_add(block_start),
+                ))?;
+                let mut data = read_data(self.reader, size, self.key)?;
+                data.truncate(size as usize);
+                Ok(data)
+            }
+            _ => {
+                let block = self
+                    .header
+                    .compression_blocks
+                    .as_ref()
+                    .and_then(|blocks| blocks.get(index))
+                    .ok_or_else(PakError::entry_invalid)?;
+
+                self.reader.seek(SeekFrom::Start(
+                    self.header.offset.saturating_add(block.start),
+                ))?;
+                let compressed_data = read_data(self.reader, block.size, self.key)?;
+
+                let mut data = Vec::with_capacity(size.min(MAX_PREALLOCATION) as usize);
+                self.header.compression_method.decompress(
+                    &mut data,
+                    &compressed_data[..block.size as usize],
+                    size as usize,
+                )?;
+                Ok(data)
+            }
+        }
+    }
+}
+
+impl<R> Read for EntryReader<'_, R>
+where
+    R: Read + Seek,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.position >= self.header.decompressed_size || buf.is_empty() {
+            return Ok(0);
+        }
+
+        let index = (self.position / self.block_size) as usize;
+        let data = match self.block.take() {
+            Some((loaded_index, data)) if loaded_index == index => data,
+            _ => self.load_block(index).map_err(io::Error::other)?,
+        };
+
+        let block_position = (self.position - index as u64 * self.block_size) as usize;
+        let available = data.len().saturating_sub(block_position);
+        if available == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "compression block is smaller than expected",
+            ));
+        }
+
+        let len = available.min(buf.len());
+        buf[..len].copy_from_slice(&data[block_position..block_position + len]);
+        self.position += len as u64;
+        self.block = Some((index, data));
+
+        Ok(len)
+    }
+}
+
+impl<R> Seek for EntryReader<'_, R>
+where
+    R: Read + Seek,
+{
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let position = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => self.header.decompressed_size.checked_add_signed(offset),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+        };
+
+        match position {
+            Some(position) => {
+                self.position = position;
+                Ok(position)
+            }
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )),
+        }
+    }
+}
+
+/// An entry which has been compressed and encrypted, but not written yet.
+/// Compression block starts are relative to the start of the entry header.
+#[derive(Debug)]
+pub(crate) struct PreparedEntry<'a> {
+    pub header: Header,
+    pub data: Cow<'a, [u8]>,
+}
+
+/// Compress, hash and encrypt the data of an entry so it can be written by [`write_prepared_entry`].
+/// This does not depend on the position the entry will be written at,
+/// so multiple entries can be prepared at the same time.
 ///
 /// # Arguments
 ///
-/// * `writer` - Anything that implements Write + Seek
 /// * `pak_version` - Version of the pak format to be used
 /// * `data` - Uncompressed data to be written
 /// * `compression_method` - What compression to use
 /// * `block_size` - size of the used compression blocks
-pub(crate) fn write_entry<W>(
-    writer: &mut W,
+/// * `key` - Key used to encrypt the entry, if `None` the entry is not encrypted
+pub(crate) fn prepare_entry<'a>(
     pak_version: PakVersion,
-    data: &Vec<u8>,
-    compress: bool,
-    compression: &CompressionMethods,
+    data: &'a [u8],
+    compression_method: Compression,
     block_size: u32,
-) -> Result<Header, PakError>
-where
-    W: Write + Seek,
-{
-    let offset = writer.stream_position()?;
+    key: Option<&AesKey>,
+) -> Result<PreparedEntry<'a>, PakError> {
     let decompressed_size = data.len() as u64;
 
-    let compress = compress && decompressed_size >= 32;
-    let compression_method = if compress {
-        compression.0[0]
+    if key.is_some() && pak_version < PakVersion::CompressionEncryption {
+        return Err(PakError::enrcryption_unsupported());
+    }
+
+    let compression_method = if decompressed_size >= 32 {
+        compression_method
     } else {
         Compression::None
     };
 
     // compress data in memory
-    let mut compressed_data = if compress {
-        Vec::with_capacity(data.len())
-    } else {
-        // this will actually never be used
-        Vec::new()
-    };
     let mut compression_blocks = None;
-    let data = match compression_method {
+    let mut data = match compression_method {
         Compression::Known(_) => {
             if pak_version < PakVersion::CompressionEncryption {
                 return Err(PakError::configuration_invalid());
             }
 
-            let block_count = (data.len() as f64 / block_size as f64).ceil() as usize;
-            let mut compression_blocks_inner = Vec::with_capacity(block_count);
-            let header_len = Header::calculate_header_len(pak_version, Some(block_count as u32));
+            let compressed_blocks = compress_blocks(compression_method, data, block_size)?;
+            let mut compression_blocks_inner = Vec::with_capacity(compressed_blocks.len());
+            let header_len =
+                Header::calculate_header_len(pak_version, Some(compressed_blocks.len() as u32));
 
-            for chunk in data.chunks(block_size as usize) {
+            let mut compressed_data = Vec::with_capacity(data.len());
+            for block_compressed_data in compressed_blocks {
                 let begin = compressed_data.len() as u64;
 
-                let block_compressed_data = compression_method.compress(chunk)?;
                 compressed_data.extend_from_slice(&block_compressed_data);
 
+                // every encrypted block starts aligned to the AES block size
+                if key.is_some() {
+                    compressed_data
+                        .resize(encryption::align(compressed_data.len() as u64) as usize, 0);
+                }
+
                 compression_blocks_inner.push(Block {
                     start: begin + header_len,
                     size: block_compressed_data.len() as u64,
@@ -116,40 +417,255 @@ where
             }
 
             compression_blocks = Some(compression_blocks_inner);
-            &compressed_data
+            Cow::Owned(compressed_data)
         }
-        Compression::None => data,
+        Compression::None => match key {
+            Some(_) => {
+                let mut padded_data = data.to_vec();
+                padded_data.resize(encryption::align(decompressed_size) as usize, 0);
+                Cow::Owned(padded_data)
+            }
+            None => Cow::Borrowed(data),
+        },
         _ => return Err(PakError::compression_unsupported(compression_method)),
     };
 
+    // uncompressed entries have a block size of 0, like in the encoded index
     let compression_block_size = if pak_version >= PakVersion::CompressionEncryption {
-        compression_blocks.as_ref().map(|blocks| {
-            if blocks.len() == 1 {
-                decompressed_size as u32
-            } else {
-                block_size
-            }
+        Some(match &compression_blocks {
+            Some(blocks) if blocks.len() == 1 => decompressed_size as u32,
+            Some(_) => block_size,
+            None => 0,
         })
     } else {
         None
     };
 
-    let mut header = Header {
+    let compressed_size = match compression_method {
+        Compression::None => decompressed_size,
+        _ => data.len() as u64,
+    };
+
+    let header = Header {
         offset: 0x00,
-        compressed_size: data.len() as u64,
+        compressed_size,
         decompressed_size,
         compression_method,
-        hash: hash(data),
+        timestamp: None,
+        hash: hash(&data),
         compression_blocks,
         compression_block_size,
-        flags: Some(0x00),
+        flags: Some(if key.is_some() { FLAG_ENCRYPTED } else { 0x00 }),
+    };
+
+    if let Some(key) = key {
+        key.encrypt(data.to_mut());
+    }
+
+    Ok(PreparedEntry { header, data })
+}
+
+/// Copy the compressed blocks of an entry without decompressing them, so it can be written by
+/// [`write_prepared_entry`] into a pak of another version or with another encryption key.
+///
+/// # Arguments
+///
+/// * `reader` - Reader of the pak containing the entry
+/// * `header` - Header of the entry from the index, has to be compressed
+/// * `source_key` - Key used to decrypt the entry if it is encrypted
+/// * `pak_version` - Version of the pak format the entry will be written in
+/// * `key` - Key used to encrypt the entry, if `None` the entry is not encrypted
+pub(crate) fn prepare_raw_entry<R>(
+    reader: &mut R,
+    header: &Header,
+    source_key: Option<&AesKey>,
+    pak_version: PakVersion,
+    key: Option<&AesKey>,
+) -> Result<PreparedEntry<'static>, PakError>
+where
+    R: Read + Seek,
+{
+    let source_blocks = match (&header.compression_method, &header.compression_blocks) {
+        (Compression::Known(_), Some(blocks)) => blocks,
+        _ => return Err(PakError::entry_invalid()),
     };
+    if pak_version < PakVersion::CompressionEncryption {
+        return Err(PakError::configuration_invalid());
+    }
+
+    let source_key = if header.is_encrypted() {
+        Some(source_key.ok_or_else(PakError::encryption_key_missing)?)
+    } else {
+        None
+    };
+
+    let header_len = Header::calculate_header_len(pak_version, Some(source_blocks.len() as u32));
+    let mut compression_blocks = Vec::with_capacity(source_blocks.len());
+    let mut data = Vec::with_capacity(header.compressed_size.min(MAX_PREALLOCATION) as usize);
+    for block in source_blocks {
+        reader.seek(SeekFrom::Start(header.offset.saturating_add(block.start)))?;
+        let block_data = read_data(reader, block.size, source_key)?;
+
+        let begin = data.len() as u64;
+        data.extend_from_slice(&block_data[..block.size as usize]);
+
+        // every encrypted block starts aligned to the AES block size
+        if key.is_some() {
+            data.resize(encryption::align(data.len() as u64) as usize, 0);
+        }
+
+        compression_blocks.push(Block {
+            start: begin + header_len,
+            size: block.size,
+        });
+    }
+
+    let header = Header {
+        offset: 0x00,
+        compressed_size: data.len() as u64,
+        decompressed_size: header.decompressed_size,
+        compression_method: header.compression_method,
+        timestamp: None,
+        hash: hash(&data),
+        compression_blocks: Some(compression_blocks),
+        compression_block_size: header.compression_block_size,
+        flags: Some(if key.is_some() { FLAG_ENCRYPTED } else { 0x00 }),
+    };
+
+    if let Some(key) = key {
+        key.encrypt(&mut data);
+    }
+
+    Ok(PreparedEntry {
+        header,
+        data: Cow::Owned(data),
+    })
+}
+
+/// Read an entry exactly as it is stored in the pak, so it can be written again without any changes.
+/// The header has to come from the index.
+pub(crate) fn read_stored_entry<R>(
+    reader: &mut R,
+    pak_version: PakVersion,
+    compression: &CompressionMethods,
+    header: &Header,
+) -> Result<PreparedEntry<'static>, PakError>
+where
+    R: Read + Seek,
+{
+    // the header in front of the data is kept as it is, encoded entries in the index of v10+ paks
+    // are missing its hash and block size. Block offsets are taken from the index where they are relative.
+    reader.seek(SeekFrom::Start(header.offset))?;
+    let stored_header = Header::read(reader, pak_version, compression)?;
+
+    let data_offset = header.data_offset(pak_version);
+    reader.seek(SeekFrom::Start(
+        header
+            .offset
+            .checked_add(data_offset)
+            .ok_or_else(PakError::entry_invalid)?,
+    ))?;
+    let data = read_bytes(
+        reader,
+        header.entry_len(pak_version).saturating_sub(data_offset),
+    )?;
+
+    Ok(PreparedEntry {
+        header: Header {
+            offset: 0x00,
+            compression_blocks: header.compression_blocks.clone(),
+            ..stored_header
+        },
+        data: Cow::Owned(data),
+    })
+}
+
+/// Compress the data in chunks of `block_size`, in parallel if the `rayon` feature is enabled.
+pub(crate) fn compress_blocks(
+    compression_method: Compression,
+    data: &[u8],
+    block_size: u32,
+) -> Result<Vec<Vec<u8>>, PakError> {
+    if block_size == 0 {
+        return Err(PakError::configuration_invalid());
+    }
+
+    #[cfg(feature = "rayon")]
+    {
+        use rayon::prelude::*;
+
+        data.par_chunks(block_size as usize)
+            .map(|chunk| compression_method.compress(chunk))
+            .collect()
+    }
+
+    #[cfg(not(feature = "rayon"))]
+    {
+        data.chunks(block_size as usize)
+            .map(|chunk| compression_method.compress(chunk))
+            .collect()
+    }
+}
+
+/// Write a prepared entry with Header at the position the writer is at
+///
+/// # Arguments
+///
+/// * `writer` - Anything that implements Write + Seek
+/// * `pak_version` - Version of the pak format to be used
+/// * `compression` - Compression methods table of the pak, has to contain the entry's compression method
+/// * `entry` - The entry returned by [`prepare_entry`]
+pub(crate) fn write_prepared_entry<W>(
+    writer: &mut W,
+    pak_version: PakVersion,
+    compression: &CompressionMethods,
+    entry: PreparedEntry,
+) -> Result<Header, PakError>
+where
+    W: Write + Seek,
+{
+    let offset = writer.stream_position()?;
+    let PreparedEntry { mut header, data } = entry;
+
+    // before RelativeChunkOffsets block offsets are absolute in the file
+    if pak_version < PakVersion::RelativeChunkOffsets {
+        for block in header.compression_blocks.iter_mut().flatten() {
+            block.start += offset;
+        }
+    }
 
     Header::write(writer, pak_version, compression, &header)?;
-    writer.write_all(data)?;
+    writer.write_all(&data)?;
 
     // the offset in the header right before the data is always 0x00, so only set here
     header.offset = offset;
 
     Ok(header)
 }
+
+/// Write an entry with Header at the position the write is at
+///
+/// # Arguments
+///
+/// * `writer` - Anything that implements Write + Seek
+/// * `pak_version` - Version of the pak format to be used
+/// * `data` - Uncompressed data to be written
+/// * `compression_method` - What compression to use, has to be in the compression methods table
+/// * `compression` - Compression methods table of the pak
+/// * `block_size` - size of the used compression blocks
+/// * `key` - Key used to encrypt the entry, if `None` the entry is not encrypted
+pub(crate) fn write_entry<W>(
+    writer: &mut W,
+    pak_version: PakVersion,
+    data: &[u8],
+    compression_method: Compression,
+    compression: &CompressionMethods,
+    block_size: u32,
+    key: Option<&AesKey>,
+) -> Result<Header, PakError>
+where
+    W: Write + Seek,
+{
+    let entry = prepare_entry(pak_version, data, compression_method, block_size, key)?;
+    write_prepared_entry(writer, pak_version, compression, entry)
+}
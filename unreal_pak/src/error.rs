@@ -39,6 +39,24 @@ impl PakError {
             kind: PakErrorKind::EncryptionUnsupported,
         }
     }
+    /// construct EncryptionKeyMissing error
+    pub fn encryption_key_missing() -> Self {
+        PakError {
+            kind: PakErrorKind::EncryptionKeyMissing,
+        }
+    }
+    /// construct EncryptionKeyInvalid error
+    pub fn encryption_key_invalid() -> Self {
+        PakError {
+            kind: PakErrorKind::EncryptionKeyInvalid,
+        }
+    }
+    /// construct IoStoreVersionUnsupported error
+    pub fn io_store_version_unsupported(version: u8) -> Self {
+        PakError {
+            kind: PakErrorKind::IoStoreVersionUnsupported(version),
+        }
+    }
     /// construct InvalidConfiguration error
     pub fn configuration_invalid() -> Self {
         PakError {
@@ -64,12 +82,54 @@ impl PakError {
             kind: PakErrorKind::EntryNotFound(file_name),
         }
     }
+    /// construct IndexHashMismatch error
+    pub fn index_hash_mismatch() -> Self {
+        PakError {
+            kind: PakErrorKind::IndexHashMismatch,
+        }
+    }
+    /// construct EntryHashMismatch error
+    pub fn entry_hash_mismatch(file_name: String) -> Self {
+        PakError {
+            kind: PakErrorKind::EntryHashMismatch(file_name),
+        }
+    }
     /// construct InvalidFile error
     pub fn entry_invalid() -> Self {
         PakError {
             kind: PakErrorKind::EntryInvalid,
         }
     }
+    /// construct SignatureInvalid error
+    pub fn signature_invalid() -> Self {
+        PakError {
+            kind: PakErrorKind::SignatureInvalid,
+        }
+    }
+    /// construct SignatureMismatch error
+    pub fn signature_mismatch() -> Self {
+        PakError {
+            kind: PakErrorKind::SignatureMismatch,
+        }
+    }
+    /// construct ChunkHashMismatch error
+    pub fn chunk_hash_mismatch(chunk: usize) -> Self {
+        PakError {
+            kind: PakErrorKind::ChunkHashMismatch(chunk),
+        }
+    }
+    /// construct SigningKeyInvalid error
+    pub fn signing_key_invalid() -> Self {
+        PakError {
+            kind: PakErrorKind::SigningKeyInvalid,
+        }
+    }
+    /// construct PackageInvalid error
+    pub fn package_invalid(reason: String) -> Self {
+        PakError {
+            kind: PakErrorKind::PackageInvalid(reason),
+        }
+    }
 }
 
 impl fmt::Display for PakError {
@@ -82,6 +142,13 @@ impl fmt::Display for PakError {
                 format!("Unsupported compression method: {method:?}")
             }
             PakErrorKind::EncryptionUnsupported => "Encryption is not supported".to_string(),
+            PakErrorKind::EncryptionKeyMissing => {
+                "Pak file is encrypted but no encryption key was provided".to_string()
+            }
+            PakErrorKind::EncryptionKeyInvalid => "Invalid encryption key".to_string(),
+            PakErrorKind::IoStoreVersionUnsupported(version) => {
+                format!("Unsupported IoStore toc version: {version}")
+            }
             PakErrorKind::ConfigurationInvalid => "Invalid configuration".to_string(),
             PakErrorKind::DoubleWrite(ref name) => {
                 format!("Attempted to write a file twice into the same PakFile, name: {name}")
@@ -92,6 +159,21 @@ impl fmt::Display for PakError {
                 format!("File not found: {file_name}")
             }
             PakErrorKind::EntryInvalid => "Invalid file".to_string(),
+            PakErrorKind::IndexHashMismatch => "Index hash does not match".to_string(),
+            PakErrorKind::EntryHashMismatch(ref file_name) => {
+                format!("Hash does not match for file: {file_name}")
+            }
+            PakErrorKind::SignatureInvalid => "Invalid signature file".to_string(),
+            PakErrorKind::SignatureMismatch => {
+                "Signature does not match the chunk hashes".to_string()
+            }
+            PakErrorKind::ChunkHashMismatch(chunk) => {
+                format!("Hash does not match for chunk: {chunk}")
+            }
+            PakErrorKind::SigningKeyInvalid => "Invalid signing key".to_string(),
+            PakErrorKind::PackageInvalid(ref reason) => {
+                format!("Package can't be converted to the zen package format: {reason}")
+            }
 
             PakErrorKind::IoError(ref err) => {
                 format!("IO error: {err}")
@@ -132,6 +214,12 @@ pub enum PakErrorKind {
     CompressionUnsupported(Compression),
     /// encryption is not supported
     EncryptionUnsupported,
+    /// the pak file is encrypted but no key was provided
+    EncryptionKeyMissing,
+    /// an encryption key could not be parsed
+    EncryptionKeyInvalid,
+    /// the IoStore toc version found is not supported by the library
+    IoStoreVersionUnsupported(u8),
     /// the state of a struct is invalid
     ConfigurationInvalid,
     /// Attempted to write a file twice into the same PakFile
@@ -143,6 +231,20 @@ pub enum PakErrorKind {
     EntryNotFound(String),
     /// a (compressed) file is corrupted or similar
     EntryInvalid,
+    /// the SHA1 hash of the index does not match the one stored in the footer
+    IndexHashMismatch,
+    /// the SHA1 hash of a file does not match the one stored in its header
+    EntryHashMismatch(String),
+    /// a signature file is not correctly formatted or uses an unsupported version
+    SignatureInvalid,
+    /// the RSA signature of a signature file does not match its chunk hashes
+    SignatureMismatch,
+    /// the CRC32 of a chunk of the pak file does not match the one in the signature file
+    ChunkHashMismatch(usize),
+    /// an RSA signing key could not be constructed
+    SigningKeyInvalid,
+    /// a package is not a cooked UE5 package or uses features which can't be converted to the zen package format
+    PackageInvalid(String),
 
     /// something went wrong during reading
     IoError(io::Error),
@@ -10,8 +10,22 @@
         - blocks
             - u64 block start
             - u64 block end
-    - u8 is encrypted flag
+    - u8 flags (0x01 encrypted, 0x02 delete record)
     - u32 block size
+
+    encoded entry header (version >= 10, only in the index):
+    - u32 bitfield
+        - bits 0-5 block size >> 11 (0x3f if it does not fit)
+        - bits 6-21 number of blocks
+        - bit 22 is encrypted flag
+        - bits 23-28 compression method
+        - bits 29-31 whether compressed size, size and offset fit into a u32
+    - u32 block size (only if it did not fit into the bitfield)
+    - u32/u64 offset
+    - u32/u64 size decompressed
+    - u32/u64 size (only when compression method is not 0)
+    - u32 block sizes (only when encrypted or more than one block)
+    delete records can not be encoded and are always stored as legacy headers in the index
 */
 
 use std::io::{self, Read, Seek, Write};
@@ -20,16 +34,24 @@ use bitvec::prelude::*;
 use byteorder::{ReadBytesExt, WriteBytesExt, LE};
 
 use crate::compression::{Compression, CompressionMethods};
+use crate::encryption;
 use crate::error::PakError;
 use crate::pakversion::PakVersion;
 
-#[derive(Debug)]
+/// Flag set on entries which have their data encrypted
+pub(crate) const FLAG_ENCRYPTED: u8 = 0x01;
+/// Flag set on delete records, which remove an entry of a lower priority pak
+pub(crate) const FLAG_DELETED: u8 = 0x02;
+
+#[derive(Debug, Clone)]
 pub(crate) struct Header {
     /// This may incorrectly be 0x00
     pub offset: u64,
     pub compressed_size: u64,
     pub decompressed_size: u64,
     pub compression_method: Compression,
+    /// Only stored in version 1
+    pub timestamp: Option<u64>,
     pub hash: [u8; 20],
     pub compression_blocks: Option<Vec<Block>>,
     pub flags: Option<u8>,
@@ -46,6 +68,35 @@ pub(crate) struct Block {
 }
 
 impl Header {
+    /// Whether the data of this entry is encrypted
+    pub(crate) fn is_encrypted(&self) -> bool {
+        self.flags.unwrap_or(0) & FLAG_ENCRYPTED != 0
+    }
+
+    /// Whether this entry is a delete record without any data
+    pub(crate) fn is_deleted(&self) -> bool {
+        self.flags.unwrap_or(0) & FLAG_DELETED != 0
+    }
+
+    /// Create the header of a delete record, these only exist in the index
+    pub(crate) fn delete_record(pak_version: PakVersion) -> Result<Self, PakError> {
+        if pak_version < PakVersion::DeleteRecords {
+            return Err(PakError::configuration_invalid());
+        }
+
+        Ok(Header {
+            offset: 0,
+            compressed_size: 0,
+            decompressed_size: 0,
+            compression_method: Compression::None,
+            timestamp: None,
+            hash: [0; 20],
+            compression_blocks: None,
+            flags: Some(FLAG_DELETED),
+            compression_block_size: Some(0),
+        })
+    }
+
     /// Read data from the reader into a Header, reader needs to be set at start of a header
     pub(crate) fn read<R: Read>(
         reader: &mut R,
@@ -61,9 +112,11 @@ impl Header {
         let compression_method =
             Compression::from_u32(reader.read_u32::<LE>()?, pak_version, compression);
 
-        if pak_version <= PakVersion::Initial {
-            let _timestamp = reader.read_u64::<LE>()?;
-        }
+        let timestamp = if pak_version <= PakVersion::Initial {
+            Some(reader.read_u64::<LE>()?)
+        } else {
+            None
+        };
 
         let mut hash = [0u8; 20];
         reader.read_exact(&mut hash)?;
@@ -74,22 +127,25 @@ impl Header {
 
         if pak_version >= PakVersion::CompressionEncryption {
             if !matches!(compression_method, Compression::None) {
-                let block_count = reader.read_u32::<LE>()? as usize;
-                let mut compression_blocks_inner = Vec::with_capacity(block_count);
+                // the block count is not used for allocating, the reader runs out of data for invalid counts
+                let block_count = reader.read_u32::<LE>()?;
+                let mut compression_blocks_inner = Vec::new();
 
                 for _ in 0..block_count {
-                    // convert old absolute to relative offsets
-                    let start_offset = reader.read_u64::<LE>()?
-                        - if pak_version < PakVersion::RelativeChunkOffsets {
-                            offset
-                        } else {
-                            0
-                        };
+                    let start_offset = reader.read_u64::<LE>()?;
                     let end_offset = reader.read_u64::<LE>()?;
-                    compression_blocks_inner.push(Block {
-                        start: start_offset,
-                        size: end_offset - start_offset,
-                    });
+
+                    // convert old absolute to relative offsets
+                    let start = match pak_version < PakVersion::RelativeChunkOffsets {
+                        true => start_offset.checked_sub(offset),
+                        false => Some(start_offset),
+                    };
+                    let size = end_offset.checked_sub(start_offset);
+                    let (Some(start), Some(size)) = (start, size) else {
+                        return Err(PakError::entry_invalid());
+                    };
+
+                    compression_blocks_inner.push(Block { start, size });
                 }
                 compression_blocks = Some(compression_blocks_inner);
             }
@@ -103,6 +159,7 @@ impl Header {
             compressed_size,
             decompressed_size,
             compression_method,
+            timestamp,
             hash,
             compression_blocks,
             compression_block_size,
@@ -120,10 +177,8 @@ impl Header {
         reader.read_exact(&mut header_bits)?;
         let header_bits = header_bits.view_bits::<Lsb0>();
 
-        // compression blocks
-        // this is actually irrelevant here because it is also included in the header before the actual data
         let mut block_size = header_bits[0..=5].load_le::<u32>();
-        let _block_count = header_bits[6..=21].load_le::<u32>();
+        let block_count = header_bits[6..=21].load_le::<u32>();
 
         if block_size == 0x3f {
             block_size = reader.read_u32::<LE>()?;
@@ -154,20 +209,125 @@ impl Header {
             read_size(29)?
         };
 
-        // compression blocks could be read here but why waste the time, they won't get used anyways
+        // compression blocks start right after the header in front of the data,
+        // a single unencrypted block is not stored as it always covers the whole entry
+        let mut compression_blocks = None;
+        if block_count > 0 {
+            let mut start = Self::calculate_header_len(pak_version, Some(block_count));
+
+            if block_count == 1 && !is_encrypted {
+                compression_blocks = Some(vec![Block {
+                    start,
+                    size: compressed_size,
+                }]);
+            } else {
+                let mut compression_blocks_inner = Vec::with_capacity(block_count as usize);
+                for _ in 0..block_count {
+                    let size = reader.read_u32::<LE>()? as u64;
+                    compression_blocks_inner.push(Block { start, size });
+                    start += if is_encrypted {
+                        encryption::align(size)
+                    } else {
+                        size
+                    };
+                }
+                compression_blocks = Some(compression_blocks_inner);
+            }
+        }
 
         Ok(Header {
             offset,
             compressed_size,
             decompressed_size,
             compression_method,
+            timestamp: None,
             hash: [0; 20],
-            compression_blocks: None,
+            compression_blocks,
             compression_block_size: Some(block_size),
-            flags: Some(if is_encrypted { 1 } else { 0 }),
+            flags: Some(if is_encrypted { FLAG_ENCRYPTED } else { 0 }),
         })
     }
 
+    /// Check if this header can be written in the (bit)encoded form
+    pub(crate) fn can_encode(
+        &self,
+        pak_version: PakVersion,
+        compression: &CompressionMethods,
+    ) -> Result<bool, PakError> {
+        let block_count = self
+            .compression_blocks
+            .as_ref()
+            .map_or(0, |blocks| blocks.len());
+        let compression_method = self.compression_method.as_u32(pak_version, compression)?;
+
+        // the encoded form has no room for the deleted flag
+        Ok(!self.is_deleted() && block_count < (1 << 16) && compression_method < (1 << 6))
+    }
+
+    /// Write (bit)encoded header, [`Header::can_encode`] must be checked before calling this
+    pub(crate) fn write_encoded<W: Write>(
+        writer: &mut W,
+        pak_version: PakVersion,
+        compression: &CompressionMethods,
+        header: &Self,
+    ) -> Result<(), PakError> {
+        let compression_method = header.compression_method.as_u32(pak_version, compression)?;
+        let is_encrypted = header.is_encrypted();
+        let block_count = header
+            .compression_blocks
+            .as_ref()
+            .map_or(0, |blocks| blocks.len() as u32);
+
+        let block_size = header.compression_block_size.unwrap_or(0);
+        let mut packed_block_size = (block_size >> 11) & 0x3f;
+        if packed_block_size << 11 != block_size {
+            packed_block_size = 0x3f;
+        }
+
+        let offset_u32 = header.offset <= u32::MAX as u64;
+        let decompressed_size_u32 = header.decompressed_size <= u32::MAX as u64;
+        let compressed_size_u32 = header.compressed_size <= u32::MAX as u64;
+
+        let mut header_bits = [0u8; 4];
+        let bits = header_bits.view_bits_mut::<Lsb0>();
+        bits[0..=5].store_le(packed_block_size);
+        bits[6..=21].store_le(block_count);
+        bits.set(22, is_encrypted);
+        bits[23..=28].store_le(compression_method);
+        bits.set(29, compressed_size_u32);
+        bits.set(30, decompressed_size_u32);
+        bits.set(31, offset_u32);
+        writer.write_all(&header_bits)?;
+
+        if packed_block_size == 0x3f {
+            writer.write_u32::<LE>(block_size)?;
+        }
+
+        let mut write_size = |size: u64, is_u32: bool| -> io::Result<()> {
+            if is_u32 {
+                writer.write_u32::<LE>(size as u32)
+            } else {
+                writer.write_u64::<LE>(size)
+            }
+        };
+
+        write_size(header.offset, offset_u32)?;
+        write_size(header.decompressed_size, decompressed_size_u32)?;
+        if !matches!(header.compression_method, Compression::None) {
+            write_size(header.compressed_size, compressed_size_u32)?;
+        }
+
+        if let Some(compression_blocks) = &header.compression_blocks {
+            if is_encrypted || compression_blocks.len() != 1 {
+                for block in compression_blocks {
+                    writer.write_u32::<LE>(block.size as u32)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     /// Write data from a Header into the writer, writer needs to be set where the header is supposed to be written
     pub(crate) fn write<W: Write>(
         writer: &mut W,
@@ -180,6 +340,10 @@ impl Header {
         writer.write_u64::<LE>(header.decompressed_size)?;
         writer.write_u32::<LE>(header.compression_method.as_u32(pak_version, compression)?)?;
 
+        if pak_version <= PakVersion::Initial {
+            writer.write_u64::<LE>(header.timestamp.unwrap_or_default())?;
+        }
+
         writer.write_all(&header.hash)?;
 
         if pak_version >= PakVersion::CompressionEncryption {
@@ -200,6 +364,37 @@ impl Header {
         Ok(())
     }
 
+    /// Size of the header in front of the entry data
+    pub(crate) fn data_offset(&self, pak_version: PakVersion) -> u64 {
+        let block_count = match self.compression_method {
+            Compression::None => None,
+            _ => self
+                .compression_blocks
+                .as_ref()
+                .map(|blocks| blocks.len() as u32),
+        };
+        Self::calculate_header_len(pak_version, block_count)
+    }
+
+    /// Size of the entry in the pak, including the header in front of its data and the encryption padding
+    pub(crate) fn entry_len(&self, pak_version: PakVersion) -> u64 {
+        let padded = |size| match self.is_encrypted() {
+            true => encryption::align(size),
+            false => size,
+        };
+
+        match (&self.compression_method, &self.compression_blocks) {
+            (Compression::None, _) | (_, None) => {
+                self.data_offset(pak_version) + padded(self.compressed_size)
+            }
+            (_, Some(blocks)) => blocks
+                .iter()
+                .map(|block| block.start + padded(block.size))
+                .max()
+                .unwrap_or_else(|| self.data_offset(pak_version)),
+        }
+    }
+
     pub(crate) fn calculate_header_len(pak_version: PakVersion, block_count: Option<u32>) -> u64 {
         let mut len = 0;
 
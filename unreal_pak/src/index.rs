@@ -1,100 +1,313 @@
+use std::collections::{BTreeMap, HashMap};
 use std::io::{Cursor, Read, Seek, SeekFrom, Write};
+use std::sync::OnceLock;
 
 use byteorder::{ReadBytesExt, WriteBytesExt, BE, LE};
 
 use unreal_helpers::{UnrealReadExt, UnrealWriteExt};
 
-use crate::compression::CompressionMethods;
+use crate::compression::{Compression, CompressionMethods};
+use crate::encryption::{self, AesKey};
 use crate::error::PakError;
 use crate::header::Header;
 use crate::pakversion::PakVersion;
-use crate::{hash, PAK_MAGIC};
+use crate::{hash, read_bytes, PAK_MAGIC};
 
 #[derive(Debug)]
 pub(crate) struct Index {
     pub mount_point: String,
     pub path_hash_seed: Option<u64>,
     pub entries: Vec<(String, Header)>,
+    /// Entries of a v10+ pak which have not been decoded yet
+    pub lazy: Option<LazyIndex>,
     pub footer: Footer,
 }
 
-impl Index {
-    pub(crate) fn read<R: Read + Seek>(mut reader: &mut R) -> Result<Self, PakError> {
-        let footer = Footer::read(&mut reader)?;
+/// Entries of a v10+ pak which are only decoded when they are looked up
+#[derive(Debug)]
+pub(crate) struct LazyIndex {
+    pak_version: PakVersion,
+    compression_methods: CompressionMethods,
+    path_hash_seed: u64,
+    /// Location of every entry by the hash of its path, `None` when the pak has no usable path hash index.
+    /// Hashes shared by multiple entries have no location.
+    path_hashes: Option<HashMap<u64, Option<i32>>>,
+    encoded_entries: Vec<u8>,
+    non_encoded_entries: Vec<Header>,
+    full_directory_index: Vec<u8>,
+    /// Name offsets by entry location, built on the first lookup
+    names: OnceLock<NameOffsets>,
+}
+
+/// Offsets of the directory and file name in the full directory index by entry location.
+/// Locations used by multiple names have no offsets.
+type NameOffsets = HashMap<i32, Option<(u64, u64)>>;
+
+impl LazyIndex {
+    /// Find an entry or delete record by hashing its path.
+    /// Returns `None` when the entry can't be found this way, because the pak has no usable path hash index
+    /// or the hash is shared with other paths.
+    pub(crate) fn find(&self, path: &str) -> Option<Result<Option<Header>, PakError>> {
+        let location = match self.path_hashes.as_ref()?.get(&path_hash(
+            path,
+            self.path_hash_seed,
+            self.pak_version,
+        )) {
+            Some(Some(location)) => *location,
+            Some(None) => return None,
+            None => return Some(Ok(None)),
+        };
+
+        let names = match self.names() {
+            Ok(names) => names,
+            Err(err) => return Some(Err(err)),
+        };
+        // path hashes ignore the case, names have to match exactly like when the index is not loaded lazily
+        match names.get(&location) {
+            Some(Some(offsets)) => Some(match self.name(*offsets) {
+                Ok(name) if name == path => self.entry(location).map(Some),
+                Ok(_) => Ok(None),
+                Err(err) => Err(err),
+            }),
+            Some(None) => None,
+            None => Some(Ok(None)),
+        }
+    }
+
+    /// Returns the name offsets by entry location, walking the full directory index without reading the names
+    fn names(&self) -> Result<&NameOffsets, PakError> {
+        if let Some(names) = self.names.get() {
+            return Ok(names);
+        }
+
+        let mut directory_reader = Cursor::new(&self.full_directory_index);
+        let skip_fstring = |reader: &mut Cursor<&Vec<u8>>| -> Result<(), PakError> {
+            let len = reader.read_i32::<LE>()? as i64;
+            // negative lengths are UTF-16 characters
+            let size = if len < 0 { -len * 2 } else { len } as u64;
+            let end = reader.position() + size;
+            if end > reader.get_ref().len() as u64 {
+                return Err(PakError::pak_invalid());
+            }
+            reader.set_position(end);
+            Ok(())
+        };
+
+        let mut names = HashMap::new();
+        let directory_count = directory_reader.read_u32::<LE>()?;
+        for _ in 0..directory_count {
+            let directory = directory_reader.position();
+            skip_fstring(&mut directory_reader)?;
+
+            let file_count = directory_reader.read_u32::<LE>()?;
+            for _ in 0..file_count {
+                let file = directory_reader.position();
+                skip_fstring(&mut directory_reader)?;
+                let location = directory_reader.read_i32::<LE>()?;
+
+                names
+                    .entry(location)
+                    .and_modify(|offsets| *offsets = None)
+                    .or_insert(Some((directory, file)));
+            }
+        }
+
+        Ok(self.names.get_or_init(|| names))
+    }
+
+    /// Read the path of an entry from the offsets of its directory and file name
+    fn name(&self, (directory, file): (u64, u64)) -> Result<String, PakError> {
+        let mut directory_reader = Cursor::new(&self.full_directory_index);
+        directory_reader.set_position(directory);
+        let directory_name = directory_reader.read_fstring()?.unwrap_or_default();
+        let directory_name = directory_name.strip_prefix('/').unwrap_or(&directory_name);
 
-        reader.seek(SeekFrom::Start(footer.index_offset))?;
+        directory_reader.set_position(file);
+        let file_name = directory_reader.read_fstring()?.unwrap_or_default();
 
-        let mount_point = reader.read_fstring()?.unwrap_or_default();
+        Ok(format!("{directory_name}{file_name}"))
+    }
+
+    /// Walk the full directory index and decode all entries
+    pub(crate) fn entries(&self) -> Result<Vec<(String, Header)>, PakError> {
+        let mut directory_reader = Cursor::new(&self.full_directory_index);
+
+        let mut entries = Vec::new();
+        let directory_count = directory_reader.read_u32::<LE>()?;
+        for _ in 0..directory_count {
+            let directory_name = directory_reader.read_fstring()?.unwrap_or_default();
+            let directory_name = directory_name.strip_prefix('/').unwrap_or(&directory_name);
+
+            let file_count = directory_reader.read_u32::<LE>()?;
+            for _ in 0..file_count {
+                let file_name = directory_reader.read_fstring()?.unwrap_or_default();
+                let location = directory_reader.read_i32::<LE>()?;
+
+                entries.push((
+                    format!("{directory_name}{file_name}"),
+                    self.entry(location)?,
+                ));
+            }
+        }
+
+        Ok(entries)
+    }
+
+    /// Decode the entry at a location from the path hash or directory index
+    fn entry(&self, location: i32) -> Result<Header, PakError> {
+        if location >= 0 {
+            let mut reader = Cursor::new(
+                self.encoded_entries
+                    .get(location as usize..)
+                    .ok_or_else(PakError::pak_invalid)?,
+            );
+            Header::read_encoded(&mut reader, self.pak_version, &self.compression_methods)
+        } else {
+            // negative locations are indices into the non encoded entries, starting at -1
+            self.non_encoded_entries
+                .get((-(location as i64) - 1) as usize)
+                .cloned()
+                .ok_or_else(PakError::pak_invalid)
+        }
+    }
+}
+
+/// Read the location of every entry by the hash of its path
+fn read_path_hash_index(data: &[u8]) -> Result<HashMap<u64, Option<i32>>, PakError> {
+    let mut reader = Cursor::new(data);
+
+    let mut path_hashes = HashMap::new();
+    for _ in 0..reader.read_u32::<LE>()? {
+        let hash = reader.read_u64::<LE>()?;
+        let location = reader.read_i32::<LE>()?;
+        path_hashes
+            .entry(hash)
+            .and_modify(|location| *location = None)
+            .or_insert(Some(location));
+    }
+
+    Ok(path_hashes)
+}
+
+impl Index {
+    /// Read the index, with `lazy` the entries of v10+ paks are not decoded and
+    /// [`Index::lazy`] is set instead of [`Index::entries`]
+    pub(crate) fn read<R: Read + Seek>(
+        reader: &mut R,
+        key: Option<&AesKey>,
+        verify: bool,
+        lazy: bool,
+    ) -> Result<Self, PakError> {
+        let footer = Footer::read(reader)?;
+        let index_encrypted = footer.index_encrypted.unwrap_or_default();
+
+        let index_data = read_index_data(
+            reader,
+            footer.index_offset,
+            footer.index_size,
+            index_encrypted,
+            key,
+            verify.then_some(&footer.index_hash),
+        )?;
+        let mut index_reader = Cursor::new(index_data);
+
+        let mount_point = index_reader.read_fstring()?.unwrap_or_default();
         let mut path_hash_seed = None;
+        let mut lazy_index = None;
 
-        let entry_count = reader.read_u32::<LE>()?;
-        let mut entries = Vec::with_capacity(entry_count as usize);
+        // counts read from the index are not used for allocating, the index runs out of data for invalid counts
+        let entry_count = index_reader.read_u32::<LE>()?;
+        let mut entries = Vec::new();
 
         if footer.pak_version < PakVersion::PathHashIndex {
             for _ in 0..entry_count {
-                let file_name = reader.read_fstring()?.unwrap_or_default();
+                let file_name = index_reader.read_fstring()?.unwrap_or_default();
 
                 entries.push((
                     file_name,
-                    Header::read(reader, footer.pak_version, &footer.compression_methods)?,
+                    Header::read(
+                        &mut index_reader,
+                        footer.pak_version,
+                        &footer.compression_methods,
+                    )?,
                 ));
             }
         } else {
-            path_hash_seed = Some(reader.read_u64::<LE>()?);
-
-            // path hash index
-            if reader.read_u32::<LE>()? != 0 {
-                let _path_hash_index_offset = reader.read_u64::<LE>()?;
-                let _path_hash_index_size = reader.read_u64::<LE>()?;
-                // skip hash
-                reader.seek(SeekFrom::Current(20))?;
-            }
-
-            let full_directory_index = if reader.read_u32::<LE>()? != 0 {
-                let full_directory_index_offset = reader.read_u64::<LE>()?;
-                let _full_directory_index_size = reader.read_u64::<LE>()?;
-                // skip hash
-                reader.seek(SeekFrom::Current(20))?;
-
-                let previous_pos = reader.stream_position()?;
-                reader.seek(SeekFrom::Start(full_directory_index_offset))?;
-
-                let directory_count = reader.read_u32::<LE>()? as usize;
-                let mut directories = Vec::new();
-                for _ in 0..directory_count {
-                    let directory_name = reader.read_fstring()?.unwrap_or_default();
-                    let file_count = reader.read_u32::<LE>()? as usize;
-                    let mut files = Vec::new();
-                    for _ in 0..file_count {
-                        let file_name = reader.read_fstring()?.unwrap_or_default();
-                        files.push((file_name, reader.read_u32::<LE>()?));
-                    }
-                    directories.push((directory_name, files));
+            let seed = index_reader.read_u64::<LE>()?;
+            path_hash_seed = Some(seed);
+
+            // the path hash index is only read to check its hash or for looking up entries lazily
+            let path_hash_index = if index_reader.read_u32::<LE>()? != 0 {
+                let path_hash_index_offset = index_reader.read_u64::<LE>()?;
+                let path_hash_index_size = index_reader.read_u64::<LE>()?;
+                let mut path_hash_index_hash = [0u8; 20];
+                index_reader.read_exact(&mut path_hash_index_hash)?;
+
+                match verify || (lazy && footer.pak_version >= PakVersion::Fnv64BugFix) {
+                    true => Some(read_index_data(
+                        reader,
+                        path_hash_index_offset,
+                        path_hash_index_size,
+                        index_encrypted,
+                        key,
+                        verify.then_some(&path_hash_index_hash),
+                    )?),
+                    false => None,
                 }
+            } else {
+                None
+            };
 
-                reader.seek(SeekFrom::Start(previous_pos))?;
-                directories
+            let full_directory_index = if index_reader.read_u32::<LE>()? != 0 {
+                let full_directory_index_offset = index_reader.read_u64::<LE>()?;
+                let full_directory_index_size = index_reader.read_u64::<LE>()?;
+                let mut full_directory_index_hash = [0u8; 20];
+                index_reader.read_exact(&mut full_directory_index_hash)?;
+
+                read_index_data(
+                    reader,
+                    full_directory_index_offset,
+                    full_directory_index_size,
+                    index_encrypted,
+                    key,
+                    verify.then_some(&full_directory_index_hash),
+                )?
             } else {
                 return Err(PakError::pak_invalid());
             };
 
-            let _encoded_size = reader.read_u32::<LE>()? as usize;
-            let position = reader.stream_position()?;
-
-            for (dir_name, dir) in &full_directory_index {
-                for (file_name, encoded_offset) in dir {
-                    let mut path = dir_name.strip_prefix('/').unwrap_or(dir_name).to_owned();
-                    path.push_str(file_name);
+            let encoded_size = index_reader.read_u32::<LE>()? as u64;
+            let encoded_entries = read_bytes(&mut index_reader, encoded_size)?;
+
+            // entries which could not be encoded are stored in the legacy format after the encoded ones
+            let non_encoded_count = index_reader.read_u32::<LE>()?;
+            let mut non_encoded_entries = Vec::new();
+            for _ in 0..non_encoded_count {
+                non_encoded_entries.push(Header::read(
+                    &mut index_reader,
+                    footer.pak_version,
+                    &footer.compression_methods,
+                )?);
+            }
 
-                    reader.seek(SeekFrom::Start(position + *encoded_offset as u64))?;
-                    let entry = Header::read_encoded(
-                        &mut reader,
-                        footer.pak_version,
-                        &footer.compression_methods,
-                    )?;
+            let lazy_entries = LazyIndex {
+                pak_version: footer.pak_version,
+                compression_methods: footer.compression_methods,
+                path_hash_seed: seed,
+                // before the bug fix half of every path is hashed, so unrelated paths share their hashes too often
+                path_hashes: path_hash_index
+                    .filter(|_| lazy && footer.pak_version >= PakVersion::Fnv64BugFix)
+                    .map(|data| read_path_hash_index(&data))
+                    .transpose()?,
+                encoded_entries,
+                non_encoded_entries,
+                full_directory_index,
+                names: OnceLock::new(),
+            };
 
-                    entries.push((path, entry));
-                }
+            match lazy {
+                true => lazy_index = Some(lazy_entries),
+                false => entries = lazy_entries.entries()?,
             }
         }
 
@@ -102,19 +315,32 @@ impl Index {
             mount_point,
             path_hash_seed,
             entries,
+            lazy: lazy_index,
             footer,
         })
     }
 
-    pub(crate) fn write<W: Write + Seek>(writer: &mut W, mut index: Self) -> Result<(), PakError> {
+    pub(crate) fn write<W: Write + Seek>(
+        writer: &mut W,
+        mut index: Self,
+        key: Option<&AesKey>,
+    ) -> Result<(), PakError> {
         let index_offset = writer.stream_position()?;
 
+        let index_encrypted = index.footer.index_encrypted.unwrap_or_default();
+        if index_encrypted && index.footer.pak_version < PakVersion::IndexEncryption {
+            return Err(PakError::enrcryption_unsupported());
+        }
+
         let mut index_writer = Cursor::new(Vec::new());
 
         index_writer.write_fstring(Some(&index.mount_point))?;
 
         index_writer.write_u32::<LE>(index.entries.len() as u32)?;
 
+        // secondary indexes which are written after the primary index in v10+
+        let mut secondary_indexes = Vec::new();
+
         if index.footer.pak_version < PakVersion::PathHashIndex {
             for (name, header) in index.entries {
                 index_writer.write_fstring(Some(name.as_str()))?;
@@ -126,17 +352,149 @@ impl Index {
                 )?;
             }
         } else {
-            dbg!(index.path_hash_seed);
-            return Err(PakError::pak_version_unsupported(index.footer.pak_version));
+            let path_hash_seed = index.path_hash_seed.unwrap_or_else(random_path_hash_seed);
+            index_writer.write_u64::<LE>(path_hash_seed)?;
+
+            let mut encoded_entries = Cursor::new(Vec::new());
+            let mut non_encoded_entries = Cursor::new(Vec::new());
+            let mut non_encoded_count = 0u32;
+
+            let mut path_hash_index = Cursor::new(Vec::new());
+            path_hash_index.write_u32::<LE>(index.entries.len() as u32)?;
+
+            let mut full_directory_index = BTreeMap::<String, BTreeMap<String, i32>>::new();
+
+            for (name, header) in &index.entries {
+                let location = if header
+                    .can_encode(index.footer.pak_version, &index.footer.compression_methods)?
+                {
+                    let location = encoded_entries.position() as i32;
+                    Header::write_encoded(
+                        &mut encoded_entries,
+                        index.footer.pak_version,
+                        &index.footer.compression_methods,
+                        header,
+                    )?;
+                    location
+                } else {
+                    Header::write(
+                        &mut non_encoded_entries,
+                        index.footer.pak_version,
+                        &index.footer.compression_methods,
+                        header,
+                    )?;
+                    non_encoded_count += 1;
+                    -(non_encoded_count as i32)
+                };
+
+                path_hash_index.write_u64::<LE>(path_hash(
+                    name,
+                    path_hash_seed,
+                    index.footer.pak_version,
+                ))?;
+                path_hash_index.write_i32::<LE>(location)?;
+
+                let (directory, file_name) = split_path(name);
+                full_directory_index
+                    .entry(directory.to_owned())
+                    .or_default()
+                    .insert(file_name.to_owned(), location);
+
+                // all parent directories need to be listed as well
+                let mut directory = directory;
+                while directory != "/" {
+                    directory = split_path(directory.trim_end_matches('/')).0;
+                    full_directory_index
+                        .entry(directory.to_owned())
+                        .or_default();
+                }
+            }
+
+            // the path hash index is followed by a pruned directory index, which we leave empty
+            path_hash_index.write_u32::<LE>(0)?;
+
+            let mut full_directory_index_writer = Cursor::new(Vec::new());
+            full_directory_index_writer.write_u32::<LE>(full_directory_index.len() as u32)?;
+            for (directory, files) in full_directory_index {
+                full_directory_index_writer.write_fstring(Some(&directory))?;
+                full_directory_index_writer.write_u32::<LE>(files.len() as u32)?;
+                for (file_name, location) in files {
+                    full_directory_index_writer.write_fstring(Some(&file_name))?;
+                    full_directory_index_writer.write_i32::<LE>(location)?;
+                }
+            }
+
+            let mut path_hash_index = path_hash_index.into_inner();
+            let mut full_directory_index = full_directory_index_writer.into_inner();
+            if index_encrypted {
+                path_hash_index.resize(encryption::align(path_hash_index.len() as u64) as usize, 0);
+                full_directory_index.resize(
+                    encryption::align(full_directory_index.len() as u64) as usize,
+                    0,
+                );
+            }
+
+            let encoded_entries = encoded_entries.into_inner();
+            let non_encoded_entries = non_encoded_entries.into_inner();
+
+            // the secondary indexes get placed directly after the primary index,
+            // so its final size has to be known before writing their offsets
+            let mut primary_index_size = index_writer.position()
+                // path hash index and full directory index info
+                + 2 * (4 + 8 + 8 + 20)
+                + 4
+                + encoded_entries.len() as u64
+                + 4
+                + non_encoded_entries.len() as u64;
+            if index_encrypted {
+                primary_index_size = encryption::align(primary_index_size);
+            }
+
+            let path_hash_index_offset = index_offset + primary_index_size;
+            let full_directory_index_offset = path_hash_index_offset + path_hash_index.len() as u64;
+
+            index_writer.write_u32::<LE>(1)?;
+            index_writer.write_u64::<LE>(path_hash_index_offset)?;
+            index_writer.write_u64::<LE>(path_hash_index.len() as u64)?;
+            index_writer.write_all(&hash(&path_hash_index))?;
+
+            index_writer.write_u32::<LE>(1)?;
+            index_writer.write_u64::<LE>(full_directory_index_offset)?;
+            index_writer.write_u64::<LE>(full_directory_index.len() as u64)?;
+            index_writer.write_all(&hash(&full_directory_index))?;
+
+            index_writer.write_u32::<LE>(encoded_entries.len() as u32)?;
+            index_writer.write_all(&encoded_entries)?;
+
+            index_writer.write_u32::<LE>(non_encoded_count)?;
+            index_writer.write_all(&non_encoded_entries)?;
+
+            secondary_indexes.push(path_hash_index);
+            secondary_indexes.push(full_directory_index);
+        }
+
+        let mut index_data = index_writer.into_inner();
+        if index_encrypted {
+            index_data.resize(encryption::align(index_data.len() as u64) as usize, 0);
         }
 
-        let index_data = index_writer.into_inner();
         index.footer.index_offset = index_offset;
         index.footer.index_size = index_data.len() as u64;
 
         index.footer.index_hash = hash(&index_data);
 
+        if index_encrypted {
+            let key = key.ok_or_else(PakError::encryption_key_missing)?;
+            key.encrypt(&mut index_data);
+            for secondary_index in secondary_indexes.iter_mut() {
+                key.encrypt(secondary_index);
+            }
+        }
+
         writer.write_all(&index_data)?;
+        for secondary_index in secondary_indexes {
+            writer.write_all(&secondary_index)?;
+        }
 
         Footer::write(writer, index.footer)?;
 
@@ -144,6 +502,72 @@ impl Index {
     }
 }
 
+/// Read a (possibly encrypted) part of the index into memory, optionally checking its hash
+fn read_index_data<R: Read + Seek>(
+    reader: &mut R,
+    offset: u64,
+    size: u64,
+    encrypted: bool,
+    key: Option<&AesKey>,
+    expected_hash: Option<&[u8; 20]>,
+) -> Result<Vec<u8>, PakError> {
+    reader.seek(SeekFrom::Start(offset))?;
+
+    let mut data = read_bytes(reader, size)?;
+
+    if encrypted {
+        let key = key.ok_or_else(PakError::encryption_key_missing)?;
+        key.decrypt(&mut data);
+    }
+
+    if let Some(expected_hash) = expected_hash {
+        if hash(&data) != *expected_hash {
+            return Err(PakError::index_hash_mismatch());
+        }
+    }
+
+    Ok(data)
+}
+
+/// Information from the footer of a pak file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FooterInfo {
+    /// Version of the pak file format
+    pub pak_version: PakVersion,
+    /// Offset of the index in the pak file
+    pub index_offset: u64,
+    /// Size of the index
+    pub index_size: u64,
+    /// SHA1 hash of the index
+    pub index_hash: [u8; 20],
+    /// Whether the index is encrypted
+    pub index_encrypted: bool,
+    /// GUID of the key used to encrypt the pak, only stored since version 7
+    pub encryption_key_guid: Option<[u8; 0x10]>,
+    /// Names of the compression methods used by entries, only stored since version 8
+    pub compression_methods: Vec<Compression>,
+}
+
+impl FooterInfo {
+    pub(crate) fn new(footer: &Footer) -> Self {
+        FooterInfo {
+            pak_version: footer.pak_version,
+            index_offset: footer.index_offset,
+            index_size: footer.index_size,
+            index_hash: footer.index_hash,
+            index_encrypted: footer.index_encrypted.unwrap_or_default(),
+            encryption_key_guid: footer.encryption_key_guid,
+            compression_methods: footer
+                .compression_methods
+                .0
+                .iter()
+                .filter(|method| **method != Compression::None)
+                .copied()
+                .collect(),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub(crate) struct Footer {
     pub pak_version: PakVersion,
@@ -188,7 +612,7 @@ impl Footer {
             reader.seek(SeekFrom::Current(1))?;
         }
 
-        let compression_methods = if pak_version >= PakVersion::FnameBasedCompressionMethod {
+        let compression_methods = if pak_version >= PakVersion::FnameBasedCompressionMethodInitial {
             CompressionMethods::from_reader(reader)?
         } else {
             CompressionMethods::default()
@@ -236,7 +660,7 @@ impl Footer {
 
         // write magic and pak version
         writer.write_u32::<BE>(PAK_MAGIC)?;
-        writer.write_u32::<LE>(footer.pak_version.to_num())?;
+        writer.write_u32::<LE>(footer.pak_version.to_num()?)?;
 
         // write index offset and length
         writer.write_u64::<LE>(footer.index_offset)?;
@@ -251,14 +675,52 @@ impl Footer {
         }
 
         // compression methods
-        if footer.pak_version >= PakVersion::FnameBasedCompressionMethod {
-            writer.write_all(footer.compression_methods.as_bytes().as_slice())?;
+        if footer.pak_version >= PakVersion::FnameBasedCompressionMethodInitial {
+            writer.write_all(
+                footer
+                    .compression_methods
+                    .as_bytes(footer.pak_version)
+                    .as_slice(),
+            )?;
         }
 
         Ok(())
     }
 }
 
+/// Split a path into its directory (with trailing slash, `/` for the root) and file name
+fn split_path(path: &str) -> (&str, &str) {
+    match path.rfind('/') {
+        Some(i) => (&path[..=i], &path[i + 1..]),
+        None => ("/", path),
+    }
+}
+
+/// Hash a path relative to the mount point like it is stored in the path hash index
+pub(crate) fn path_hash(path: &str, seed: u64, pak_version: PakVersion) -> u64 {
+    const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+    const FNV_PRIME: u64 = 0x00000100000001b3;
+
+    let data = path
+        .to_lowercase()
+        .encode_utf16()
+        .flat_map(u16::to_le_bytes)
+        .collect::<Vec<_>>();
+
+    // before the bug fix only the first half of the utf-16 data was hashed
+    // because the length in characters was used instead of the length in bytes
+    let data = if pak_version >= PakVersion::Fnv64BugFix {
+        &data[..]
+    } else {
+        &data[..data.len() / 2]
+    };
+
+    data.iter()
+        .fold(FNV_OFFSET.wrapping_add(seed), |hash, byte| {
+            (hash ^ *byte as u64).wrapping_mul(FNV_PRIME)
+        })
+}
+
 // 64 bit LE num, but always less than u32::MAX
 pub(crate) fn random_path_hash_seed() -> u64 {
     use rand::Rng;
@@ -0,0 +1,372 @@
+/*
+    Header of a cooked UE5 package (.uasset/.umap), only the parts needed for the zen package format.
+    The export data is in the .uexp file, which ends with the package file tag.
+
+    - summary
+        - u32 tag 0x9E2A83C1
+        - i32 legacy file version (-8 for UE5)
+        - i32 legacy UE3 version
+        - i32 UE4 object version, i32 UE5 object version (both 0 for unversioned packages)
+        - i32 licensee version
+        - i32 custom version count, custom versions (16 bytes guid + i32 version each)
+        - i32 total header size, the size of the .uasset file
+        - FString folder name
+        - u32 package flags
+        - i32 name count, i32 name offset
+        - i32 soft object path count, i32 soft object path offset (UE5 version >= ADD_SOFTOBJECTPATH_LIST)
+        - i32 gatherable text data count, i32 gatherable text data offset
+        - i32 export count, i32 export offset
+        - i32 import count, i32 import offset
+        - i32 depends offset
+        - i32 soft package reference count, i32 soft package reference offset
+        - i32 searchable names offset
+        - i32 thumbnail table offset
+        - 16 bytes guid
+        - i32 generation count, generations (i32 export count + i32 name count each)
+        - saved and compatible engine version (u16 major, u16 minor, u16 patch, u32 changelist, FString branch each)
+        - u32 compression flags, i32 compressed chunk count (always 0)
+        - u32 package source
+        - i32 additional packages to cook count, FString packages
+        - i32 asset registry data offset
+        - i64 bulk data start offset
+        - i32 world tile info offset
+        - i32 chunk id count, i32 chunk ids
+        - i32 preload dependency count, i32 preload dependency offset
+        - i32 names referenced from export data count (UE5 version >= NAMES_REFERENCED_FROM_EXPORT_DATA)
+        - i64 payload toc offset (UE5 version >= PAYLOAD_TOC)
+    - name map at the name offset
+        - FString name, u16 + u16 hashes
+    - import map at the import offset
+        - FName class package, FName class name, i32 outer index, FName object name
+        - i32 import optional (UE5 version >= OPTIONAL_RESOURCES)
+    - export map at the export offset
+        - i32 class index, i32 super index, i32 template index, i32 outer index
+        - FName object name
+        - u32 object flags
+        - i64 serial size, i64 serial offset
+        - i32 forced export, i32 not for client, i32 not for server
+        - 16 bytes package guid (UE5 version < REMOVE_OBJECT_EXPORT_PACKAGE_GUID)
+        - i32 is inherited instance (UE5 version >= TRACK_OBJECT_EXPORT_IS_INHERITED)
+        - u32 package flags
+        - i32 not always loaded for editor game
+        - i32 is asset
+        - i32 generate public hash (UE5 version >= OPTIONAL_RESOURCES)
+        - i32 first export dependency, i32 counts of the 4 preload dependency lists
+    - preload dependencies at the preload dependency offset
+        - i32 package indices, each export has its serialize before serialize, create before serialize,
+          serialize before create and create before create dependencies starting at its first export dependency
+
+    FNames are an i32 index into the name map and an i32 number.
+    Package indices are 0 for null, -1 - index for imports and 1 + index for exports.
+*/
+
+use std::io::{Cursor, Read, Seek, SeekFrom};
+
+use byteorder::{ReadBytesExt, LE};
+
+use unreal_helpers::UnrealReadExt;
+
+use crate::error::PakError;
+
+/// Tag at the start of a package and at the end of its .uexp file
+pub(crate) const PACKAGE_FILE_TAG: u32 = 0x9E2A83C1;
+
+/// Package flag set for cooked packages
+const PKG_FILTER_EDITOR_ONLY: u32 = 0x80000000;
+
+// UE5 object versions changing the package header
+const NAMES_REFERENCED_FROM_EXPORT_DATA: i32 = 1001;
+const PAYLOAD_TOC: i32 = 1002;
+const OPTIONAL_RESOURCES: i32 = 1003;
+const REMOVE_OBJECT_EXPORT_PACKAGE_GUID: i32 = 1005;
+const TRACK_OBJECT_EXPORT_IS_INHERITED: i32 = 1006;
+const ADD_SOFTOBJECTPATH_LIST: i32 = 1008;
+const DATA_RESOURCES: i32 = 1009;
+
+/// Name map index and number of a name
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct LegacyName {
+    pub index: i32,
+    pub number: i32,
+}
+
+/// Entry of the import map
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct LegacyImport {
+    pub outer_index: i32,
+    pub object_name: LegacyName,
+}
+
+/// Entry of the export map with its preload dependencies
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct LegacyExport {
+    pub class_index: i32,
+    pub super_index: i32,
+    pub template_index: i32,
+    pub outer_index: i32,
+    pub object_name: LegacyName,
+    pub object_flags: u32,
+    pub serial_size: i64,
+    pub serial_offset: i64,
+    pub not_for_client: bool,
+    pub not_for_server: bool,
+    pub generate_public_hash: bool,
+    pub serialize_before_serialize: Vec<i32>,
+    pub create_before_serialize: Vec<i32>,
+    pub serialize_before_create: Vec<i32>,
+    pub create_before_create: Vec<i32>,
+}
+
+/// Parsed header of a cooked package
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct LegacyPackage {
+    pub total_header_size: u32,
+    pub package_flags: u32,
+    pub names: Vec<String>,
+    pub imports: Vec<LegacyImport>,
+    pub exports: Vec<LegacyExport>,
+}
+
+fn read_name<R: Read>(reader: &mut R) -> Result<LegacyName, PakError> {
+    Ok(LegacyName {
+        index: reader.read_i32::<LE>()?,
+        number: reader.read_i32::<LE>()?,
+    })
+}
+
+fn read_bool<R: Read>(reader: &mut R) -> Result<bool, PakError> {
+    Ok(reader.read_i32::<LE>()? == 1)
+}
+
+fn seek<R: Seek>(reader: &mut R, offset: i64) -> Result<(), PakError> {
+    let offset = u64::try_from(offset)
+        .map_err(|_| PakError::package_invalid(format!("Invalid offset {offset}")))?;
+    reader.seek(SeekFrom::Start(offset))?;
+    Ok(())
+}
+
+fn count(count: i32) -> Result<usize, PakError> {
+    usize::try_from(count).map_err(|_| PakError::package_invalid(format!("Invalid count {count}")))
+}
+
+impl LegacyPackage {
+    /// Read the header of a cooked package.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - Contents of the .uasset or .umap file
+    /// * `object_version_ue5` - UE5 object version used for unversioned packages
+    pub(crate) fn read(data: &[u8], object_version_ue5: i32) -> Result<Self, PakError> {
+        let mut reader = Cursor::new(data);
+
+        if reader.read_u32::<LE>()? != PACKAGE_FILE_TAG {
+            return Err(PakError::package_invalid(
+                "Not a package file, the tag is missing".to_owned(),
+            ));
+        }
+        let legacy_file_version = reader.read_i32::<LE>()?;
+        if legacy_file_version != -8 {
+            return Err(PakError::package_invalid(format!(
+                "Unsupported legacy file version {legacy_file_version}, only UE5 packages can be converted"
+            )));
+        }
+        let _legacy_ue3_version = reader.read_i32::<LE>()?;
+        let _object_version = reader.read_i32::<LE>()?;
+        let object_version_ue5 = match reader.read_i32::<LE>()? {
+            0 => object_version_ue5,
+            version => version,
+        };
+        let _licensee_version = reader.read_i32::<LE>()?;
+        let custom_version_count = count(reader.read_i32::<LE>()?)?;
+        reader.seek(SeekFrom::Current(custom_version_count as i64 * 20))?;
+
+        let total_header_size = reader.read_i32::<LE>()?;
+        let _folder_name = reader.read_fstring()?;
+        let package_flags = reader.read_u32::<LE>()?;
+        if package_flags & PKG_FILTER_EDITOR_ONLY == 0 {
+            return Err(PakError::package_invalid(
+                "The package is not cooked".to_owned(),
+            ));
+        }
+
+        let name_count = count(reader.read_i32::<LE>()?)?;
+        let name_offset = reader.read_i32::<LE>()?;
+        if object_version_ue5 >= ADD_SOFTOBJECTPATH_LIST {
+            let _soft_object_paths_count = reader.read_i32::<LE>()?;
+            let _soft_object_paths_offset = reader.read_i32::<LE>()?;
+        }
+        let _gatherable_text_data_count = reader.read_i32::<LE>()?;
+        let _gatherable_text_data_offset = reader.read_i32::<LE>()?;
+        let export_count = count(reader.read_i32::<LE>()?)?;
+        let export_offset = reader.read_i32::<LE>()?;
+        let import_count = count(reader.read_i32::<LE>()?)?;
+        let import_offset = reader.read_i32::<LE>()?;
+        let _depends_offset = reader.read_i32::<LE>()?;
+        let _soft_package_reference_count = reader.read_i32::<LE>()?;
+        let _soft_package_reference_offset = reader.read_i32::<LE>()?;
+        let _searchable_names_offset = reader.read_i32::<LE>()?;
+        let _thumbnail_table_offset = reader.read_i32::<LE>()?;
+        reader.seek(SeekFrom::Current(16))?; // guid
+
+        let generation_count = count(reader.read_i32::<LE>()?)?;
+        reader.seek(SeekFrom::Current(generation_count as i64 * 8))?;
+
+        // saved and compatible engine version
+        for _ in 0..2 {
+            reader.seek(SeekFrom::Current(10))?;
+            let _branch = reader.read_fstring()?;
+        }
+
+        let _compression_flags = reader.read_u32::<LE>()?;
+        if reader.read_i32::<LE>()? != 0 {
+            return Err(PakError::package_invalid(
+                "Compressed packages are not supported".to_owned(),
+            ));
+        }
+        let _package_source = reader.read_u32::<LE>()?;
+        for _ in 0..count(reader.read_i32::<LE>()?)? {
+            let _additional_package_to_cook = reader.read_fstring()?;
+        }
+        let _asset_registry_data_offset = reader.read_i32::<LE>()?;
+        let _bulk_data_start_offset = reader.read_i64::<LE>()?;
+        let _world_tile_info_offset = reader.read_i32::<LE>()?;
+        let chunk_id_count = count(reader.read_i32::<LE>()?)?;
+        reader.seek(SeekFrom::Current(chunk_id_count as i64 * 4))?;
+        let _preload_dependency_count = reader.read_i32::<LE>()?;
+        let preload_dependency_offset = reader.read_i32::<LE>()?;
+        if object_version_ue5 >= NAMES_REFERENCED_FROM_EXPORT_DATA {
+            let _names_referenced_from_export_data_count = reader.read_i32::<LE>()?;
+        }
+        if object_version_ue5 >= PAYLOAD_TOC {
+            let _payload_toc_offset = reader.read_i64::<LE>()?;
+        }
+        if object_version_ue5 >= DATA_RESOURCES {
+            return Err(PakError::package_invalid(
+                "Packages with a data resource table (UE 5.2+) are not supported".to_owned(),
+            ));
+        }
+
+        // counts are not used for allocating, the reader runs out of data for invalid counts
+        seek(&mut reader, name_offset as i64)?;
+        let mut names = Vec::new();
+        for _ in 0..name_count {
+            names.push(reader.read_fstring()?.unwrap_or_default());
+            let _hashes = reader.read_u32::<LE>()?;
+        }
+
+        seek(&mut reader, import_offset as i64)?;
+        let mut imports = Vec::new();
+        for _ in 0..import_count {
+            let _class_package = read_name(&mut reader)?;
+            let _class_name = read_name(&mut reader)?;
+            let outer_index = reader.read_i32::<LE>()?;
+            let object_name = read_name(&mut reader)?;
+            if object_version_ue5 >= OPTIONAL_RESOURCES {
+                let _import_optional = read_bool(&mut reader)?;
+            }
+            imports.push(LegacyImport {
+                outer_index,
+                object_name,
+            });
+        }
+
+        seek(&mut reader, export_offset as i64)?;
+        let mut exports = Vec::new();
+        let mut dependencies = Vec::new();
+        for _ in 0..export_count {
+            let class_index = reader.read_i32::<LE>()?;
+            let super_index = reader.read_i32::<LE>()?;
+            let template_index = reader.read_i32::<LE>()?;
+            let outer_index = reader.read_i32::<LE>()?;
+            let object_name = read_name(&mut reader)?;
+            let object_flags = reader.read_u32::<LE>()?;
+            let serial_size = reader.read_i64::<LE>()?;
+            let serial_offset = reader.read_i64::<LE>()?;
+            let _forced_export = read_bool(&mut reader)?;
+            let not_for_client = read_bool(&mut reader)?;
+            let not_for_server = read_bool(&mut reader)?;
+            if object_version_ue5 < REMOVE_OBJECT_EXPORT_PACKAGE_GUID {
+                reader.seek(SeekFrom::Current(16))?; // package guid
+            }
+            if object_version_ue5 >= TRACK_OBJECT_EXPORT_IS_INHERITED {
+                let _is_inherited_instance = read_bool(&mut reader)?;
+            }
+            let _package_flags = reader.read_u32::<LE>()?;
+            let _not_always_loaded_for_editor_game = read_bool(&mut reader)?;
+            let _is_asset = read_bool(&mut reader)?;
+            let generate_public_hash = match object_version_ue5 >= OPTIONAL_RESOURCES {
+                true => read_bool(&mut reader)?,
+                false => false,
+            };
+            let first_export_dependency = reader.read_i32::<LE>()?;
+            let mut counts = [0usize; 4];
+            for value in &mut counts {
+                *value = count(reader.read_i32::<LE>()?)?;
+            }
+            dependencies.push((first_export_dependency, counts));
+
+            exports.push(LegacyExport {
+                class_index,
+                super_index,
+                template_index,
+                outer_index,
+                object_name,
+                object_flags,
+                serial_size,
+                serial_offset,
+                not_for_client,
+                not_for_server,
+                generate_public_hash,
+                serialize_before_serialize: Vec::new(),
+                create_before_serialize: Vec::new(),
+                serialize_before_create: Vec::new(),
+                create_before_create: Vec::new(),
+            });
+        }
+
+        for (export, (first_export_dependency, counts)) in exports.iter_mut().zip(dependencies) {
+            if counts.iter().all(|count| *count == 0) {
+                continue;
+            }
+            let offset = preload_dependency_offset as i64 + first_export_dependency as i64 * 4;
+            seek(&mut reader, offset)?;
+            for (list, count) in [
+                &mut export.serialize_before_serialize,
+                &mut export.create_before_serialize,
+                &mut export.serialize_before_create,
+                &mut export.create_before_create,
+            ]
+            .into_iter()
+            .zip(counts)
+            {
+                for _ in 0..count {
+                    list.push(reader.read_i32::<LE>()?);
+                }
+            }
+        }
+
+        Ok(LegacyPackage {
+            total_header_size: u32::try_from(total_header_size).map_err(|_| {
+                PakError::package_invalid(format!("Invalid header size {total_header_size}"))
+            })?,
+            package_flags,
+            names,
+            imports,
+            exports,
+        })
+    }
+
+    /// Get the string of a name, with the number appended like the engine does
+    pub(crate) fn name(&self, name: LegacyName) -> Result<String, PakError> {
+        let string = usize::try_from(name.index)
+            .ok()
+            .and_then(|index| self.names.get(index))
+            .ok_or_else(|| {
+                PakError::package_invalid(format!("Invalid name index {}", name.index))
+            })?;
+        Ok(match name.number {
+            0 => string.clone(),
+            number => format!("{string}_{}", number - 1),
+        })
+    }
+}
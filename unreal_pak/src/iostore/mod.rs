@@ -0,0 +1,15 @@
+//! IoStore containers, used by UE 4.26+ and UE5 to store most of the game content.
+//!
+//! A container consists of a `.utoc` file with the table of contents and one or more
+//! `.ucas` files (partitions) with the compressed chunk data.
+
+mod legacy;
+mod reader;
+mod toc;
+mod writer;
+mod zen;
+
+pub use reader::IoStoreReader;
+pub use toc::{IoChunkId, IoChunkType, IoStoreTocVersion};
+pub use writer::IoStoreWriter;
+pub use zen::EngineVersion;
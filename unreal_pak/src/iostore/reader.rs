@@ -0,0 +1,219 @@
+//! Reader for IoStore containers
+
+use std::collections::HashMap;
+use std::io::{Cursor, Read, Seek, SeekFrom};
+
+use crate::compression::Compression;
+use crate::encryption::{self, AesKey};
+use crate::error::PakError;
+
+use super::toc::{IoChunkId, IoStoreTocVersion, Toc};
+
+/// An IoStore container reader with its data kept on disk and only read on demand.
+#[derive(Debug)]
+pub struct IoStoreReader<R>
+where
+    R: Read + Seek,
+{
+    toc: Option<Toc>,
+    chunks: HashMap<IoChunkId, usize>,
+    files: HashMap<String, usize>,
+    key: Option<AesKey>,
+    partitions: Vec<R>,
+}
+
+impl<R> IoStoreReader<R>
+where
+    R: Read + Seek,
+{
+    /// Creates a new `IoStoreReader` that reads chunk data from the provided `.ucas` reader.
+    /// When using a reader that uses syscalls like a `File` it is recommended to wrap it in a
+    /// [`std::io::BufReader`] to avoid unnecessary syscalls.
+    pub fn new(cas: R) -> Self {
+        Self {
+            toc: None,
+            chunks: HashMap::new(),
+            files: HashMap::new(),
+            key: None,
+            partitions: vec![cas],
+        }
+    }
+
+    /// Add the reader for the next partition (`_s1.ucas`, `_s2.ucas`, ...) of a container
+    /// which is split into multiple `.ucas` files.
+    pub fn add_partition(&mut self, cas: R) {
+        self.partitions.push(cas);
+    }
+
+    /// Set the AES key used to decrypt the directory index and chunks of encrypted containers.
+    pub fn set_encryption_key(&mut self, key: AesKey) {
+        self.key = Some(key);
+    }
+
+    /// Load the table of contents from the `.utoc` file to start reading chunks.
+    pub fn load_toc<T: Read>(&mut self, mut toc_reader: T) -> Result<(), PakError> {
+        let mut toc_data = Vec::new();
+        toc_reader.read_to_end(&mut toc_data)?;
+        let toc = Toc::read(&mut Cursor::new(toc_data), self.key.as_ref())?;
+
+        self.chunks = toc
+            .chunk_ids
+            .iter()
+            .enumerate()
+            .map(|(index, id)| (*id, index))
+            .collect();
+        self.files = toc
+            .files
+            .iter()
+            .map(|(name, index)| (name.clone(), *index as usize))
+            .collect();
+        self.toc = Some(toc);
+
+        Ok(())
+    }
+
+    /// Version of the loaded `.utoc` file
+    pub fn version(&self) -> Option<IoStoreTocVersion> {
+        self.toc.as_ref().map(|toc| toc.version)
+    }
+
+    /// Id of the loaded container
+    pub fn container_id(&self) -> Option<u64> {
+        self.toc.as_ref().map(|toc| toc.container_id)
+    }
+
+    /// Mount point of the files in the directory index
+    pub fn mount_point(&self) -> Option<&str> {
+        self.toc.as_ref().map(|toc| toc.mount_point.as_str())
+    }
+
+    /// GUID of the encryption key used by this container
+    pub fn encryption_key_guid(&self) -> Option<[u8; 0x10]> {
+        self.toc.as_ref().map(|toc| toc.encryption_key_guid)
+    }
+
+    /// Returns the ids of all chunks in the container.
+    pub fn get_chunk_ids(&self) -> Vec<IoChunkId> {
+        self.toc
+            .as_ref()
+            .map(|toc| toc.chunk_ids.clone())
+            .unwrap_or_default()
+    }
+
+    /// Checks if the container contains a chunk with the given id
+    pub fn contains_chunk(&self, id: &IoChunkId) -> bool {
+        self.chunks.contains_key(id)
+    }
+
+    /// Returns the names of all files in the directory index, relative to the mount point.
+    pub fn get_file_names(&self) -> Vec<&String> {
+        self.toc
+            .as_ref()
+            .map(|toc| toc.files.iter().map(|(name, _)| name).collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns the id of the chunk containing the file with the given name.
+    pub fn get_file_chunk_id(&self, name: &String) -> Option<IoChunkId> {
+        let index = *self.files.get(name)?;
+        self.toc.as_ref()?.chunk_ids.get(index).copied()
+    }
+
+    /// Reads a file from the directory index into memory and returns its data.
+    pub fn read_file(&mut self, name: &String) -> Result<Vec<u8>, PakError> {
+        let index = *self
+            .files
+            .get(name)
+            .ok_or_else(|| PakError::entry_not_found(name.clone()))?;
+        self.read_chunk_at(index)
+    }
+
+    /// Reads a chunk from the container into memory and returns its data.
+    pub fn read_chunk(&mut self, id: &IoChunkId) -> Result<Vec<u8>, PakError> {
+        let index = *self
+            .chunks
+            .get(id)
+            .ok_or_else(|| PakError::entry_not_found(id.to_string()))?;
+        self.read_chunk_at(index)
+    }
+
+    fn read_chunk_at(&mut self, index: usize) -> Result<Vec<u8>, PakError> {
+        let toc = self
+            .toc
+            .as_ref()
+            .ok_or_else(PakError::configuration_invalid)?;
+        let offset_and_length = *toc.offsets.get(index).ok_or_else(PakError::pak_invalid)?;
+        if offset_and_length.length == 0 {
+            return Ok(Vec::new());
+        }
+
+        let key = if toc.is_encrypted() {
+            Some(
+                self.key
+                    .as_ref()
+                    .ok_or_else(PakError::encryption_key_missing)?,
+            )
+        } else {
+            None
+        };
+
+        let block_size = toc.compression_block_size as u64;
+        let first_block = offset_and_length.offset / block_size;
+        let last_block = (offset_and_length.offset + offset_and_length.length - 1) / block_size;
+
+        let mut data = Vec::new();
+        for block_index in first_block..=last_block {
+            let block = toc
+                .compressed_blocks
+                .get(block_index as usize)
+                .ok_or_else(PakError::pak_invalid)?;
+
+            let partition = self
+                .partitions
+                .get_mut((block.offset / toc.partition_size) as usize)
+                .ok_or_else(PakError::pak_invalid)?;
+            partition.seek(SeekFrom::Start(block.offset % toc.partition_size))?;
+
+            let size = match key {
+                Some(_) => encryption::align(block.compressed_size as u64),
+                None => block.compressed_size as u64,
+            };
+            let mut compressed_data = vec![0u8; size as usize];
+            partition.read_exact(&mut compressed_data)?;
+            if let Some(key) = key {
+                key.decrypt(&mut compressed_data);
+            }
+            compressed_data.truncate(block.compressed_size as usize);
+
+            match block.compression_method {
+                0 => data.extend_from_slice(&compressed_data),
+                method => {
+                    let compression = toc
+                        .compression_methods
+                        .get(method as usize - 1)
+                        .copied()
+                        .unwrap_or(Compression::None);
+                    if matches!(compression, Compression::None) {
+                        return Err(PakError::pak_invalid());
+                    }
+                    compression.decompress(
+                        &mut data,
+                        &compressed_data,
+                        block.uncompressed_size as usize,
+                    )?;
+                }
+            }
+        }
+
+        // the chunk does not have to start at the beginning of its first block
+        let start = (offset_and_length.offset - first_block * block_size) as usize;
+        let end = start + offset_and_length.length as usize;
+        if data.len() < end {
+            return Err(PakError::entry_invalid());
+        }
+        data.truncate(end);
+        data.drain(..start);
+
+        Ok(data)
+    }
+}
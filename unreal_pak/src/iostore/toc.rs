@@ -0,0 +1,665 @@
+/*
+    .utoc file (all integers little endian unless noted otherwise):
+    - header (TocHeaderSize bytes)
+        - 16 bytes magic "-==--==--==--==-"
+        - u8 version, u8 + u16 reserved
+        - u32 header size
+        - u32 entry count
+        - u32 compressed block entry count
+        - u32 compressed block entry size (12)
+        - u32 compression method name count
+        - u32 compression method name length (32)
+        - u32 compression block size
+        - u32 directory index size
+        - u32 partition count
+        - u64 container id
+        - 16 bytes encryption key guid
+        - u8 container flags, u8 + u16 reserved
+        - u32 perfect hash seed count
+        - u64 partition size
+        - u32 chunks without perfect hash count
+        - reserved until header size
+    - chunk ids (12 bytes each)
+        - u64 id
+        - u16 index (big endian)
+        - u8 padding
+        - u8 chunk type
+    - offsets and lengths in the uncompressed chunk data (5 + 5 bytes, big endian)
+    - i32 perfect hash seeds (version >= PerfectHash)
+    - i32 chunks without perfect hash (version >= PerfectHashWithOverflow)
+    - compressed blocks (12 bytes each)
+        - 5 bytes offset in the .ucas file
+        - 3 bytes compressed size
+        - 3 bytes uncompressed size
+        - u8 compression method, index into the compression method names starting at 1
+    - compression method names (32 bytes each)
+    - signatures (only when signed)
+        - i32 hash size
+        - toc signature, block signature (hash size bytes each)
+        - 20 bytes SHA1 hash per compressed block
+    - directory index (directory index size bytes, encrypted when the container is encrypted)
+        - FString mount point
+        - i32 directory count, directories
+            - u32 name, u32 first child, u32 next sibling, u32 first file
+        - i32 file count, files
+            - u32 name, u32 next file, u32 toc entry index
+        - i32 string count, FString strings
+        - names are indices into the strings, u32::MAX is used for none
+    - chunk metas (entry count)
+        - chunk hash (32 bytes, 20 bytes for version >= ReplaceIoChunkHashWithIoHash)
+        - u8 flags
+*/
+
+use std::fmt;
+use std::io::{self, Read, Seek, Write};
+
+use byteorder::{ReadBytesExt, WriteBytesExt, BE, LE};
+use naive_cityhash::cityhash64;
+
+use unreal_helpers::{UnrealReadExt, UnrealWriteExt};
+
+use crate::compression::Compression;
+use crate::encryption::{self, AesKey};
+use crate::error::PakError;
+use crate::read_bytes;
+
+pub(crate) const TOC_MAGIC: [u8; 16] = *b"-==--==--==--==-";
+pub(crate) const TOC_HEADER_SIZE: u32 = 144;
+pub(crate) const COMPRESSED_BLOCK_ENTRY_SIZE: u32 = 12;
+pub(crate) const COMPRESSION_METHOD_NAME_LENGTH: u32 = 32;
+
+pub(crate) const CONTAINER_FLAG_COMPRESSED: u8 = 0x01;
+pub(crate) const CONTAINER_FLAG_ENCRYPTED: u8 = 0x02;
+pub(crate) const CONTAINER_FLAG_SIGNED: u8 = 0x04;
+pub(crate) const CONTAINER_FLAG_INDEXED: u8 = 0x08;
+
+/// Marks a missing name, child or sibling in the directory index
+const INVALID_HANDLE: u32 = u32::MAX;
+
+/// Version of the .utoc file format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum IoStoreTocVersion {
+    /// UE 4.25
+    Initial = 1,
+    /// UE 4.26
+    DirectoryIndex = 2,
+    /// UE 4.27
+    PartitionSize = 3,
+    /// UE 5.0
+    PerfectHash = 4,
+    /// UE 5.0
+    PerfectHashWithOverflow = 5,
+    /// UE 5.2
+    OnDemandMetaData = 6,
+    /// UE 5.3
+    RemovedOnDemandMetaData = 7,
+    /// UE 5.4+
+    ReplaceIoChunkHashWithIoHash = 8,
+}
+
+impl IoStoreTocVersion {
+    /// Get the version from its number, returns `None` for unknown versions
+    pub fn from_num(num: u8) -> Option<Self> {
+        Some(match num {
+            1 => Self::Initial,
+            2 => Self::DirectoryIndex,
+            3 => Self::PartitionSize,
+            4 => Self::PerfectHash,
+            5 => Self::PerfectHashWithOverflow,
+            6 => Self::OnDemandMetaData,
+            7 => Self::RemovedOnDemandMetaData,
+            8 => Self::ReplaceIoChunkHashWithIoHash,
+            _ => return None,
+        })
+    }
+}
+
+/// Type of a chunk, only the types used for package data are supported
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IoChunkType {
+    /// Package summary and exports (`.uasset`/`.umap` and `.uexp`)
+    ExportBundleData,
+    /// Bulk data (`.ubulk`)
+    BulkData,
+    /// Optional bulk data (`.uptnl`)
+    OptionalBulkData,
+    /// Memory mapped bulk data (`.m.ubulk`)
+    MemoryMappedBulkData,
+}
+
+impl IoChunkType {
+    /// Get the number of the chunk type, which changed between UE4 and UE5
+    pub fn to_num(self, version: IoStoreTocVersion) -> u8 {
+        let num = match self {
+            Self::ExportBundleData => 1,
+            Self::BulkData => 2,
+            Self::OptionalBulkData => 3,
+            Self::MemoryMappedBulkData => 4,
+        };
+        // UE4 had an additional InstallManifest type before all of these
+        if version < IoStoreTocVersion::PerfectHash {
+            num + 1
+        } else {
+            num
+        }
+    }
+}
+
+/// Id of a package, the CityHash64 of the lowercase UTF-16 package name
+pub(crate) fn package_id(package_name: &str) -> u64 {
+    let bytes = package_name
+        .to_lowercase()
+        .encode_utf16()
+        .flat_map(u16::to_le_bytes)
+        .collect::<Vec<_>>();
+    cityhash64(&bytes)
+}
+
+/// Identifies a chunk of data in an IoStore container
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct IoChunkId(pub [u8; 12]);
+
+impl IoChunkId {
+    /// Create a chunk id from its parts
+    pub fn new(id: u64, index: u16, chunk_type: u8) -> Self {
+        let mut bytes = [0u8; 12];
+        bytes[0..8].copy_from_slice(&id.to_le_bytes());
+        bytes[8..10].copy_from_slice(&index.to_be_bytes());
+        bytes[11] = chunk_type;
+        IoChunkId(bytes)
+    }
+
+    /// Create the id of a chunk belonging to a package, like its export data or bulk data.
+    ///
+    /// # Arguments
+    ///
+    /// * `package_name` - Name of the package like `/Game/Maps/Map`, case insensitive
+    /// * `chunk_type` - Type of the chunk
+    /// * `version` - Version of the container, which decides the numbers used for chunk types
+    pub fn from_package_name(
+        package_name: &str,
+        chunk_type: IoChunkType,
+        version: IoStoreTocVersion,
+    ) -> Self {
+        Self::new(package_id(package_name), 0, chunk_type.to_num(version))
+    }
+
+    /// Id of the chunk, for package data this is the package id
+    pub fn id(&self) -> u64 {
+        u64::from_le_bytes(self.0[0..8].try_into().unwrap_or_default())
+    }
+
+    /// Index of the chunk
+    pub fn index(&self) -> u16 {
+        u16::from_be_bytes([self.0[8], self.0[9]])
+    }
+
+    /// Type of the chunk. The meaning of the values depends on the engine version.
+    pub fn chunk_type(&self) -> u8 {
+        self.0[11]
+    }
+}
+
+impl fmt::Display for IoChunkId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for byte in self.0 {
+            write!(f, "{byte:02x}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Position of a chunk in the uncompressed data of the container
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct OffsetAndLength {
+    pub offset: u64,
+    pub length: u64,
+}
+
+impl OffsetAndLength {
+    fn read<R: Read>(reader: &mut R) -> io::Result<Self> {
+        Ok(OffsetAndLength {
+            offset: reader.read_uint::<BE>(5)?,
+            length: reader.read_uint::<BE>(5)?,
+        })
+    }
+
+    fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_uint::<BE>(self.offset, 5)?;
+        writer.write_uint::<BE>(self.length, 5)
+    }
+}
+
+/// A compression block in the .ucas file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct CompressedBlock {
+    pub offset: u64,
+    pub compressed_size: u32,
+    pub uncompressed_size: u32,
+    /// 0 for no compression, otherwise index into the compression methods starting at 1
+    pub compression_method: u8,
+}
+
+impl CompressedBlock {
+    fn read<R: Read>(reader: &mut R) -> io::Result<Self> {
+        Ok(CompressedBlock {
+            offset: reader.read_uint::<LE>(5)?,
+            compressed_size: reader.read_uint::<LE>(3)? as u32,
+            uncompressed_size: reader.read_uint::<LE>(3)? as u32,
+            compression_method: reader.read_u8()?,
+        })
+    }
+
+    fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_uint::<LE>(self.offset, 5)?;
+        writer.write_uint::<LE>(self.compressed_size as u64, 3)?;
+        writer.write_uint::<LE>(self.uncompressed_size as u64, 3)?;
+        writer.write_u8(self.compression_method)
+    }
+}
+
+/// Hash and flags of a chunk, only used when writing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ChunkMeta {
+    /// Hash of the uncompressed chunk data, see [`chunk_hash`]
+    pub hash: [u8; 20],
+    pub flags: u8,
+}
+
+/// Hash of the uncompressed chunk data stored in the chunk meta.
+///
+/// Starting with `ReplaceIoChunkHashWithIoHash` this is an IoHash, the BLAKE3 hash truncated to 20 bytes,
+/// older versions use SHA1.
+pub(crate) fn chunk_hash(data: &[u8], version: IoStoreTocVersion) -> [u8; 20] {
+    if version >= IoStoreTocVersion::ReplaceIoChunkHashWithIoHash {
+        let mut hash = [0u8; 20];
+        hash.copy_from_slice(&blake3::hash(data).as_bytes()[..20]);
+        hash
+    } else {
+        crate::hash(data)
+    }
+}
+
+/// Chunk meta flag set when the chunk is stored compressed
+pub(crate) const CHUNK_META_FLAG_COMPRESSED: u8 = 0x01;
+
+/// Parsed .utoc file
+#[derive(Debug)]
+pub(crate) struct Toc {
+    pub version: IoStoreTocVersion,
+    pub container_id: u64,
+    pub container_flags: u8,
+    pub encryption_key_guid: [u8; 0x10],
+    pub compression_block_size: u32,
+    pub partition_size: u64,
+    pub chunk_ids: Vec<IoChunkId>,
+    pub offsets: Vec<OffsetAndLength>,
+    pub compressed_blocks: Vec<CompressedBlock>,
+    pub compression_methods: Vec<Compression>,
+    pub mount_point: String,
+    /// paths relative to the mount point and their toc entry index
+    pub files: Vec<(String, u32)>,
+}
+
+impl Toc {
+    pub(crate) fn is_encrypted(&self) -> bool {
+        self.container_flags & CONTAINER_FLAG_ENCRYPTED != 0
+    }
+
+    pub(crate) fn read<R: Read + Seek>(
+        reader: &mut R,
+        key: Option<&AesKey>,
+    ) -> Result<Self, PakError> {
+        let mut magic = [0u8; 16];
+        reader.read_exact(&mut magic)?;
+        if magic != TOC_MAGIC {
+            return Err(PakError::pak_invalid());
+        }
+
+        let version_num = reader.read_u8()?;
+        let version = IoStoreTocVersion::from_num(version_num)
+            .ok_or_else(|| PakError::io_store_version_unsupported(version_num))?;
+        let _reserved = reader.read_u24::<LE>()?;
+
+        let header_size = reader.read_u32::<LE>()?;
+        let entry_count = reader.read_u32::<LE>()?;
+        let compressed_block_entry_count = reader.read_u32::<LE>()?;
+        let compressed_block_entry_size = reader.read_u32::<LE>()?;
+        let compression_method_name_count = reader.read_u32::<LE>()?;
+        let compression_method_name_length = reader.read_u32::<LE>()?;
+        let compression_block_size = reader.read_u32::<LE>()?;
+        let directory_index_size = reader.read_u32::<LE>()?;
+        let _partition_count = reader.read_u32::<LE>()?;
+        let container_id = reader.read_u64::<LE>()?;
+        let mut encryption_key_guid = [0u8; 0x10];
+        reader.read_exact(&mut encryption_key_guid)?;
+        let container_flags = reader.read_u8()?;
+        let _reserved = reader.read_u24::<LE>()?;
+        let perfect_hash_seed_count = reader.read_u32::<LE>()?;
+        let partition_size = reader.read_u64::<LE>()?;
+        let chunks_without_perfect_hash_count = reader.read_u32::<LE>()?;
+
+        if compressed_block_entry_size != COMPRESSED_BLOCK_ENTRY_SIZE
+            || compression_method_name_length > 0x100
+            || compression_block_size == 0
+        {
+            return Err(PakError::pak_invalid());
+        }
+
+        // older versions did not have partitions, so everything is in a single partition
+        let partition_size = if version < IoStoreTocVersion::PartitionSize || partition_size == 0 {
+            u64::MAX
+        } else {
+            partition_size
+        };
+
+        reader.seek(io::SeekFrom::Start(header_size as u64))?;
+
+        // counts are not used for allocating, the reader runs out of data for invalid counts
+        let mut chunk_ids = Vec::new();
+        for _ in 0..entry_count {
+            let mut id = [0u8; 12];
+            reader.read_exact(&mut id)?;
+            chunk_ids.push(IoChunkId(id));
+        }
+
+        let mut offsets = Vec::new();
+        for _ in 0..entry_count {
+            offsets.push(OffsetAndLength::read(reader)?);
+        }
+
+        // chunks are looked up with a map, so the perfect hash is not needed
+        if version >= IoStoreTocVersion::PerfectHash {
+            reader.seek(io::SeekFrom::Current(perfect_hash_seed_count as i64 * 4))?;
+        }
+        if version >= IoStoreTocVersion::PerfectHashWithOverflow {
+            reader.seek(io::SeekFrom::Current(
+                chunks_without_perfect_hash_count as i64 * 4,
+            ))?;
+        }
+
+        let mut compressed_blocks = Vec::new();
+        for _ in 0..compressed_block_entry_count {
+            compressed_blocks.push(CompressedBlock::read(reader)?);
+        }
+
+        let mut compression_methods = Vec::new();
+        for _ in 0..compression_method_name_count {
+            let name = reader.read_vec(compression_method_name_length as usize)?;
+            let len = name
+                .iter()
+                .position(|byte| *byte == 0)
+                .unwrap_or(name.len());
+            let method = std::str::from_utf8(&name[..len])
+                .ok()
+                .and_then(Compression::from_name)
+                .unwrap_or_else(|| {
+                    let mut bytes = [0u8; 0x20];
+                    let len = len.min(0x20);
+                    bytes[..len].copy_from_slice(&name[..len]);
+                    Compression::Unknown(bytes)
+                });
+            compression_methods.push(method);
+        }
+
+        if container_flags & CONTAINER_FLAG_SIGNED != 0 {
+            let hash_size = reader.read_i32::<LE>()? as i64;
+            reader.seek(io::SeekFrom::Current(
+                hash_size * 2 + compressed_block_entry_count as i64 * 20,
+            ))?;
+        }
+
+        let mut mount_point = String::new();
+        let mut files = Vec::new();
+        if version >= IoStoreTocVersion::DirectoryIndex
+            && container_flags & CONTAINER_FLAG_INDEXED != 0
+            && directory_index_size > 0
+        {
+            let mut directory_index = read_bytes(reader, directory_index_size as u64)?;
+            if container_flags & CONTAINER_FLAG_ENCRYPTED != 0 {
+                key.ok_or_else(PakError::encryption_key_missing)?
+                    .decrypt(&mut directory_index);
+            }
+            (mount_point, files) = read_directory_index(&mut io::Cursor::new(directory_index))?;
+        }
+
+        Ok(Toc {
+            version,
+            container_id,
+            container_flags,
+            encryption_key_guid,
+            compression_block_size,
+            partition_size,
+            chunk_ids,
+            offsets,
+            compressed_blocks,
+            compression_methods,
+            mount_point,
+            files,
+        })
+    }
+
+    /// Write the toc, metas must contain an entry for every chunk
+    pub(crate) fn write<W: Write>(
+        &self,
+        writer: &mut W,
+        key: Option<&AesKey>,
+        metas: &[ChunkMeta],
+    ) -> Result<(), PakError> {
+        let mut directory_index = Vec::new();
+        if self.container_flags & CONTAINER_FLAG_INDEXED != 0 {
+            write_directory_index(&mut directory_index, &self.mount_point, &self.files)?;
+            if self.is_encrypted() {
+                let key = key.ok_or_else(PakError::encryption_key_missing)?;
+                directory_index.resize(encryption::align(directory_index.len() as u64) as usize, 0);
+                key.encrypt(&mut directory_index);
+            }
+        }
+
+        writer.write_all(&TOC_MAGIC)?;
+        writer.write_u8(self.version as u8)?;
+        writer.write_u24::<LE>(0)?;
+        writer.write_u32::<LE>(TOC_HEADER_SIZE)?;
+        writer.write_u32::<LE>(self.chunk_ids.len() as u32)?;
+        writer.write_u32::<LE>(self.compressed_blocks.len() as u32)?;
+        writer.write_u32::<LE>(COMPRESSED_BLOCK_ENTRY_SIZE)?;
+        writer.write_u32::<LE>(self.compression_methods.len() as u32)?;
+        writer.write_u32::<LE>(COMPRESSION_METHOD_NAME_LENGTH)?;
+        writer.write_u32::<LE>(self.compression_block_size)?;
+        writer.write_u32::<LE>(directory_index.len() as u32)?;
+        // partition count
+        writer.write_u32::<LE>(1)?;
+        writer.write_u64::<LE>(self.container_id)?;
+        writer.write_all(&self.encryption_key_guid)?;
+        writer.write_u8(self.container_flags)?;
+        writer.write_u24::<LE>(0)?;
+        // without perfect hash seeds the engine falls back to a map for looking up chunks
+        writer.write_u32::<LE>(0)?;
+        writer.write_u64::<LE>(self.partition_size)?;
+        writer.write_u32::<LE>(0)?;
+        // reserved
+        writer.write_all(&[0u8; 44])?;
+
+        for chunk_id in &self.chunk_ids {
+            writer.write_all(&chunk_id.0)?;
+        }
+        for offset in &self.offsets {
+            offset.write(writer)?;
+        }
+        for block in &self.compressed_blocks {
+            block.write(writer)?;
+        }
+        for method in &self.compression_methods {
+            writer.write_all(&method.as_bytes())?;
+        }
+        writer.write_all(&directory_index)?;
+
+        for meta in metas {
+            writer.write_all(&meta.hash)?;
+            if self.version < IoStoreTocVersion::ReplaceIoChunkHashWithIoHash {
+                writer.write_all(&[0u8; 12])?;
+            }
+            writer.write_u8(meta.flags)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Read the directory index and flatten it into a list of paths and toc entry indices
+fn read_directory_index<R: Read + Seek>(
+    reader: &mut R,
+) -> Result<(String, Vec<(String, u32)>), PakError> {
+    let mount_point = reader.read_fstring()?.unwrap_or_default();
+
+    let mut directories = Vec::new();
+    for _ in 0..reader.read_u32::<LE>()? {
+        directories.push([
+            reader.read_u32::<LE>()?,
+            reader.read_u32::<LE>()?,
+            reader.read_u32::<LE>()?,
+            reader.read_u32::<LE>()?,
+        ]);
+    }
+    let mut file_entries = Vec::new();
+    for _ in 0..reader.read_u32::<LE>()? {
+        file_entries.push([
+            reader.read_u32::<LE>()?,
+            reader.read_u32::<LE>()?,
+            reader.read_u32::<LE>()?,
+        ]);
+    }
+    let mut strings = Vec::new();
+    for _ in 0..reader.read_u32::<LE>()? {
+        strings.push(reader.read_fstring()?.unwrap_or_default());
+    }
+
+    let name = |index: u32| -> Result<&str, PakError> {
+        strings
+            .get(index as usize)
+            .map(String::as_str)
+            .ok_or_else(PakError::pak_invalid)
+    };
+
+    let mut files = Vec::new();
+    if directories.is_empty() {
+        return Ok((mount_point, files));
+    }
+
+    // every directory and file can only be visited once, more visits mean the index contains a cycle
+    let mut visits = 0usize;
+    let max_visits = directories.len() + file_entries.len();
+
+    let mut stack = vec![(0u32, String::new())];
+    while let Some((directory_index, parent_path)) = stack.pop() {
+        let mut directory_index = directory_index;
+        while directory_index != INVALID_HANDLE {
+            visits += 1;
+            if visits > max_visits {
+                return Err(PakError::pak_invalid());
+            }
+
+            let [directory_name, first_child, next_sibling, first_file] = *directories
+                .get(directory_index as usize)
+                .ok_or_else(PakError::pak_invalid)?;
+
+            let mut path = parent_path.clone();
+            if directory_name != INVALID_HANDLE {
+                path.push_str(name(directory_name)?);
+                path.push('/');
+            }
+
+            let mut file_index = first_file;
+            while file_index != INVALID_HANDLE {
+                visits += 1;
+                if visits > max_visits {
+                    return Err(PakError::pak_invalid());
+                }
+
+                let [file_name, next_file, toc_index] = *file_entries
+                    .get(file_index as usize)
+                    .ok_or_else(PakError::pak_invalid)?;
+                files.push((format!("{path}{}", name(file_name)?), toc_index));
+                file_index = next_file;
+            }
+
+            if first_child != INVALID_HANDLE {
+                stack.push((first_child, path));
+            }
+            directory_index = next_sibling;
+        }
+    }
+
+    files.sort();
+    Ok((mount_point, files))
+}
+
+/// Write a directory index for the given paths relative to the mount point and their toc entry indices
+fn write_directory_index<W: Write>(
+    writer: &mut W,
+    mount_point: &str,
+    files: &[(String, u32)],
+) -> Result<(), PakError> {
+    let mut strings: Vec<&str> = Vec::new();
+    let mut intern = |string| match strings.iter().position(|existing| *existing == string) {
+        Some(index) => index as u32,
+        None => {
+            strings.push(string);
+            strings.len() as u32 - 1
+        }
+    };
+
+    // [name, first child, next sibling, first file], the root directory has no name
+    let mut directories = vec![[INVALID_HANDLE; 4]];
+    // [name, next file, toc entry index]
+    let mut file_entries: Vec<[u32; 3]> = Vec::new();
+
+    for (path, toc_index) in files {
+        let mut components = path.split('/').filter(|component| !component.is_empty());
+        let Some(file_name) = components.next_back() else {
+            continue;
+        };
+
+        let mut directory = 0;
+        for component in components {
+            let name = intern(component);
+
+            // find the subdirectory or append it to the children
+            let mut child = directories[directory][1];
+            let mut last_child = None;
+            while child != INVALID_HANDLE && directories[child as usize][0] != name {
+                last_child = Some(child as usize);
+                child = directories[child as usize][2];
+            }
+            if child == INVALID_HANDLE {
+                child = directories.len() as u32;
+                directories.push([name, INVALID_HANDLE, INVALID_HANDLE, INVALID_HANDLE]);
+                match last_child {
+                    Some(last_child) => directories[last_child][2] = child,
+                    None => directories[directory][1] = child,
+                }
+            }
+            directory = child as usize;
+        }
+
+        // files are prepended, which is cheaper than walking to the end of the list
+        file_entries.push([intern(file_name), directories[directory][3], *toc_index]);
+        directories[directory][3] = file_entries.len() as u32 - 1;
+    }
+
+    writer.write_fstring(Some(mount_point))?;
+    writer.write_u32::<LE>(directories.len() as u32)?;
+    for value in directories.iter().flatten() {
+        writer.write_u32::<LE>(*value)?;
+    }
+    writer.write_u32::<LE>(file_entries.len() as u32)?;
+    for value in file_entries.iter().flatten() {
+        writer.write_u32::<LE>(*value)?;
+    }
+    writer.write_u32::<LE>(strings.len() as u32)?;
+    for string in strings {
+        writer.write_fstring(Some(string))?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,308 @@
+//! Writer for IoStore containers
+
+use std::collections::HashSet;
+use std::io::Write;
+
+use crate::compression::Compression;
+use crate::encryption::{self, AesKey};
+use crate::entry::compress_blocks;
+use crate::error::PakError;
+
+use super::toc::{
+    chunk_hash, package_id, ChunkMeta, CompressedBlock, IoChunkId, IoChunkType, IoStoreTocVersion,
+    OffsetAndLength, Toc, CHUNK_META_FLAG_COMPRESSED, CONTAINER_FLAG_COMPRESSED,
+    CONTAINER_FLAG_ENCRYPTED, CONTAINER_FLAG_INDEXED,
+};
+use super::zen::{
+    convert_package, write_container_header, EngineVersion, StoreEntry, CONTAINER_HEADER_CHUNK_TYPE,
+};
+
+/// Largest size that fits into the 3 byte sizes of compressed blocks
+const MAX_BLOCK_SIZE: u32 = 0xffffff;
+
+/// An IoStore container writer which writes chunk data to the `.ucas` file as chunks are added
+/// and the table of contents to the `.utoc` file when finished.
+///
+/// Only a single partition is written and the container has no perfect hash,
+/// so the engine looks up chunks with a map.
+/// Packages added with [`IoStoreWriter::add_package`] are listed in the container header chunk,
+/// which is written when finishing the container.
+#[derive(Debug)]
+pub struct IoStoreWriter<W>
+where
+    W: Write,
+{
+    /// Version of the .utoc file format
+    pub version: IoStoreTocVersion,
+    /// Id of the container, see [`IoStoreWriter::set_container_name`]
+    pub container_id: u64,
+    /// Mount point of the directory index. Typically `../../../`.
+    pub mount_point: String,
+    /// Compression method used for chunks added after this is set
+    pub compression: Compression,
+    /// Compression block size, see [`IoStoreWriter::set_block_size`]
+    block_size: u32,
+    /// Encrypt chunks and the directory index, requires an encryption key
+    pub encrypt: bool,
+    /// GUID of the used encryption key, games might only load containers using a specific key GUID
+    pub encryption_key_guid: [u8; 0x10],
+    /// Engine version the packages were cooked with, has to be set before adding packages
+    pub engine_version: Option<EngineVersion>,
+    store_entries: Vec<StoreEntry>,
+    chunk_ids: Vec<IoChunkId>,
+    chunk_set: HashSet<IoChunkId>,
+    offsets: Vec<OffsetAndLength>,
+    metas: Vec<ChunkMeta>,
+    compressed_blocks: Vec<CompressedBlock>,
+    compression_methods: Vec<Compression>,
+    files: Vec<(String, u32)>,
+    file_set: HashSet<String>,
+    cas_offset: u64,
+    key: Option<AesKey>,
+    writer: W,
+}
+
+impl<W> IoStoreWriter<W>
+where
+    W: Write,
+{
+    /// Creates a new `IoStoreWriter` that writes chunk data to the provided `.ucas` writer.
+    /// When using a writer that uses syscalls like a `File` it is recommended to wrap it in a
+    /// [`std::io::BufWriter`] to avoid unnecessary syscalls.
+    pub fn new(cas: W, version: IoStoreTocVersion) -> Self {
+        Self {
+            version,
+            container_id: 0,
+            mount_point: "../../../".to_owned(),
+            compression: Compression::zlib(),
+            block_size: 0x010000,
+            encrypt: false,
+            encryption_key_guid: [0u8; 0x10],
+            engine_version: None,
+            store_entries: Vec::new(),
+            chunk_ids: Vec::new(),
+            chunk_set: HashSet::new(),
+            offsets: Vec::new(),
+            metas: Vec::new(),
+            compressed_blocks: Vec::new(),
+            compression_methods: Vec::new(),
+            files: Vec::new(),
+            file_set: HashSet::new(),
+            cas_offset: 0,
+            key: None,
+            writer: cas,
+        }
+    }
+
+    /// Set the AES key used to encrypt chunks and the directory index.
+    pub fn set_encryption_key(&mut self, key: AesKey) {
+        self.key = Some(key);
+    }
+
+    /// Set the compression block size, which can not be changed after the first chunk was added
+    /// because chunk offsets are stored in blocks.
+    pub fn set_block_size(&mut self, block_size: u32) -> Result<(), PakError> {
+        if block_size == 0 || block_size > MAX_BLOCK_SIZE || !self.chunk_ids.is_empty() {
+            return Err(PakError::configuration_invalid());
+        }
+        self.block_size = block_size;
+        Ok(())
+    }
+
+    /// Returns the compression block size.
+    pub fn block_size(&self) -> u32 {
+        self.block_size
+    }
+
+    /// Set the container id from the name of the container, which is the file name without extension.
+    pub fn set_container_name(&mut self, name: &str) {
+        self.container_id = package_id(name);
+    }
+
+    /// Returns the ids of all chunks which have been added.
+    pub fn get_chunk_ids(&self) -> Vec<IoChunkId> {
+        self.chunk_ids.clone()
+    }
+
+    /// Writes the data of a chunk into the `.ucas` file.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Id of the chunk, has to be unique in the container
+    /// * `name` - Path of the file in the directory index relative to the mount point, if the chunk should be listed there
+    /// * `data` - Uncompressed data of the chunk
+    pub fn add_chunk(
+        &mut self,
+        id: IoChunkId,
+        name: Option<&str>,
+        data: &[u8],
+    ) -> Result<(), PakError> {
+        if self.chunk_set.contains(&id) {
+            return Err(PakError::double_write(id.to_string()));
+        }
+        if let Some(name) = name {
+            if self.file_set.contains(name) {
+                return Err(PakError::double_write(name.to_owned()));
+            }
+        }
+
+        let key = if self.encrypt {
+            Some(
+                self.key
+                    .as_ref()
+                    .ok_or_else(PakError::encryption_key_missing)?,
+            )
+        } else {
+            None
+        };
+
+        let method = match self.compression {
+            Compression::None => 0,
+            compression => match self
+                .compression_methods
+                .iter()
+                .position(|method| *method == compression)
+            {
+                Some(index) => index as u8 + 1,
+                None => {
+                    self.compression_methods.push(compression);
+                    self.compression_methods.len() as u8
+                }
+            },
+        };
+
+        let compressed = match method {
+            0 => Vec::new(),
+            _ => compress_blocks(self.compression, data, self.block_size)?,
+        };
+
+        // every chunk starts at the beginning of a block
+        let offset = self.compressed_blocks.len() as u64 * self.block_size as u64;
+
+        let mut is_compressed = false;
+        for (index, block) in data.chunks(self.block_size as usize).enumerate() {
+            // blocks which do not get smaller are stored without compression
+            let (mut stored, compression_method) = match compressed.get(index) {
+                Some(compressed) if compressed.len() < block.len() => {
+                    is_compressed = true;
+                    (compressed.clone(), method)
+                }
+                _ => (block.to_vec(), 0),
+            };
+
+            self.compressed_blocks.push(CompressedBlock {
+                offset: self.cas_offset,
+                compressed_size: stored.len() as u32,
+                uncompressed_size: block.len() as u32,
+                compression_method,
+            });
+
+            if let Some(key) = key {
+                stored.resize(encryption::align(stored.len() as u64) as usize, 0);
+                key.encrypt(&mut stored);
+            }
+            self.writer.write_all(&stored)?;
+            self.cas_offset += stored.len() as u64;
+        }
+
+        let index = self.chunk_ids.len() as u32;
+        self.chunk_ids.push(id);
+        self.chunk_set.insert(id);
+        self.offsets.push(OffsetAndLength {
+            offset,
+            length: data.len() as u64,
+        });
+        self.metas.push(ChunkMeta {
+            hash: chunk_hash(data, self.version),
+            flags: if is_compressed {
+                CHUNK_META_FLAG_COMPRESSED
+            } else {
+                0
+            },
+        });
+        if let Some(name) = name {
+            self.files.push((name.to_owned(), index));
+            self.file_set.insert(name.to_owned());
+        }
+
+        Ok(())
+    }
+
+    /// Converts a cooked package to the zen package format and writes it into the `.ucas` file.
+    ///
+    /// Only UE5 packages are supported. Bulk data stored in separate files has to be added with
+    /// [`IoStoreWriter::add_chunk`], bulk data at the end of the `.uexp` file is not supported.
+    ///
+    /// # Arguments
+    ///
+    /// * `package_name` - Name of the package like `/Game/Maps/Map`
+    /// * `name` - Path of the `.uasset` or `.umap` file in the directory index relative to the mount point, if the package should be listed there
+    /// * `uasset` - Contents of the `.uasset` or `.umap` file
+    /// * `uexp` - Contents of the `.uexp` file
+    pub fn add_package(
+        &mut self,
+        package_name: &str,
+        name: Option<&str>,
+        uasset: &[u8],
+        uexp: &[u8],
+    ) -> Result<(), PakError> {
+        let engine_version = self
+            .engine_version
+            .ok_or_else(PakError::configuration_invalid)?;
+        if self.version < IoStoreTocVersion::PerfectHash {
+            return Err(PakError::io_store_version_unsupported(self.version as u8));
+        }
+
+        let package = convert_package(package_name, uasset, uexp, engine_version)?;
+        let id =
+            IoChunkId::from_package_name(package_name, IoChunkType::ExportBundleData, self.version);
+        self.add_chunk(id, name, &package.data)?;
+        self.store_entries.push(package.store_entry);
+
+        Ok(())
+    }
+
+    /// Finish writing the container by writing the table of contents to the `.utoc` writer.
+    /// Returns the `.ucas` writer.
+    pub fn finish_write<T: Write>(mut self, mut toc_writer: T) -> Result<W, PakError> {
+        if let Some(engine_version) = self.engine_version {
+            if !self.store_entries.is_empty() {
+                let header =
+                    write_container_header(self.container_id, &self.store_entries, engine_version)?;
+                let id = IoChunkId::new(self.container_id, 0, CONTAINER_HEADER_CHUNK_TYPE);
+                self.add_chunk(id, None, &header)?;
+            }
+        }
+
+        let mut container_flags = 0;
+        if !self.compression_methods.is_empty() {
+            container_flags |= CONTAINER_FLAG_COMPRESSED;
+        }
+        if self.encrypt {
+            container_flags |= CONTAINER_FLAG_ENCRYPTED;
+        }
+        if !self.files.is_empty() && self.version >= IoStoreTocVersion::DirectoryIndex {
+            container_flags |= CONTAINER_FLAG_INDEXED;
+        }
+
+        let toc = Toc {
+            version: self.version,
+            container_id: self.container_id,
+            container_flags,
+            encryption_key_guid: self.encryption_key_guid,
+            compression_block_size: self.block_size,
+            partition_size: u64::MAX,
+            chunk_ids: self.chunk_ids,
+            offsets: self.offsets,
+            compressed_blocks: self.compressed_blocks,
+            compression_methods: self.compression_methods,
+            mount_point: self.mount_point,
+            files: self.files,
+        };
+        toc.write(&mut toc_writer, self.key.as_ref(), &self.metas)?;
+        toc_writer.flush()?;
+        self.writer.flush()?;
+
+        Ok(self.writer)
+    }
+}
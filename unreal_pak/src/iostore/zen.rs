@@ -0,0 +1,614 @@
+/*
+    Zen package, the export bundle data chunk of a package (UE 5.0 and 5.1, all integers little endian):
+    - summary
+        - u32 has versioning info (always 0)
+        - u32 header size
+        - mapped name of the package
+        - u32 package flags
+        - u32 cooked header size, the size of the legacy .uasset file
+        - i32 imported public export hashes offset
+        - i32 import map offset
+        - i32 export map offset
+        - i32 export bundle entries offset
+        - i32 graph data offset
+    - name batch
+        - u32 name count, if not 0:
+            - u32 string bytes
+            - u64 hash algorithm id 0xC1640000
+            - u64 CityHash64 of each lowercase name
+            - 2 byte header per name: u8 is wide << 7 | length >> 8, u8 length & 0xff
+            - name strings without terminator, wide strings aligned to 2 bytes
+    - u64 imported public export hashes
+    - import map (package object index per legacy import)
+    - export map (72 bytes each)
+        - u64 cooked serial offset, u64 cooked serial size (the legacy serial offset and size)
+        - mapped object name
+        - package object index outer, class, super and template
+        - u64 public export hash
+        - u32 object flags
+        - u8 filter flags, 3 bytes padding
+    - export bundle entries (u32 export index, u32 command type each)
+    - graph data
+        - export bundle headers (u64 serial offset, u32 first entry index, u32 entry count each)
+        - i32 internal arc count, arcs (i32 from export bundle, i32 to export bundle each)
+        - for each imported package in the order of the store entry:
+            i32 external arc count, arcs (i32 from import index, u8 from command type, i32 to export bundle each)
+    - export data in the order of the serialize commands
+
+    Mapped names are a u32 index into the name map with the type in the upper 2 bits (0 for the package name map)
+    and a u32 number.
+    Package object indices are a u64 with the type in the upper 2 bits:
+    - export: index into the export map
+    - script import: CityHash64 of the lowercase UTF-16 object path with `.` and `:` replaced by `/`
+    - package import: imported package index << 32 | imported public export hash index
+    - null: all bits set, also used for imports of packages
+
+    Container header, stored in the chunk with the container id and type 6:
+    - u32 signature 0x496f436e
+    - u32 version (1 for UE 5.0, 2 for UE 5.1)
+    - u64 container id
+    - u32 package count (version < 2)
+    - i32 package count, u64 package ids
+    - i32 store entries size, store entries (24 bytes each) followed by their array data
+        - i32 export count, i32 export bundle count
+        - u32 imported package count, u32 offset from this field to the u64 imported package ids
+        - u32 shader map hash count, u32 offset from this field to the 20 byte shader map hashes
+    - i32 optional segment package count, i32 optional segment store entries size (version >= 2)
+    - empty name batch of redirect names
+    - i32 localized package count
+    - i32 package redirect count
+*/
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::io::Write;
+
+use byteorder::{WriteBytesExt, LE};
+use naive_cityhash::cityhash64;
+
+use crate::error::PakError;
+
+use super::legacy::{LegacyExport, LegacyPackage, PACKAGE_FILE_TAG};
+use super::toc::package_id;
+
+/// Chunk type of the container header
+pub(crate) const CONTAINER_HEADER_CHUNK_TYPE: u8 = 6;
+
+const CONTAINER_HEADER_SIGNATURE: u32 = 0x496f436e;
+const NAME_HASH_ALGORITHM_ID: u64 = 0xC1640000;
+const SUMMARY_SIZE: usize = 44;
+
+const EXPORT_COMMAND_CREATE: u32 = 0;
+const EXPORT_COMMAND_SERIALIZE: u32 = 1;
+
+const RF_PUBLIC: u32 = 0x01;
+
+const NULL_INDEX: u64 = u64::MAX;
+const SCRIPT_IMPORT: u64 = 1 << 62;
+const PACKAGE_IMPORT: u64 = 2 << 62;
+const INDEX_MASK: u64 = !(3 << 62);
+
+/// Engine version of the packages added to an IoStore container
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[allow(non_camel_case_types)]
+pub enum EngineVersion {
+    /// UE 5.0
+    UE5_0,
+    /// UE 5.1
+    UE5_1,
+}
+
+impl EngineVersion {
+    /// UE5 object version of unversioned packages saved by this engine version
+    fn object_version_ue5(self) -> i32 {
+        match self {
+            Self::UE5_0 => 1004,
+            Self::UE5_1 => 1008,
+        }
+    }
+
+    fn container_header_version(self) -> u32 {
+        match self {
+            Self::UE5_0 => 1,
+            Self::UE5_1 => 2,
+        }
+    }
+}
+
+/// Entry of a package in the container header
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct StoreEntry {
+    pub package_id: u64,
+    pub export_count: i32,
+    pub export_bundle_count: i32,
+    pub imported_packages: Vec<u64>,
+}
+
+/// A package converted to the zen package format
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ZenPackage {
+    pub data: Vec<u8>,
+    pub store_entry: StoreEntry,
+}
+
+/// CityHash64 of the lowercase UTF-16 string
+fn lowercase_hash(string: &str) -> u64 {
+    let bytes = string
+        .to_ascii_lowercase()
+        .encode_utf16()
+        .flat_map(u16::to_le_bytes)
+        .collect::<Vec<_>>();
+    cityhash64(&bytes)
+}
+
+/// Write a name batch, the hashes use the lowercase name in the encoding it is stored with
+fn write_name_batch(writer: &mut Vec<u8>, names: &[String]) -> Result<(), PakError> {
+    writer.write_u32::<LE>(names.len() as u32)?;
+    if names.is_empty() {
+        return Ok(());
+    }
+
+    let mut hashes = Vec::new();
+    let mut headers = Vec::new();
+    let mut strings = Vec::new();
+    for name in names {
+        let is_wide = !name.is_ascii();
+        let length = match is_wide {
+            true => name.encode_utf16().count(),
+            false => name.len(),
+        };
+        if length > 0x7fff {
+            return Err(PakError::package_invalid(format!("Name too long: {name}")));
+        }
+        headers.push((is_wide as u8) << 7 | (length >> 8) as u8);
+        headers.push(length as u8);
+
+        if is_wide {
+            let bytes = name
+                .encode_utf16()
+                .flat_map(u16::to_le_bytes)
+                .collect::<Vec<_>>();
+            hashes.push(lowercase_hash(name));
+            // the strings start at an even offset, so this aligns wide strings in the package
+            if strings.len() % 2 == 1 {
+                strings.push(0);
+            }
+            strings.extend_from_slice(&bytes);
+        } else {
+            hashes.push(cityhash64(name.to_ascii_lowercase().as_bytes()));
+            strings.extend_from_slice(name.as_bytes());
+        }
+    }
+
+    writer.write_u32::<LE>(strings.len() as u32)?;
+    writer.write_u64::<LE>(NAME_HASH_ALGORITHM_ID)?;
+    for hash in hashes {
+        writer.write_u64::<LE>(hash)?;
+    }
+    writer.write_all(&headers)?;
+    writer.write_all(&strings)?;
+    Ok(())
+}
+
+/// Get the path of an object by following its outers, returns the names from the outermost object
+fn object_path(
+    package: &LegacyPackage,
+    mut index: i32,
+    is_import: bool,
+) -> Result<Vec<String>, PakError> {
+    let mut path = Vec::new();
+    while index != 0 {
+        if path.len() > package.imports.len() + package.exports.len() {
+            return Err(PakError::package_invalid("Cyclic outers".to_owned()));
+        }
+        let (name, outer_index) = match (is_import, index) {
+            (true, index) if index < 0 => package
+                .imports
+                .get((-index - 1) as usize)
+                .map(|import| (import.object_name, import.outer_index)),
+            (false, index) if index > 0 => package
+                .exports
+                .get((index - 1) as usize)
+                .map(|export| (export.object_name, export.outer_index)),
+            _ => None,
+        }
+        .ok_or_else(|| PakError::package_invalid(format!("Invalid outer index {index}")))?;
+        path.push(package.name(name)?);
+        index = outer_index;
+    }
+    path.reverse();
+    Ok(path)
+}
+
+/// Zen package header being built from a legacy package
+struct Converter<'a> {
+    package: &'a LegacyPackage,
+    import_indices: Vec<u64>,
+    imported_packages: Vec<u64>,
+    imported_public_export_hashes: Vec<u64>,
+    /// Imported package index of every import which belongs to an imported package
+    import_packages: HashMap<usize, usize>,
+}
+
+impl<'a> Converter<'a> {
+    fn new(package: &'a LegacyPackage) -> Result<Self, PakError> {
+        let mut converter = Converter {
+            package,
+            import_indices: Vec::new(),
+            imported_packages: Vec::new(),
+            imported_public_export_hashes: Vec::new(),
+            import_packages: HashMap::new(),
+        };
+
+        for (index, import) in package.imports.iter().enumerate() {
+            let path = object_path(package, -(index as i32) - 1, true)?;
+            let object_index = if path[0].starts_with("/Script/") {
+                SCRIPT_IMPORT
+                    | (lowercase_hash(&path.join("/").replace(['.', ':'], "/")) & INDEX_MASK)
+            } else {
+                let id = package_id(&path[0]);
+                let package_index = match converter.imported_packages.iter().position(|p| *p == id)
+                {
+                    Some(package_index) => package_index,
+                    None => {
+                        converter.imported_packages.push(id);
+                        converter.imported_packages.len() - 1
+                    }
+                };
+                converter.import_packages.insert(index, package_index);
+
+                if import.outer_index == 0 {
+                    NULL_INDEX
+                } else {
+                    converter
+                        .imported_public_export_hashes
+                        .push(lowercase_hash(&path[1..].join("/")));
+                    PACKAGE_IMPORT
+                        | (package_index as u64) << 32
+                        | (converter.imported_public_export_hashes.len() - 1) as u64
+                }
+            };
+            converter.import_indices.push(object_index);
+        }
+
+        Ok(converter)
+    }
+
+    /// Convert a legacy package index to a package object index
+    fn object_index(&self, index: i32) -> Result<u64, PakError> {
+        let object_index = match index {
+            0 => Some(NULL_INDEX),
+            index if index < 0 => self.import_indices.get((-index - 1) as usize).copied(),
+            index => {
+                Some(index as u64 - 1).filter(|index| *index < self.package.exports.len() as u64)
+            }
+        };
+        object_index
+            .ok_or_else(|| PakError::package_invalid(format!("Invalid package index {index}")))
+    }
+}
+
+/// Order the create and serialize commands of the exports so that every command comes after
+/// the commands of its preload dependencies inside the package
+fn export_bundle_entries(exports: &[LegacyExport]) -> Result<Vec<(u32, u32)>, PakError> {
+    let node = |export: usize, command: u32| export * 2 + command as usize;
+
+    let mut edges = vec![Vec::new(); exports.len() * 2];
+    let mut add_edge = |from: usize, to: usize| edges[from].push(to);
+    for (index, export) in exports.iter().enumerate() {
+        add_edge(
+            node(index, EXPORT_COMMAND_CREATE),
+            node(index, EXPORT_COMMAND_SERIALIZE),
+        );
+        if export.outer_index > 0 {
+            add_edge(
+                node(export.outer_index as usize - 1, EXPORT_COMMAND_CREATE),
+                node(index, EXPORT_COMMAND_CREATE),
+            );
+        }
+        for (dependencies, from, to) in [
+            (
+                &export.serialize_before_serialize,
+                EXPORT_COMMAND_SERIALIZE,
+                EXPORT_COMMAND_SERIALIZE,
+            ),
+            (
+                &export.create_before_serialize,
+                EXPORT_COMMAND_CREATE,
+                EXPORT_COMMAND_SERIALIZE,
+            ),
+            (
+                &export.serialize_before_create,
+                EXPORT_COMMAND_SERIALIZE,
+                EXPORT_COMMAND_CREATE,
+            ),
+            (
+                &export.create_before_create,
+                EXPORT_COMMAND_CREATE,
+                EXPORT_COMMAND_CREATE,
+            ),
+        ] {
+            for dependency in dependencies {
+                if *dependency > 0 && *dependency as usize <= exports.len() {
+                    add_edge(node(*dependency as usize - 1, from), node(index, to));
+                }
+            }
+        }
+    }
+
+    let mut incoming = vec![0usize; edges.len()];
+    for to in edges.iter().flatten() {
+        incoming[*to] += 1;
+    }
+
+    // keep the original order where the dependencies allow it
+    let mut ready = (0..edges.len())
+        .filter(|node| incoming[*node] == 0)
+        .map(Reverse)
+        .collect::<BinaryHeap<_>>();
+    let mut entries = Vec::with_capacity(edges.len());
+    while let Some(Reverse(node)) = ready.pop() {
+        entries.push(((node / 2) as u32, (node % 2) as u32));
+        for to in &edges[node] {
+            incoming[*to] -= 1;
+            if incoming[*to] == 0 {
+                ready.push(Reverse(*to));
+            }
+        }
+    }
+
+    if entries.len() != edges.len() {
+        return Err(PakError::package_invalid(
+            "Cyclic preload dependencies".to_owned(),
+        ));
+    }
+    Ok(entries)
+}
+
+/// Convert a cooked package to the zen package format.
+///
+/// # Arguments
+///
+/// * `package_name` - Name of the package like `/Game/Maps/Map`
+/// * `uasset` - Contents of the .uasset or .umap file
+/// * `uexp` - Contents of the .uexp file
+/// * `engine_version` - Engine version the package was cooked with
+pub(crate) fn convert_package(
+    package_name: &str,
+    uasset: &[u8],
+    uexp: &[u8],
+    engine_version: EngineVersion,
+) -> Result<ZenPackage, PakError> {
+    let package = LegacyPackage::read(uasset, engine_version.object_version_ue5())?;
+    let converter = Converter::new(&package)?;
+
+    let header_size = package.total_header_size as i64;
+    if uasset.len() as i64 != header_size {
+        return Err(PakError::package_invalid(
+            "The header size does not match the size of the .uasset file".to_owned(),
+        ));
+    }
+    if uexp.len() < 4 || uexp[uexp.len() - 4..] != PACKAGE_FILE_TAG.to_le_bytes() {
+        return Err(PakError::package_invalid(
+            "The .uexp file does not end with the package file tag".to_owned(),
+        ));
+    }
+    let exports_size = uexp.len() as i64 - 4;
+
+    let mut export_data = Vec::with_capacity(package.exports.len());
+    let mut exports_end = 0;
+    for export in &package.exports {
+        let start = export.serial_offset - header_size;
+        let end = start.checked_add(export.serial_size);
+        match end {
+            Some(end) if start >= 0 && export.serial_size >= 0 && end <= exports_size => {
+                export_data.push(&uexp[start as usize..end as usize]);
+                exports_end = exports_end.max(end);
+            }
+            _ => {
+                return Err(PakError::package_invalid(
+                    "Export data outside of the .uexp file".to_owned(),
+                ))
+            }
+        }
+    }
+    if exports_end != exports_size {
+        return Err(PakError::package_invalid(
+            "Data after the exports in the .uexp file, bulk data has to be stored in a .ubulk file"
+                .to_owned(),
+        ));
+    }
+
+    let mut names = package.names.clone();
+    let name_index = match names
+        .iter()
+        .position(|name| name.eq_ignore_ascii_case(package_name))
+    {
+        Some(index) => index,
+        None => {
+            names.push(package_name.to_owned());
+            names.len() - 1
+        }
+    };
+
+    let mut header = vec![0u8; SUMMARY_SIZE];
+    write_name_batch(&mut header, &names)?;
+
+    let imported_public_export_hashes_offset = header.len();
+    for hash in &converter.imported_public_export_hashes {
+        header.write_u64::<LE>(*hash)?;
+    }
+
+    let import_map_offset = header.len();
+    for index in &converter.import_indices {
+        header.write_u64::<LE>(*index)?;
+    }
+
+    let export_map_offset = header.len();
+    for (index, export) in package.exports.iter().enumerate() {
+        let path = object_path(&package, index as i32 + 1, false)?;
+        let public_export_hash =
+            match export.object_flags & RF_PUBLIC != 0 || export.generate_public_hash {
+                true => lowercase_hash(&path.join("/")),
+                false => 0,
+            };
+        if export.object_name.index < 0 || export.object_name.index as usize >= names.len() {
+            return Err(PakError::package_invalid(format!(
+                "Invalid name index {}",
+                export.object_name.index
+            )));
+        }
+
+        header.write_u64::<LE>(export.serial_offset as u64)?;
+        header.write_u64::<LE>(export.serial_size as u64)?;
+        header.write_u32::<LE>(export.object_name.index as u32)?;
+        header.write_u32::<LE>(export.object_name.number as u32)?;
+        for index in [
+            export.outer_index,
+            export.class_index,
+            export.super_index,
+            export.template_index,
+        ] {
+            header.write_u64::<LE>(converter.object_index(index)?)?;
+        }
+        header.write_u64::<LE>(public_export_hash)?;
+        header.write_u32::<LE>(export.object_flags)?;
+        header.write_u8(export.not_for_client as u8 | (export.not_for_server as u8) << 1)?;
+        header.write_all(&[0u8; 3])?;
+    }
+
+    // all exports are in a single export bundle
+    let entries = export_bundle_entries(&package.exports)?;
+    let export_bundle_entries_offset = header.len();
+    for (export, command) in &entries {
+        header.write_u32::<LE>(*export)?;
+        header.write_u32::<LE>(*command)?;
+    }
+
+    let graph_data_offset = header.len();
+    header.write_u64::<LE>(0)?;
+    header.write_u32::<LE>(0)?;
+    header.write_u32::<LE>(entries.len() as u32)?;
+    header.write_i32::<LE>(0)?;
+
+    // arcs from the imports to the export bundle, grouped by imported package
+    let mut external_arcs = vec![Vec::new(); converter.imported_packages.len()];
+    for export in &package.exports {
+        for (dependencies, from) in [
+            (&export.serialize_before_serialize, EXPORT_COMMAND_SERIALIZE),
+            (&export.create_before_serialize, EXPORT_COMMAND_CREATE),
+            (&export.serialize_before_create, EXPORT_COMMAND_SERIALIZE),
+            (&export.create_before_create, EXPORT_COMMAND_CREATE),
+        ] {
+            for dependency in dependencies.iter().filter(|dependency| **dependency < 0) {
+                let import = (-dependency - 1) as usize;
+                if let Some(package_index) = converter.import_packages.get(&import) {
+                    let arc = (import as i32, from as u8);
+                    if !external_arcs[*package_index].contains(&arc) {
+                        external_arcs[*package_index].push(arc);
+                    }
+                }
+            }
+        }
+    }
+    for arcs in external_arcs {
+        header.write_i32::<LE>(arcs.len() as i32)?;
+        for (import, command) in arcs {
+            header.write_i32::<LE>(import)?;
+            header.write_u8(command)?;
+            header.write_i32::<LE>(0)?;
+        }
+    }
+
+    let header_len = header.len();
+    let mut summary = &mut header[..SUMMARY_SIZE];
+    summary.write_u32::<LE>(0)?;
+    summary.write_u32::<LE>(header_len as u32)?;
+    summary.write_u32::<LE>(name_index as u32)?;
+    summary.write_u32::<LE>(0)?;
+    summary.write_u32::<LE>(package.package_flags)?;
+    summary.write_u32::<LE>(package.total_header_size)?;
+    for offset in [
+        imported_public_export_hashes_offset,
+        import_map_offset,
+        export_map_offset,
+        export_bundle_entries_offset,
+        graph_data_offset,
+    ] {
+        summary.write_i32::<LE>(offset as i32)?;
+    }
+
+    let mut data = header;
+    for (export, command) in &entries {
+        if *command == EXPORT_COMMAND_SERIALIZE {
+            data.extend_from_slice(export_data[*export as usize]);
+        }
+    }
+
+    Ok(ZenPackage {
+        data,
+        store_entry: StoreEntry {
+            package_id: package_id(package_name),
+            export_count: package.exports.len() as i32,
+            export_bundle_count: 1,
+            imported_packages: converter.imported_packages,
+        },
+    })
+}
+
+/// Write the container header listing the packages of a container
+pub(crate) fn write_container_header(
+    container_id: u64,
+    store_entries: &[StoreEntry],
+    engine_version: EngineVersion,
+) -> Result<Vec<u8>, PakError> {
+    let version = engine_version.container_header_version();
+
+    let mut header = Vec::new();
+    header.write_u32::<LE>(CONTAINER_HEADER_SIGNATURE)?;
+    header.write_u32::<LE>(version)?;
+    header.write_u64::<LE>(container_id)?;
+    if version < 2 {
+        header.write_u32::<LE>(store_entries.len() as u32)?;
+    }
+    header.write_i32::<LE>(store_entries.len() as i32)?;
+    for entry in store_entries {
+        header.write_u64::<LE>(entry.package_id)?;
+    }
+
+    let mut entries = Vec::new();
+    let mut data_offset = store_entries.len() * 24;
+    for entry in store_entries {
+        entries.write_i32::<LE>(entry.export_count)?;
+        entries.write_i32::<LE>(entry.export_bundle_count)?;
+        let field_offset = entries.len();
+        entries.write_u32::<LE>(entry.imported_packages.len() as u32)?;
+        entries.write_u32::<LE>(match entry.imported_packages.is_empty() {
+            true => 0,
+            false => (data_offset - field_offset) as u32,
+        })?;
+        data_offset += entry.imported_packages.len() * 8;
+        // no shader map hashes
+        entries.write_u32::<LE>(0)?;
+        entries.write_u32::<LE>(0)?;
+    }
+    for entry in store_entries {
+        for package in &entry.imported_packages {
+            entries.write_u64::<LE>(*package)?;
+        }
+    }
+    header.write_i32::<LE>(entries.len() as i32)?;
+    header.write_all(&entries)?;
+
+    if version >= 2 {
+        // no optional segment packages
+        header.write_i32::<LE>(0)?;
+        header.write_i32::<LE>(0)?;
+    }
+    // no redirect names, localized packages and package redirects
+    header.write_u32::<LE>(0)?;
+    header.write_i32::<LE>(0)?;
+    header.write_i32::<LE>(0)?;
+
+    Ok(header)
+}
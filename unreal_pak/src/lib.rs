@@ -16,27 +16,55 @@ File parts:
 //!
 //! Utility crate for working with Unreal Engine .pak files.
 //! Supports both reading and writing and aims to support all pak versions.
-//! Encrytion is currently unsupported
+//! Encrypted pak files can be read by providing an [`AesKey`].
+//! IoStore containers (`.utoc`/`.ucas`) can be read with [`iostore::IoStoreReader`]
+//! and written with [`iostore::IoStoreWriter`].
+//! Signature files (`.sig`) of paks can be generated and verified with [`signature::PakSignature`].
 
 pub mod compression;
+pub mod diff;
+pub mod encryption;
 mod entry;
 pub mod error;
 mod header;
 mod index;
+pub mod iostore;
 pub mod pakmemory;
 pub mod pakreader;
 pub mod pakversion;
 pub mod pakwriter;
+pub mod repack;
+pub mod signature;
+pub mod vfs;
 
+pub use diff::PakDiff;
 pub use pakmemory::PakMemory;
 pub use pakreader::PakReader;
 pub use pakwriter::PakWriter;
+pub use repack::repack;
+pub use vfs::PakVfs;
 
 pub use compression::Compression;
+pub use encryption::AesKey;
 pub use error::PakError;
 
 pub(crate) const PAK_MAGIC: u32 = u32::from_be_bytes([0xE1, 0x12, 0x6F, 0x5A]);
 
+/// Largest buffer allocated up front for data with a size read from the file.
+/// Larger buffers grow while the data is read, so invalid sizes can not allocate more memory than the file contains.
+pub(crate) const MAX_PREALLOCATION: u64 = 0x1000000;
+
+/// Read `size` bytes without trusting `size` for allocating the buffer
+pub(crate) fn read_bytes<R: std::io::Read>(reader: &mut R, size: u64) -> std::io::Result<Vec<u8>> {
+    use std::io::Read;
+    let mut data = Vec::with_capacity(size.min(MAX_PREALLOCATION) as usize);
+    reader.take(size).read_to_end(&mut data)?;
+    if data.len() as u64 != size {
+        return Err(std::io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(data)
+}
+
 pub(crate) fn hash(data: &[u8]) -> [u8; 20] {
     use sha1::{Digest, Sha1};
     let mut hasher = Sha1::new();
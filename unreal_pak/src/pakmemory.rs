@@ -1,11 +1,16 @@
 //! PakMemory data structure for more flexible pak files
 
-use std::collections::BTreeMap;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet};
 use std::io::{Read, Seek, Write};
 
-use crate::compression::CompressionMethods;
-use crate::entry::{read_entry, write_entry};
+use crate::compression::{Compression, CompressionMethods};
+use crate::encryption::AesKey;
+use crate::entry::{
+    prepare_entry, read_entry, read_stored_entry, write_prepared_entry, PreparedEntry,
+};
 use crate::error::PakError;
+use crate::header::Header;
 use crate::index::{random_path_hash_seed, Footer, Index};
 use crate::pakversion::PakVersion;
 
@@ -17,11 +22,48 @@ pub struct PakMemory {
     pub pak_version: PakVersion,
     /// mount point (Unreal stuff)
     pub mount_point: String,
-    /// the compression method preferred for this file
-    compression: CompressionMethods,
-    /// the compression block size
+    /// the compression method used for entries which have been set,
+    /// loaded entries keep the compression method they were stored with
+    pub compression: Compression,
+    /// the compression block size used for entries which have been set
     pub block_size: u32,
-    entries: BTreeMap<String, Vec<u8>>,
+    /// encrypt the data of all entries when writing, requires an encryption key
+    pub encrypt_entries: bool,
+    /// encrypt the index when writing, requires an encryption key
+    pub encrypt_index: bool,
+    /// GUID of the used encryption key, games might only load paks using a specific key GUID
+    pub encryption_key_guid: [u8; 0x10],
+    entries: BTreeMap<String, MemoryEntry>,
+    deleted_entries: BTreeSet<String>,
+    /// compression methods table of the loaded pak, kept so entries use the same indices when written again
+    compression_methods: CompressionMethods,
+    path_hash_seed: Option<u64>,
+    key: Option<AesKey>,
+}
+
+/// Data of an entry in a PakMemory
+#[derive(Debug)]
+struct MemoryEntry {
+    data: Vec<u8>,
+    /// the entry exactly as it was stored in a loaded pak,
+    /// written again unchanged as long as neither the entry nor the version or encryption change
+    stored: Option<StoredEntry>,
+}
+
+/// An entry as it was stored in a loaded pak
+#[derive(Debug)]
+struct StoredEntry {
+    pak_version: PakVersion,
+    header: Header,
+    /// the stored data, `None` if it is the same as the data of the entry,
+    /// which is the case for uncompressed and unencrypted entries
+    data: Option<Vec<u8>>,
+}
+
+impl MemoryEntry {
+    fn new(data: Vec<u8>) -> Self {
+        MemoryEntry { data, stored: None }
+    }
 }
 
 impl PakMemory {
@@ -30,29 +72,100 @@ impl PakMemory {
         Self {
             pak_version,
             mount_point: "../../../".to_owned(),
-            compression: CompressionMethods::default(),
+            compression: Compression::None,
             block_size: 0x010000,
+            encrypt_entries: false,
+            encrypt_index: false,
+            encryption_key_guid: [0u8; 0x10],
             entries: BTreeMap::new(),
+            deleted_entries: BTreeSet::new(),
+            compression_methods: CompressionMethods::default(),
+            path_hash_seed: None,
+            key: None,
+        }
+    }
+
+    /// Set the AES key used to decrypt pak files when loading them and to encrypt them when writing.
+    pub fn set_encryption_key(&mut self, key: AesKey) {
+        // entries encrypted with the previous key have to be encrypted again
+        for entry in self.entries.values_mut() {
+            if matches!(&entry.stored, Some(stored) if stored.header.is_encrypted()) {
+                entry.stored = None;
+            }
         }
+        self.key = Some(key);
     }
 
-    /// Loads the data contained in the pak file in the reader into this PakMemory
+    /// Loads the data contained in the pak file in the reader into this PakMemory.
+    /// Entries which are not changed afterwards are written exactly as they were stored,
+    /// including their compression method and block size.
     pub fn load<R: Read + Seek>(&mut self, mut reader: &mut R) -> Result<(), PakError> {
-        let index = Index::read(reader)?;
+        let index = Index::read(reader, self.key.as_ref(), false, false)?;
 
         self.pak_version = index.footer.pak_version;
         self.mount_point = index.mount_point.clone();
-        self.compression = index.footer.compression_methods;
+        self.compression_methods = index.footer.compression_methods;
+        self.path_hash_seed = index.path_hash_seed;
+        // older versions don't have a compression methods table, so look at the entries instead
+        self.compression = std::iter::once(index.footer.compression_methods.0[0])
+            .chain(
+                index
+                    .entries
+                    .iter()
+                    .map(|(_, header)| header.compression_method),
+            )
+            .find(|method| !matches!(method, Compression::None))
+            .unwrap_or_default();
+        self.encrypt_index = index.footer.index_encrypted.unwrap_or_default();
+        self.encryption_key_guid = index.footer.encryption_key_guid.unwrap_or_default();
+        if let Some(block_size) = index
+            .entries
+            .iter()
+            .find(|(_, header)| !matches!(header.compression_method, Compression::None))
+            .and_then(|(_, header)| header.compression_block_size)
+        {
+            self.block_size = block_size;
+        }
+        self.encrypt_entries = index
+            .entries
+            .iter()
+            .any(|(_, header)| header.is_encrypted());
 
         for (name, header) in index.entries {
+            if header.is_deleted() {
+                self.entries.remove(&name);
+                self.deleted_entries.insert(name);
+                continue;
+            }
+
+            let data = read_entry(
+                &mut reader,
+                self.pak_version,
+                &index.footer.compression_methods,
+                header.offset,
+                self.key.as_ref(),
+                &name,
+                false,
+            )?;
+            let stored = read_stored_entry(
+                &mut reader,
+                self.pak_version,
+                &index.footer.compression_methods,
+                &header,
+            )?;
+            let stored = StoredEntry {
+                pak_version: self.pak_version,
+                header: stored.header,
+                // don't keep the same data twice
+                data: (*stored.data != data[..]).then(|| stored.data.into_owned()),
+            };
+            self.deleted_entries.remove(&name);
             self.entries.insert(
                 name,
-                read_entry(
-                    &mut reader,
-                    self.pak_version,
-                    &self.compression,
-                    header.offset,
-                )?,
+                MemoryEntry {
+                    data,
+                    stored: Some(stored),
+                },
             );
         }
 
@@ -78,29 +191,68 @@ impl PakMemory {
 
     /// Get the data of an entry.
     pub fn get_entry(&self, name: &String) -> Option<&Vec<u8>> {
-        self.entries.get(name)
+        self.entries.get(name).map(|entry| &entry.data)
     }
 
-    /// Set the data for an entry
+    /// Set the data for an entry, replacing a delete record with the same name
     pub fn set_entry(&mut self, name: String, data: Vec<u8>) {
-        self.entries.insert(name, data);
+        self.deleted_entries.remove(&name);
+        self.entries.insert(name, MemoryEntry::new(data));
+    }
+
+    /// Returns the names of all delete records stored in this PakMemory.
+    pub fn get_delete_records(&self) -> Vec<&String> {
+        self.deleted_entries.iter().collect()
+    }
+
+    /// Checks if the pak file contains a delete record with the given name
+    pub fn is_deleted(&self, name: &String) -> bool {
+        self.deleted_entries.contains(name)
+    }
+
+    /// Replace an entry with a delete record, which removes the entry with the same name from
+    /// paks with a lower priority. Requires at least [`PakVersion::DeleteRecords`] when writing.
+    pub fn set_deleted(&mut self, name: String) {
+        self.entries.remove(&name);
+        self.deleted_entries.insert(name);
+    }
+
+    /// Remove an entry or delete record from this PakMemory. Returns the data of the removed entry.
+    pub fn remove_entry(&mut self, name: &String) -> Option<Vec<u8>> {
+        self.deleted_entries.remove(name);
+        self.entries.remove(name).map(|entry| entry.data)
     }
 
     /// Write all the data as a finished pak file into the provided writer.
     pub fn write<W: Write + Seek>(&self, writer: &mut W) -> Result<(), PakError> {
         let mut written_entries = Vec::new();
 
-        for (name, data) in self.entries.iter() {
-            let header = write_entry(
-                writer,
-                self.pak_version,
-                data,
-                true,
-                &self.compression,
-                self.block_size,
-            )?;
-            written_entries.push((name.clone(), header));
+        let key = if self.encrypt_entries {
+            Some(
+                self.key
+                    .as_ref()
+                    .ok_or_else(PakError::encryption_key_missing)?,
+            )
+        } else {
+            None
+        };
+
+        let mut compression_methods = self.compression_methods;
+        compression_methods.add(self.compression, self.pak_version)?;
+
+        // entries are compressed up front, which can happen in parallel
+        let prepared_entries = self.prepare_entries(key, &mut compression_methods)?;
+        for (name, entry) in prepared_entries {
+            let header =
+                write_prepared_entry(writer, self.pak_version, &compression_methods, entry)?;
+            written_entries.push((name, header));
+        }
+
+        for name in &self.deleted_entries {
+            written_entries.push((name.clone(), Header::delete_record(self.pak_version)?));
         }
+        // keep the order of the index the same as for paks written by PakWriter
+        written_entries.sort_by(|(a, _), (b, _)| a.cmp(b));
 
         let footer = Footer {
             pak_version: self.pak_version,
@@ -108,19 +260,78 @@ impl PakMemory {
             index_offset: 0,
             index_size: 0,
             index_hash: [0u8; 20],
-            compression_methods: self.compression,
-            index_encrypted: Some(false),
-            encryption_key_guid: Some([0u8; 0x10]),
+            compression_methods,
+            index_encrypted: Some(self.encrypt_index),
+            encryption_key_guid: Some(self.encryption_key_guid),
         };
 
         let index = Index {
             mount_point: self.mount_point.clone(),
-            path_hash_seed: Some(random_path_hash_seed()),
+            path_hash_seed: Some(self.path_hash_seed.unwrap_or_else(random_path_hash_seed)),
             entries: written_entries,
+            lazy: None,
             footer,
         };
 
-        Index::write(writer, index)
+        Index::write(writer, index, self.key.as_ref())
+    }
+
+    /// Compress and encrypt all entries, in parallel if the `rayon` feature is enabled.
+    /// Entries which have been loaded and not changed are not compressed again.
+    fn prepare_entries(
+        &self,
+        key: Option<&AesKey>,
+        compression_methods: &mut CompressionMethods,
+    ) -> Result<Vec<(String, PreparedEntry<'_>)>, PakError> {
+        #[cfg(feature = "rayon")]
+        use rayon::prelude::*;
+
+        // the compression methods of loaded entries have to be in the table before any entry is written
+        let mut layouts = Vec::with_capacity(self.entries.len());
+        for (name, entry) in &self.entries {
+            let layout = match &entry.stored {
+                Some(stored)
+                    if compression_methods
+                        .can_store(stored.header.compression_method, self.pak_version) =>
+                {
+                    compression_methods.add(stored.header.compression_method, self.pak_version)?;
+                    (
+                        stored.header.compression_method,
+                        stored
+                            .header
+                            .compression_block_size
+                            .unwrap_or(self.block_size),
+                    )
+                }
+                _ => (self.compression, self.block_size),
+            };
+            layouts.push((name, entry, layout));
+        }
+
+        #[cfg(feature = "rayon")]
+        let layouts = layouts.into_par_iter();
+        #[cfg(not(feature = "rayon"))]
+        let layouts = layouts.into_iter();
+
+        layouts
+            .map(|(name, entry, (compression, block_size))| {
+                let entry = match &entry.stored {
+                    Some(stored)
+                        if stored.pak_version == self.pak_version
+                            && stored.header.is_encrypted() == key.is_some() =>
+                    {
+                        PreparedEntry {
+                            header: stored.header.clone(),
+                            data: Cow::Borrowed(stored.data.as_ref().unwrap_or(&entry.data)),
+                        }
+                    }
+                    _ => {
+                        prepare_entry(self.pak_version, &entry.data, compression, block_size, key)?
+                    }
+                };
+                Ok((name.clone(), entry))
+            })
+            .collect()
     }
 
     /// Iterate over the entries in the PakMemory
@@ -130,13 +341,13 @@ impl PakMemory {
 }
 
 /// An iterator over the entries of a PakMemory
-pub struct PakMemoryIter<'a>(std::collections::btree_map::Iter<'a, String, Vec<u8>>);
+pub struct PakMemoryIter<'a>(std::collections::btree_map::Iter<'a, String, MemoryEntry>);
 
 impl<'a> Iterator for PakMemoryIter<'a> {
     type Item = (&'a String, &'a Vec<u8>);
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.0.next()
+        self.0.next().map(|(name, entry)| (name, &entry.data))
     }
 }
 
@@ -1,13 +1,18 @@
 //! PakFile data structure for reading large pak files
 
-use std::collections::BTreeMap;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet};
 use std::io::{Read, Seek};
+use std::sync::OnceLock;
 
 use crate::compression::CompressionMethods;
+use crate::encryption::AesKey;
 use crate::entry::read_entry;
+pub use crate::entry::{EntryInfo, EntryReader};
 use crate::error::PakError;
 use crate::header::Header;
-use crate::index::Index;
+pub use crate::index::FooterInfo;
+use crate::index::{Index, LazyIndex};
 use crate::pakversion::PakVersion;
 
 /// An Unreal pak file reader with it's data kept on disk and only read on demand.
@@ -21,10 +26,37 @@ where
     /// mount point (Unreal stuff)
     pub mount_point: String,
     compression: CompressionMethods,
-    entries: BTreeMap<String, Header>,
+    footer: Option<FooterInfo>,
+    /// entries and delete records, only filled on first use when the index was loaded lazily
+    entries: OnceLock<Entries>,
+    lazy_index: Option<LazyIndex>,
+    key: Option<AesKey>,
+    verify_hashes: bool,
+    lazy: bool,
     reader: R,
 }
 
+/// Entries and names of delete records of a pak
+#[derive(Debug, Default)]
+struct Entries {
+    entries: BTreeMap<String, Header>,
+    deleted_entries: BTreeSet<String>,
+}
+
+impl Entries {
+    fn new(index_entries: Vec<(String, Header)>) -> Self {
+        let mut entries = Entries::default();
+        for (name, header) in index_entries {
+            if header.is_deleted() {
+                entries.deleted_entries.insert(name);
+            } else {
+                entries.entries.insert(name, header);
+            }
+        }
+        entries
+    }
+}
+
 impl<R> PakReader<R>
 where
     R: Read + Seek,
@@ -37,62 +69,202 @@ where
             pak_version: PakVersion::Invalid,
             mount_point: "".to_owned(),
             compression: Default::default(),
-            entries: BTreeMap::new(),
+            footer: None,
+            entries: OnceLock::new(),
+            lazy_index: None,
+            key: None,
+            verify_hashes: false,
+            lazy: false,
             reader,
         }
     }
 
+    /// Set the AES key used to decrypt the index and encrypted entries.
+    pub fn set_encryption_key(&mut self, key: AesKey) {
+        self.key = Some(key);
+    }
+
+    /// Enable checking the SHA1 hashes of the index when loading it and of entries when reading them.
+    /// A mismatch results in a [`PakErrorKind::IndexHashMismatch`] or [`PakErrorKind::EntryHashMismatch`] error.
+    ///
+    /// [`PakErrorKind::IndexHashMismatch`]: crate::error::PakErrorKind::IndexHashMismatch
+    /// [`PakErrorKind::EntryHashMismatch`]: crate::error::PakErrorKind::EntryHashMismatch
+    pub fn set_verify_hashes(&mut self, verify: bool) {
+        self.verify_hashes = verify;
+    }
+
+    /// Enable loading the index of v10+ paks lazily.
+    /// Entries are not decoded in [`PakReader::load_index`] but only when they are needed,
+    /// which is a lot faster when only a few entries of a large pak are read.
+    /// From [`PakVersion::Fnv64BugFix`] on entries are found by the hash of their name,
+    /// which is then compared with the name in the full directory index,
+    /// all entries are only decoded once they are listed or iterated.
+    ///
+    /// An invalid full directory index is not detected when loading lazily,
+    /// looking up or listing the entries returns the error instead.
+    pub fn set_lazy_index(&mut self, lazy: bool) {
+        self.lazy = lazy;
+    }
+
     /// Load the entry info contained in the footer into memory to start reading individual entries.
     pub fn load_index(&mut self) -> Result<(), PakError> {
-        let index = Index::read(&mut self.reader)?;
+        let index = Index::read(
+            &mut self.reader,
+            self.key.as_ref(),
+            self.verify_hashes,
+            self.lazy,
+        )?;
 
         self.pak_version = index.footer.pak_version;
         self.mount_point = index.mount_point.clone();
+        self.footer = Some(FooterInfo::new(&index.footer));
         self.compression = index.footer.compression_methods;
 
-        for (name, header) in index.entries {
-            self.entries.insert(name, header);
-        }
+        self.entries = match index.lazy {
+            Some(_) => OnceLock::new(),
+            None => OnceLock::from(Entries::new(index.entries)),
+        };
+        self.lazy_index = index.lazy;
 
         Ok(())
     }
 
+    /// Returns all entries, walking the full directory index if it has been loaded lazily
+    fn entries(&self) -> Result<&Entries, PakError> {
+        all_entries(&self.entries, &self.lazy_index)
+    }
+
+    /// Find an entry or delete record through the path hash index if the index has been loaded lazily
+    /// and the entries have not been walked yet, `None` if the full directory index has to be used instead
+    fn find_by_hash(&self, name: &str) -> Option<Result<Option<Header>, PakError>> {
+        match self.entries.get() {
+            Some(_) => None,
+            None => self.lazy_index.as_ref()?.find(name),
+        }
+    }
+
+    /// Find the header of an entry which is not a delete record
+    fn find_entry(&self, name: &str) -> Result<Option<Cow<'_, Header>>, PakError> {
+        match self.find_by_hash(name) {
+            Some(header) => Ok(header?
+                .filter(|header| !header.is_deleted())
+                .map(Cow::Owned)),
+            None => Ok(self.entries()?.entries.get(name).map(Cow::Borrowed)),
+        }
+    }
+
+    /// Returns the version of the pak file, [`PakVersion::Invalid`] until the index is loaded.
+    pub fn get_pak_version(&self) -> PakVersion {
+        self.pak_version
+    }
+
+    /// Returns the information stored in the footer, `None` until the index is loaded.
+    pub fn footer_info(&self) -> Option<&FooterInfo> {
+        self.footer.as_ref()
+    }
+
     /// Returns the names of all entries which have been found.
-    pub fn get_entry_names(&self) -> Vec<&String> {
-        self.entries.keys().collect()
+    pub fn get_entry_names(&self) -> Result<Vec<&String>, PakError> {
+        Ok(self.entries()?.entries.keys().collect())
     }
 
     /// Checks if the pak file contains an entry with the given name
-    pub fn contains_entry(&self, name: &String) -> bool {
-        self.entries.contains_key(name)
+    #[allow(clippy::ptr_arg)]
+    pub fn contains_entry(&self, name: &String) -> Result<bool, PakError> {
+        Ok(self.find_entry(name)?.is_some())
     }
 
-    /// Reads an entry from the pak on disk into memory and returns it's data.
-    pub fn read_entry(&mut self, name: &String) -> Result<Vec<u8>, PakError> {
-        let header = self
+    /// Returns the names of all delete records found.
+    /// Delete records remove entries of the same name from paks with a lower priority.
+    pub fn get_delete_records(&self) -> Result<Vec<&String>, PakError> {
+        Ok(self.entries()?.deleted_entries.iter().collect())
+    }
+
+    /// Checks if the pak file contains a delete record with the given name
+    #[allow(clippy::ptr_arg)]
+    pub fn is_deleted(&self, name: &String) -> Result<bool, PakError> {
+        match self.find_by_hash(name) {
+            Some(header) => Ok(header?.is_some_and(|header| header.is_deleted())),
+            None => Ok(self.entries()?.deleted_entries.contains(name)),
+        }
+    }
+
+    /// Returns the metadata of the entry with the given name.
+    pub fn entry_info(&self, name: &str) -> Result<Option<EntryInfo>, PakError> {
+        Ok(self.find_entry(name)?.map(|header| EntryInfo::new(&header)))
+    }
+
+    /// Iterate over the names and metadata of all entries without reading their data.
+    pub fn entry_infos(&self) -> Result<impl Iterator<Item = (&String, EntryInfo)>, PakError> {
+        Ok(self
+            .entries()?
             .entries
-            .get(name)
-            .ok_or_else(|| PakError::entry_not_found(name.clone()))?;
-        self.read_entry_at_offset(header.offset)
+            .iter()
+            .map(|(name, header)| (name, EntryInfo::new(header))))
     }
 
-    fn read_entry_at_offset(&mut self, offset: u64) -> Result<Vec<u8>, PakError> {
+    pub(crate) fn get_header(&self, name: &str) -> Result<Option<Cow<'_, Header>>, PakError> {
+        self.find_entry(name)
+    }
+
+    /// Returns the reader, the header of the entry with the given name and the key, for reading raw entry data
+    pub(crate) fn raw_entry(
+        &mut self,
+        name: &str,
+    ) -> Result<(&mut R, Header, Option<&AesKey>), PakError> {
+        let header = self
+            .find_entry(name)?
+            .ok_or_else(|| PakError::entry_not_found(name.to_owned()))?
+            .into_owned();
+        Ok((&mut self.reader, header, self.key.as_ref()))
+    }
+
+    /// Reads an entry from the pak on disk into memory and returns it's data.
+    #[allow(clippy::ptr_arg)]
+    pub fn read_entry(&mut self, name: &String) -> Result<Vec<u8>, PakError> {
+        let offset = self
+            .find_entry(name)?
+            .ok_or_else(|| PakError::entry_not_found(name.clone()))?
+            .offset;
         read_entry(
             &mut self.reader,
             self.pak_version,
             &self.compression,
             offset,
+            self.key.as_ref(),
+            name,
+            self.verify_hashes,
         )
     }
 
-    /// Iterate over the entries in the PakReader
-    pub fn iter(&mut self) -> PakReaderIter<R> {
-        PakReaderIter {
+    /// Opens an entry for streaming reads without loading all of its data into memory.
+    /// Compression blocks are decompressed lazily as they are read, which also allows cheap seeking.
+    #[allow(clippy::ptr_arg)]
+    pub fn open_entry(&mut self, name: &String) -> Result<EntryReader<'_, R>, PakError> {
+        let header = self
+            .find_entry(name)?
+            .ok_or_else(|| PakError::entry_not_found(name.clone()))?
+            .into_owned();
+        EntryReader::new(
+            &mut self.reader,
+            self.pak_version,
+            header,
+            self.key.as_ref(),
+        )
+    }
+
+    /// Iterate over the entries in the PakReader.
+    /// Fails if the full directory index of a lazily loaded index is invalid.
+    pub fn iter(&mut self) -> Result<PakReaderIter<'_, R>, PakError> {
+        let iter = all_entries(&self.entries, &self.lazy_index)?.entries.iter();
+        Ok(PakReaderIter {
             reader: &mut self.reader,
             pak_version: self.pak_version,
             compression: self.compression,
-            iter: self.entries.iter(),
-        }
+            key: self.key.as_ref(),
+            verify_hashes: self.verify_hashes,
+            iter,
+        })
     }
 
     /// Consumes the `PakReader`, returning the wrapped reader.
@@ -102,6 +274,22 @@ where
     }
 }
 
+/// Returns the entries, walking the full directory index of a lazily loaded index on first use
+fn all_entries<'a>(
+    entries: &'a OnceLock<Entries>,
+    lazy_index: &Option<LazyIndex>,
+) -> Result<&'a Entries, PakError> {
+    if let Some(entries) = entries.get() {
+        return Ok(entries);
+    }
+
+    let walked = match lazy_index {
+        Some(lazy_index) => Entries::new(lazy_index.entries()?),
+        None => Entries::default(),
+    };
+    Ok(entries.get_or_init(|| walked))
+}
+
 /// An iterator over the entries of a PakReader
 pub struct PakReaderIter<'a, R>
 where
@@ -110,6 +298,8 @@ where
     reader: &'a mut R,
     pak_version: PakVersion,
     compression: CompressionMethods,
+    key: Option<&'a AesKey>,
+    verify_hashes: bool,
     iter: std::collections::btree_map::Iter<'a, String, Header>,
 }
 
@@ -128,21 +318,11 @@ where
                     self.pak_version,
                     &self.compression,
                     header.offset,
+                    self.key,
+                    name,
+                    self.verify_hashes,
                 ),
             )
         })
     }
 }
-
-impl<'a, R> IntoIterator for &'a mut PakReader<R>
-where
-    R: Read + Seek,
-{
-    type Item = (&'a String, Result<Vec<u8>, PakError>);
-
-    type IntoIter = PakReaderIter<'a, R>;
-
-    fn into_iter(self) -> Self::IntoIter {
-        self.iter()
-    }
-}
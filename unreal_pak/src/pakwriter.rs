@@ -1,14 +1,19 @@
 //! PakFile data structure for writing large pak files
 
-use std::collections::BTreeMap;
-use std::io::{Seek, Write};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io::{self, Cursor, Read, Seek, SeekFrom, Write};
 
-use crate::compression::CompressionMethods;
-use crate::entry::write_entry;
+use crate::compression::{Compression, CompressionMethods};
+use crate::encryption::AesKey;
+use crate::entry::{prepare_raw_entry, write_entry, write_prepared_entry, PreparedEntry};
 use crate::error::PakError;
 use crate::header::Header;
 use crate::index::{random_path_hash_seed, Footer, Index};
+use crate::pakreader::PakReader;
 use crate::pakversion::PakVersion;
+use crate::read_bytes;
 
 /// An Unreal pak file writer which allows incrementally writing data.
 /// Good for working with very large files, but it has restrictions when it
@@ -24,14 +29,55 @@ where
     pub pak_version: PakVersion,
     /// Mount point. Typically `../../../`.
     pub mount_point: String,
-    /// Compression method preferred for this file
-    compression: CompressionMethods,
+    /// Compression method preferred for this file, used by [`PakWriter::write_entry`]
+    pub compression: Compression,
+    /// Table of all compression methods used in this file
+    compression_methods: CompressionMethods,
     /// Compression block size
     pub block_size: u32,
+    /// Encrypt the data of all entries written after this is set, requires an encryption key
+    pub encrypt_entries: bool,
+    /// Encrypt the index, requires an encryption key
+    pub encrypt_index: bool,
+    /// GUID of the used encryption key, games might only load paks using a specific key GUID
+    pub encryption_key_guid: [u8; 0x10],
     entries: BTreeMap<String, Header>,
+    /// entries of an existing pak opened with [`PakWriter::open_for_append`] which can still be replaced
+    replaceable: BTreeSet<String>,
+    path_hash_seed: Option<u64>,
+    key: Option<AesKey>,
+    /// cuts off the rest of a pak opened with [`PakWriter::open_for_append`] when finishing
+    set_len: Option<fn(&mut W, u64) -> io::Result<()>>,
     writer: W,
 }
 
+/// A writer which can be truncated, so a pak can become smaller when it is opened with
+/// [`PakWriter::open_for_append`]
+pub trait SetLen {
+    /// Truncates or extends the written data to `len` bytes
+    fn set_len(&mut self, len: u64) -> io::Result<()>;
+}
+
+impl SetLen for File {
+    fn set_len(&mut self, len: u64) -> io::Result<()> {
+        File::set_len(self, len)
+    }
+}
+
+impl SetLen for Cursor<Vec<u8>> {
+    fn set_len(&mut self, len: u64) -> io::Result<()> {
+        self.get_mut().resize(len as usize, 0);
+        Ok(())
+    }
+}
+
+impl SetLen for Cursor<&mut Vec<u8>> {
+    fn set_len(&mut self, len: u64) -> io::Result<()> {
+        self.get_mut().resize(len as usize, 0);
+        Ok(())
+    }
+}
+
 impl<W> PakWriter<W>
 where
     W: Write + Seek,
@@ -43,13 +89,26 @@ where
         Self {
             pak_version,
             mount_point: "../../../".to_owned(),
-            compression: CompressionMethods::zlib(),
+            compression: Compression::zlib(),
+            compression_methods: CompressionMethods::default(),
             block_size: 0x010000,
+            encrypt_entries: false,
+            encrypt_index: false,
+            encryption_key_guid: [0u8; 0x10],
             entries: BTreeMap::new(),
+            replaceable: BTreeSet::new(),
+            path_hash_seed: None,
+            key: None,
+            set_len: None,
             writer,
         }
     }
 
+    /// Set the AES key used to encrypt entries and the index.
+    pub fn set_encryption_key(&mut self, key: AesKey) {
+        self.key = Some(key);
+    }
+
     /// Returns the names of all entries which have been found.
     pub fn get_entry_names(&self) -> Vec<&String> {
         self.entries.keys().collect()
@@ -61,46 +120,287 @@ where
     pub fn write_entry(
         &mut self,
         name: &String,
-        data: &Vec<u8>,
+        data: &[u8],
         compress: bool,
     ) -> Result<(), PakError> {
-        if self.entries.contains_key(name) {
-            return Err(PakError::double_write(name.clone()));
-        }
+        let compression = if compress {
+            self.compression
+        } else {
+            Compression::None
+        };
+        self.write_entry_with_compression(name, data, compression)
+    }
+
+    /// Writes the given data into the pak file on disk using a specific compression method.
+    /// Different compression methods can be mixed in one file, but there can be at most 5 per file (4 for UE 4.22).
+    /// Entries under 32 bytes are never compressed.
+    pub fn write_entry_with_compression(
+        &mut self,
+        name: &String,
+        data: &[u8],
+        compression: Compression,
+    ) -> Result<(), PakError> {
+        self.check_double_write(name)?;
+
+        let key = if self.encrypt_entries {
+            Some(
+                self.key
+                    .as_ref()
+                    .ok_or_else(PakError::encryption_key_missing)?,
+            )
+        } else {
+            None
+        };
+
+        self.compression_methods
+            .add(compression, self.pak_version)?;
 
         let header = write_entry(
             &mut self.writer,
             self.pak_version,
             data,
-            compress,
-            &self.compression,
+            compression,
+            &self.compression_methods,
             self.block_size,
+            key,
         )?;
         self.entries.insert(name.clone(), header);
 
         Ok(())
     }
 
-    /// Finish writing the pak file by writing index and footer
-    pub fn finish_write(mut self) -> Result<(), PakError> {
+    /// Copies an entry from a `PakReader`, which can use another pak version or encryption key.
+    ///
+    /// The compressed blocks of the entry are copied without decompressing them when this pak can store the
+    /// compression method of the entry. Otherwise the entry is decompressed and compressed again using
+    /// [`PakWriter::compression`], or stored uncompressed if that can not be stored either.
+    /// Uncompressed entries stay uncompressed.
+    ///
+    /// Returns whether the compressed blocks were copied as they are.
+    pub fn copy_entry<R>(
+        &mut self,
+        reader: &mut PakReader<R>,
+        name: &String,
+    ) -> Result<bool, PakError>
+    where
+        R: Read + Seek,
+    {
+        let compression = reader
+            .entry_info(name)?
+            .ok_or_else(|| PakError::entry_not_found(name.clone()))?
+            .compression;
+
+        if matches!(compression, Compression::None)
+            || !self
+                .compression_methods
+                .can_store(compression, self.pak_version)
+        {
+            let data = reader.read_entry(name)?;
+            let compression = match compression {
+                Compression::None => Compression::None,
+                _ if self
+                    .compression_methods
+                    .can_store(self.compression, self.pak_version) =>
+                {
+                    self.compression
+                }
+                _ => Compression::None,
+            };
+            self.write_entry_with_compression(name, &data, compression)?;
+            return Ok(false);
+        }
+
+        self.check_double_write(name)?;
+
+        let key = if self.encrypt_entries {
+            Some(
+                self.key
+                    .as_ref()
+                    .ok_or_else(PakError::encryption_key_missing)?,
+            )
+        } else {
+            None
+        };
+
+        let (source, header, source_key) = reader.raw_entry(name)?;
+        let entry = prepare_raw_entry(source, &header, source_key, self.pak_version, key)?;
+
+        self.compression_methods
+            .add(compression, self.pak_version)?;
+        let header = write_prepared_entry(
+            &mut self.writer,
+            self.pak_version,
+            &self.compression_methods,
+            entry,
+        )?;
+        self.entries.insert(name.clone(), header);
+
+        Ok(true)
+    }
+
+    /// Writes a delete record, which removes the entry with the given name from paks with a lower priority.
+    /// Delete records have no data and are only stored in the index.
+    /// Requires at least [`PakVersion::DeleteRecords`].
+    pub fn write_delete_record(&mut self, name: &String) -> Result<(), PakError> {
+        self.check_double_write(name)?;
+
+        self.entries
+            .insert(name.clone(), Header::delete_record(self.pak_version)?);
+
+        Ok(())
+    }
+
+    /// Entries can only be written once, except for entries of a pak opened with [`PakWriter::open_for_append`]
+    fn check_double_write(&mut self, name: &String) -> Result<(), PakError> {
+        if self.entries.contains_key(name) && !self.replaceable.remove(name) {
+            return Err(PakError::double_write(name.clone()));
+        }
+        Ok(())
+    }
+
+    /// Finish writing the pak file by writing index and footer. Returns the wrapped writer,
+    /// which is positioned at the end of the pak.
+    ///
+    /// When appending to an existing pak the new pak can be smaller than the old one,
+    /// so the writer is truncated to the end of the new pak.
+    pub fn finish_write(mut self) -> Result<W, PakError> {
         let footer = Footer {
             pak_version: self.pak_version,
             // these are set in write_index
             index_offset: 0,
             index_size: 0,
             index_hash: [0u8; 20],
-            compression_methods: self.compression,
-            index_encrypted: Some(false),
-            encryption_key_guid: Some([0u8; 0x10]),
+            compression_methods: self.compression_methods,
+            index_encrypted: Some(self.encrypt_index),
+            encryption_key_guid: Some(self.encryption_key_guid),
         };
 
         let index = Index {
             mount_point: self.mount_point,
-            path_hash_seed: Some(random_path_hash_seed()),
+            path_hash_seed: Some(self.path_hash_seed.unwrap_or_else(random_path_hash_seed)),
             entries: self.entries.into_iter().collect::<Vec<_>>(),
+            lazy: None,
             footer,
         };
 
-        Index::write(&mut self.writer, index)
+        Index::write(&mut self.writer, index, self.key.as_ref())?;
+        self.writer.flush()?;
+
+        if let Some(set_len) = self.set_len {
+            let len = self.writer.stream_position()?;
+            set_len(&mut self.writer, len)?;
+        }
+
+        Ok(self.writer)
+    }
+}
+
+impl<W> PakWriter<W>
+where
+    W: Read + Write + Seek + SetLen,
+{
+    /// Opens an existing pak for adding and replacing entries without rewriting the data of the existing entries.
+    /// New entries are written after the last entry, over the old index, and [`PakWriter::finish_write`]
+    /// writes a new index and footer and truncates the writer after them.
+    ///
+    /// Replaced entries leave their old data behind as dead space, which can be reclaimed with [`PakWriter::compact`].
+    /// Every existing entry can be replaced once, writing an entry twice is still an error.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - Reader and writer of the existing pak, like a `File` opened for reading and writing
+    /// * `key` - Key used to decrypt the index, and to encrypt it again if it was encrypted
+    pub fn open_for_append(mut writer: W, key: Option<AesKey>) -> Result<Self, PakError> {
+        let index = Index::read(&mut writer, key.as_ref(), false, false)?;
+        writer.seek(SeekFrom::Start(index.footer.index_offset))?;
+
+        let mut entries = index.entries.into_iter().collect::<BTreeMap<_, _>>();
+        // the index is written with the block offsets as they are in the file
+        if index.footer.pak_version < PakVersion::RelativeChunkOffsets {
+            for header in entries.values_mut() {
+                for block in header.compression_blocks.iter_mut().flatten() {
+                    block.start += header.offset;
+                }
+            }
+        }
+
+        Ok(Self {
+            pak_version: index.footer.pak_version,
+            mount_point: index.mount_point,
+            compression: Compression::zlib(),
+            compression_methods: index.footer.compression_methods,
+            block_size: 0x010000,
+            encrypt_entries: false,
+            encrypt_index: index.footer.index_encrypted.unwrap_or_default(),
+            encryption_key_guid: index.footer.encryption_key_guid.unwrap_or_default(),
+            replaceable: entries.keys().cloned().collect(),
+            entries,
+            path_hash_seed: index.path_hash_seed,
+            key,
+            set_len: Some(<W as SetLen>::set_len),
+            writer,
+        })
+    }
+
+    /// Moves all entries to the start of the pak, closing the gaps left behind by replaced entries.
+    /// Entries written afterwards are placed after the last moved entry.
+    ///
+    /// # Warning
+    ///
+    /// Entries are moved in place, but the index of the pak still points at their old offsets
+    /// until [`PakWriter::finish_write`] writes the new index.
+    /// **If anything fails or the writer is dropped before that, the pak is left corrupted.**
+    /// Work on a copy of the pak if it must not be lost, or use [`repack`] to write a compacted pak into a new file.
+    ///
+    /// [`repack`]: crate::repack::repack
+    pub fn compact(&mut self) -> Result<(), PakError> {
+        let mut entries = self
+            .entries
+            .iter_mut()
+            .filter(|(_, header)| !header.is_deleted())
+            .map(|(_, header)| header)
+            .collect::<Vec<_>>();
+        entries.sort_by_key(|header| header.offset);
+
+        let mut position = 0;
+        for header in entries {
+            let mut relative = Header {
+                offset: 0,
+                ..header.clone()
+            };
+            if self.pak_version < PakVersion::RelativeChunkOffsets {
+                for block in relative.compression_blocks.iter_mut().flatten() {
+                    block.start -= header.offset;
+                }
+            }
+
+            let len = relative.entry_len(self.pak_version);
+            if header.offset != position {
+                self.writer.seek(SeekFrom::Start(header.offset))?;
+                let data = read_bytes(&mut self.writer, len)?;
+                self.writer.seek(SeekFrom::Start(position))?;
+
+                if self.pak_version < PakVersion::RelativeChunkOffsets {
+                    // the header in front of the data contains absolute block offsets, so it has to be written again
+                    let data_offset = relative.data_offset(self.pak_version) as usize;
+                    *header = write_prepared_entry(
+                        &mut self.writer,
+                        self.pak_version,
+                        &self.compression_methods,
+                        PreparedEntry {
+                            header: relative,
+                            data: Cow::Borrowed(&data[data_offset..]),
+                        },
+                    )?;
+                } else {
+                    self.writer.write_all(&data)?;
+                    header.offset = position;
+                }
+            }
+            position += len;
+        }
+
+        self.writer.seek(SeekFrom::Start(position))?;
+        Ok(())
     }
 }
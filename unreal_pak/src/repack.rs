@@ -0,0 +1,56 @@
+//! Converting pak files to another pak version
+
+use std::io::{Read, Seek, Write};
+
+use crate::error::PakError;
+use crate::pakreader::PakReader;
+use crate::pakwriter::PakWriter;
+
+/// Counts of how the entries were copied by [`repack`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RepackStats {
+    /// Entries whose compressed blocks were copied as they are
+    pub copied: usize,
+    /// Entries which were uncompressed or had to be compressed again
+    pub rewritten: usize,
+    /// Delete records which were copied
+    pub delete_records: usize,
+}
+
+/// Copy all entries and delete records of a pak into a `PakWriter`, which can use another pak version.
+///
+/// The mount point of the `reader` is used for the new pak, all other settings like the compression method and
+/// encryption are taken from the `writer`. Compressed blocks are kept as they are unless the new pak version can
+/// not store their compression method, see [`PakWriter::copy_entry`]. The index of the `reader` has to be loaded
+/// and [`PakWriter::finish_write`] has to be called afterwards.
+pub fn repack<R, W>(
+    reader: &mut PakReader<R>,
+    writer: &mut PakWriter<W>,
+) -> Result<RepackStats, PakError>
+where
+    R: Read + Seek,
+    W: Write + Seek,
+{
+    writer.mount_point = reader.mount_point.clone();
+
+    let mut stats = RepackStats::default();
+
+    let names = reader
+        .get_entry_names()?
+        .into_iter()
+        .cloned()
+        .collect::<Vec<_>>();
+    for name in &names {
+        match writer.copy_entry(reader, name)? {
+            true => stats.copied += 1,
+            false => stats.rewritten += 1,
+        }
+    }
+
+    for name in reader.get_delete_records()? {
+        writer.write_delete_record(name)?;
+        stats.delete_records += 1;
+    }
+
+    Ok(stats)
+}
@@ -0,0 +1,214 @@
+//! RSA signatures of pak files stored in `.sig` files
+//!
+//! A `.sig` file contains the CRC32 of every 64 KiB chunk of its pak file and an RSA signature
+//! of the SHA1 hash of those CRCs, made with the private signing key of the project.
+//! The engine checks the signature with the public key when mounting the pak and the chunk CRCs when reading it.
+
+use std::fmt;
+use std::io::{Read, Write};
+
+use byteorder::{ReadBytesExt, WriteBytesExt, LE};
+use rsa::{BigUint, Pkcs1v15Sign, RsaPrivateKey, RsaPublicKey};
+
+use crate::error::PakError;
+use crate::hash;
+
+/// Magic at the start of `.sig` files, older engine versions used a format without it
+const SIGNATURE_MAGIC: u32 = 0x73832DAA;
+/// Only version of the `.sig` format using the magic
+const SIGNATURE_VERSION: u32 = 1;
+/// Size of the chunks of the pak file which have their CRC32 stored
+pub const CHUNK_SIZE: usize = 64 * 1024;
+
+/// A public RSA key used to verify pak signatures
+#[derive(Debug, Clone)]
+pub struct VerifyingKey(RsaPublicKey);
+
+impl VerifyingKey {
+    /// Create a key from its modulus and public exponent.
+    /// Both are little endian like the base64 decoded values in the `Crypto.json` of a project.
+    pub fn new(modulus: &[u8], exponent: &[u8]) -> Result<Self, PakError> {
+        RsaPublicKey::new(
+            BigUint::from_bytes_le(modulus),
+            BigUint::from_bytes_le(exponent),
+        )
+        .map(Self)
+        .map_err(|_| PakError::signing_key_invalid())
+    }
+}
+
+/// A private RSA key used to sign paks
+#[derive(Clone)]
+pub struct SigningKey(RsaPrivateKey);
+
+impl SigningKey {
+    /// Create a key from its modulus, public exponent and private exponent.
+    /// All of them are little endian like the base64 decoded values in the `Crypto.json` of a project.
+    pub fn new(
+        modulus: &[u8],
+        public_exponent: &[u8],
+        private_exponent: &[u8],
+    ) -> Result<Self, PakError> {
+        RsaPrivateKey::from_components(
+            BigUint::from_bytes_le(modulus),
+            BigUint::from_bytes_le(public_exponent),
+            BigUint::from_bytes_le(private_exponent),
+            Vec::new(),
+        )
+        .map(Self)
+        .map_err(|_| PakError::signing_key_invalid())
+    }
+
+    /// Generate a new random key with a modulus of the given size in bits.
+    pub fn generate(bits: usize) -> Result<Self, PakError> {
+        RsaPrivateKey::new(&mut rand::thread_rng(), bits)
+            .map(Self)
+            .map_err(|_| PakError::signing_key_invalid())
+    }
+
+    /// Returns the public key for verifying signatures made with this key.
+    pub fn verifying_key(&self) -> VerifyingKey {
+        VerifyingKey(self.0.to_public_key())
+    }
+}
+
+impl fmt::Debug for SigningKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // never print the actual key
+        f.write_str("SigningKey(..)")
+    }
+}
+
+/// Contents of a `.sig` file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PakSignature {
+    /// RSA signature of the SHA1 hash of the chunk hashes
+    pub encrypted_hash: Vec<u8>,
+    /// CRC32 of every chunk of the pak file, see [`CHUNK_SIZE`]
+    pub chunk_hashes: Vec<u32>,
+}
+
+impl PakSignature {
+    /// Read a `.sig` file.
+    pub fn read<R: Read>(reader: &mut R) -> Result<Self, PakError> {
+        if reader.read_u32::<LE>()? != SIGNATURE_MAGIC
+            || reader.read_u32::<LE>()? != SIGNATURE_VERSION
+        {
+            return Err(PakError::signature_invalid());
+        }
+
+        let len = read_len(reader)?;
+        let mut encrypted_hash = Vec::new();
+        reader.take(len as u64).read_to_end(&mut encrypted_hash)?;
+        if encrypted_hash.len() != len {
+            return Err(PakError::signature_invalid());
+        }
+
+        // the length is not trusted for allocating, the reader runs out of data for invalid lengths
+        let len = read_len(reader)?;
+        let mut chunk_hashes = Vec::new();
+        for _ in 0..len {
+            chunk_hashes.push(reader.read_u32::<LE>()?);
+        }
+
+        Ok(Self {
+            encrypted_hash,
+            chunk_hashes,
+        })
+    }
+
+    /// Write a `.sig` file.
+    pub fn write<W: Write>(&self, writer: &mut W) -> Result<(), PakError> {
+        writer.write_u32::<LE>(SIGNATURE_MAGIC)?;
+        writer.write_u32::<LE>(SIGNATURE_VERSION)?;
+
+        writer.write_i32::<LE>(self.encrypted_hash.len() as i32)?;
+        writer.write_all(&self.encrypted_hash)?;
+
+        writer.write_i32::<LE>(self.chunk_hashes.len() as i32)?;
+        for chunk_hash in &self.chunk_hashes {
+            writer.write_u32::<LE>(*chunk_hash)?;
+        }
+
+        Ok(())
+    }
+
+    /// Create the signature of a pak by hashing all of its data, the reader has to be at the start of the pak.
+    pub fn generate<R: Read>(pak: &mut R, key: &SigningKey) -> Result<Self, PakError> {
+        let chunk_hashes = chunk_hashes(pak)?;
+        let encrypted_hash = key
+            .0
+            .sign(
+                Pkcs1v15Sign::new_unprefixed(),
+                &principal_hash(&chunk_hashes),
+            )
+            .map_err(|_| PakError::signing_key_invalid())?;
+
+        Ok(Self {
+            encrypted_hash,
+            chunk_hashes,
+        })
+    }
+
+    /// Check that the chunk hashes have been signed by the private key belonging to `key`.
+    pub fn verify_signature(&self, key: &VerifyingKey) -> Result<(), PakError> {
+        key.0
+            .verify(
+                Pkcs1v15Sign::new_unprefixed(),
+                &principal_hash(&self.chunk_hashes),
+                &self.encrypted_hash,
+            )
+            .map_err(|_| PakError::signature_mismatch())
+    }
+
+    /// Check the signature and that the chunk hashes match the data of the pak,
+    /// the reader has to be at the start of the pak.
+    ///
+    /// A [`PakErrorKind::ChunkHashMismatch`] error contains the index of the first chunk which does not match.
+    ///
+    /// [`PakErrorKind::ChunkHashMismatch`]: crate::error::PakErrorKind::ChunkHashMismatch
+    pub fn verify<R: Read>(&self, pak: &mut R, key: &VerifyingKey) -> Result<(), PakError> {
+        self.verify_signature(key)?;
+
+        let chunk_hashes = chunk_hashes(pak)?;
+        match chunk_hashes
+            .iter()
+            .zip(&self.chunk_hashes)
+            .position(|(actual, expected)| actual != expected)
+        {
+            Some(chunk) => Err(PakError::chunk_hash_mismatch(chunk)),
+            None if chunk_hashes.len() != self.chunk_hashes.len() => Err(
+                PakError::chunk_hash_mismatch(chunk_hashes.len().min(self.chunk_hashes.len())),
+            ),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Read the length of an array, which is stored as a signed integer
+fn read_len<R: Read>(reader: &mut R) -> Result<usize, PakError> {
+    usize::try_from(reader.read_i32::<LE>()?).map_err(|_| PakError::signature_invalid())
+}
+
+/// CRC32 of every chunk of the data
+fn chunk_hashes<R: Read>(reader: &mut R) -> Result<Vec<u32>, PakError> {
+    let mut chunk_hashes = Vec::new();
+    let mut chunk = Vec::with_capacity(CHUNK_SIZE);
+    loop {
+        chunk.clear();
+        reader.take(CHUNK_SIZE as u64).read_to_end(&mut chunk)?;
+        if chunk.is_empty() {
+            return Ok(chunk_hashes);
+        }
+        chunk_hashes.push(crc32fast::hash(&chunk));
+    }
+}
+
+/// SHA1 hash of the chunk hashes, which is what gets signed
+fn principal_hash(chunk_hashes: &[u32]) -> [u8; 20] {
+    let bytes = chunk_hashes
+        .iter()
+        .flat_map(|chunk_hash| chunk_hash.to_le_bytes())
+        .collect::<Vec<_>>();
+    hash(&bytes)
+}
@@ -0,0 +1,235 @@
+//! Layered virtual filesystem over multiple pak files
+
+use std::collections::BTreeMap;
+use std::io::{Read, Seek};
+use std::path::Path;
+
+use crate::error::PakError;
+use crate::pakreader::{EntryReader, PakReader};
+
+/// Priority added to paks with a `_P` suffix, the same as the engine does for patch paks
+const PATCH_PRIORITY: i32 = 100;
+
+/// A pak mounted into a [`PakVfs`]
+#[derive(Debug)]
+struct MountedPak<R>
+where
+    R: Read + Seek,
+{
+    name: String,
+    priority: i32,
+    /// mount point without leading `../` and `/`, either empty or ending with `/`
+    mount_point: String,
+    reader: PakReader<R>,
+}
+
+/// Which pak provides a path
+#[derive(Debug, Clone, Copy)]
+struct Resolved {
+    pak: usize,
+    deleted: bool,
+}
+
+/// An entry of a directory listing returned by [`PakVfs::read_dir`]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum VfsDirEntry {
+    /// A subdirectory, the name does not include a trailing `/`
+    Directory(String),
+    /// A file
+    File(String),
+}
+
+/// A virtual filesystem which layers multiple pak files like the engine does.
+///
+/// Paks are mounted with an order, paks with a higher order override files of paks with a lower order.
+/// Paks with a `_P` suffix (patch paks) get 100 added to their order and
+/// when orders are equal the pak mounted last wins.
+/// Delete records remove files of paks with a lower priority.
+///
+/// Paths are relative to the engine root, so mount points like `../../../` are removed.
+/// A file `Content/Asset.uasset` in a pak with the mount point `../../../Game/` is found at
+/// `Game/Content/Asset.uasset`.
+#[derive(Debug)]
+pub struct PakVfs<R>
+where
+    R: Read + Seek,
+{
+    paks: Vec<MountedPak<R>>,
+    files: BTreeMap<String, Resolved>,
+}
+
+impl<R> Default for PakVfs<R>
+where
+    R: Read + Seek,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<R> PakVfs<R>
+where
+    R: Read + Seek,
+{
+    /// Creates a new empty `PakVfs`.
+    pub fn new() -> Self {
+        PakVfs {
+            paks: Vec::new(),
+            files: BTreeMap::new(),
+        }
+    }
+
+    /// Mount a pak file. This loads the index of the reader, so an encryption key has to be set before.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - File name of the pak, used to detect patch paks and to report which pak provides a file
+    /// * `reader` - Reader for the pak file
+    /// * `order` - Priority of the pak, paks with a higher order override files from lower ones
+    pub fn mount(
+        &mut self,
+        name: &str,
+        mut reader: PakReader<R>,
+        order: i32,
+    ) -> Result<(), PakError> {
+        reader.load_index()?;
+
+        let is_patch = Path::new(name)
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .is_some_and(|stem| stem.ends_with("_P"));
+        let priority = order + if is_patch { PATCH_PRIORITY } else { 0 };
+
+        let mount_point = normalize_mount_point(&reader.mount_point);
+        let index = self.paks.len();
+
+        let entries = reader
+            .get_entry_names()?
+            .into_iter()
+            .map(|name| (name, false))
+            .chain(
+                reader
+                    .get_delete_records()?
+                    .into_iter()
+                    .map(|name| (name, true)),
+            );
+        for (entry_name, deleted) in entries {
+            let path = format!("{mount_point}{entry_name}");
+            let overrides = match self.files.get(&path) {
+                Some(resolved) => self.paks[resolved.pak].priority <= priority,
+                None => true,
+            };
+            if overrides {
+                self.files.insert(
+                    path,
+                    Resolved {
+                        pak: index,
+                        deleted,
+                    },
+                );
+            }
+        }
+
+        self.paks.push(MountedPak {
+            name: name.to_owned(),
+            priority,
+            mount_point,
+            reader,
+        });
+
+        Ok(())
+    }
+
+    /// Returns the names of all mounted paks in the order they were mounted.
+    pub fn get_pak_names(&self) -> Vec<&str> {
+        self.paks.iter().map(|pak| pak.name.as_str()).collect()
+    }
+
+    /// Checks if a file exists and was not removed by a delete record.
+    pub fn contains(&self, path: &str) -> bool {
+        self.resolve(path).is_some()
+    }
+
+    /// Returns the name of the pak which provides the file at the given path.
+    pub fn winning_pak(&self, path: &str) -> Option<&str> {
+        self.resolve(path).map(|pak| pak.name.as_str())
+    }
+
+    /// Iterate over all existing files and the names of the paks providing them.
+    pub fn files(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.files
+            .iter()
+            .filter(|(_, resolved)| !resolved.deleted)
+            .map(|(path, resolved)| (path.as_str(), self.paks[resolved.pak].name.as_str()))
+    }
+
+    /// Lists the files and subdirectories of a directory, merged over all mounted paks.
+    /// Use an empty path for the root directory.
+    pub fn read_dir(&self, path: &str) -> Vec<VfsDirEntry> {
+        let mut prefix = path.trim_matches('/').to_owned();
+        if !prefix.is_empty() {
+            prefix.push('/');
+        }
+
+        let mut entries = Vec::new();
+        for (file_path, _) in self
+            .files
+            .range(prefix.clone()..)
+            .take_while(|(file_path, _)| file_path.starts_with(&prefix))
+            .filter(|(_, resolved)| !resolved.deleted)
+        {
+            entries.push(match file_path[prefix.len()..].split_once('/') {
+                Some((directory, _)) => VfsDirEntry::Directory(directory.to_owned()),
+                None => VfsDirEntry::File(file_path[prefix.len()..].to_owned()),
+            });
+        }
+
+        entries.sort();
+        entries.dedup();
+        entries
+    }
+
+    /// Reads the data of a file from the pak which provides it.
+    pub fn read(&mut self, path: &str) -> Result<Vec<u8>, PakError> {
+        let (pak, entry_name) = self.resolve_mut(path)?;
+        pak.reader.read_entry(&entry_name)
+    }
+
+    /// Opens a file for streaming reads from the pak which provides it.
+    pub fn open(&mut self, path: &str) -> Result<EntryReader<'_, R>, PakError> {
+        let (pak, entry_name) = self.resolve_mut(path)?;
+        pak.reader.open_entry(&entry_name)
+    }
+
+    fn resolve(&self, path: &str) -> Option<&MountedPak<R>> {
+        match self.files.get(path) {
+            Some(resolved) if !resolved.deleted => Some(&self.paks[resolved.pak]),
+            _ => None,
+        }
+    }
+
+    fn resolve_mut(&mut self, path: &str) -> Result<(&mut MountedPak<R>, String), PakError> {
+        let resolved = match self.files.get(path) {
+            Some(resolved) if !resolved.deleted => *resolved,
+            _ => return Err(PakError::entry_not_found(path.to_owned())),
+        };
+
+        let pak = &mut self.paks[resolved.pak];
+        let entry_name = path[pak.mount_point.len()..].to_owned();
+        Ok((pak, entry_name))
+    }
+}
+
+/// Remove leading `../` and `/` from a mount point and make sure it ends with a `/`
+fn normalize_mount_point(mount_point: &str) -> String {
+    let mut mount_point = mount_point.replace('\\', "/");
+    while let Some(stripped) = mount_point.strip_prefix("../") {
+        mount_point = stripped.to_owned();
+    }
+
+    let mut mount_point = mount_point.trim_start_matches('/').to_owned();
+    if !mount_point.is_empty() && !mount_point.ends_with('/') {
+        mount_point.push('/');
+    }
+    mount_point
+}
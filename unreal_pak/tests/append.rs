@@ -0,0 +1,108 @@
+use std::io::{Cursor, Read};
+
+use unreal_pak::{
+    error::PakErrorKind, pakversion::PakVersion, AesKey, PakError, PakReader, PakWriter,
+};
+
+const VERSIONS: [PakVersion; 4] = [
+    PakVersion::IndexEncryption,
+    PakVersion::RelativeChunkOffsets,
+    PakVersion::FnameBasedCompressionMethod,
+    PakVersion::Fnv64BugFix,
+];
+
+const KEY: [u8; 32] = [0x42; 32];
+
+fn data(name: &str, len: usize) -> Vec<u8> {
+    name.as_bytes().iter().copied().cycle().take(len).collect()
+}
+
+fn finish(writer: PakWriter<Cursor<Vec<u8>>>) -> Result<Vec<u8>, PakError> {
+    Ok(writer.finish_write()?.into_inner())
+}
+
+fn check(pak: Vec<u8>, expected: &[(&str, Vec<u8>)]) -> Result<(), PakError> {
+    let mut reader = PakReader::new(Cursor::new(pak));
+    reader.set_encryption_key(AesKey::new(KEY));
+    reader.set_verify_hashes(true);
+    reader.load_index()?;
+
+    assert_eq!(reader.mount_point, "../../../Game/");
+    assert_eq!(
+        reader.get_entry_names()?,
+        expected.iter().map(|(name, _)| *name).collect::<Vec<_>>()
+    );
+    for (name, data) in expected {
+        assert_eq!(reader.read_entry(&name.to_string())?, *data);
+
+        let mut streamed = Vec::new();
+        reader
+            .open_entry(&name.to_string())?
+            .read_to_end(&mut streamed)?;
+        assert_eq!(streamed, *data);
+    }
+    Ok(())
+}
+
+#[test]
+fn append_and_compact() -> Result<(), PakError> {
+    for pak_version in VERSIONS {
+        for encrypted in [false, true] {
+            let mut writer = PakWriter::new(Cursor::new(Vec::new()), pak_version);
+            writer.mount_point = "../../../Game/".to_owned();
+            writer.block_size = 0x100;
+            writer.encrypt_entries = encrypted;
+            writer.encrypt_index = encrypted;
+            writer.set_encryption_key(AesKey::new(KEY));
+            writer.write_entry(&"a.txt".to_string(), &data("a", 0x250), true)?;
+            writer.write_entry(&"b.txt".to_string(), &data("b", 0x300), false)?;
+            writer.write_entry(&"c.txt".to_string(), &data("c", 0x80), false)?;
+            let pak = finish(writer)?;
+
+            let key = encrypted.then(|| AesKey::new(KEY));
+            let mut writer = PakWriter::open_for_append(Cursor::new(pak.clone()), key.clone())?;
+            assert_eq!(writer.pak_version, pak_version);
+            assert_eq!(writer.encrypt_index, encrypted);
+            writer.write_entry(&"b.txt".to_string(), &data("B", 0x280), true)?;
+            writer.write_entry(&"d.txt".to_string(), &data("d", 0x40), false)?;
+            let err = writer
+                .write_entry(&"b.txt".to_string(), b"again", false)
+                .unwrap_err();
+            assert!(matches!(err.kind, PakErrorKind::DoubleWrite(_)));
+            let appended = finish(writer)?;
+
+            // existing data is kept in place
+            assert!(appended.len() > pak.len());
+            let expected = [
+                ("a.txt", data("a", 0x250)),
+                ("b.txt", data("B", 0x280)),
+                ("c.txt", data("c", 0x80)),
+                ("d.txt", data("d", 0x40)),
+            ];
+            check(appended.clone(), &expected)?;
+
+            let mut writer =
+                PakWriter::open_for_append(Cursor::new(appended.clone()), key.clone())?;
+            writer.compact()?;
+            writer.write_entry(&"e.txt".to_string(), &data("e", 0x20), false)?;
+            let compacted = finish(writer)?;
+
+            let mut expected = expected.to_vec();
+            expected.push(("e.txt", data("e", 0x20)));
+            // the dead space of the replaced entry is gone
+            assert!(compacted.len() < appended.len());
+            check(compacted, &expected)?;
+
+            // compacting after replacing entries also removes the data they replaced
+            let mut writer = PakWriter::open_for_append(Cursor::new(pak.clone()), key.clone())?;
+            writer.write_entry(&"b.txt".to_string(), &data("B", 0x280), true)?;
+            writer.write_entry(&"d.txt".to_string(), &data("d", 0x40), false)?;
+            writer.compact()?;
+            let compacted = finish(writer)?;
+            assert!(compacted.len() < appended.len());
+            check(compacted, &expected[..4])?;
+        }
+    }
+
+    Ok(())
+}
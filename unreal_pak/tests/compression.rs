@@ -0,0 +1,101 @@
+use std::io::{self, Cursor};
+
+use unreal_pak::{
+    compression::{register_codec, CompressionCodec},
+    pakversion::PakVersion,
+    Compression, PakError, PakMemory, PakReader, PakWriter,
+};
+
+/// Simple codec which "compresses" data by inverting all bits
+struct InvertCodec;
+
+impl CompressionCodec for InvertCodec {
+    fn name(&self) -> &'static str {
+        "Invert"
+    }
+
+    fn compress(&self, data: &[u8]) -> io::Result<Vec<u8>> {
+        Ok(data.iter().map(|byte| !byte).collect())
+    }
+
+    fn decompress(&self, buf: &mut Vec<u8>, data: &[u8], _: usize) -> io::Result<()> {
+        buf.extend(data.iter().map(|byte| !byte));
+        Ok(())
+    }
+}
+
+fn test_data() -> Vec<u8> {
+    b"Some text that is long enough to be compressed in multiple blocks. ".repeat(3000)
+}
+
+#[test]
+fn builtin_codecs() -> Result<(), PakError> {
+    for compression in [
+        Compression::zlib(),
+        Compression::gzip(),
+        Compression::zstd(),
+        Compression::lz4(),
+    ] {
+        let mut pak = PakMemory::new(PakVersion::Fnv64BugFix);
+        pak.compression = compression;
+        pak.set_entry("data.txt".to_string(), test_data());
+
+        let mut cursor = Cursor::new(Vec::new());
+        pak.write(&mut cursor)?;
+        assert!(cursor.get_ref().len() < test_data().len() / 2);
+
+        cursor.set_position(0);
+        let loaded = PakMemory::load_from(&mut cursor)?;
+        assert_eq!(loaded.compression, compression);
+        assert_eq!(
+            loaded.get_entry(&"data.txt".to_string()),
+            Some(&test_data())
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn mixed_codecs() -> Result<(), PakError> {
+    register_codec(InvertCodec);
+    let invert = Compression::from_name("invert").expect("codec was registered");
+
+    let methods = [
+        Compression::None,
+        Compression::zlib(),
+        Compression::zstd(),
+        Compression::lz4(),
+        Compression::gzip(),
+        invert,
+    ];
+
+    for pak_version in [PakVersion::FrozenIndex, PakVersion::Fnv64BugFix] {
+        let mut cursor = Cursor::new(Vec::new());
+        let mut writer = PakWriter::new(&mut cursor, pak_version);
+        for (i, method) in methods.iter().enumerate() {
+            writer.write_entry_with_compression(&format!("{i}.txt"), &test_data(), *method)?;
+        }
+
+        // a sixth compression method does not fit into the table
+        writer.compression = Compression::Known("Oodle");
+        assert!(writer
+            .write_entry(&"oodle.txt".to_string(), &test_data(), true)
+            .is_err());
+
+        writer.finish_write()?;
+
+        let mut reader = PakReader::new(Cursor::new(cursor.into_inner()));
+        reader.load_index()?;
+        for i in 0..methods.len() {
+            assert_eq!(reader.read_entry(&format!("{i}.txt"))?, test_data());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn unknown_codec() {
+    assert!(Compression::from_name("DoesNotExist").is_none());
+}
@@ -0,0 +1,75 @@
+use std::io::Cursor;
+
+use unreal_pak::{
+    error::PakErrorKind, pakversion::PakVersion, PakError, PakMemory, PakReader, PakWriter,
+};
+
+const VERSIONS: [PakVersion; 4] = [
+    PakVersion::DeleteRecords,
+    PakVersion::FrozenIndex,
+    PakVersion::PathHashIndex,
+    PakVersion::Fnv64BugFix,
+];
+
+#[test]
+fn pak_writer_delete_records() -> Result<(), PakError> {
+    for pak_version in VERSIONS {
+        let mut cursor = Cursor::new(Vec::new());
+        let mut writer = PakWriter::new(&mut cursor, pak_version);
+        writer.write_entry(&"Game/kept.txt".to_string(), b"kept", false)?;
+        writer.write_delete_record(&"Game/Content/removed.uasset".to_string())?;
+        assert!(writer
+            .write_delete_record(&"Game/kept.txt".to_string())
+            .is_err());
+        writer.finish_write()?;
+
+        let mut reader = PakReader::new(Cursor::new(cursor.into_inner()));
+        reader.load_index()?;
+
+        assert_eq!(reader.get_entry_names()?, vec!["Game/kept.txt"]);
+        assert_eq!(
+            reader.get_delete_records()?,
+            vec!["Game/Content/removed.uasset"]
+        );
+        assert!(reader.is_deleted(&"Game/Content/removed.uasset".to_string())?);
+        assert!(!reader.is_deleted(&"Game/kept.txt".to_string())?);
+
+        let err = reader
+            .read_entry(&"Game/Content/removed.uasset".to_string())
+            .unwrap_err();
+        assert!(matches!(err.kind, PakErrorKind::EntryNotFound(_)));
+        assert_eq!(reader.read_entry(&"Game/kept.txt".to_string())?, b"kept");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn pak_memory_delete_records() -> Result<(), PakError> {
+    for pak_version in VERSIONS {
+        let mut pak = PakMemory::new(pak_version);
+        pak.set_entry("kept.txt".to_string(), b"kept".to_vec());
+        pak.set_entry("removed.txt".to_string(), b"removed".to_vec());
+        pak.set_deleted("removed.txt".to_string());
+        assert_eq!(pak.get_entry(&"removed.txt".to_string()), None);
+
+        let mut cursor = Cursor::new(Vec::new());
+        pak.write(&mut cursor)?;
+        cursor.set_position(0);
+
+        let loaded = PakMemory::load_from(&mut cursor)?;
+        assert_eq!(loaded.get_entry_names(), vec!["kept.txt"]);
+        assert_eq!(loaded.get_delete_records(), vec!["removed.txt"]);
+        assert!(loaded.is_deleted(&"removed.txt".to_string()));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn unsupported_version() {
+    let mut writer = PakWriter::new(Cursor::new(Vec::new()), PakVersion::RelativeChunkOffsets);
+    assert!(writer
+        .write_delete_record(&"removed.txt".to_string())
+        .is_err());
+}
@@ -0,0 +1,86 @@
+use std::io::Cursor;
+
+use unreal_pak::{pakversion::PakVersion, Compression, PakDiff, PakError, PakReader};
+
+mod shared;
+use shared::TestPak;
+
+const DATA: &[u8] = b"some data that compresses well. some data that compresses well.";
+
+fn pak(
+    pak_version: PakVersion,
+    mount_point: &str,
+    entries: &[(&str, &[u8], bool)],
+) -> Result<PakReader<Cursor<Vec<u8>>>, PakError> {
+    let mut pak = TestPak::new(pak_version).mount_point(mount_point);
+    for (name, data, compress) in entries {
+        pak = pak.entry(name, data, *compress);
+    }
+    pak.open()
+}
+
+#[test]
+fn diff_paks() -> Result<(), PakError> {
+    let changed_data = DATA.to_ascii_uppercase();
+    for new_version in [
+        PakVersion::FnameBasedCompressionMethod,
+        PakVersion::Fnv64BugFix,
+    ] {
+        let mut old = pak(
+            PakVersion::FnameBasedCompressionMethod,
+            "../../../",
+            &[
+                ("same.txt", DATA, true),
+                ("changed.txt", DATA, true),
+                ("recompressed.txt", DATA, true),
+                ("removed.txt", DATA, false),
+            ],
+        )?;
+        let mut new = pak(
+            new_version,
+            "../../../Game/",
+            &[
+                ("same.txt", DATA, true),
+                ("changed.txt", &changed_data, true),
+                ("recompressed.txt", DATA, false),
+                ("added.txt", DATA, false),
+            ],
+        )?;
+
+        let diff = PakDiff::compare(&mut old, &mut new)?;
+        assert!(!diff.is_empty());
+        assert_eq!(
+            diff.mount_point,
+            Some(("../../../".to_owned(), "../../../Game/".to_owned()))
+        );
+        assert_eq!(
+            diff.pak_version,
+            (new_version != PakVersion::FnameBasedCompressionMethod)
+                .then_some((PakVersion::FnameBasedCompressionMethod, new_version))
+        );
+        assert_eq!(diff.added, vec!["added.txt"]);
+        assert_eq!(diff.removed, vec!["removed.txt"]);
+
+        assert_eq!(diff.changed.len(), 2);
+        let changed = &diff.changed[0];
+        assert_eq!(changed.name, "changed.txt");
+        assert!(changed.content_changed);
+        assert_eq!(changed.compression, None);
+        assert_eq!(changed.decompressed_size, None);
+
+        let recompressed = &diff.changed[1];
+        assert_eq!(recompressed.name, "recompressed.txt");
+        assert!(!recompressed.content_changed);
+        assert_eq!(
+            recompressed.compression,
+            Some((Compression::zlib(), Compression::None))
+        );
+        assert!(recompressed.compressed_size.is_some());
+
+        let mut same = pak(new_version, "../../../", &[("same.txt", DATA, true)])?;
+        let mut same_copy = pak(new_version, "../../../", &[("same.txt", DATA, true)])?;
+        assert!(PakDiff::compare(&mut same, &mut same_copy)?.is_empty());
+    }
+
+    Ok(())
+}
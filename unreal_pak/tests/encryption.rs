@@ -0,0 +1,150 @@
+use std::fs::File;
+use std::io::Cursor;
+
+use unreal_pak::{error::PakErrorKind, pakversion::PakVersion, AesKey, PakError, PakReader};
+
+mod shared;
+use shared::{test_entries, TestPak};
+
+const KEY: &str = "0x0102030405060708090A0B0C0D0E0F101112131415161718191A1B1C1D1E1F20";
+
+/// Key of `000-TestPak-cus-C-Enc_P.pak`, which is `000-TestPak-cus-C_P.pak` with its entries and index encrypted
+const FIXTURE_KEY: &str = "0x2B2A8F5D19E6F3C1A07B4D96E8C25F31D0A9B7E4C6F2851D3E7A09B4C6D8E1F2";
+
+fn open_fixture(name: &str) -> Result<PakReader<File>, PakError> {
+    let path = format!("{}/testfiles/{name}", env!("CARGO_MANIFEST_DIR"));
+    Ok(PakReader::new(File::open(path)?))
+}
+
+fn test_pak(
+    pak_version: PakVersion,
+    encrypt_entries: bool,
+    encrypt_index: bool,
+    compress: bool,
+) -> Result<TestPak, PakError> {
+    Ok(TestPak::new(pak_version)
+        .key(AesKey::from_hex(KEY)?)
+        .encrypt_entries(encrypt_entries)
+        .encrypt_index(encrypt_index)
+        .encryption_key_guid([0xAB; 0x10])
+        .entries(test_entries(), compress))
+}
+
+fn check_pak(pak: TestPak) -> Result<(), PakError> {
+    let mut reader = pak.open()?;
+    for (name, data) in test_entries() {
+        assert_eq!(reader.read_entry(&name)?, data);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn encrypted_roundtrip() -> Result<(), PakError> {
+    for pak_version in [
+        PakVersion::FnameBasedCompressionMethod,
+        PakVersion::Fnv64BugFix,
+    ] {
+        for encrypt_entries in [false, true] {
+            for encrypt_index in [false, true] {
+                for compress in [false, true] {
+                    check_pak(test_pak(
+                        pak_version,
+                        encrypt_entries,
+                        encrypt_index,
+                        compress,
+                    )?)?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn encrypted_data_is_not_plain() -> Result<(), PakError> {
+    let data = test_pak(PakVersion::Fnv64BugFix, true, true, false)?.write()?;
+    let needle = b"Some text that is long enough";
+    assert!(!data.windows(needle.len()).any(|window| window == needle));
+    assert!(!data.windows(12).any(|window| window == b"Game/Content"));
+
+    Ok(())
+}
+
+#[test]
+fn missing_key() -> Result<(), PakError> {
+    let mut reader = PakReader::new(Cursor::new(
+        test_pak(PakVersion::Fnv64BugFix, false, true, true)?.write()?,
+    ));
+    let err = reader.load_index().unwrap_err();
+    assert!(matches!(err.kind, PakErrorKind::EncryptionKeyMissing));
+
+    let mut reader = PakReader::new(Cursor::new(
+        test_pak(PakVersion::Fnv64BugFix, true, false, true)?.write()?,
+    ));
+    reader.load_index()?;
+    let err = reader
+        .read_entry(&"Game/Content/text.txt".to_string())
+        .unwrap_err();
+    assert!(matches!(err.kind, PakErrorKind::EncryptionKeyMissing));
+
+    Ok(())
+}
+
+#[test]
+fn encrypted_fixture() -> Result<(), PakError> {
+    let mut plain = open_fixture("000-TestPak-cus-C_P.pak")?;
+    plain.load_index()?;
+
+    let mut encrypted = open_fixture("000-TestPak-cus-C-Enc_P.pak")?;
+    encrypted.set_encryption_key(AesKey::from_hex(FIXTURE_KEY)?);
+    encrypted.set_verify_hashes(true);
+    encrypted.load_index()?;
+
+    assert_eq!(
+        encrypted.get_pak_version(),
+        PakVersion::FnameBasedCompressionMethod
+    );
+    assert_eq!(encrypted.mount_point, plain.mount_point);
+    assert_eq!(encrypted.get_entry_names()?, plain.get_entry_names()?);
+
+    let names = plain
+        .get_entry_names()?
+        .into_iter()
+        .cloned()
+        .collect::<Vec<_>>();
+    for name in &names {
+        let info = encrypted.entry_info(name)?.expect("entry exists");
+        assert!(info.encrypted);
+        assert_eq!(encrypted.read_entry(name)?, plain.read_entry(name)?);
+    }
+    assert_eq!(
+        encrypted.read_entry(&"Astro/smoll.txt".to_string())?,
+        b"smoll\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn encrypted_fixture_wrong_key() -> Result<(), PakError> {
+    let mut reader = open_fixture("000-TestPak-cus-C-Enc_P.pak")?;
+    let err = reader.load_index().unwrap_err();
+    assert!(matches!(err.kind, PakErrorKind::EncryptionKeyMissing));
+
+    let mut reader = open_fixture("000-TestPak-cus-C-Enc_P.pak")?;
+    reader.set_encryption_key(AesKey::from_hex(KEY)?);
+    reader.set_verify_hashes(true);
+    let err = reader.load_index().unwrap_err();
+    assert!(matches!(err.kind, PakErrorKind::IndexHashMismatch));
+
+    Ok(())
+}
+
+#[test]
+fn invalid_key() {
+    assert!(AesKey::from_hex("0x1234").is_err());
+    assert!(AesKey::from_hex(&"zz".repeat(32)).is_err());
+    assert!(AesKey::from_hex(&KEY[2..]).is_ok());
+}
@@ -0,0 +1,79 @@
+use std::io::Cursor;
+
+use unreal_pak::{pakversion::PakVersion, AesKey, Compression, PakError, PakReader};
+
+mod shared;
+use shared::{TestPak, KEY};
+
+const DATA: &[u8] =
+    b"entry info data, entry info data, entry info data, entry info data, entry info data";
+
+fn pak(pak_version: PakVersion, encrypted: bool) -> Result<PakReader<Cursor<Vec<u8>>>, PakError> {
+    let mut pak = TestPak::new(pak_version)
+        .block_size(0x20)
+        .key(AesKey::new(KEY))
+        .encrypt_entries(encrypted);
+    if pak_version >= PakVersion::CompressionEncryption {
+        pak = pak.entry("Game/compressed.txt", DATA, true);
+    }
+    pak.entry("Game/stored.txt", DATA, false).open()
+}
+
+#[test]
+fn entry_info() -> Result<(), PakError> {
+    for pak_version in [
+        PakVersion::RelativeChunkOffsets,
+        PakVersion::FnameBasedCompressionMethod,
+        PakVersion::Fnv64BugFix,
+    ] {
+        for encrypted in [false, true] {
+            let reader = pak(pak_version, encrypted)?;
+
+            let compressed = reader
+                .entry_info("Game/compressed.txt")?
+                .expect("entry exists");
+            assert_eq!(compressed.offset, 0);
+            assert_eq!(compressed.compression, Compression::zlib());
+            assert_eq!(compressed.decompressed_size, DATA.len() as u64);
+            assert_eq!(compressed.compression_block_size, Some(0x20));
+            assert_eq!(compressed.block_count(), 3);
+            // the compressed size includes the padding between encrypted blocks
+            let block_sizes = compressed.block_sizes.iter().sum::<u64>();
+            match encrypted {
+                true => assert!(block_sizes < compressed.compressed_size),
+                false => assert_eq!(block_sizes, compressed.compressed_size),
+            }
+            assert_eq!(compressed.encrypted, encrypted);
+            assert_eq!(compressed.timestamp, None);
+
+            let stored = reader.entry_info("Game/stored.txt")?.expect("entry exists");
+            assert!(stored.offset > compressed.offset);
+            assert_eq!(stored.compression, Compression::None);
+            assert_eq!(stored.compressed_size, DATA.len() as u64);
+            assert_eq!(stored.block_count(), 0);
+            // encoded entries do not have a hash in the index
+            assert_eq!(
+                stored.hash.is_some(),
+                pak_version < PakVersion::PathHashIndex
+            );
+
+            assert!(reader.entry_info("Game/missing.txt")?.is_none());
+
+            let infos = reader.entry_infos()?.collect::<Vec<_>>();
+            assert_eq!(infos.len(), 2);
+            assert_eq!(infos[0].0, "Game/compressed.txt");
+            assert_eq!(infos[0].1, compressed);
+            assert_eq!(infos[1].1, stored);
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn timestamp() -> Result<(), PakError> {
+    let reader = pak(PakVersion::Initial, false)?;
+    let info = reader.entry_info("Game/stored.txt")?.expect("entry exists");
+    assert_eq!(info.timestamp, Some(0));
+    Ok(())
+}
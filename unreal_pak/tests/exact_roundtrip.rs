@@ -0,0 +1,124 @@
+use std::io::Cursor;
+
+use unreal_pak::{pakversion::PakVersion, AesKey, Compression, PakError, PakMemory, PakReader};
+
+mod shared;
+use shared::{text, TestPak};
+
+const KEY: &str = "0x0102030405060708090A0B0C0D0E0F101112131415161718191A1B1C1D1E1F20";
+
+const VERSIONS: [PakVersion; 12] = [
+    PakVersion::Initial,
+    PakVersion::NoTimestamps,
+    PakVersion::CompressionEncryption,
+    PakVersion::IndexEncryption,
+    PakVersion::RelativeChunkOffsets,
+    PakVersion::DeleteRecords,
+    PakVersion::EncryptionKeyGuid,
+    PakVersion::FnameBasedCompressionMethodInitial,
+    PakVersion::FnameBasedCompressionMethod,
+    PakVersion::FrozenIndex,
+    PakVersion::PathHashIndex,
+    PakVersion::Fnv64BugFix,
+];
+
+fn write_pak(pak_version: PakVersion, encrypt: bool) -> Result<Vec<u8>, PakError> {
+    let compress = pak_version >= PakVersion::CompressionEncryption;
+    let mut pak = TestPak::new(pak_version)
+        .key(AesKey::from_hex(KEY)?)
+        .mount_point("../../../Game/")
+        .block_size(0x1000)
+        .encrypt_entries(encrypt)
+        .encrypt_index(encrypt)
+        .encryption_key_guid([0x12; 0x10])
+        .entry("Content/a.txt", &text(200), false)
+        .entry("Content/b.txt", &text(200), compress);
+    if pak_version >= PakVersion::FnameBasedCompressionMethodInitial {
+        pak = pak.entry_with_compression("Content/c.txt", &text(200), Compression::zstd());
+    }
+    if pak_version >= PakVersion::DeleteRecords {
+        pak = pak.delete_record("Content/d.txt");
+    }
+    pak.entry("Content/e.bin", b"tiny", compress).write()
+}
+
+fn load(data: &[u8]) -> Result<PakMemory, PakError> {
+    let mut pak = PakMemory::new(PakVersion::Invalid);
+    pak.set_encryption_key(AesKey::from_hex(KEY)?);
+    pak.load(&mut Cursor::new(data))?;
+    Ok(pak)
+}
+
+fn write(pak: &PakMemory) -> Result<Vec<u8>, PakError> {
+    let mut cursor = Cursor::new(Vec::new());
+    pak.write(&mut cursor)?;
+    Ok(cursor.into_inner())
+}
+
+#[test]
+fn unchanged_pak_is_identical() -> Result<(), PakError> {
+    for pak_version in VERSIONS {
+        for encrypt in [false, true] {
+            if encrypt && pak_version < PakVersion::IndexEncryption {
+                continue;
+            }
+
+            let original = write_pak(pak_version, encrypt)?;
+            let pak = load(&original)?;
+            assert_eq!(pak.pak_version, pak_version);
+            assert_eq!(pak.mount_point, "../../../Game/");
+            if pak_version >= PakVersion::CompressionEncryption {
+                assert_eq!(pak.block_size, 0x1000);
+            }
+
+            assert!(
+                write(&pak)? == original,
+                "{pak_version:?} encrypted: {encrypt} changed"
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn changed_entries_keep_layout() -> Result<(), PakError> {
+    for pak_version in [
+        PakVersion::FnameBasedCompressionMethodInitial,
+        PakVersion::Fnv64BugFix,
+    ] {
+        let mut pak = load(&write_pak(pak_version, false)?)?;
+        pak.set_entry("Content/a.txt".to_string(), b"changed".to_vec());
+        pak.set_entry("Content/f.txt".to_string(), text(200));
+
+        let mut reader = PakReader::new(Cursor::new(write(&pak)?));
+        reader.load_index()?;
+        assert_eq!(reader.get_pak_version(), pak_version);
+        assert_eq!(
+            reader
+                .footer_info()
+                .map(|footer| footer.compression_methods.clone()),
+            Some(vec![Compression::zlib(), Compression::zstd()])
+        );
+
+        let compression = |name: &str| -> Result<_, PakError> {
+            Ok(reader.entry_info(name)?.map(|info| info.compression))
+        };
+        assert_eq!(compression("Content/b.txt")?, Some(Compression::zlib()));
+        assert_eq!(compression("Content/c.txt")?, Some(Compression::zstd()));
+        assert_eq!(compression("Content/f.txt")?, Some(Compression::zlib()));
+
+        assert_eq!(reader.read_entry(&"Content/a.txt".to_string())?, b"changed");
+        assert_eq!(reader.read_entry(&"Content/c.txt".to_string())?, text(200));
+        assert!(reader.is_deleted(&"Content/d.txt".to_string())?);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn invalid_version_is_not_written() {
+    assert!(PakVersion::Invalid.to_num().is_err());
+    assert_eq!(PakVersion::Fnv64BugFix.to_num().ok(), Some(11));
+    assert!(write(&PakMemory::new(PakVersion::Invalid)).is_err());
+}
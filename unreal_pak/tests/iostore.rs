@@ -0,0 +1,389 @@
+use std::io::{Cursor, Write};
+
+use aes::cipher::{BlockEncrypt, KeyInit};
+use byteorder::{ReadBytesExt, WriteBytesExt, BE, LE};
+use flate2::write::ZlibEncoder;
+
+use unreal_pak::{
+    error::PakErrorKind,
+    iostore::{
+        EngineVersion, IoChunkId, IoChunkType, IoStoreReader, IoStoreTocVersion, IoStoreWriter,
+    },
+    AesKey, Compression, PakError,
+};
+
+const KEY: [u8; 32] = [0x24; 32];
+const BLOCK_SIZE: u64 = 64;
+
+fn chunk_a() -> Vec<u8> {
+    b"uncompressed chunk data".repeat(2)
+}
+
+fn chunk_b() -> Vec<u8> {
+    (0..150u8).collect()
+}
+
+fn fstring(writer: &mut Vec<u8>, string: &str) {
+    writer.write_i32::<LE>(string.len() as i32 + 1).unwrap();
+    writer.write_all(string.as_bytes()).unwrap();
+    writer.write_u8(0).unwrap();
+}
+
+fn encrypt(data: &mut Vec<u8>) {
+    data.resize(data.len().div_ceil(16) * 16, 0);
+    let cipher = aes::Aes256::new(&KEY.into());
+    for block in data.chunks_mut(16) {
+        cipher.encrypt_block(block.into());
+    }
+}
+
+/// Build a container with an uncompressed chunk A and a zlib compressed chunk B spanning 3 blocks
+fn build_container(encrypted: bool) -> (Vec<u8>, Vec<u8>) {
+    let mut cas = Vec::new();
+    // (offset, compressed size, uncompressed size, method)
+    let mut blocks = Vec::new();
+    let mut offsets = Vec::new();
+
+    let mut uncompressed_offset = 0;
+    for (data, compress) in [(chunk_a(), false), (chunk_b(), true)] {
+        offsets.push((uncompressed_offset, data.len() as u64));
+        for block in data.chunks(BLOCK_SIZE as usize) {
+            let mut stored = if compress {
+                let mut encoder = ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(block).unwrap();
+                encoder.finish().unwrap()
+            } else {
+                block.to_vec()
+            };
+            blocks.push((
+                cas.len() as u64,
+                stored.len() as u64,
+                block.len() as u64,
+                u8::from(compress),
+            ));
+            if encrypted {
+                encrypt(&mut stored);
+            }
+            cas.extend_from_slice(&stored);
+            uncompressed_offset += BLOCK_SIZE;
+        }
+    }
+
+    let mut directory_index = Vec::new();
+    fstring(&mut directory_index, "../../../");
+    // root and Game directory: name, first child, next sibling, first file
+    directory_index.write_u32::<LE>(2).unwrap();
+    for value in [u32::MAX, 1, u32::MAX, u32::MAX, 0, u32::MAX, u32::MAX, 0] {
+        directory_index.write_u32::<LE>(value).unwrap();
+    }
+    // files: name, next file, toc entry index
+    directory_index.write_u32::<LE>(2).unwrap();
+    for value in [1, 1, 0, 2, u32::MAX, 1] {
+        directory_index.write_u32::<LE>(value).unwrap();
+    }
+    directory_index.write_u32::<LE>(3).unwrap();
+    for string in ["Game", "A.uasset", "B.ubulk"] {
+        fstring(&mut directory_index, string);
+    }
+    if encrypted {
+        encrypt(&mut directory_index);
+    }
+
+    let mut toc = Vec::new();
+    toc.write_all(b"-==--==--==--==-").unwrap();
+    toc.write_u8(IoStoreTocVersion::PerfectHashWithOverflow as u8)
+        .unwrap();
+    toc.write_all(&[0; 3]).unwrap();
+    toc.write_u32::<LE>(144).unwrap();
+    toc.write_u32::<LE>(2).unwrap();
+    toc.write_u32::<LE>(blocks.len() as u32).unwrap();
+    toc.write_u32::<LE>(12).unwrap();
+    toc.write_u32::<LE>(1).unwrap();
+    toc.write_u32::<LE>(32).unwrap();
+    toc.write_u32::<LE>(BLOCK_SIZE as u32).unwrap();
+    toc.write_u32::<LE>(directory_index.len() as u32).unwrap();
+    toc.write_u32::<LE>(1).unwrap();
+    toc.write_u64::<LE>(0x1234).unwrap();
+    toc.write_all(&[0; 16]).unwrap();
+    // compressed, indexed and maybe encrypted
+    toc.write_u8(if encrypted { 0x0b } else { 0x09 }).unwrap();
+    toc.write_all(&[0; 3]).unwrap();
+    toc.write_u32::<LE>(0).unwrap();
+    toc.write_u64::<LE>(u64::MAX).unwrap();
+    toc.write_u32::<LE>(0).unwrap();
+    toc.resize(144, 0);
+
+    toc.write_all(&IoChunkId::new(1, 0, 2).0).unwrap();
+    toc.write_all(&IoChunkId::new(2, 0, 3).0).unwrap();
+    for (offset, length) in offsets {
+        toc.write_uint::<BE>(offset, 5).unwrap();
+        toc.write_uint::<BE>(length, 5).unwrap();
+    }
+    for (offset, compressed_size, uncompressed_size, method) in blocks {
+        toc.write_uint::<LE>(offset, 5).unwrap();
+        toc.write_uint::<LE>(compressed_size, 3).unwrap();
+        toc.write_uint::<LE>(uncompressed_size, 3).unwrap();
+        toc.write_u8(method).unwrap();
+    }
+    let mut name = b"Zlib".to_vec();
+    name.resize(32, 0);
+    toc.write_all(&name).unwrap();
+    toc.write_all(&directory_index).unwrap();
+    // chunk metas are not read
+    toc.write_all(&[0; 66]).unwrap();
+
+    (toc, cas)
+}
+
+#[test]
+fn read_container() -> Result<(), PakError> {
+    for encrypted in [false, true] {
+        let (toc, cas) = build_container(encrypted);
+
+        let mut reader = IoStoreReader::new(Cursor::new(cas));
+        reader.set_encryption_key(AesKey::new(KEY));
+        reader.load_toc(Cursor::new(toc))?;
+
+        assert_eq!(
+            reader.version(),
+            Some(IoStoreTocVersion::PerfectHashWithOverflow)
+        );
+        assert_eq!(reader.container_id(), Some(0x1234));
+        assert_eq!(reader.mount_point(), Some("../../../"));
+        assert_eq!(
+            reader.get_file_names(),
+            vec!["Game/A.uasset", "Game/B.ubulk"]
+        );
+
+        let id_b = IoChunkId::new(2, 0, 3);
+        assert_eq!(id_b.id(), 2);
+        assert_eq!(id_b.chunk_type(), 3);
+        assert_eq!(
+            reader.get_file_chunk_id(&"Game/B.ubulk".to_string()),
+            Some(id_b)
+        );
+
+        assert_eq!(reader.read_chunk(&IoChunkId::new(1, 0, 2))?, chunk_a());
+        assert_eq!(reader.read_chunk(&id_b)?, chunk_b());
+        assert_eq!(reader.read_file(&"Game/A.uasset".to_string())?, chunk_a());
+
+        let err = reader.read_chunk(&IoChunkId::new(3, 0, 2)).unwrap_err();
+        assert!(matches!(err.kind, PakErrorKind::EntryNotFound(_)));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn missing_key() {
+    let (toc, cas) = build_container(true);
+    let mut reader = IoStoreReader::new(Cursor::new(cas));
+    let err = reader.load_toc(Cursor::new(toc)).unwrap_err();
+    assert!(matches!(err.kind, PakErrorKind::EncryptionKeyMissing));
+}
+
+#[test]
+fn invalid_magic() {
+    let mut reader = IoStoreReader::new(Cursor::new(Vec::new()));
+    let err = reader.load_toc(Cursor::new(vec![0u8; 200])).unwrap_err();
+    assert!(matches!(err.kind, PakErrorKind::PakInvalid));
+}
+
+#[test]
+fn write_container() -> Result<(), PakError> {
+    let version = IoStoreTocVersion::PerfectHashWithOverflow;
+    let package =
+        IoChunkId::from_package_name("/Game/Maps/Map", IoChunkType::ExportBundleData, version);
+    let bulk = IoChunkId::from_package_name("/Game/Maps/Map", IoChunkType::BulkData, version);
+    assert_eq!(package.id(), bulk.id());
+    assert_eq!(package.chunk_type(), 1);
+    assert_eq!(
+        IoChunkType::BulkData.to_num(IoStoreTocVersion::PartitionSize),
+        3
+    );
+
+    let compressible = b"compressible chunk data ".repeat(20);
+    for (compression, encrypted) in [
+        (Compression::None, false),
+        (Compression::zlib(), false),
+        (Compression::zlib(), true),
+    ] {
+        let mut writer = IoStoreWriter::new(Vec::new(), version);
+        writer.compression = compression;
+        writer.set_block_size(BLOCK_SIZE as u32)?;
+        writer.encrypt = encrypted;
+        writer.set_encryption_key(AesKey::new(KEY));
+        writer.set_container_name("pakchunk99-Mod");
+
+        writer.add_chunk(package, Some("Game/Content/Maps/Map.umap"), &compressible)?;
+        writer.add_chunk(bulk, Some("Game/Content/Maps/Map.ubulk"), &chunk_b())?;
+        writer.add_chunk(
+            IoChunkId::new(3, 0, 2),
+            Some("Game/Content/Empty.ubulk"),
+            &[],
+        )?;
+        writer.add_chunk(IoChunkId::new(4, 0, 2), None, &chunk_a())?;
+
+        let err = writer.add_chunk(bulk, None, &[]).unwrap_err();
+        assert!(matches!(err.kind, PakErrorKind::DoubleWrite(_)));
+        let err = writer.set_block_size(0x100).unwrap_err();
+        assert!(matches!(err.kind, PakErrorKind::ConfigurationInvalid));
+
+        let mut toc = Vec::new();
+        let cas = writer.finish_write(&mut toc)?;
+
+        let mut reader = IoStoreReader::new(Cursor::new(cas));
+        reader.set_encryption_key(AesKey::new(KEY));
+        reader.load_toc(Cursor::new(toc))?;
+
+        assert_eq!(reader.version(), Some(version));
+        assert_eq!(reader.mount_point(), Some("../../../"));
+        assert_eq!(
+            reader.get_file_names(),
+            vec![
+                "Game/Content/Empty.ubulk",
+                "Game/Content/Maps/Map.ubulk",
+                "Game/Content/Maps/Map.umap"
+            ]
+        );
+        assert_eq!(reader.get_chunk_ids().len(), 4);
+        assert_eq!(
+            reader.get_file_chunk_id(&"Game/Content/Maps/Map.ubulk".to_string()),
+            Some(bulk)
+        );
+
+        assert_eq!(reader.read_chunk(&package)?, compressible);
+        assert_eq!(
+            reader.read_file(&"Game/Content/Maps/Map.ubulk".to_string())?,
+            chunk_b()
+        );
+        assert!(reader
+            .read_file(&"Game/Content/Empty.ubulk".to_string())?
+            .is_empty());
+        assert_eq!(reader.read_chunk(&IoChunkId::new(4, 0, 2))?, chunk_a());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn write_missing_key() {
+    let mut writer = IoStoreWriter::new(Vec::new(), IoStoreTocVersion::PerfectHashWithOverflow);
+    writer.encrypt = true;
+    let err = writer
+        .add_chunk(IoChunkId::new(1, 0, 1), None, b"data")
+        .unwrap_err();
+    assert!(matches!(err.kind, PakErrorKind::EncryptionKeyMissing));
+}
+
+#[test]
+fn chunk_hashes() -> Result<(), PakError> {
+    for (version, hash) in [
+        (
+            IoStoreTocVersion::PerfectHashWithOverflow,
+            // SHA1
+            "a9993e364706816aba3e25717850c26c9cd0d89d",
+        ),
+        (
+            IoStoreTocVersion::ReplaceIoChunkHashWithIoHash,
+            // BLAKE3 truncated to 20 bytes
+            "6437b3ac38465133ffb63b75273a8db548c55846",
+        ),
+    ] {
+        let hash = (0..hash.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hash[i..i + 2], 16).unwrap())
+            .collect::<Vec<_>>();
+
+        let mut writer = IoStoreWriter::new(Vec::new(), version);
+        writer.add_chunk(IoChunkId::new(1, 0, 1), None, b"abc")?;
+        let mut toc = Vec::new();
+        writer.finish_write(&mut toc)?;
+
+        assert!(toc.windows(hash.len()).any(|window| window == hash));
+    }
+
+    Ok(())
+}
+
+const PACKAGE: &[u8] = include_bytes!("../testfiles/PublicHousingPlot_Root.umap");
+const PACKAGE_EXPORTS: &[u8] = include_bytes!("../testfiles/PublicHousingPlot_Root.uexp");
+
+#[test]
+fn write_package() -> Result<(), PakError> {
+    let version = IoStoreTocVersion::PerfectHashWithOverflow;
+    let package_name = "/Game/PublicHousingPlot_Root";
+    let file_name = "Game/Content/PublicHousingPlot_Root.umap";
+
+    let mut writer = IoStoreWriter::new(Vec::new(), version);
+    writer.set_container_name("pakchunk99-Mod");
+    let err = writer
+        .add_package(package_name, Some(file_name), PACKAGE, PACKAGE_EXPORTS)
+        .unwrap_err();
+    assert!(matches!(err.kind, PakErrorKind::ConfigurationInvalid));
+
+    writer.engine_version = Some(EngineVersion::UE5_1);
+    let err = writer
+        .add_package(
+            package_name,
+            None,
+            PACKAGE,
+            &PACKAGE_EXPORTS[..PACKAGE_EXPORTS.len() - 4],
+        )
+        .unwrap_err();
+    assert!(matches!(err.kind, PakErrorKind::PackageInvalid(_)));
+    writer.add_package(package_name, Some(file_name), PACKAGE, PACKAGE_EXPORTS)?;
+    let container_id = writer.container_id;
+
+    let mut toc = Vec::new();
+    let cas = writer.finish_write(&mut toc)?;
+    let mut reader = IoStoreReader::new(Cursor::new(cas));
+    reader.load_toc(Cursor::new(toc))?;
+    assert_eq!(reader.get_file_names(), vec![file_name]);
+
+    let package = reader.read_file(&file_name.to_string())?;
+    let mut summary = Cursor::new(&package);
+    assert_eq!(summary.read_u32::<LE>()?, 0);
+    let header_size = summary.read_u32::<LE>()? as usize;
+    // the package name is added to the name map
+    let name_index = summary.read_u32::<LE>()?;
+    let _name_number = summary.read_u32::<LE>()?;
+    let _package_flags = summary.read_u32::<LE>()?;
+    assert_eq!(summary.read_u32::<LE>()? as usize, PACKAGE.len());
+    let mut offsets = [0; 5];
+    for offset in &mut offsets {
+        *offset = summary.read_i32::<LE>()? as usize;
+    }
+    assert!(offsets.windows(2).all(|pair| pair[0] <= pair[1]));
+    assert!(offsets[4] < header_size);
+
+    let mut names = Cursor::new(&package[44..]);
+    assert_eq!(names.read_u32::<LE>()?, name_index + 1);
+    // the export data is moved behind the header without the package file tag
+    assert_eq!(package.len() - header_size, PACKAGE_EXPORTS.len() - 4);
+
+    let header = reader.read_chunk(&IoChunkId::new(container_id, 0, 6))?;
+    let mut header = Cursor::new(header);
+    assert_eq!(header.read_u32::<LE>()?, 0x496f436e);
+    assert_eq!(header.read_u32::<LE>()?, 2);
+    assert_eq!(header.read_u64::<LE>()?, container_id);
+    assert_eq!(header.read_i32::<LE>()?, 1);
+    let package_id =
+        IoChunkId::from_package_name(package_name, IoChunkType::ExportBundleData, version);
+    assert_eq!(header.read_u64::<LE>()?, package_id.id());
+
+    Ok(())
+}
+
+#[test]
+fn write_package_unsupported_version() {
+    let mut writer = IoStoreWriter::new(Vec::new(), IoStoreTocVersion::PartitionSize);
+    writer.engine_version = Some(EngineVersion::UE5_1);
+    let err = writer
+        .add_package("/Game/Package", None, PACKAGE, PACKAGE_EXPORTS)
+        .unwrap_err();
+    assert!(matches!(
+        err.kind,
+        PakErrorKind::IoStoreVersionUnsupported(3)
+    ));
+}
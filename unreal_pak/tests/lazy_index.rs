@@ -0,0 +1,141 @@
+use std::io::{Cursor, Read};
+
+use unreal_pak::{error::PakErrorKind, pakversion::PakVersion, AesKey, PakError, PakReader};
+
+mod shared;
+use shared::{text, TestPak};
+
+const KEY: &str = "0x0102030405060708090A0B0C0D0E0F101112131415161718191A1B1C1D1E1F20";
+
+const VERSIONS: [PakVersion; 4] = [
+    PakVersion::DeleteRecords,
+    PakVersion::FrozenIndex,
+    PakVersion::PathHashIndex,
+    PakVersion::Fnv64BugFix,
+];
+
+fn write_pak(pak_version: PakVersion, encrypt_index: bool) -> Result<Vec<u8>, PakError> {
+    let mut pak = TestPak::new(pak_version)
+        .key(AesKey::from_hex(KEY)?)
+        .encrypt_index(encrypt_index)
+        .entry("metadata.json", b"{}", false)
+        .entry("Game/Content/Mod/large.txt", &text(100), true);
+    for i in 0..50 {
+        pak = pak.entry(
+            &format!("Game/Content/Mod/{i}.uasset"),
+            &[i as u8; 4],
+            false,
+        );
+    }
+    pak.delete_record("Game/Content/removed.uasset").write()
+}
+
+fn open(data: Vec<u8>, lazy: bool) -> Result<PakReader<Cursor<Vec<u8>>>, PakError> {
+    let mut reader = PakReader::new(Cursor::new(data));
+    reader.set_encryption_key(AesKey::from_hex(KEY)?);
+    reader.set_verify_hashes(true);
+    reader.set_lazy_index(lazy);
+    reader.load_index()?;
+    Ok(reader)
+}
+
+#[test]
+fn lazy_lookup() -> Result<(), PakError> {
+    for pak_version in VERSIONS {
+        for encrypt_index in [false, true] {
+            let mut reader = open(write_pak(pak_version, encrypt_index)?, true)?;
+
+            assert_eq!(reader.read_entry(&"metadata.json".to_string())?, b"{}");
+            assert_eq!(
+                reader.read_entry(&"Game/Content/Mod/7.uasset".to_string())?,
+                [7; 4]
+            );
+            assert!(reader.contains_entry(&"Game/Content/Mod/large.txt".to_string())?);
+            assert_eq!(
+                reader
+                    .entry_info("Game/Content/Mod/large.txt")?
+                    .map(|info| info.decompressed_size),
+                Some(4800)
+            );
+
+            let mut data = Vec::new();
+            reader
+                .open_entry(&"Game/Content/Mod/large.txt".to_string())?
+                .read_to_end(&mut data)?;
+            assert_eq!(data, text(100));
+
+            assert!(!reader.contains_entry(&"missing.json".to_string())?);
+            // shares the path hash with metadata.json before the hash bug fix
+            assert!(!reader.contains_entry(&"metadata.yaml".to_string())?);
+            // names are case sensitive like when the index is loaded eagerly
+            assert!(!reader.contains_entry(&"Metadata.json".to_string())?);
+            assert!(!reader.contains_entry(&"game/content/mod/7.uasset".to_string())?);
+            assert!(!reader.is_deleted(&"Game/Content/Removed.uasset".to_string())?);
+            let err = reader.read_entry(&"missing.json".to_string()).unwrap_err();
+            assert!(matches!(err.kind, PakErrorKind::EntryNotFound(_)));
+
+            assert!(reader.is_deleted(&"Game/Content/removed.uasset".to_string())?);
+            assert!(!reader.contains_entry(&"Game/Content/removed.uasset".to_string())?);
+            assert!(!reader.is_deleted(&"metadata.json".to_string())?);
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn lazy_listing() -> Result<(), PakError> {
+    for pak_version in VERSIONS {
+        let data = write_pak(pak_version, true)?;
+        let eager = open(data.clone(), false)?;
+        let mut lazy = open(data, true)?;
+
+        // looking up an entry first must not prevent listing all of them later
+        assert!(lazy.contains_entry(&"metadata.json".to_string())?);
+
+        assert_eq!(lazy.get_entry_names()?, eager.get_entry_names()?);
+        assert_eq!(lazy.get_delete_records()?, eager.get_delete_records()?);
+        let names = lazy
+            .get_entry_names()?
+            .into_iter()
+            .cloned()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            lazy.iter()?
+                .map(|(name, _)| name.clone())
+                .collect::<Vec<_>>()
+        );
+
+        // lookups keep working once the entries are listed
+        assert_eq!(lazy.read_entry(&"metadata.json".to_string())?, b"{}");
+        assert!(lazy.is_deleted(&"Game/Content/removed.uasset".to_string())?);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn lazy_invalid_directory_index() -> Result<(), PakError> {
+    let mut data = write_pak(PakVersion::Fnv64BugFix, false)?;
+    // cut the directory name short, so its terminator is missing
+    let directory = b"\x12\0\0\0Game/Content/Mod/\0";
+    let position = data
+        .windows(directory.len())
+        .position(|window| window == directory)
+        .unwrap();
+    data[position..position + 4].copy_from_slice(&5i32.to_le_bytes());
+
+    let mut reader = PakReader::new(Cursor::new(data));
+    reader.set_lazy_index(true);
+    reader.load_index()?;
+
+    assert!(reader.read_entry(&"metadata.json".to_string()).is_err());
+    assert!(reader.entry_info("metadata.json").is_err());
+    assert!(reader.get_entry_names().is_err());
+    assert!(reader.get_delete_records().is_err());
+    assert!(reader.entry_infos().is_err());
+    assert!(reader.iter().is_err());
+
+    Ok(())
+}
@@ -0,0 +1,68 @@
+use std::io::{Cursor, Read};
+
+use unreal_pak::{pakversion::PakVersion, AesKey, PakError, PakReader};
+
+mod shared;
+use shared::{TestPak, KEY};
+
+fn create(pak_version: PakVersion, encrypted: bool) -> Result<Vec<u8>, PakError> {
+    let compress = pak_version >= PakVersion::CompressionEncryption;
+    TestPak::new(pak_version)
+        .block_size(0x40)
+        .encrypted(encrypted)
+        .entry("dir/a.txt", &[b'a'; 0x90], compress)
+        .entry("b.txt", b"uncompressed", false)
+        .write()
+}
+
+/// Parse the pak and read all entries, errors are expected but nothing may panic
+fn parse(pak: Vec<u8>) {
+    let mut reader = PakReader::new(Cursor::new(pak));
+    reader.set_encryption_key(AesKey::new(KEY));
+    reader.set_verify_hashes(true);
+    if reader.load_index().is_err() {
+        return;
+    }
+
+    let names = match reader.get_entry_names() {
+        Ok(names) => names.into_iter().cloned().collect::<Vec<_>>(),
+        Err(_) => return,
+    };
+    for name in &names {
+        let _ = reader.read_entry(name);
+        if let Ok(mut entry) = reader.open_entry(name) {
+            let _ = entry.read_to_end(&mut Vec::new());
+        }
+    }
+}
+
+#[test]
+fn malformed_paks() -> Result<(), PakError> {
+    for pak_version in [
+        PakVersion::Initial,
+        PakVersion::CompressionEncryption,
+        PakVersion::FnameBasedCompressionMethod,
+        PakVersion::FrozenIndex,
+        PakVersion::Fnv64BugFix,
+    ] {
+        for encrypted in [false, true] {
+            if encrypted && pak_version < PakVersion::IndexEncryption {
+                continue;
+            }
+            let pak = create(pak_version, encrypted)?;
+
+            for len in 0..pak.len() {
+                parse(pak[..len].to_vec());
+            }
+
+            for position in 0..pak.len() {
+                for value in [0x00, 0xff, 0x80, 0x7f, pak[position] ^ 0x01] {
+                    let mut pak = pak.clone();
+                    pak[position] = value;
+                    parse(pak);
+                }
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,66 @@
+use std::io::Cursor;
+
+use unreal_pak::{pakversion::PakVersion, PakError, PakMemory};
+
+mod shared;
+use shared::{test_entries, TestPak};
+
+const VERSIONS: [PakVersion; 10] = [
+    PakVersion::NoTimestamps,
+    PakVersion::CompressionEncryption,
+    PakVersion::IndexEncryption,
+    PakVersion::RelativeChunkOffsets,
+    PakVersion::DeleteRecords,
+    PakVersion::EncryptionKeyGuid,
+    PakVersion::FnameBasedCompressionMethod,
+    PakVersion::FrozenIndex,
+    PakVersion::PathHashIndex,
+    PakVersion::Fnv64BugFix,
+];
+
+#[test]
+fn pak_writer_roundtrip() -> Result<(), PakError> {
+    for pak_version in VERSIONS {
+        // compression is only supported starting with CompressionEncryption
+        let compression = if pak_version >= PakVersion::CompressionEncryption {
+            vec![false, true]
+        } else {
+            vec![false]
+        };
+
+        for compress in compression {
+            let mut reader = TestPak::new(pak_version)
+                .entries(test_entries(), compress)
+                .open()?;
+
+            assert_eq!(reader.get_entry_names()?.len(), test_entries().len());
+            for (name, data) in test_entries() {
+                assert_eq!(reader.read_entry(&name)?, data, "{pak_version:?} {name}");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn pak_memory_roundtrip() -> Result<(), PakError> {
+    for pak_version in [PakVersion::PathHashIndex, PakVersion::Fnv64BugFix] {
+        let mut pak = PakMemory::new(pak_version);
+        for (name, data) in test_entries() {
+            pak.set_entry(name, data);
+        }
+
+        let mut cursor = Cursor::new(Vec::new());
+        pak.write(&mut cursor)?;
+        cursor.set_position(0);
+
+        let loaded = PakMemory::load_from(&mut cursor)?;
+        assert_eq!(loaded.pak_version, pak_version);
+        for (name, data) in test_entries() {
+            assert_eq!(loaded.get_entry(&name), Some(&data));
+        }
+    }
+
+    Ok(())
+}
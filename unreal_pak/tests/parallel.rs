@@ -0,0 +1,42 @@
+use std::io::Cursor;
+
+use unreal_pak::{pakversion::PakVersion, AesKey, Compression, PakError, PakMemory};
+
+mod shared;
+use shared::{test_entries, TestPak, KEY};
+
+/// Output has to be the same whether entries and blocks are compressed in parallel or not,
+/// so a PakWriter writing entries one by one has to produce the same file as a PakMemory.
+#[test]
+fn identical_output() -> Result<(), PakError> {
+    for pak_version in [PakVersion::RelativeChunkOffsets, PakVersion::FrozenIndex] {
+        for encrypt in [false, true] {
+            // a PakMemory writes its entries sorted by name
+            let mut entries = test_entries();
+            entries.sort();
+            let written = TestPak::new(pak_version)
+                .key(AesKey::new(KEY))
+                .encrypt_entries(encrypt)
+                .entries(entries, true)
+                .write()?;
+
+            let mut pak = PakMemory::new(pak_version);
+            pak.set_encryption_key(AesKey::new(KEY));
+            pak.encrypt_entries = encrypt;
+            pak.compression = Compression::zlib();
+            for (name, data) in test_entries() {
+                pak.set_entry(name, data);
+            }
+
+            let mut first = Cursor::new(Vec::new());
+            pak.write(&mut first)?;
+            let mut second = Cursor::new(Vec::new());
+            pak.write(&mut second)?;
+
+            assert!(written == *first.get_ref(), "{pak_version:?}");
+            assert!(first.get_ref() == second.get_ref(), "{pak_version:?}");
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,147 @@
+use std::io::Cursor;
+
+use unreal_pak::{
+    pakversion::PakVersion, repack, repack::RepackStats, AesKey, Compression, PakError, PakReader,
+    PakWriter,
+};
+
+mod shared;
+use shared::{data, TestPak, KEY};
+
+const VERSIONS: [PakVersion; 6] = [
+    PakVersion::NoTimestamps,
+    PakVersion::IndexEncryption,
+    PakVersion::RelativeChunkOffsets,
+    PakVersion::FnameBasedCompressionMethod,
+    PakVersion::PathHashIndex,
+    PakVersion::Fnv64BugFix,
+];
+
+fn create(
+    pak_version: PakVersion,
+    compression: Compression,
+    encrypted: bool,
+) -> Result<Vec<u8>, PakError> {
+    let compress = pak_version >= PakVersion::CompressionEncryption;
+    let mut pak = TestPak::new(pak_version)
+        .mount_point("../../../Game/")
+        .block_size(0x100)
+        .compression(compression)
+        .encrypted(encrypted)
+        .entry("a.txt", &data("abc", 0x350), compress)
+        .entry("b.txt", &data("b", 0x80), false);
+    if pak_version >= PakVersion::DeleteRecords {
+        pak = pak.delete_record("c.txt");
+    }
+    pak.write()
+}
+
+fn open(pak: Vec<u8>) -> Result<PakReader<Cursor<Vec<u8>>>, PakError> {
+    let mut reader = PakReader::new(Cursor::new(pak));
+    reader.set_encryption_key(AesKey::new(KEY));
+    reader.set_verify_hashes(true);
+    reader.load_index()?;
+    Ok(reader)
+}
+
+fn convert(
+    pak: Vec<u8>,
+    pak_version: PakVersion,
+    encrypted: bool,
+) -> Result<(Vec<u8>, RepackStats), PakError> {
+    let mut reader = open(pak)?;
+    let mut writer = PakWriter::new(Cursor::new(Vec::new()), pak_version);
+    writer.encrypt_entries = encrypted;
+    writer.set_encryption_key(AesKey::new(KEY));
+    let stats = repack(&mut reader, &mut writer)?;
+    Ok((writer.finish_write()?.into_inner(), stats))
+}
+
+#[test]
+fn repack_versions() -> Result<(), PakError> {
+    for from in VERSIONS {
+        for to in VERSIONS {
+            for encrypted in [false, true] {
+                if encrypted
+                    && (from < PakVersion::IndexEncryption || to < PakVersion::IndexEncryption)
+                {
+                    continue;
+                }
+                // delete records can not be stored in older versions
+                if from >= PakVersion::DeleteRecords && to < PakVersion::DeleteRecords {
+                    continue;
+                }
+
+                let pak = create(from, Compression::zlib(), encrypted)?;
+                let (repacked, stats) = convert(
+                    pak.clone(),
+                    to,
+                    !encrypted && to >= PakVersion::IndexEncryption,
+                )?;
+
+                let mut old = open(pak)?;
+                let mut new = open(repacked)?;
+                assert_eq!(new.get_pak_version(), to);
+                assert_eq!(new.mount_point, "../../../Game/");
+                assert_eq!(new.get_entry_names()?, old.get_entry_names()?);
+                assert_eq!(new.get_delete_records()?, old.get_delete_records()?);
+                for name in ["a.txt", "b.txt"] {
+                    let name = name.to_string();
+                    assert_eq!(new.read_entry(&name)?, old.read_entry(&name)?);
+                }
+
+                let old_info = old.entry_info("a.txt")?.unwrap();
+                let new_info = new.entry_info("a.txt")?.unwrap();
+                if from >= PakVersion::CompressionEncryption
+                    && to >= PakVersion::CompressionEncryption
+                {
+                    // compressed blocks are kept
+                    assert_eq!(stats.copied, 1);
+                    assert_eq!(new_info.compression, Compression::zlib());
+                    assert_eq!(new_info.block_sizes, old_info.block_sizes);
+                } else {
+                    assert_eq!(stats.copied, 0);
+                    assert_eq!(new_info.compression, Compression::None);
+                }
+                assert_eq!(stats.copied + stats.rewritten, 2);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn repack_recompress() -> Result<(), PakError> {
+    // Zstd can only be stored in paks with a compression methods table, older versions use the writer's compression
+    let pak = create(
+        PakVersion::FnameBasedCompressionMethod,
+        Compression::zstd(),
+        false,
+    )?;
+    let (repacked, stats) = convert(pak.clone(), PakVersion::EncryptionKeyGuid, false)?;
+    assert_eq!(stats.copied, 0);
+    assert_eq!(stats.rewritten, 2);
+
+    let mut old = open(pak)?;
+    let mut new = open(repacked)?;
+    assert_eq!(
+        new.entry_info("a.txt")?.unwrap().compression,
+        Compression::zlib()
+    );
+    assert_eq!(
+        new.read_entry(&"a.txt".to_string())?,
+        old.read_entry(&"a.txt".to_string())?
+    );
+
+    let (repacked, stats) = convert(
+        new.into_inner().into_inner(),
+        PakVersion::Fnv64BugFix,
+        false,
+    )?;
+    assert_eq!(stats.copied, 1);
+    assert_eq!(
+        open(repacked)?.read_entry(&"a.txt".to_string())?,
+        data("abc", 0x350)
+    );
+    Ok(())
+}
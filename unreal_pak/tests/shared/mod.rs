@@ -0,0 +1,203 @@
+//! Test paks shared by the integration tests
+
+#![allow(dead_code)]
+
+use std::io::Cursor;
+
+use unreal_pak::{pakversion::PakVersion, AesKey, Compression, PakError, PakReader, PakWriter};
+
+/// Key used for encrypted test paks
+pub const KEY: [u8; 32] = [0x42; 32];
+
+/// Text which compresses well, 48 bytes for every repetition
+pub fn text(repeat: usize) -> Vec<u8> {
+    b"Some text that is long enough to be compressed. ".repeat(repeat)
+}
+
+/// Data of `len` bytes repeating the bytes of `name`
+pub fn data(name: &str, len: usize) -> Vec<u8> {
+    name.as_bytes().iter().copied().cycle().take(len).collect()
+}
+
+/// Data of `len` bytes which does not repeat within a compression block
+pub fn binary(len: u32) -> Vec<u8> {
+    (0..len).map(|i| (i % 251) as u8).collect()
+}
+
+/// Entries in nested directories, from a tiny one up to one spanning multiple compression blocks
+pub fn test_entries() -> Vec<(String, Vec<u8>)> {
+    vec![
+        (
+            "root.txt".to_string(),
+            b"file in the root directory".to_vec(),
+        ),
+        ("Game/small.txt".to_string(), b"tiny".to_vec()),
+        ("Game/Content/text.txt".to_string(), text(100)),
+        (
+            "Game/Content/Deep/Path/large.bin".to_string(),
+            binary(0x30000),
+        ),
+    ]
+}
+
+/// How an entry of a [`TestPak`] is written
+#[derive(Debug, Clone)]
+enum TestEntry {
+    /// Written with [`PakWriter::write_entry`], compressed with the compression method of the pak or not
+    Data(Vec<u8>, bool),
+    /// Written with [`PakWriter::write_entry_with_compression`]
+    Compressed(Vec<u8>, Compression),
+    /// Written with [`PakWriter::write_delete_record`]
+    Deleted,
+}
+
+/// Builder for paks written by a [`PakWriter`] into memory, entries are written in the order they are added
+#[derive(Clone)]
+pub struct TestPak {
+    pak_version: PakVersion,
+    mount_point: Option<String>,
+    compression: Option<Compression>,
+    block_size: Option<u32>,
+    key: Option<AesKey>,
+    encrypt_entries: bool,
+    encrypt_index: bool,
+    encryption_key_guid: Option<[u8; 0x10]>,
+    entries: Vec<(String, TestEntry)>,
+}
+
+impl TestPak {
+    pub fn new(pak_version: PakVersion) -> Self {
+        TestPak {
+            pak_version,
+            mount_point: None,
+            compression: None,
+            block_size: None,
+            key: None,
+            encrypt_entries: false,
+            encrypt_index: false,
+            encryption_key_guid: None,
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn mount_point(mut self, mount_point: &str) -> Self {
+        self.mount_point = Some(mount_point.to_string());
+        self
+    }
+
+    pub fn compression(mut self, compression: Compression) -> Self {
+        self.compression = Some(compression);
+        self
+    }
+
+    pub fn block_size(mut self, block_size: u32) -> Self {
+        self.block_size = Some(block_size);
+        self
+    }
+
+    /// Set the key used for writing and reading the pak, nothing is encrypted without also enabling encryption
+    pub fn key(mut self, key: AesKey) -> Self {
+        self.key = Some(key);
+        self
+    }
+
+    /// Encrypt the entries and the index with [`KEY`]
+    pub fn encrypted(self, encrypted: bool) -> Self {
+        self.key(AesKey::new(KEY))
+            .encrypt_entries(encrypted)
+            .encrypt_index(encrypted)
+    }
+
+    pub fn encrypt_entries(mut self, encrypt_entries: bool) -> Self {
+        self.encrypt_entries = encrypt_entries;
+        self
+    }
+
+    pub fn encrypt_index(mut self, encrypt_index: bool) -> Self {
+        self.encrypt_index = encrypt_index;
+        self
+    }
+
+    pub fn encryption_key_guid(mut self, encryption_key_guid: [u8; 0x10]) -> Self {
+        self.encryption_key_guid = Some(encryption_key_guid);
+        self
+    }
+
+    pub fn entry(mut self, name: &str, data: &[u8], compress: bool) -> Self {
+        self.entries
+            .push((name.to_string(), TestEntry::Data(data.to_vec(), compress)));
+        self
+    }
+
+    pub fn entry_with_compression(
+        mut self,
+        name: &str,
+        data: &[u8],
+        compression: Compression,
+    ) -> Self {
+        self.entries.push((
+            name.to_string(),
+            TestEntry::Compressed(data.to_vec(), compression),
+        ));
+        self
+    }
+
+    pub fn entries(mut self, entries: Vec<(String, Vec<u8>)>, compress: bool) -> Self {
+        self.entries.extend(
+            entries
+                .into_iter()
+                .map(|(name, data)| (name, TestEntry::Data(data, compress))),
+        );
+        self
+    }
+
+    pub fn delete_record(mut self, name: &str) -> Self {
+        self.entries.push((name.to_string(), TestEntry::Deleted));
+        self
+    }
+
+    /// Write the pak
+    pub fn write(&self) -> Result<Vec<u8>, PakError> {
+        let mut writer = PakWriter::new(Cursor::new(Vec::new()), self.pak_version);
+        if let Some(mount_point) = &self.mount_point {
+            writer.mount_point = mount_point.clone();
+        }
+        if let Some(compression) = self.compression {
+            writer.compression = compression;
+        }
+        if let Some(block_size) = self.block_size {
+            writer.block_size = block_size;
+        }
+        if let Some(key) = &self.key {
+            writer.set_encryption_key(key.clone());
+        }
+        writer.encrypt_entries = self.encrypt_entries;
+        writer.encrypt_index = self.encrypt_index;
+        if let Some(encryption_key_guid) = self.encryption_key_guid {
+            writer.encryption_key_guid = encryption_key_guid;
+        }
+
+        for (name, entry) in &self.entries {
+            match entry {
+                TestEntry::Data(data, compress) => writer.write_entry(name, data, *compress)?,
+                TestEntry::Compressed(data, compression) => {
+                    writer.write_entry_with_compression(name, data, *compression)?
+                }
+                TestEntry::Deleted => writer.write_delete_record(name)?,
+            }
+        }
+
+        Ok(writer.finish_write()?.into_inner())
+    }
+
+    /// Write the pak and load its index with hash verification enabled
+    pub fn open(&self) -> Result<PakReader<Cursor<Vec<u8>>>, PakError> {
+        let mut reader = PakReader::new(Cursor::new(self.write()?));
+        if let Some(key) = &self.key {
+            reader.set_encryption_key(key.clone());
+        }
+        reader.set_verify_hashes(true);
+        reader.load_index()?;
+        Ok(reader)
+    }
+}
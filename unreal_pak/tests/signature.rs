@@ -0,0 +1,79 @@
+use std::io::Cursor;
+
+use unreal_pak::{
+    error::PakErrorKind,
+    pakversion::PakVersion,
+    signature::{PakSignature, SigningKey, CHUNK_SIZE},
+    PakError, PakWriter,
+};
+
+fn create_pak() -> Result<Vec<u8>, PakError> {
+    let mut writer = PakWriter::new(Cursor::new(Vec::new()), PakVersion::Fnv64BugFix);
+    let data = (0..CHUNK_SIZE * 2).map(|i| i as u8).collect::<Vec<_>>();
+    writer.write_entry(&"a.bin".to_string(), &data, false)?;
+    writer.write_entry(&"b.txt".to_string(), b"some text", false)?;
+    Ok(writer.finish_write()?.into_inner())
+}
+
+#[test]
+fn sign_and_verify() -> Result<(), PakError> {
+    let key = SigningKey::generate(512)?;
+    let pak = create_pak()?;
+
+    let signature = PakSignature::generate(&mut Cursor::new(&pak), &key)?;
+    assert_eq!(signature.chunk_hashes.len(), pak.len().div_ceil(CHUNK_SIZE));
+    assert_eq!(signature.encrypted_hash.len(), 64);
+
+    let mut sig_file = Vec::new();
+    signature.write(&mut sig_file)?;
+    assert_eq!(&sig_file[..4], &0x73832DAAu32.to_le_bytes());
+    let signature = PakSignature::read(&mut Cursor::new(sig_file))?;
+
+    signature.verify(&mut Cursor::new(&pak), &key.verifying_key())?;
+
+    // modified data
+    let mut modified = pak.clone();
+    modified[CHUNK_SIZE + 10] ^= 0xff;
+    let err = signature
+        .verify(&mut Cursor::new(&modified), &key.verifying_key())
+        .unwrap_err();
+    assert!(matches!(err.kind, PakErrorKind::ChunkHashMismatch(1)));
+
+    // missing data
+    let err = signature
+        .verify(
+            &mut Cursor::new(&pak[..CHUNK_SIZE * 2]),
+            &key.verifying_key(),
+        )
+        .unwrap_err();
+    assert!(matches!(err.kind, PakErrorKind::ChunkHashMismatch(2)));
+
+    // signed with another key
+    let other_key = SigningKey::generate(512)?;
+    let err = signature
+        .verify(&mut Cursor::new(&pak), &other_key.verifying_key())
+        .unwrap_err();
+    assert!(matches!(err.kind, PakErrorKind::SignatureMismatch));
+
+    // modified chunk hashes
+    let mut forged = signature.clone();
+    forged.chunk_hashes[0] ^= 1;
+    let err = forged.verify_signature(&key.verifying_key()).unwrap_err();
+    assert!(matches!(err.kind, PakErrorKind::SignatureMismatch));
+
+    Ok(())
+}
+
+#[test]
+fn invalid_signature_file() {
+    let err = PakSignature::read(&mut Cursor::new([0u8; 16])).unwrap_err();
+    assert!(matches!(err.kind, PakErrorKind::SignatureInvalid));
+
+    // the array length is larger than the file
+    let mut sig_file = Vec::new();
+    sig_file.extend_from_slice(&0x73832DAAu32.to_le_bytes());
+    sig_file.extend_from_slice(&1u32.to_le_bytes());
+    sig_file.extend_from_slice(&i32::MAX.to_le_bytes());
+    let err = PakSignature::read(&mut Cursor::new(sig_file)).unwrap_err();
+    assert!(matches!(err.kind, PakErrorKind::SignatureInvalid));
+}
@@ -0,0 +1,64 @@
+use std::io::{Read, Seek, SeekFrom};
+
+use unreal_pak::{pakversion::PakVersion, AesKey, PakError};
+
+mod shared;
+use shared::{binary, TestPak, KEY};
+
+#[test]
+fn seek_and_read() -> Result<(), PakError> {
+    let data = binary(0x34567);
+
+    for pak_version in [
+        PakVersion::CompressionEncryption,
+        PakVersion::FrozenIndex,
+        PakVersion::Fnv64BugFix,
+    ] {
+        for (compress, encrypt) in [(false, false), (true, false), (false, true), (true, true)] {
+            let mut reader = TestPak::new(pak_version)
+                .key(AesKey::new(KEY))
+                .encrypt_entries(encrypt)
+                .entry("small.txt", b"tiny", compress)
+                .entry("data.bin", &data, compress)
+                .open()?;
+
+            let mut entry = reader.open_entry(&"data.bin".to_string())?;
+            assert_eq!(entry.len(), data.len() as u64);
+
+            // read from the middle of the second block
+            let mut buf = vec![0u8; 1000];
+            entry.seek(SeekFrom::Start(0x18000))?;
+            entry.read_exact(&mut buf)?;
+            assert_eq!(buf, data[0x18000..0x18000 + 1000], "{pak_version:?}");
+
+            // read across a block boundary
+            entry.seek(SeekFrom::Start(0xfff0))?;
+            entry.read_exact(&mut buf)?;
+            assert_eq!(buf, data[0xfff0..0xfff0 + 1000]);
+
+            // read the tail
+            let mut tail = Vec::new();
+            entry.seek(SeekFrom::End(-100))?;
+            entry.read_to_end(&mut tail)?;
+            assert_eq!(tail, data[data.len() - 100..]);
+
+            // read everything
+            let mut all = Vec::new();
+            entry.rewind()?;
+            entry.read_to_end(&mut all)?;
+            assert_eq!(all, data);
+
+            assert!(entry
+                .seek(SeekFrom::Current(-(data.len() as i64) - 1))
+                .is_err());
+
+            let mut small = String::new();
+            reader
+                .open_entry(&"small.txt".to_string())?
+                .read_to_string(&mut small)?;
+            assert_eq!(small, "tiny");
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,81 @@
+use std::io::Cursor;
+
+use unreal_pak::{error::PakErrorKind, pakversion::PakVersion, PakError, PakReader};
+
+mod shared;
+use shared::TestPak;
+
+const DATA: &[u8] = b"Some data which is going to be corrupted.";
+
+fn write_pak(pak_version: PakVersion, compress: bool) -> Result<Vec<u8>, PakError> {
+    TestPak::new(pak_version)
+        .mount_point("../../../Game/")
+        .entry("data.txt", DATA, compress)
+        .write()
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> usize {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .expect("needle is contained in haystack")
+}
+
+#[test]
+fn valid_pak() -> Result<(), PakError> {
+    for pak_version in [PakVersion::FrozenIndex, PakVersion::Fnv64BugFix] {
+        for compress in [false, true] {
+            let mut reader = TestPak::new(pak_version)
+                .mount_point("../../../Game/")
+                .entry("data.txt", DATA, compress)
+                .open()?;
+            assert_eq!(reader.read_entry(&"data.txt".to_string())?, DATA);
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn corrupted_entry() -> Result<(), PakError> {
+    for pak_version in [PakVersion::FrozenIndex, PakVersion::Fnv64BugFix] {
+        let mut pak = write_pak(pak_version, false)?;
+        let pos = find(&pak, DATA);
+        pak[pos] ^= 0xff;
+
+        let mut reader = PakReader::new(Cursor::new(pak.clone()));
+        reader.load_index()?;
+        assert_ne!(reader.read_entry(&"data.txt".to_string())?, DATA);
+
+        let mut reader = PakReader::new(Cursor::new(pak));
+        reader.set_verify_hashes(true);
+        reader.load_index()?;
+        let err = reader.read_entry(&"data.txt".to_string()).unwrap_err();
+        assert!(matches!(err.kind, PakErrorKind::EntryHashMismatch(name) if name == "data.txt"));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn corrupted_index() -> Result<(), PakError> {
+    for pak_version in [PakVersion::FrozenIndex, PakVersion::Fnv64BugFix] {
+        let mut pak = write_pak(pak_version, false)?;
+        let pos = find(&pak, b"../../../Game/");
+        pak[pos + 9] = b'g';
+
+        let mut reader = PakReader::new(Cursor::new(pak.clone()));
+        reader.load_index()?;
+        assert_eq!(reader.mount_point, "../../../game/");
+
+        let mut reader = PakReader::new(Cursor::new(pak));
+        reader.set_verify_hashes(true);
+        let err = reader.load_index().unwrap_err();
+        assert!(
+            matches!(err.kind, PakErrorKind::IndexHashMismatch),
+            "{err:?}"
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,137 @@
+use std::io::{Cursor, Read};
+
+use unreal_pak::{
+    error::PakErrorKind, pakversion::PakVersion, vfs::VfsDirEntry, PakError, PakReader, PakVfs,
+};
+
+mod shared;
+use shared::TestPak;
+
+fn pak(
+    mount_point: &str,
+    entries: &[(&str, &str)],
+    deleted: &[&str],
+) -> PakReader<Cursor<Vec<u8>>> {
+    let mut pak = TestPak::new(PakVersion::Fnv64BugFix).mount_point(mount_point);
+    for (name, data) in entries {
+        pak = pak.entry(name, data.as_bytes(), false);
+    }
+    for name in deleted {
+        pak = pak.delete_record(name);
+    }
+    PakReader::new(Cursor::new(pak.write().expect("writing pak in memory")))
+}
+
+fn test_vfs() -> Result<PakVfs<Cursor<Vec<u8>>>, PakError> {
+    let mut vfs = PakVfs::new();
+    vfs.mount(
+        "pakchunk0-WindowsNoEditor.pak",
+        pak(
+            "../../../",
+            &[
+                ("Game/Content/A.uasset", "base a"),
+                ("Game/Content/B.uasset", "base b"),
+                ("Game/Content/Maps/Map.umap", "base map"),
+                ("Engine/Config/Base.ini", "engine"),
+            ],
+            &[],
+        ),
+        0,
+    )?;
+    // patch paks override later paks with a higher order
+    vfs.mount(
+        "pakchunk0-WindowsNoEditor_P.pak",
+        pak(
+            "../../../Game/Content/",
+            &[("A.uasset", "patched a")],
+            &["B.uasset"],
+        ),
+        0,
+    )?;
+    vfs.mount(
+        "999-Mod.pak",
+        pak(
+            "../../../Game/",
+            &[
+                ("Content/A.uasset", "mod a"),
+                ("Content/Mod/C.uasset", "mod c"),
+            ],
+            &[],
+        ),
+        50,
+    )?;
+    Ok(vfs)
+}
+
+#[test]
+fn priorities() -> Result<(), PakError> {
+    let mut vfs = test_vfs()?;
+
+    assert_eq!(vfs.read("Game/Content/A.uasset")?, b"patched a");
+    assert_eq!(
+        vfs.winning_pak("Game/Content/A.uasset"),
+        Some("pakchunk0-WindowsNoEditor_P.pak")
+    );
+    assert_eq!(
+        vfs.winning_pak("Game/Content/Mod/C.uasset"),
+        Some("999-Mod.pak")
+    );
+    assert_eq!(
+        vfs.winning_pak("Engine/Config/Base.ini"),
+        Some("pakchunk0-WindowsNoEditor.pak")
+    );
+
+    let mut data = String::new();
+    vfs.open("Game/Content/Maps/Map.umap")?
+        .read_to_string(&mut data)?;
+    assert_eq!(data, "base map");
+
+    Ok(())
+}
+
+#[test]
+fn delete_records() -> Result<(), PakError> {
+    let mut vfs = test_vfs()?;
+
+    assert!(!vfs.contains("Game/Content/B.uasset"));
+    assert_eq!(vfs.winning_pak("Game/Content/B.uasset"), None);
+    let err = vfs.read("Game/Content/B.uasset").unwrap_err();
+    assert!(matches!(err.kind, PakErrorKind::EntryNotFound(_)));
+
+    // a pak with a higher priority can add the file back
+    vfs.mount(
+        "Restore_P.pak",
+        pak("../../../", &[("Game/Content/B.uasset", "restored b")], &[]),
+        1,
+    )?;
+    assert_eq!(vfs.read("Game/Content/B.uasset")?, b"restored b");
+
+    Ok(())
+}
+
+#[test]
+fn directory_listing() -> Result<(), PakError> {
+    let vfs = test_vfs()?;
+
+    assert_eq!(
+        vfs.read_dir(""),
+        vec![
+            VfsDirEntry::Directory("Engine".to_string()),
+            VfsDirEntry::Directory("Game".to_string()),
+        ]
+    );
+    assert_eq!(
+        vfs.read_dir("Game/Content/"),
+        vec![
+            VfsDirEntry::Directory("Maps".to_string()),
+            VfsDirEntry::Directory("Mod".to_string()),
+            VfsDirEntry::File("A.uasset".to_string()),
+        ]
+    );
+
+    let files = vfs.files().collect::<Vec<_>>();
+    assert_eq!(files.len(), 4);
+    assert!(files.contains(&("Game/Content/Mod/C.uasset", "999-Mod.pak")));
+
+    Ok(())
+}
@@ -1,12 +1,17 @@
 use std::fs::{File, OpenOptions};
 use std::io::{BufReader, BufWriter, Write};
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 use std::process::exit;
 use std::time::SystemTime;
 
 use clap::{Parser, Subcommand};
 use path_absolutize::Absolutize;
-use unreal_pak::{pakversion::PakVersion, PakReader, PakWriter};
+use rayon::prelude::*;
+use unreal_pak::{
+    iostore::{EngineVersion, IoChunkId, IoChunkType, IoStoreTocVersion, IoStoreWriter},
+    pakversion::PakVersion,
+    repack, Compression, PakDiff, PakError, PakReader, PakWriter,
+};
 use walkdir::WalkDir;
 
 /// Command line tool for working with Unreal Engine .pak files.
@@ -22,7 +27,7 @@ struct Args {
 
 #[derive(Subcommand, Debug)]
 enum Commands {
-    /// Check an entire .pak file if it is valid.
+    /// Check an entire .pak file if it is valid, including the SHA1 hashes of the index and all entries.
     Check {
         /// The .pak file to check
         pakfile: String,
@@ -34,23 +39,113 @@ enum Commands {
         pakfile: String,
     },
 
+    /// Compare two .pak files, listing added, removed and changed entries.
+    Diff {
+        /// The old .pak file
+        old: String,
+        /// The new .pak file
+        new: String,
+    },
+
+    /// List the entries of a .pak file with their sizes and compression.
+    List {
+        /// The .pak file to list
+        pakfile: String,
+        /// Print the list as JSON
+        #[clap(short, long)]
+        json: bool,
+    },
+
+    /// Show the information stored in the footer of a .pak file.
+    Info {
+        /// The .pak file to show
+        pakfile: String,
+    },
+
     /// Extract a .pak file to a directory.
     Extract {
         /// The .pak file to extract
         pakfile: String,
         /// The directory to extract to, if not specified the .pak file name will be used
         outdir: Option<String>,
+        /// Only extract entries matching a glob pattern like `Game/Content/**/*.uasset`, can be used multiple times
+        #[clap(short, long)]
+        filter: Vec<String>,
+        /// Number of threads used for extracting, uses all cores by default
+        #[clap(short, long, default_value_t = 0)]
+        threads: usize,
     },
 
     /// create a new .pak file from the files from a directory, optionally disabling compression.
     Create {
-        /// The directory to create the file from
+        /// The directory to create the file from, or the response file when `--response-file` is set
         indir: String,
         /// The .pak file to create, if not supplied the dir name will be used
         pakfile: Option<String>,
         /// Do not use compression when writing the file
         #[clap(short, long)]
         no_compression: bool,
+        /// Version of the pak file format, from 1 to 11
+        #[clap(long, default_value_t = 8)]
+        pak_version: u32,
+        /// Mount point of the pak file
+        #[clap(short, long, default_value = "../../../")]
+        mount_point: String,
+        /// Compression method, like Zlib, Gzip, Zstd or LZ4
+        #[clap(short, long, default_value = "Zlib")]
+        compression: String,
+        /// Size of the compression blocks
+        #[clap(short, long, default_value_t = 0x10000)]
+        block_size: u32,
+        /// Read the files from an UnrealPak response file instead of a directory.
+        /// Each line contains the quoted source path and the path in the pak including the mount point,
+        /// like `"C:/Mod/Asset.uasset" "../../../Game/Content/Asset.uasset"`. Other options on the line are ignored.
+        #[clap(short, long)]
+        response_file: bool,
+    },
+
+    /// Convert a .pak file to another pak version, keeping compressed data where possible.
+    Repack {
+        /// The .pak file to convert
+        pakfile: String,
+        /// The .pak file to create
+        output: String,
+        /// Version of the new pak file format, from 1 to 11
+        #[clap(long)]
+        pak_version: u32,
+        /// Compression method for entries whose compression method can not be stored in the new version
+        #[clap(short, long, default_value = "Zlib")]
+        compression: String,
+    },
+
+    /// Add the files from a directory to an existing .pak file, replacing entries with the same name.
+    Append {
+        /// The .pak file to add the files to
+        pakfile: String,
+        /// The directory with the files to add
+        indir: String,
+        /// Do not use compression when writing the files
+        #[clap(short, long)]
+        no_compression: bool,
+        /// Remove the space left behind by replaced entries. Entries are moved in place, so the pak is corrupted if writing fails
+        #[clap(short, long)]
+        compact: bool,
+    },
+
+    /// Create an IoStore container (.utoc/.ucas) and its companion .pak file from the files from a directory.
+    /// Packages (.uasset, .umap with their .uexp) are converted to the zen package format and go into the container
+    /// together with bulk data (.ubulk, .m.ubulk, .uptnl), all other files go into the .pak file.
+    CreateIoStore {
+        /// The directory to create the container from
+        indir: String,
+        /// Path of the files to create without extension, if not supplied the dir name will be used
+        output: Option<String>,
+        /// Do not use compression when writing the files
+        #[clap(short, long)]
+        no_compression: bool,
+        /// Engine version the packages were cooked with, 5.0 or 5.1
+        #[clap(short, long, default_value = "5.1")]
+        engine_version: String,
     },
 }
 
@@ -68,9 +163,10 @@ fn main() {
         Commands::Check { pakfile } => {
             let file = open_file(Path::new(&pakfile));
             let mut pak = PakReader::new(file);
+            pak.set_verify_hashes(true);
             check_header(&mut pak);
 
-            for (i, (file_name, data)) in pak.iter().enumerate() {
+            for (i, (file_name, data)) in listing(pak.iter()).enumerate() {
                 println!("Record {i}: {file_name:?}");
 
                 match data {
@@ -82,7 +178,158 @@ fn main() {
                 }
             }
         }
-        Commands::Extract { pakfile, outdir } => {
+        Commands::Diff { old, new } => {
+            let mut old_pak = PakReader::new(open_file(Path::new(&old)));
+            check_header(&mut old_pak);
+            let mut new_pak = PakReader::new(open_file(Path::new(&new)));
+            check_header(&mut new_pak);
+
+            let diff = match PakDiff::compare(&mut old_pak, &mut new_pak) {
+                Ok(diff) => diff,
+                Err(err) => {
+                    eprintln!("Error comparing paks! Error: {err}");
+                    exit(1);
+                }
+            };
+
+            if let Some((old, new)) = diff.pak_version {
+                println!("Pak version: {old:?} -> {new:?}");
+            }
+            if let Some((old, new)) = &diff.mount_point {
+                println!("Mount point: {old:?} -> {new:?}");
+            }
+            for name in &diff.added {
+                println!("+ {name}");
+            }
+            for name in &diff.removed {
+                println!("- {name}");
+            }
+            for entry in &diff.changed {
+                let mut changes = Vec::new();
+                if entry.content_changed {
+                    changes.push("content changed".to_owned());
+                }
+                if let Some((old, new)) = entry.compression {
+                    changes.push(format!("compression {old} -> {new}"));
+                }
+                if let Some((old, new)) = entry.decompressed_size {
+                    changes.push(format!("size {old} -> {new}"));
+                }
+                if let Some((old, new)) = entry.compressed_size {
+                    changes.push(format!("compressed size {old} -> {new}"));
+                }
+                if let Some((old, new)) = entry.encrypted {
+                    changes.push(format!("encrypted {old} -> {new}"));
+                }
+                println!("~ {} ({})", entry.name, changes.join(", "));
+            }
+
+            println!(
+                "{} added, {} removed, {} changed",
+                diff.added.len(),
+                diff.removed.len(),
+                diff.changed.len()
+            );
+        }
+        Commands::List { pakfile, json } => {
+            let mut pak = PakReader::new(open_file(Path::new(&pakfile)));
+            if let Err(err) = pak.load_index() {
+                eprintln!("Error reading header! Error: {err}");
+                exit(1);
+            }
+
+            let entries = listing(pak.entry_infos()).collect::<Vec<_>>();
+
+            if json {
+                let entries = entries
+                    .iter()
+                    .map(|(name, info)| {
+                        serde_json::json!({
+                            "name": name,
+                            "offset": info.offset,
+                            "size": info.decompressed_size,
+                            "compressed_size": info.compressed_size,
+                            "ratio": ratio(info.compressed_size, info.decompressed_size),
+                            "compression": info.compression.to_string(),
+                            "blocks": info.block_count(),
+                            "encrypted": info.encrypted,
+                            "hash": info.hash.as_ref().map(|hash| hex(hash)),
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                let list = serde_json::json!({
+                    "mount_point": pak.mount_point,
+                    "entries": entries,
+                    "delete_records": listing(pak.get_delete_records()),
+                });
+                println!("{}", serde_json::to_string_pretty(&list).unwrap());
+                // keep the output valid JSON
+                return;
+            }
+
+            println!(
+                "{:>12} {:>12} {:>7} {:<11} Name",
+                "Size", "Compressed", "Ratio", "Compression"
+            );
+            let (mut total_size, mut total_compressed) = (0, 0);
+            for (name, info) in &entries {
+                println!(
+                    "{:>12} {:>12} {:>6.1}% {:<11} {name}",
+                    info.decompressed_size,
+                    info.compressed_size,
+                    ratio(info.compressed_size, info.decompressed_size),
+                    info.compression.to_string()
+                );
+                total_size += info.decompressed_size;
+                total_compressed += info.compressed_size;
+            }
+            for name in listing(pak.get_delete_records()) {
+                println!("{:>12} {:>12} {:>7} {:<11} {name}", "", "", "", "deleted");
+            }
+            println!(
+                "{total_size:>12} {total_compressed:>12} {:>6.1}% {} entries",
+                ratio(total_compressed, total_size),
+                entries.len()
+            );
+        }
+        Commands::Info { pakfile } => {
+            let mut pak = PakReader::new(open_file(Path::new(&pakfile)));
+            check_header(&mut pak);
+
+            let footer = pak.footer_info().unwrap();
+            match footer.pak_version.to_num() {
+                Ok(num) => println!("Pak version: {:?} ({num})", footer.pak_version),
+                Err(_) => println!("Pak version: unknown"),
+            }
+            println!("Mount point: {:?}", pak.mount_point);
+            println!(
+                "Delete records: {}",
+                listing(pak.get_delete_records()).len()
+            );
+            println!("Index offset: {:#x}", footer.index_offset);
+            println!("Index size: {:#x}", footer.index_size);
+            println!("Index hash: {}", hex(&footer.index_hash));
+            println!("Index encrypted: {}", footer.index_encrypted);
+            match footer.encryption_key_guid {
+                Some(guid) => println!("Encryption key GUID: {}", hex(&guid)),
+                None => println!("Encryption key GUID: not stored"),
+            }
+            println!(
+                "Compression methods: {}",
+                footer
+                    .compression_methods
+                    .iter()
+                    .map(|method| method.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+        Commands::Extract {
+            pakfile,
+            outdir,
+            filter,
+            threads,
+        } => {
             let path = Path::new(&pakfile);
             let file = open_file(path);
             let mut pak = PakReader::new(file);
@@ -94,64 +341,83 @@ fn main() {
                 None => path.parent().unwrap().join(path.file_stem().unwrap()),
             };
 
-            println!("Extracting to {output_folder:?}");
+            let patterns = filter
+                .iter()
+                .map(|filter| match glob::Pattern::new(filter) {
+                    Ok(pattern) => pattern,
+                    Err(err) => {
+                        eprintln!("Invalid filter {filter:?}! Error: {err}");
+                        exit(1);
+                    }
+                })
+                .collect::<Vec<_>>();
+            let file_names = listing(pak.get_entry_names())
+                .into_iter()
+                .filter(|name| {
+                    patterns.is_empty() || patterns.iter().any(|pattern| pattern.matches(name))
+                })
+                .cloned()
+                .collect::<Vec<_>>();
 
-            for (i, (file_name, data)) in pak.iter().enumerate() {
-                match data {
-                    Ok(data) => {
-                        let path = output_folder.join(file_name);
-                        let dir_path = match path.parent() {
-                            Some(dir) => dir,
-                            None => {
-                                eprintln!("No parent directories found! {i}: {file_name:?}");
+            if let Some(file_name) = file_names.iter().find(|name| !is_relative_path(name)) {
+                eprintln!("Refusing to extract {file_name:?}, it would be written outside of {output_folder:?}!");
+                exit(1);
+            }
+
+            println!("Extracting {} files to {output_folder:?}", file_names.len());
+
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap();
+            pool.install(|| {
+                // every thread reads from its own file handle
+                file_names.par_iter().enumerate().for_each_init(
+                    || {
+                        let mut pak = PakReader::new(open_file(path));
+                        check_header_quiet(&mut pak);
+                        pak
+                    },
+                    |pak, (i, file_name)| {
+                        let data = match pak.read_entry(file_name) {
+                            Ok(data) => data,
+                            Err(err) => {
+                                eprintln!("Error reading record {i}: {file_name:?}! Error: {err}");
                                 exit(1);
                             }
                         };
 
-                        // Create the parent directories, then files.
-                        match std::fs::create_dir_all(dir_path) {
-                            Ok(_) => {
-                                // Create the file
-                                let mut file = match File::create(&path) {
-                                    Ok(file) => file,
-                                    Err(err) => {
-                                        eprintln!(
-                                            "Error creating file {i}: {path:?}! Error: {err}"
-                                        );
-                                        exit(1);
-                                    }
-                                };
-                                // Write the file
-                                match file.write_all(data.as_slice()) {
-                                    Ok(_) => {
-                                        println!("Record {i}: {file_name}");
-                                    }
-                                    Err(err) => {
-                                        eprintln!(
-                                            "Error writing to file {i}: {path:?}! Error: {err}"
-                                        );
-                                        exit(1);
-                                    }
-                                }
-                            }
+                        let path = output_folder.join(file_name);
+                        let result = match path.parent() {
+                            Some(dir_path) => std::fs::create_dir_all(dir_path),
+                            None => Ok(()),
+                        }
+                        .and_then(|_| File::create(&path))
+                        .and_then(|mut file| file.write_all(&data));
+                        match result {
+                            Ok(_) => println!("Record {i}: {file_name}"),
                             Err(err) => {
-                                eprintln!("Error creating directories {dir_path:?}! Error: {err}");
+                                eprintln!("Error writing to file {i}: {path:?}! Error: {err}");
                                 exit(1);
                             }
-                        };
-                    }
-                    Err(err) => {
-                        eprintln!("Error reading record {i}: {file_name:?}! Error: {err}");
-                        exit(1);
-                    }
-                }
-            }
+                        }
+                    },
+                );
+            });
         }
         Commands::Create {
             indir,
             pakfile,
             no_compression,
+            pak_version,
+            mount_point,
+            compression,
+            block_size,
+            response_file,
         } => {
+            let pak_version = parse_pak_version(pak_version);
+            let compression = parse_compression(&compression);
+
             let pakfile = match pakfile {
                 Some(pakfile) => Path::new(&pakfile).absolutize().unwrap().to_path_buf(),
                 None => {
@@ -165,7 +431,10 @@ fn main() {
                 }
             };
             let indir = Path::new(&indir).absolutize().unwrap().to_path_buf();
-            let indir_len = indir.components().count();
+            let files = match response_file {
+                true => read_response_file(&indir, &mount_point),
+                false => collect_files(&indir),
+            };
 
             println!("Creating {pakfile:?}");
 
@@ -179,34 +448,103 @@ fn main() {
 
             let file = OpenOptions::new().append(true).open(&pakfile).unwrap();
 
-            let mut pak = PakWriter::new(
-                BufWriter::new(file),
-                PakVersion::FnameBasedCompressionMethod,
-            );
-
-            // Get all files and write them to the .pak file
-            let files = WalkDir::new(&indir)
-                .into_iter()
-                .filter_map(|entry| entry.ok())
-                .filter(|entry| entry.file_type().is_file())
-                .collect::<Vec<_>>();
-            //files.sort_unstable_by_key(|entry| entry.file_name().to_owned());
+            let mut pak = PakWriter::new(BufWriter::new(file), pak_version);
+            pak.mount_point = mount_point;
+            pak.compression = compression;
+            pak.block_size = block_size;
 
             println!("Writing {} files", files.len());
 
-            for (i, entry) in files.iter().enumerate() {
-                // file_path is the OS absolute path, file_name is the folders and file name written to the pak
-                let file_path = entry.path();
-                let mut components = file_path.components();
-                for _ in 0..indir_len {
-                    components.next();
+            for (i, (file_path, file_name)) in files.iter().enumerate() {
+                let file_data = match std::fs::read(file_path) {
+                    Ok(file_data) => file_data,
+                    Err(err) => {
+                        eprintln!("Error reading file {file_path:?}! Error: {err}");
+                        exit(1);
+                    }
+                };
+
+                match pak.write_entry(file_name, &file_data, !no_compression) {
+                    Ok(_) => println!("Wrote file {i}: {file_name}"),
+                    Err(err) => {
+                        eprintln!("Error writing file in pak {file_name:?}! Error: {err}");
+                        exit(1);
+                    }
                 }
+            }
+
+            match pak.finish_write() {
+                Ok(_) => println!("Finsihed writing pak index and footer"),
+                Err(err) => {
+                    eprintln!("Error writing pak index or footer! Error: {err}");
+                    exit(1);
+                }
+            }
+        }
+        Commands::Repack {
+            pakfile,
+            output,
+            pak_version,
+            compression,
+        } => {
+            let pak_version = parse_pak_version(pak_version);
+            let compression = parse_compression(&compression);
+
+            let mut reader = PakReader::new(open_file(Path::new(&pakfile)));
+            check_header(&mut reader);
+
+            println!("Converting to {pak_version:?}");
+
+            let mut pak =
+                PakWriter::new(BufWriter::new(create_file(Path::new(&output))), pak_version);
+            pak.compression = compression;
+
+            match repack(&mut reader, &mut pak) {
+                Ok(stats) => println!(
+                    "Copied {} entries, rewrote {} entries and {} delete records",
+                    stats.copied, stats.rewritten, stats.delete_records
+                ),
+                Err(err) => {
+                    eprintln!("Error converting pak! Error: {err}");
+                    exit(1);
+                }
+            }
+
+            match pak.finish_write() {
+                Ok(_) => println!("Finished writing pak index and footer"),
+                Err(err) => {
+                    eprintln!("Error writing pak index or footer! Error: {err}");
+                    exit(1);
+                }
+            }
+        }
+        Commands::Append {
+            pakfile,
+            indir,
+            no_compression,
+            compact,
+        } => {
+            let indir = Path::new(&indir).absolutize().unwrap().to_path_buf();
 
-                let mut file_name = components.as_path().to_string_lossy().replace('\\', "/");
-                if file_name.starts_with('/') {
-                    file_name = file_name[1..].to_owned();
+            let file = match OpenOptions::new().read(true).write(true).open(&pakfile) {
+                Ok(file) => file,
+                Err(err) => {
+                    eprintln!("Could not find/open file! Error: {err}");
+                    exit(1);
                 }
+            };
+            let mut pak = match PakWriter::open_for_append(file, None) {
+                Ok(pak) => pak,
+                Err(err) => {
+                    eprintln!("Error reading header! Error: {err}");
+                    exit(1);
+                }
+            };
 
+            let files = collect_files(&indir);
+            println!("Writing {} files", files.len());
+
+            for (i, (file_path, file_name)) in files.iter().enumerate() {
                 let file_data = match std::fs::read(file_path) {
                     Ok(file_data) => file_data,
                     Err(err) => {
@@ -215,7 +553,7 @@ fn main() {
                     }
                 };
 
-                match pak.write_entry(&file_name, &file_data, !no_compression) {
+                match pak.write_entry(file_name, &file_data, !no_compression) {
                     Ok(_) => println!("Wrote file {i}: {file_name}"),
                     Err(err) => {
                         eprintln!("Error writing file in pak {file_name:?}! Error: {err}");
@@ -224,8 +562,119 @@ fn main() {
                 }
             }
 
+            // compacting moves entries in place, so nothing may fail between it and writing the new index
+            if compact {
+                if let Err(err) = pak.compact() {
+                    eprintln!("Error compacting pak! Error: {err}");
+                    exit(1);
+                }
+            }
+
             match pak.finish_write() {
-                Ok(_) => println!("Finsihed writing pak index and footer"),
+                Ok(_) => println!("Finished writing pak index and footer"),
+                Err(err) => {
+                    eprintln!("Error writing pak index or footer! Error: {err}");
+                    exit(1);
+                }
+            }
+        }
+        Commands::CreateIoStore {
+            indir,
+            output,
+            no_compression,
+            engine_version,
+        } => {
+            let engine_version = parse_engine_version(&engine_version);
+            let indir = Path::new(&indir).absolutize().unwrap().to_path_buf();
+            let output = match output {
+                Some(output) => Path::new(&output).absolutize().unwrap().to_path_buf(),
+                None => indir.clone(),
+            };
+            let container_name = output.file_name().unwrap().to_string_lossy().to_string();
+            let output_path = |extension: &str| {
+                let mut path = output.clone().into_os_string();
+                path.push(extension);
+                PathBuf::from(path)
+            };
+
+            let files = collect_files(&indir);
+            // .uexp files are added together with their package
+            for (_, file_name) in &files {
+                if let Some(stem) = file_name.strip_suffix(".uexp") {
+                    let has_package = [".uasset", ".umap"].into_iter().any(|extension| {
+                        files
+                            .iter()
+                            .any(|(_, other)| other.strip_suffix(extension) == Some(stem))
+                    });
+                    if !has_package {
+                        eprintln!("No .uasset or .umap file found for {file_name:?}!");
+                        exit(1);
+                    }
+                }
+            }
+
+            println!("Creating {:?}", output_path(".utoc"));
+
+            let version = IoStoreTocVersion::PerfectHashWithOverflow;
+            let mut container =
+                IoStoreWriter::new(BufWriter::new(create_file(&output_path(".ucas"))), version);
+            container.set_container_name(&container_name);
+            container.engine_version = Some(engine_version);
+            let mut pak = PakWriter::new(
+                BufWriter::new(create_file(&output_path(".pak"))),
+                PakVersion::Fnv64BugFix,
+            );
+            if no_compression {
+                container.compression = Compression::None;
+            }
+
+            println!("Writing {} files", files.len());
+
+            for (i, (file_path, file_name)) in files.iter().enumerate() {
+                if file_name.ends_with(".uexp") {
+                    continue;
+                }
+                let file_data = read_file(file_path);
+
+                let package = file_name
+                    .strip_suffix(".uasset")
+                    .or_else(|| file_name.strip_suffix(".umap"));
+                let result = if let Some(stem) = package {
+                    let Some(package_name) = package_name(stem) else {
+                        eprintln!("Package {file_name:?} is not in a Content directory!");
+                        exit(1);
+                    };
+                    let uexp = read_file(&file_path.with_extension("uexp"));
+                    container
+                        .add_package(&package_name, Some(file_name), &file_data, &uexp)
+                        .map(|_| "container")
+                } else {
+                    match package_chunk_id(file_name, version) {
+                        Some(chunk_id) => container
+                            .add_chunk(chunk_id, Some(file_name), &file_data)
+                            .map(|_| "container"),
+                        None => pak
+                            .write_entry(file_name, &file_data, !no_compression)
+                            .map(|_| "pak"),
+                    }
+                };
+                match result {
+                    Ok(target) => println!("Wrote file {i} to {target}: {file_name}"),
+                    Err(err) => {
+                        eprintln!("Error writing file {file_name:?}! Error: {err}");
+                        exit(1);
+                    }
+                }
+            }
+
+            if let Err(err) =
+                container.finish_write(BufWriter::new(create_file(&output_path(".utoc"))))
+            {
+                eprintln!("Error writing container toc! Error: {err}");
+                exit(1);
+            }
+            match pak.finish_write() {
+                Ok(_) => println!("Finished writing container toc and pak index"),
                 Err(err) => {
                     eprintln!("Error writing pak index or footer! Error: {err}");
                     exit(1);
@@ -239,6 +688,17 @@ fn main() {
     )
 }
 
+/// Unwrap the entries or delete records of a pak, exiting if they can't be listed
+fn listing<T>(result: Result<T, PakError>) -> T {
+    match result {
+        Ok(listing) => listing,
+        Err(err) => {
+            eprintln!("Error listing entries! Error: {err}");
+            exit(1);
+        }
+    }
+}
+
 fn open_file(path: &Path) -> BufReader<File> {
     match OpenOptions::new().read(true).open(path) {
         Ok(file) => BufReader::new(file),
@@ -249,6 +709,210 @@ fn open_file(path: &Path) -> BufReader<File> {
     }
 }
 
+fn create_file(path: &Path) -> File {
+    match File::create(path) {
+        Ok(file) => file,
+        Err(err) => {
+            eprintln!("Could not create file {path:?}! Error: {err}");
+            exit(1);
+        }
+    }
+}
+
+/// Get all files in a directory with their OS path and their path relative to the directory using `/` as separator
+fn collect_files(indir: &Path) -> Vec<(PathBuf, String)> {
+    let indir_len = indir.components().count();
+
+    WalkDir::new(indir)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| {
+            let mut components = entry.path().components();
+            for _ in 0..indir_len {
+                components.next();
+            }
+
+            let file_name = components.as_path().to_string_lossy().replace('\\', "/");
+            let file_name = file_name.trim_start_matches('/').to_owned();
+            (entry.into_path(), file_name)
+        })
+        .collect()
+}
+
+/// Check that an entry name is a relative path which stays inside the directory it is joined to
+fn is_relative_path(file_name: &str) -> bool {
+    Path::new(file_name)
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+}
+
+fn read_file(path: &Path) -> Vec<u8> {
+    match std::fs::read(path) {
+        Ok(data) => data,
+        Err(err) => {
+            eprintln!("Error reading file {path:?}! Error: {err}");
+            exit(1);
+        }
+    }
+}
+
+/// Get the package name from the path of a file without extension relative to the `../../../` mount point.
+///
+/// `Engine/Content/X` is `/Engine/X`, `Game/Plugins/Plugin/Content/X` is `/Plugin/X`
+/// and `Game/Content/X` is `/Game/X`.
+fn package_name(stem: &str) -> Option<String> {
+    let components = stem.split('/').collect::<Vec<_>>();
+    let content = components
+        .iter()
+        .rposition(|component| *component == "Content")?;
+    let root = match (components.first(), content) {
+        (Some(&"Engine"), 1) => "Engine",
+        (_, 1) => "Game",
+        (_, 0) => return None,
+        _ => components[content - 1],
+    };
+    Some(format!("/{root}/{}", components[content + 1..].join("/")))
+}
+
+/// Get the chunk id for a bulk data file which belongs into an IoStore container
+fn package_chunk_id(file_name: &str, version: IoStoreTocVersion) -> Option<IoChunkId> {
+    let (stem, chunk_type) = [
+        (".m.ubulk", IoChunkType::MemoryMappedBulkData),
+        (".ubulk", IoChunkType::BulkData),
+        (".uptnl", IoChunkType::OptionalBulkData),
+    ]
+    .into_iter()
+    .find_map(|(extension, chunk_type)| {
+        file_name
+            .strip_suffix(extension)
+            .map(|stem| (stem, chunk_type))
+    })?;
+
+    Some(IoChunkId::from_package_name(
+        &package_name(stem)?,
+        chunk_type,
+        version,
+    ))
+}
+
+fn parse_pak_version(pak_version: u32) -> PakVersion {
+    match PakVersion::from_num(pak_version) {
+        PakVersion::Invalid => {
+            eprintln!("Unsupported pak version {pak_version}!");
+            exit(1);
+        }
+        pak_version => pak_version,
+    }
+}
+
+fn parse_engine_version(engine_version: &str) -> EngineVersion {
+    match engine_version {
+        "5.0" => EngineVersion::UE5_0,
+        "5.1" => EngineVersion::UE5_1,
+        _ => {
+            eprintln!(
+                "Unsupported engine version {engine_version}, only 5.0 and 5.1 are supported!"
+            );
+            exit(1);
+        }
+    }
+}
+
+fn parse_compression(compression: &str) -> Compression {
+    match Compression::from_name(compression) {
+        Some(compression) => compression,
+        None if compression.eq_ignore_ascii_case("none") => Compression::None,
+        None => {
+            eprintln!("Unknown compression method {compression:?}!");
+            exit(1);
+        }
+    }
+}
+
+fn check_header_quiet(pak: &mut PakReader<BufReader<File>>) {
+    if let Err(err) = pak.load_index() {
+        eprintln!("Error reading header! Error: {err}");
+        exit(1);
+    }
+}
+
+/// Read an UnrealPak response file, returning the source paths and the paths relative to the mount point
+fn read_response_file(path: &Path, mount_point: &str) -> Vec<(PathBuf, String)> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(err) => {
+            eprintln!("Error reading response file {path:?}! Error: {err}");
+            exit(1);
+        }
+    };
+
+    let mut files = Vec::new();
+    for line in content.lines() {
+        let mut arguments = split_arguments(line).into_iter();
+        let (Some(source), Some(destination)) = (arguments.next(), arguments.next()) else {
+            if !line.trim().is_empty() {
+                eprintln!("Invalid response file line {line:?}!");
+                exit(1);
+            }
+            continue;
+        };
+
+        let destination = destination.replace('\\', "/");
+        match destination.strip_prefix(mount_point) {
+            Some(file_name) => files.push((PathBuf::from(source), file_name.to_owned())),
+            None => {
+                eprintln!("Path {destination:?} is not inside the mount point {mount_point:?}!");
+                exit(1);
+            }
+        }
+    }
+    files
+}
+
+/// Split a line into whitespace separated arguments, which may be quoted
+fn split_arguments(line: &str) -> Vec<String> {
+    let mut arguments = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut in_argument = false;
+
+    for char in line.chars() {
+        match char {
+            '"' => {
+                quoted = !quoted;
+                in_argument = true;
+            }
+            char if char.is_whitespace() && !quoted => {
+                if in_argument {
+                    arguments.push(std::mem::take(&mut current));
+                    in_argument = false;
+                }
+            }
+            char => {
+                current.push(char);
+                in_argument = true;
+            }
+        }
+    }
+    if in_argument {
+        arguments.push(current);
+    }
+    arguments
+}
+
+/// Compressed size in percent of the decompressed size
+fn ratio(compressed_size: u64, decompressed_size: u64) -> f64 {
+    match decompressed_size {
+        0 => 100.0,
+        _ => compressed_size as f64 / decompressed_size as f64 * 100.0,
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
 fn check_header(pak: &mut PakReader<BufReader<File>>) {
     match pak.load_index() {
         Ok(_) => println!("Header is ok"),
@@ -257,5 +921,5 @@ fn check_header(pak: &mut PakReader<BufReader<File>>) {
             exit(1);
         }
     }
-    println!("Found {:?} records", pak.get_entry_names().len());
+    println!("Found {:?} records", listing(pak.get_entry_names()).len());
 }